@@ -0,0 +1,100 @@
+//! Benchmarks of the configuration layer: reading e4docker.conf and the buttons, reading the style and saving
+//! the buttons, in memory through [MemoryStorage] so the disk is not measured.
+
+use configparser::ini::Ini;
+use criterion::{criterion_group, criterion_main, Criterion};
+use e4docker::{
+    e4config::{self, E4Config},
+    e4storage::{self, MemoryStorage},
+    e4style::E4Style,
+    translations::Translations,
+};
+use std::{hint::black_box, path::PathBuf, sync::Arc};
+
+/// The buttons of the configuration, as a crowded dock.
+const BUTTONS: usize = 40;
+
+fn config_dir() -> PathBuf {
+    PathBuf::from("memory").join("e4docker")
+}
+
+/// Return e4docker.conf with BUTTONS buttons and a style.
+fn e4docker_conf() -> String {
+    let buttons: Vec<String> = (1..=BUTTONS)
+        .map(|i| format!("button{}=button-{}", i, i))
+        .collect();
+    format!(
+        "[e4docker]
+number_of_buttons={}
+frame_margin=10
+margin_between_buttons=20
+icon_width=48
+icon_height=48
+x=100
+y=50
+frame_style=flat
+icon_tint=monochrome
+icon_corner_radius=0.2
+[buttons]
+{}
+",
+        BUTTONS,
+        buttons.join("\n")
+    )
+}
+
+/// Return a storage holding e4docker.conf and the configuration files of its buttons.
+fn storage() -> Arc<MemoryStorage> {
+    let dir = config_dir();
+    let storage = (1..=BUTTONS).fold(
+        MemoryStorage::new().with_file(e4config::config_file(&dir), &e4docker_conf()),
+        |storage, i| {
+            storage.with_file(
+                e4config::button_file(&dir, &format!("button-{}", i)),
+                &format!(
+                    "[button]\ncommand=/usr/bin/program-{}\narguments=--new-window\nicon=button-{}.png\n",
+                    i, i
+                ),
+            )
+        },
+    );
+    Arc::new(storage)
+}
+
+fn parse(c: &mut Criterion) {
+    let storage = storage();
+    let assets_dir = config_dir().join("assets");
+    c.bench_function("read the configuration", |b| {
+        b.iter(|| {
+            E4Config::read_from(storage.clone(), black_box(&config_dir()), &assets_dir).unwrap()
+        })
+    });
+    let mut ini = Ini::new();
+    ini.read(e4docker_conf()).unwrap();
+    c.bench_function("read the style", |b| {
+        b.iter(|| E4Style::from_ini(black_box(&ini)).unwrap())
+    });
+}
+
+fn save(c: &mut Criterion) {
+    let conf = e4docker_conf();
+    let mut ini = Ini::new();
+    ini.read(conf.clone()).unwrap();
+    ini.set("e4docker", "x", Some("200".to_string()));
+    c.bench_function("merge e4docker.conf", |b| {
+        b.iter(|| e4storage::merge_ini(black_box(&conf), &ini))
+    });
+    let translations = Translations::get_instance();
+    let mut config =
+        E4Config::read_from(storage(), &config_dir(), &config_dir().join("assets")).unwrap();
+    c.bench_function("save the buttons", |b| {
+        b.iter(|| {
+            config
+                .update_buttons(|buttons| black_box(buttons).reverse(), translations.clone())
+                .unwrap()
+        })
+    });
+}
+
+criterion_group!(benches, parse, save);
+criterion_main!(benches);
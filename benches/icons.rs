@@ -0,0 +1,83 @@
+//! Benchmarks of the icons pipeline: decoding the icons of the buttons, converting them to PNG, scaling
+//! and styling them, as when the dock starts.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use e4docker::{
+    e4icon,
+    e4style::{E4IconEffects, E4IconTint},
+};
+use image::{ImageFormat, RgbaImage};
+use std::{hint::black_box, io::Cursor};
+
+/// The side of the icons decoded, as the largest icons of the theme.
+const ICON_SIZE: u32 = 512;
+
+/// Return a gradient of ICON_SIZE x ICON_SIZE pixels encoded in format.
+fn icon(format: ImageFormat) -> Vec<u8> {
+    let image = RgbaImage::from_fn(ICON_SIZE, ICON_SIZE, |x, y| {
+        image::Rgba([(x / 2) as u8, (y / 2) as u8, ((x + y) / 4) as u8, 255])
+    });
+    let mut cursor = Cursor::new(vec![]);
+    image.write_to(&mut cursor, format).unwrap();
+    cursor.into_inner()
+}
+
+fn decode(c: &mut Criterion) {
+    let png_data = icon(ImageFormat::Png);
+    let bmp_data = icon(ImageFormat::Bmp);
+    c.bench_function("decode png", |b| {
+        b.iter(|| e4icon::decode_to_png(black_box(&png_data)).unwrap())
+    });
+    c.bench_function("convert bmp to png", |b| {
+        b.iter(|| e4icon::decode_to_png(black_box(&bmp_data)).unwrap())
+    });
+    let path = std::env::temp_dir().join(format!("e4docker-bench-{}.png", std::process::id()));
+    std::fs::write(&path, &png_data).unwrap();
+    c.bench_function("read png", |b| {
+        b.iter(|| e4icon::to_png(black_box(&path)).unwrap())
+    });
+    let _ = std::fs::remove_file(&path);
+    c.bench_function("draw monogram", |b| {
+        b.iter(|| e4icon::monogram(black_box("Firefox")))
+    });
+}
+
+fn scale(c: &mut Criterion) {
+    let png_data = icon(ImageFormat::Png);
+    c.bench_function("scale to the saved size", |b| {
+        b.iter(|| {
+            e4icon::scale_to_fit(
+                black_box(&png_data),
+                e4icon::SAVED_ICON_SIZE,
+                e4icon::SAVED_ICON_SIZE,
+            )
+            .unwrap()
+        })
+    });
+    c.bench_function("scale to a button", |b| {
+        b.iter(|| e4icon::scale_to_fit(black_box(&png_data), 64, 64).unwrap())
+    });
+}
+
+fn style(c: &mut Criterion) {
+    let png_data = e4icon::scale_to_fit(&icon(ImageFormat::Png), 128, 128).unwrap();
+    let effects = E4IconEffects {
+        corner_radius: 0.2,
+        shadow: true,
+    };
+    c.bench_function("style without tint and effects", |b| {
+        b.iter(|| {
+            e4icon::styled(
+                black_box(&png_data),
+                E4IconTint::None,
+                E4IconEffects::default(),
+            )
+        })
+    });
+    c.bench_function("style with tint and effects", |b| {
+        b.iter(|| e4icon::styled(black_box(&png_data), E4IconTint::Monochrome, effects))
+    });
+}
+
+criterion_group!(benches, decode, scale, style);
+criterion_main!(benches);
@@ -0,0 +1,46 @@
+#![windows_subsystem = "windows"]
+//! E4Docker drawn with egui, for the displays where FLTK scales badly, like Wayland with a HiDPI scale.
+//!
+//! It shows the dock of the same configuration directory as e4docker and launches the commands of its buttons.
+//! The buttons are edited, and the widgets shown, by e4docker.
+
+use e4docker::{
+    e4cli::E4ExitCode, e4egui, e4initialize, e4instance, e4sync, tr, translations::Translations,
+};
+use std::env;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let translations = Translations::get_instance();
+    // A profile has its own configuration directory, and its own dock
+    if let Some(profile) = e4instance::profile_from_args(&args) {
+        if !e4instance::is_valid_profile(&profile) {
+            eprintln!(
+                "{}",
+                tr!(translations, format, "invalid-profile", &[&profile])
+            );
+            E4ExitCode::Usage.exit();
+        }
+        e4initialize::set_profile(&profile);
+    }
+    let local_config_dir = match e4initialize::get_package_config_dir(translations.clone()) {
+        Ok(local_config_dir) => local_config_dir,
+        Err(e) => {
+            eprintln!("{}", e);
+            E4ExitCode::ConfigNotFound.exit();
+        }
+    };
+    // The configuration can be kept in a synced directory
+    let project_config_dir = e4sync::config_dir(&local_config_dir, translations.clone());
+    // One dock per profile, whatever draws it
+    let _instance = match e4instance::acquire(&local_config_dir) {
+        Ok(Some(instance)) => Some(instance),
+        Ok(None) => return,
+        // A read-only directory cannot be locked: the dock runs anyway
+        Err(_) => None,
+    };
+    if let Err(e) = e4egui::run(&project_config_dir, translations) {
+        eprintln!("{}", e);
+        E4ExitCode::Failure.exit();
+    }
+}
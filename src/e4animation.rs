@@ -0,0 +1,138 @@
+use crate::e4config::{button_file, E4Config, BUTTON_BUTTON_SECTION};
+use fltk::{app, button::Button, image::PngImage, prelude::*};
+use image::{
+    codecs::{gif::GifDecoder, png::PngDecoder},
+    AnimationDecoder, Frames, ImageFormat, ImageReader,
+};
+use std::{
+    cell::RefCell,
+    fs::File,
+    io::{BufReader, Cursor},
+    path::Path,
+    sync::Once,
+    time::{Duration, Instant},
+};
+
+/// The key of the configuration file of a button animating its icon, or not, whatever the style.
+const ANIMATE: &str = "ANIMATE";
+
+/// The seconds between two checks of the frames to show.
+const TICK: f64 = 0.05;
+
+/// The shortest delay of a frame: the shorter ones are shown for [DEFAULT_FRAME_DELAY], as browsers do.
+const MIN_FRAME_DELAY: Duration = Duration::from_millis(20);
+
+/// The delay of the frames shown for less than [MIN_FRAME_DELAY].
+const DEFAULT_FRAME_DELAY: Duration = Duration::from_millis(100);
+
+/// Starts the timer showing the frames once.
+static TIMER: Once = Once::new();
+
+/// An icon cycling its frames on a button.
+struct E4Animation {
+    name: String,
+    button: Button,
+    frames: Vec<(PngImage, Duration)>,
+    current: usize,
+    next: Instant,
+}
+
+thread_local! {
+    /// The animated icons, shown by the timer.
+    static ANIMATIONS: RefCell<Vec<E4Animation>> = const { RefCell::new(vec![]) };
+}
+
+/// Return true if the button named button_name animates its icon: its ANIMATE key, or the
+/// animate_icons option of the style if missing.
+pub fn is_animated(config: &E4Config, button_name: &str) -> bool {
+    config
+        .storage
+        .load_ini(&button_file(&config.config_dir, button_name))
+        .ok()
+        .and_then(|ini| ini.getbool(BUTTON_BUTTON_SECTION, ANIMATE).ok().flatten())
+        .unwrap_or(config.style.animate_icons)
+}
+
+/// Return the frames of the animated GIF or APNG image in path, as PNG data with their delays:
+/// nothing if it is not animated.
+pub fn frames(path: &Path) -> Result<Vec<(Vec<u8>, Duration)>, Box<dyn std::error::Error>> {
+    let reader = BufReader::new(File::open(path)?);
+    let frames: Frames = match ImageReader::open(path)?.with_guessed_format()?.format() {
+        Some(ImageFormat::Gif) => GifDecoder::new(reader)?.into_frames(),
+        Some(ImageFormat::Png) => {
+            let decoder = PngDecoder::new(reader)?;
+            if !decoder.is_apng()? {
+                return Ok(vec![]);
+            }
+            decoder.apng()?.into_frames()
+        }
+        _ => return Ok(vec![]),
+    };
+    let frames = frames.collect_frames()?;
+    if frames.len() < 2 {
+        return Ok(vec![]);
+    }
+    frames
+        .into_iter()
+        .map(|frame| {
+            let (numerator, denominator) = frame.delay().numer_denom_ms();
+            let delay = Duration::from_millis((numerator / denominator.max(1)) as u64);
+            let delay = if delay < MIN_FRAME_DELAY {
+                DEFAULT_FRAME_DELAY
+            } else {
+                delay
+            };
+            let mut cursor = Cursor::new(vec![]);
+            frame
+                .into_buffer()
+                .write_to(&mut cursor, ImageFormat::Png)?;
+            Ok((cursor.into_inner(), delay))
+        })
+        .collect()
+}
+
+/// Cycle the frames, already scaled, on button, the one of the button named name, replacing its previous
+/// animation. It must be called in the main thread.
+pub fn animate(name: &str, button: &Button, frames: Vec<(PngImage, Duration)>) {
+    if frames.is_empty() {
+        return;
+    }
+    let animation = E4Animation {
+        name: name.to_string(),
+        button: button.clone(),
+        frames,
+        current: 0,
+        next: Instant::now(),
+    };
+    ANIMATIONS.with(|animations| {
+        let mut animations = animations.borrow_mut();
+        animations.retain(|animation| animation.name != name);
+        animations.push(animation);
+    });
+    TIMER.call_once(|| {
+        app::add_timeout3(TICK, |handle| {
+            show_frames();
+            app::repeat_timeout3(TICK, handle);
+        });
+    });
+}
+
+/// Show the next frame of the animations whose frame has been shown long enough.
+fn show_frames() {
+    let now = Instant::now();
+    ANIMATIONS.with(|animations| {
+        let mut animations = animations.borrow_mut();
+        // The buttons of a dock drawn again are deleted
+        animations.retain(|animation| !animation.button.was_deleted());
+        for animation in animations.iter_mut() {
+            if now < animation.next {
+                continue;
+            }
+            let (frame, delay) = &animation.frames[animation.current];
+            animation.button.set_image(Some(frame.clone()));
+            animation.button.redraw();
+            animation.next = now + *delay;
+            animation.current = (animation.current + 1) % animation.frames.len();
+        }
+    });
+}
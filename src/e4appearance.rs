@@ -0,0 +1,244 @@
+use crate::{
+    e4events::{self, E4Event},
+    e4style::E4Theme,
+};
+use fltk::{app, enums::Color, window::Window};
+use std::{
+    process::Command,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Once,
+    },
+    thread,
+    time::Duration,
+};
+
+/// How often the preference of the system is read again, with THEME=auto.
+const POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// The colors of the light theme: the background, the background of the inputs, the text and the menu bar.
+const LIGHT_COLORS: [(u8, u8, u8); 4] = [
+    (0xc0, 0xc0, 0xc0),
+    (0xff, 0xff, 0xff),
+    (0x00, 0x00, 0x00),
+    (0xe8, 0xdc, 0xca),
+];
+
+/// The colors of the dark theme, as in [LIGHT_COLORS].
+const DARK_COLORS: [(u8, u8, u8); 4] = [
+    (0x33, 0x33, 0x33),
+    (0x22, 0x22, 0x22),
+    (0xe6, 0xe6, 0xe6),
+    (0x45, 0x3f, 0x38),
+];
+
+/// True if the theme follows the preference of the system.
+static AUTO: AtomicBool = AtomicBool::new(false);
+
+/// True if the dark colors are applied.
+static DARK: AtomicBool = AtomicBool::new(false);
+
+/// Starts the thread reading the preference of the system once.
+static WATCHER: Once = Once::new();
+
+/// Return true if the color-scheme of the org.freedesktop.appearance portal, as printed by gdbus,
+/// prefers the dark colors, false if it prefers the light ones and None if it has no preference.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4appearance::dark_from_portal;
+///
+/// assert_eq!(dark_from_portal("(<<uint32 1>>,)"), Some(true));
+/// assert_eq!(dark_from_portal("(<<uint32 2>>,)"), Some(false));
+/// assert_eq!(dark_from_portal("(<<uint32 0>>,)"), None);
+/// ```
+pub fn dark_from_portal(output: &str) -> Option<bool> {
+    let (_, value) = output.split_once("uint32")?;
+    let value: String = value
+        .trim_start()
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    match value.as_str() {
+        "1" => Some(true),
+        "2" => Some(false),
+        _ => None,
+    }
+}
+
+/// Return true if the AppsUseLightTheme value of the Windows registry, as printed by reg query,
+/// prefers the dark colors, false if it prefers the light ones and None if it is missing.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4appearance::dark_from_registry;
+///
+/// let output = "HKEY_CURRENT_USER\\Software\\Microsoft\\Windows\\CurrentVersion\\Themes\\Personalize\n    \
+///               AppsUseLightTheme    REG_DWORD    0x0\n";
+/// assert_eq!(dark_from_registry(output), Some(true));
+/// assert_eq!(dark_from_registry(""), None);
+/// ```
+pub fn dark_from_registry(output: &str) -> Option<bool> {
+    let line = output
+        .lines()
+        .find(|line| line.contains("AppsUseLightTheme"))?;
+    let value = line.split_whitespace().last()?;
+    let value = u32::from_str_radix(value.trim_start_matches("0x"), 16).ok()?;
+    Some(value == 0)
+}
+
+/// Return the standard output of program run with args, if it succeeds.
+/// On Windows it runs without a console window, polled by the watcher.
+fn output_of(program: &str, args: &[&str]) -> Option<String> {
+    let mut command = Command::new(program);
+    command.args(args);
+    #[cfg(target_os = "windows")]
+    {
+        use std::os::windows::process::CommandExt;
+        command.creation_flags(crate::e4command::CREATE_NO_WINDOW);
+    }
+    match command.output() {
+        Ok(output) if output.status.success() => {
+            Some(String::from_utf8_lossy(&output.stdout).to_string())
+        }
+        _ => None,
+    }
+}
+
+/// Return true if the system prefers the dark colors, from the registry: None if it cannot be read.
+#[cfg(target_os = "windows")]
+pub fn system_prefers_dark() -> Option<bool> {
+    dark_from_registry(&output_of(
+        "reg",
+        &[
+            "query",
+            r"HKCU\Software\Microsoft\Windows\CurrentVersion\Themes\Personalize",
+            "/v",
+            "AppsUseLightTheme",
+        ],
+    )?)
+}
+
+/// Return true if the system prefers the dark colors, from the style of the interface: it is only set when dark.
+#[cfg(target_os = "macos")]
+pub fn system_prefers_dark() -> Option<bool> {
+    Some(
+        output_of("defaults", &["read", "-g", "AppleInterfaceStyle"])
+            .is_some_and(|style| style.trim() == "Dark"),
+    )
+}
+
+/// Return true if the system prefers the dark colors, from the org.freedesktop.appearance portal:
+/// None if it cannot be read, or without gdbus.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn system_prefers_dark() -> Option<bool> {
+    dark_from_portal(&output_of(
+        "gdbus",
+        &[
+            "call",
+            "--session",
+            "--dest",
+            "org.freedesktop.portal.Desktop",
+            "--object-path",
+            "/org/freedesktop/portal/desktop",
+            "--method",
+            "org.freedesktop.portal.Settings.Read",
+            "org.freedesktop.appearance",
+            "color-scheme",
+        ],
+    )?)
+}
+
+/// Apply the colors of theme to the widgets created from now on. With [E4Theme::Auto] the preference
+/// of the system is watched: when it changes the window is drawn again, with a [E4Event::ConfigChanged].
+pub fn apply(theme: E4Theme) {
+    let dark = match theme {
+        E4Theme::Light => false,
+        E4Theme::Dark => true,
+        E4Theme::Auto => system_prefers_dark().unwrap_or(false),
+    };
+    AUTO.store(theme == E4Theme::Auto, Ordering::SeqCst);
+    DARK.store(dark, Ordering::SeqCst);
+    let [(r, g, b), (r2, g2, b2), (fr, fg, fb), _] = colors();
+    app::background(r, g, b);
+    app::background2(r2, g2, b2);
+    app::foreground(fr, fg, fb);
+    if theme == E4Theme::Auto {
+        WATCHER.call_once(watch);
+    }
+}
+
+/// Read the preference of the system from time to time, drawing the window again when it changes.
+fn watch() {
+    thread::spawn(|| loop {
+        thread::sleep(POLL_INTERVAL);
+        if !AUTO.load(Ordering::SeqCst) {
+            continue;
+        }
+        if let Some(dark) = system_prefers_dark() {
+            if DARK.swap(dark, Ordering::SeqCst) != dark {
+                e4events::publish(E4Event::ConfigChanged);
+            }
+        }
+    });
+}
+
+/// Return the colors of the applied theme.
+fn colors() -> [(u8, u8, u8); 4] {
+    if is_dark() {
+        DARK_COLORS
+    } else {
+        LIGHT_COLORS
+    }
+}
+
+/// Return true if the dark colors are applied.
+pub fn is_dark() -> bool {
+    DARK.load(Ordering::SeqCst)
+}
+
+/// Return the color of the menu bar in the applied theme.
+pub fn menu_color() -> Color {
+    let (r, g, b) = colors()[3];
+    Color::from_rgb(r, g, b)
+}
+
+/// Make the title bar of wind, once shown, dark or light as the applied theme, through the Desktop Window Manager.
+#[cfg(target_os = "windows")]
+pub fn style_title_bar(wind: &Window) {
+    use fltk::prelude::WindowExt;
+
+    /// DWMWA_USE_IMMERSIVE_DARK_MODE, since Windows 10 20H1.
+    const DARK_MODE_ATTRIBUTE: u32 = 20;
+
+    #[link(name = "dwmapi")]
+    extern "system" {
+        fn DwmSetWindowAttribute(
+            hwnd: *mut std::ffi::c_void,
+            attribute: u32,
+            value: *const std::ffi::c_void,
+            size: u32,
+        ) -> i32;
+    }
+
+    let hwnd = wind.raw_handle();
+    if hwnd.is_null() {
+        return;
+    }
+    let dark = is_dark() as i32;
+    // The older versions of Windows ignore the attribute: the title bar stays light
+    unsafe {
+        DwmSetWindowAttribute(
+            hwnd as *mut std::ffi::c_void,
+            DARK_MODE_ATTRIBUTE,
+            &dark as *const i32 as *const std::ffi::c_void,
+            std::mem::size_of::<i32>() as u32,
+        );
+    }
+}
+
+/// Make the title bar of wind dark or light as the applied theme: only on Windows, elsewhere it follows the system.
+#[cfg(not(target_os = "windows"))]
+pub fn style_title_bar(_wind: &Window) {}
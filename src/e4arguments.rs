@@ -0,0 +1,184 @@
+use crate::{
+    e4command::{join_arguments, split_arguments},
+    tr,
+    translations::Translations,
+};
+use fltk::{
+    browser::HoldBrowser, button::Button, enums::CallbackTrigger, input::Input, prelude::*,
+};
+use fltk_grid::Grid;
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+/// An editor of the arguments of a command, one per row, instead of a line to be quoted by hand.
+/// The selected row is edited in the input below the list.
+#[derive(Clone)]
+pub struct E4ArgumentsEditor {
+    grid: Grid,
+    browser: HoldBrowser,
+    input: Input,
+    arguments: Rc<RefCell<Vec<String>>>,
+}
+
+impl E4ArgumentsEditor {
+    /// Create the editor in the current group: put it in its place with [E4ArgumentsEditor::widget].
+    pub fn new(translations: Arc<Mutex<Translations>>) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut grid = Grid::default();
+        grid.show_grid(false);
+        grid.set_gap(5, 5);
+        grid.set_layout(5, 6);
+
+        let mut browser = HoldBrowser::default();
+        // The arguments are shown as they are, without the @ formatting of the browser
+        browser.set_format_char('\0');
+        grid.set_widget(&mut browser, 0..4, 0..5)?;
+
+        let mut buttons = vec![];
+        for (row, (label, key, default)) in [
+            ("+", "add-argument", "Add an argument"),
+            ("-", "remove-argument", "Remove the argument"),
+            ("@8>", "move-argument-up", "Move the argument up"),
+            ("@2>", "move-argument-down", "Move the argument down"),
+        ]
+        .into_iter()
+        .enumerate()
+        {
+            let mut button = Button::default().with_label(label);
+            button.set_tooltip(&tr!(translations, get_or_default, key, default));
+            grid.set_widget(&mut button, row, 5)?;
+            buttons.push(button);
+        }
+
+        let mut input = Input::default();
+        input.deactivate();
+        grid.set_widget(&mut input, 4, 0..6)?;
+        grid.end();
+
+        let editor = Self {
+            grid,
+            browser,
+            input,
+            arguments: Rc::new(RefCell::new(vec![])),
+        };
+        editor.set_callbacks(buttons);
+        Ok(editor)
+    }
+
+    /// Return the widget of the editor, to put it in a grid.
+    pub fn widget(&mut self) -> &mut Grid {
+        &mut self.grid
+    }
+
+    /// Show arguments, one per row.
+    pub fn set_arguments(&mut self, arguments: &[String]) {
+        *self.arguments.borrow_mut() = arguments.to_vec();
+        self.refresh(None);
+    }
+
+    /// Return the arguments, one per row.
+    pub fn arguments(&self) -> Vec<String> {
+        self.arguments.borrow().clone()
+    }
+
+    /// Show the arguments of a line of a button configuration file.
+    pub fn set_value(&mut self, line: &str) {
+        self.set_arguments(&split_arguments(line));
+    }
+
+    /// Return the arguments in a line for a button configuration file, quoted where needed.
+    pub fn value(&self) -> String {
+        join_arguments(&self.arguments.borrow())
+    }
+
+    /// Edit the selected row in the input and add, remove or move the rows with the buttons.
+    fn set_callbacks(&self, mut buttons: Vec<Button>) {
+        let mut editor = self.clone();
+        self.browser.clone().set_callback(move |_| {
+            let selected = editor.selected();
+            editor.refresh(selected);
+        });
+
+        // The row changes while it is typed
+        let mut editor = self.clone();
+        let mut input = self.input.clone();
+        input.set_trigger(CallbackTrigger::Changed);
+        input.set_callback(move |input| {
+            if let Some(index) = editor.selected() {
+                editor.arguments.borrow_mut()[index] = input.value();
+                editor.browser.set_text(index as i32 + 1, &input.value());
+            }
+        });
+
+        let mut editor = self.clone();
+        buttons[0].set_callback(move |_| {
+            let index = match editor.selected() {
+                Some(index) => index + 1,
+                None => editor.arguments.borrow().len(),
+            };
+            editor.arguments.borrow_mut().insert(index, String::new());
+            editor.refresh(Some(index));
+            let _ = editor.input.take_focus();
+        });
+
+        let mut editor = self.clone();
+        buttons[1].set_callback(move |_| {
+            if let Some(index) = editor.selected() {
+                editor.arguments.borrow_mut().remove(index);
+                let len = editor.arguments.borrow().len();
+                editor.refresh(if len > 0 {
+                    Some(index.min(len - 1))
+                } else {
+                    None
+                });
+            }
+        });
+
+        let mut editor = self.clone();
+        buttons[2].set_callback(move |_| {
+            if let Some(index) = editor.selected().filter(|index| *index > 0) {
+                editor.arguments.borrow_mut().swap(index, index - 1);
+                editor.refresh(Some(index - 1));
+            }
+        });
+
+        let mut editor = self.clone();
+        buttons[3].set_callback(move |_| {
+            let len = editor.arguments.borrow().len();
+            if let Some(index) = editor.selected().filter(|index| index + 1 < len) {
+                editor.arguments.borrow_mut().swap(index, index + 1);
+                editor.refresh(Some(index + 1));
+            }
+        });
+    }
+
+    /// Return the index of the selected row.
+    fn selected(&self) -> Option<usize> {
+        match self.browser.value() {
+            0 => None,
+            line => Some(line as usize - 1),
+        }
+    }
+
+    /// Show the arguments again, selecting the row at index and editing it in the input.
+    fn refresh(&mut self, index: Option<usize>) {
+        self.browser.clear();
+        for argument in self.arguments.borrow().iter() {
+            self.browser.add(argument);
+        }
+        match index {
+            Some(index) => {
+                self.browser.select(index as i32 + 1);
+                self.input.set_value(&self.arguments.borrow()[index]);
+                self.input.activate();
+            }
+            None => {
+                self.input.set_value("");
+                self.input.deactivate();
+            }
+        }
+        self.browser.redraw();
+    }
+}
@@ -0,0 +1,328 @@
+use crate::{
+    e4button::GENERIC,
+    e4config::{
+        button_file, config_file, E4Config, BUTTON_BUTTON_SECTION, E4DOCKER_DOCKER_SECTION,
+    },
+    e4events::{self, E4Event},
+    e4layout, tr,
+    translations::Translations,
+};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// The key of e4docker.conf looking for the unused icons when the dock starts.
+const CLEAN_ASSETS_ON_START: &str = "CLEAN_ASSETS_ON_START";
+
+/// The keys of the configuration file of a button naming an icon: the icon of the button,
+/// the one of a toggle when off and the one of the full trash.
+const ICON_KEYS: [&str; 3] = ["ICON", "ICON_OFF", "ICON_FULL"];
+
+/// The directory of the assets directory where the unused icons are archived.
+pub const ARCHIVE_DIR: &str = "archive";
+
+/// The names of the unused icons listed when asking what to do with them: the others are counted.
+const LISTED_ICONS: usize = 10;
+
+/// Return true if the unused icons are looked for when the dock of config starts.
+pub fn clean_on_start(config: &E4Config) -> bool {
+    config
+        .storage
+        .load_ini(&config_file(&config.config_dir))
+        .ok()
+        .and_then(|ini| {
+            ini.getbool(E4DOCKER_DOCKER_SECTION, CLEAN_ASSETS_ON_START)
+                .ok()
+                .flatten()
+        })
+        .unwrap_or(false)
+}
+
+/// Return the icons named by the buttons of config, shown or hidden, and by the generic one: the name of
+/// the button, the key naming the icon and its value.
+pub(crate) fn button_icons(config: &E4Config) -> Vec<(String, &'static str, String)> {
+    let names = config
+        .buttons
+        .iter()
+        .cloned()
+        .chain(e4layout::hidden_buttons(config))
+        .chain([GENERIC.to_string()]);
+    let mut icons = vec![];
+    for name in names {
+        let ini = match config
+            .storage
+            .load_ini(&button_file(&config.config_dir, &name))
+        {
+            Ok(ini) => ini,
+            Err(_) => continue,
+        };
+        for key in ICON_KEYS {
+            if let Some(icon) = ini.get(BUTTON_BUTTON_SECTION, key) {
+                let icon = icon.trim();
+                if !icon.is_empty() {
+                    icons.push((name.clone(), key, icon.to_string()));
+                }
+            }
+        }
+    }
+    icons
+}
+
+/// Return the icons named by the buttons of config, shown or hidden, and by the generic one, as paths in the
+/// assets directory when they are relative.
+pub fn referenced_assets(config: &E4Config) -> HashSet<PathBuf> {
+    button_icons(config)
+        .into_iter()
+        .map(|(_, _, icon)| config.assets_dir.join(icon))
+        .collect()
+}
+
+/// Return the files of the assets directory of config named by no button, sorted. The generic icon, the hidden
+/// files, the downloaded favicons and the archived icons, in their own directories, are kept.
+pub fn orphaned_assets(config: &E4Config) -> Vec<PathBuf> {
+    let referenced = referenced_assets(config);
+    let mut generic = config.assets_dir.join(GENERIC);
+    generic.set_extension("png");
+    let entries = match fs::read_dir(&config.assets_dir) {
+        Ok(entries) => entries,
+        Err(_) => return vec![],
+    };
+    let mut orphans: Vec<PathBuf> = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.is_file()
+                && *path != generic
+                && !referenced.contains(path)
+                && !path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        })
+        .collect();
+    orphans.sort();
+    orphans
+}
+
+/// Move files to the archive directory of assets_dir, numbering the ones whose name is taken.
+pub fn archive(assets_dir: &Path, files: &[PathBuf]) -> Result<(), Box<dyn std::error::Error>> {
+    let archive_dir = assets_dir.join(ARCHIVE_DIR);
+    fs::create_dir_all(&archive_dir)?;
+    for file in files {
+        let file_name = match file.file_name() {
+            Some(file_name) => file_name.to_string_lossy().to_string(),
+            None => continue,
+        };
+        let mut destination = archive_dir.join(&file_name);
+        let mut copy = 1;
+        while destination.exists() {
+            copy += 1;
+            destination = archive_dir.join(format!("{}-{}", copy, file_name));
+        }
+        fs::rename(file, &destination)?;
+    }
+    Ok(())
+}
+
+/// Look for the icons of config named by no button and offer to delete them or to archive them.
+/// If quiet, nothing is shown without unused icons, as when the dock starts.
+pub fn clean(config: &E4Config, quiet: bool, translations: Arc<Mutex<Translations>>) {
+    let orphans = orphaned_assets(config);
+    if orphans.is_empty() {
+        if !quiet {
+            fltk::dialog::message_default(&tr!(
+                translations,
+                get_or_default,
+                "no-unused-icons",
+                "No unused icons"
+            ));
+        }
+        return;
+    }
+    let mut names: Vec<String> = orphans
+        .iter()
+        .take(LISTED_ICONS)
+        .filter_map(|path| path.file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .collect();
+    if orphans.len() > LISTED_ICONS {
+        names.push(tr!(
+            translations,
+            format,
+            "and-others",
+            &[&(orphans.len() - LISTED_ICONS).to_string()]
+        ));
+    }
+    let message = tr!(
+        translations,
+        format,
+        "unused-icons-found",
+        &[&orphans.len().to_string(), &names.join("\n")]
+    );
+    let choice = fltk::dialog::choice2_default(
+        &message,
+        &tr!(translations, get_or_default, "cancel", "Cancel"),
+        &tr!(translations, get_or_default, "archive", "Archive"),
+        &tr!(translations, get_or_default, "delete", "Delete"),
+    );
+    let result = match choice {
+        Some(1) => archive(&config.assets_dir, &orphans),
+        Some(2) => orphans
+            .iter()
+            .try_for_each(fs::remove_file)
+            .map_err(|e| e.into()),
+        _ => return,
+    };
+    if let Err(e) = result {
+        fltk::dialog::alert_default(&tr!(
+            translations,
+            format,
+            "cannot-clean-the-icons",
+            &[&e.to_string()]
+        ));
+    }
+}
+
+/// An icon of a button named by its absolute path, outside the assets directory.
+#[derive(Clone, Debug, PartialEq)]
+pub struct E4ExternalIcon {
+    /// The name of the button.
+    pub button: String,
+    /// The key of the configuration file of the button naming the icon, like ICON.
+    pub key: String,
+    /// The path of the icon.
+    pub path: PathBuf,
+}
+
+/// Return the icons of the buttons of config named by their absolute paths outside the assets directory:
+/// the button breaks when they are moved.
+pub fn external_icons(config: &E4Config) -> Vec<E4ExternalIcon> {
+    button_icons(config)
+        .into_iter()
+        .map(|(button, key, icon)| E4ExternalIcon {
+            button,
+            key: key.to_string(),
+            path: PathBuf::from(icon),
+        })
+        .filter(|icon| icon.path.is_absolute() && !icon.path.starts_with(&config.assets_dir))
+        .collect()
+}
+
+/// Copy the icons, the existing ones, in the assets directory of config, numbering the ones whose name
+/// is taken, and name them by their file names in the configuration files of their buttons.
+/// Return the icons which cannot be found.
+pub fn migrate(
+    config: &E4Config,
+    icons: &[E4ExternalIcon],
+) -> Result<Vec<E4ExternalIcon>, Box<dyn std::error::Error>> {
+    let mut missing = vec![];
+    // The icons shared by the buttons are copied once
+    let mut copies: Vec<(PathBuf, String)> = vec![];
+    for icon in icons {
+        if !icon.path.is_file() {
+            missing.push(icon.clone());
+            continue;
+        }
+        let file_name = match copies.iter().find(|(path, _)| *path == icon.path) {
+            Some((_, file_name)) => file_name.clone(),
+            None => {
+                let file_name = unique_file_name(&config.assets_dir, &icon.path);
+                fs::copy(&icon.path, config.assets_dir.join(&file_name))?;
+                copies.push((icon.path.clone(), file_name.clone()));
+                file_name
+            }
+        };
+        let button_file = button_file(&config.config_dir, &icon.button);
+        let mut ini = config.storage.load_ini(&button_file)?;
+        ini.set(BUTTON_BUTTON_SECTION, &icon.key, Some(file_name));
+        config.storage.write_ini(&button_file, &ini)?;
+    }
+    Ok(missing)
+}
+
+/// Return the file name of path, numbered if it is taken in assets_dir.
+fn unique_file_name(assets_dir: &Path, path: &Path) -> String {
+    let stem = path
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path
+        .extension()
+        .map(|extension| format!(".{}", extension.to_string_lossy()))
+        .unwrap_or_default();
+    let mut file_name = format!("{}{}", stem, extension);
+    let mut copy = 1;
+    while assets_dir.join(&file_name).exists() {
+        copy += 1;
+        file_name = format!("{}-{}{}", stem, copy, extension);
+    }
+    file_name
+}
+
+/// Look for the icons of config outside the assets directory and offer to copy them in, telling the ones
+/// which cannot be found. The dock is drawn again once they are copied.
+pub fn migrate_icons(config: &E4Config, translations: Arc<Mutex<Translations>>) {
+    let icons = external_icons(config);
+    if icons.is_empty() {
+        fltk::dialog::message_default(&tr!(
+            translations,
+            get_or_default,
+            "no-external-icons",
+            "Every icon is in the assets directory"
+        ));
+        return;
+    }
+    let mut lines: Vec<String> = icons
+        .iter()
+        .take(LISTED_ICONS)
+        .map(|icon| format!("{}: {}", icon.button, icon.path.display()))
+        .collect();
+    if icons.len() > LISTED_ICONS {
+        lines.push(tr!(
+            translations,
+            format,
+            "and-others",
+            &[&(icons.len() - LISTED_ICONS).to_string()]
+        ));
+    }
+    let message = tr!(
+        translations,
+        format,
+        "external-icons-found",
+        &[&icons.len().to_string(), &lines.join("\n")]
+    );
+    let choice = fltk::dialog::choice2_default(
+        &message,
+        &tr!(translations, get_or_default, "cancel", "Cancel"),
+        &tr!(translations, get_or_default, "copy", "Copy"),
+        "",
+    );
+    if choice != Some(1) {
+        return;
+    }
+    match migrate(config, &icons) {
+        Ok(missing) => {
+            e4events::publish(E4Event::ConfigChanged);
+            if !missing.is_empty() {
+                let lines: Vec<String> = missing
+                    .iter()
+                    .map(|icon| format!("{}: {}", icon.button, icon.path.display()))
+                    .collect();
+                fltk::dialog::alert_default(&tr!(
+                    translations,
+                    format,
+                    "missing-icons",
+                    &[&lines.join("\n")]
+                ));
+            }
+        }
+        Err(e) => fltk::dialog::alert_default(&tr!(
+            translations,
+            format,
+            "cannot-copy-the-icons",
+            &[&e.to_string()]
+        )),
+    }
+}
@@ -0,0 +1,76 @@
+use crate::e4button::E4Button;
+use fltk::{app, enums::Color};
+use std::{
+    cell::RefCell,
+    sync::{Arc, Mutex},
+};
+
+/// The seconds between two changes of the blinking indicators.
+const BLINK_INTERVAL: f64 = 0.5;
+
+/// The color of the indicator of a button asking for attention, when lit: orange.
+const ATTENTION_COLOR: (u8, u8, u8) = (255, 140, 0);
+
+thread_local! {
+    /// The names of the buttons asking for attention, and the buttons of the dock while they blink.
+    static ATTENTION: RefCell<(Vec<String>, Option<Arc<Mutex<Vec<E4Button>>>>)> = const { RefCell::new((vec![], None)) };
+}
+
+/// Make the indicator of the button named name, among buttons, blink until it is clicked: see [end].
+/// It must be called in the main thread.
+pub fn request(buttons: &Arc<Mutex<Vec<E4Button>>>, name: &str) {
+    let is_blinking = ATTENTION.with(|attention| {
+        let mut attention = attention.borrow_mut();
+        if !attention.0.iter().any(|requested| requested == name) {
+            attention.0.push(name.to_string());
+        }
+        attention.1.replace(buttons.clone()).is_some()
+    });
+    if !is_blinking {
+        blink(true);
+    }
+}
+
+/// Light or dim the indicators of the buttons asking for attention, and blink again after a while.
+/// The blinking stops with the last of them.
+fn blink(lit: bool) {
+    let buttons = ATTENTION.with(|attention| attention.borrow().1.clone());
+    let buttons = match buttons {
+        Some(buttons) => buttons,
+        None => return,
+    };
+    let mut buttons = buttons.lock().unwrap();
+    let is_blinking = ATTENTION.with(|attention| {
+        let mut attention = attention.borrow_mut();
+        // A removed button asks for nothing
+        attention
+            .0
+            .retain(|name| buttons.iter().any(|button| button.name == *name));
+        let (r, g, b) = ATTENTION_COLOR;
+        for button in buttons.iter_mut() {
+            if attention.0.contains(&button.name) {
+                button.border.flash(lit.then(|| Color::from_rgb(r, g, b)));
+            }
+        }
+        if attention.0.is_empty() {
+            attention.1 = None;
+        }
+        attention.1.is_some()
+    });
+    if is_blinking {
+        app::add_timeout3(BLINK_INTERVAL, move |_| blink(!lit));
+    }
+}
+
+/// End the attention asked by button, if any, showing its indicator as usual.
+pub fn end(button: &mut E4Button) {
+    let was_requested = ATTENTION.with(|attention| {
+        let names = &mut attention.borrow_mut().0;
+        let count = names.len();
+        names.retain(|name| *name != button.name);
+        names.len() != count
+    });
+    if was_requested {
+        button.border.flash(None);
+    }
+}
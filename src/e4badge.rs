@@ -0,0 +1,139 @@
+use crate::{
+    e4command::shell_command,
+    e4config::{E4Config, BUTTON_BUTTON_SECTION},
+};
+use fltk::{
+    button::Button,
+    draw,
+    enums::{Align, Color, Font},
+    prelude::*,
+};
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// The seconds between two runs of the badge command, when not set.
+const DEFAULT_BADGE_INTERVAL: u64 = 60;
+
+/// The longest text of a badge: a longer one is cut, ending with +.
+const MAX_BADGE_CHARS: usize = 4;
+
+/// The mark starting the output of a badge command to ask for attention.
+const ATTENTION_MARK: char = '!';
+
+/// A badge drawn on the icon of a button, read from the configuration file of its button:
+///
+/// ```ini
+/// [button]
+/// command=/usr/bin/thunderbird
+/// badge_command=notmuch count tag:unread
+/// badge_interval=30
+/// ```
+///
+/// The badge shows the first line printed by badge_command, run by the shell every badge_interval seconds
+/// by the process checker. Nothing is shown when it prints nothing or 0.
+///
+/// A line starting with ! asks for attention, like `!3` for new mail: the indicator of the button blinks
+/// until it is clicked. It blinks again only after a line without the mark.
+#[derive(Clone, Debug)]
+pub struct E4Badge {
+    /// The command printing the text of the badge.
+    pub command: String,
+    /// The time between two runs of the command.
+    pub interval: Duration,
+    /// The text shown, shared with the drawing of the button.
+    text: Arc<Mutex<Option<String>>>,
+}
+
+/// Return the text of a badge from the output of its command: its first line, cut if too long.
+/// An empty output or 0 shows no badge.
+pub fn badge_text(output: &str) -> Option<String> {
+    let line = output.lines().next().unwrap_or("").trim();
+    let line = line.strip_prefix(ATTENTION_MARK).unwrap_or(line).trim();
+    if line.is_empty() || line == "0" {
+        return None;
+    }
+    if line.chars().count() <= MAX_BADGE_CHARS {
+        Some(line.to_string())
+    } else {
+        let cut: String = line.chars().take(MAX_BADGE_CHARS - 1).collect();
+        Some(format!("{}+", cut))
+    }
+}
+
+/// Return true if the output of a badge command asks for attention: its first line starts with !.
+pub fn badge_attention(output: &str) -> bool {
+    output
+        .lines()
+        .next()
+        .unwrap_or("")
+        .trim()
+        .starts_with(ATTENTION_MARK)
+}
+
+impl E4Badge {
+    /// Read the badge of the button button_name, if it has a badge command.
+    pub fn read(config: &E4Config, button_name: &str) -> Option<Self> {
+        let config_file = crate::e4config::button_file(&config.config_dir, button_name);
+        let ini = config.storage.load_ini(&config_file).ok()?;
+        let command = ini
+            .get(BUTTON_BUTTON_SECTION, "BADGE_COMMAND")
+            .map(|command| command.trim().to_string())
+            .filter(|command| !command.is_empty())?;
+        // At least a second, not to run the command all the time
+        let interval = ini
+            .get(BUTTON_BUTTON_SECTION, "BADGE_INTERVAL")
+            .and_then(|interval| interval.trim().parse::<u64>().ok())
+            .unwrap_or(DEFAULT_BADGE_INTERVAL)
+            .max(1);
+        Some(Self {
+            command,
+            interval: Duration::from_secs(interval),
+            text: Arc::new(Mutex::new(None)),
+        })
+    }
+
+    /// Run the command and return the text of the badge, and true if it asks for attention.
+    /// It can be slow: it is called by the process checker.
+    pub fn poll(&self) -> (Option<String>, bool) {
+        match shell_command(&self.command).output() {
+            Ok(output) => {
+                let output = String::from_utf8_lossy(&output.stdout);
+                (badge_text(&output), badge_attention(&output))
+            }
+            Err(_) => (None, false),
+        }
+    }
+
+    /// Return the text shown.
+    pub fn text(&self) -> Option<String> {
+        self.text.lock().unwrap().clone()
+    }
+
+    /// Show text on button, or no badge if None.
+    pub fn show(&self, button: &mut Button, text: Option<String>) {
+        *self.text.lock().unwrap() = text;
+        button.redraw();
+    }
+
+    /// Draw the badge in the top right corner of button, after its icon.
+    pub fn attach(&self, button: &mut Button) {
+        let text = self.text.clone();
+        button.draw(move |button| {
+            let text = match text.lock().unwrap().clone() {
+                Some(text) => text,
+                None => return,
+            };
+            draw::set_font(Font::HelveticaBold, (button.h() / 3).max(8));
+            let (text_width, text_height) = draw::measure(&text, false);
+            let h = text_height + 2;
+            let w = (text_width + 6).max(h);
+            let (x, y) = (button.x() + button.w() - w, button.y());
+            draw::set_draw_color(Color::Red);
+            draw::draw_rounded_rectf(x, y, w, h, h / 2);
+            draw::set_draw_color(Color::White);
+            draw::draw_text2(&text, x, y, w, h, Align::Center);
+        });
+    }
+}
@@ -0,0 +1,129 @@
+use regex::Regex;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The extension of a macOS application bundle.
+const APP_EXTENSION: &str = "app";
+
+/// The magic number at the beginning of an .icns file.
+const ICNS_MAGIC: &[u8; 4] = b"icns";
+
+/// The signature at the beginning of a PNG image.
+const PNG_SIGNATURE: &[u8; 8] = b"\x89PNG\r\n\x1a\n";
+
+/// Error raised when a bundle or an icns file cannot be read.
+#[derive(Debug)]
+struct BundleError {
+    details: String,
+}
+
+impl std::fmt::Display for BundleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for BundleError {}
+
+/// Return true if path is a macOS .app bundle.
+pub fn is_app_bundle(path: &Path) -> bool {
+    match path.extension().and_then(std::ffi::OsStr::to_str) {
+        Some(extension) => extension.eq_ignore_ascii_case(APP_EXTENSION),
+        None => false,
+    }
+}
+
+/// Read a string value from the Info.plist of a bundle.
+/// Only XML property lists are supported.
+fn info_plist_value(bundle: &Path, key: &str) -> Option<String> {
+    let info_plist = bundle.join("Contents").join("Info.plist");
+    let content = fs::read_to_string(info_plist).ok()?;
+    let pattern = format!(
+        r"<key>\s*{}\s*</key>\s*<string>([^<]*)</string>",
+        regex::escape(key)
+    );
+    let regex = Regex::new(&pattern).ok()?;
+    let captures = regex.captures(&content)?;
+    Some(captures.get(1)?.as_str().trim().to_string())
+}
+
+/// Return the name of the executable of a bundle, as shown in the process list.
+/// Fall back to the bundle name (Safari.app -> Safari) when Info.plist cannot be read.
+pub fn bundle_executable(bundle: &Path) -> Option<String> {
+    if !is_app_bundle(bundle) {
+        return None;
+    }
+    match info_plist_value(bundle, "CFBundleExecutable") {
+        Some(executable) if !executable.is_empty() => Some(executable),
+        _ => bundle
+            .file_stem()
+            .and_then(std::ffi::OsStr::to_str)
+            .map(|s| s.to_string()),
+    }
+}
+
+/// Return the path of the .icns icon of a bundle.
+pub fn bundle_icon(bundle: &Path) -> Option<PathBuf> {
+    let resources = bundle.join("Contents").join("Resources");
+    let mut icon = match info_plist_value(bundle, "CFBundleIconFile") {
+        Some(name) if !name.is_empty() => resources.join(name),
+        _ => resources.join("AppIcon"),
+    };
+    if icon.extension().is_none() {
+        icon.set_extension("icns");
+    }
+    if icon.exists() {
+        Some(icon)
+    } else {
+        None
+    }
+}
+
+/// Return the largest PNG encoded image contained in an .icns file.
+pub fn icns_to_png(path: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let data = fs::read(path)?;
+    if data.len() < 8 || &data[0..4] != ICNS_MAGIC {
+        return Err(Box::new(BundleError {
+            details: format!("{} is not an icns file", path.display()),
+        }));
+    }
+
+    // Each entry is made of a 4 bytes type, a 4 bytes big endian length and the data
+    let mut best: Option<&[u8]> = None;
+    let mut offset = 8;
+    while offset + 8 <= data.len() {
+        let length = u32::from_be_bytes([
+            data[offset + 4],
+            data[offset + 5],
+            data[offset + 6],
+            data[offset + 7],
+        ]) as usize;
+        if length < 8 || offset + length > data.len() {
+            break;
+        }
+        let entry = &data[offset + 8..offset + length];
+        if entry.starts_with(PNG_SIGNATURE) && best.is_none_or(|b| entry.len() > b.len()) {
+            best = Some(entry);
+        }
+        offset += length;
+    }
+
+    match best {
+        Some(png) => Ok(png.to_vec()),
+        None => Err(Box::new(BundleError {
+            details: format!("{} does not contain PNG images", path.display()),
+        })),
+    }
+}
+
+/// Return the PNG data of the icon of a bundle.
+pub fn bundle_icon_png(bundle: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    match bundle_icon(bundle) {
+        Some(icon) => icns_to_png(&icon),
+        None => Err(Box::new(BundleError {
+            details: format!("{} has no icon", bundle.display()),
+        })),
+    }
+}
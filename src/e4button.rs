@@ -1,29 +1,188 @@
 use crate::{
-    e4command::E4Command, e4config::E4Config, e4icon::E4Icon, tr, translations::Translations,
+    e4arguments::E4ArgumentsEditor,
+    e4badge::E4Badge,
+    e4command::{self, E4Command},
+    e4config::E4Config,
+    e4events::{self, E4Event},
+    e4icon::{self, E4Icon},
+    e4profile::{self, E4Phase},
+    e4storage::{E4Storage, FsStorage},
+    e4style::{E4Accent, E4ButtonColors, E4IconEffects, E4IconTint, E4Modifier},
+    e4toggle::E4Toggle,
+    e4widget::E4StateIcons,
+    tr,
+    translations::Translations,
 };
 use configparser::ini::Ini;
 use fltk::{
     app, button::Button, enums::Color, frame::Frame, input::Input, prelude::*, window::Window,
 };
-use image::ImageReader;
-use pelite::pe32::{Pe as Pe32, PeFile as PeFile32};
-use pelite::pe64::{Pe as Pe64, PeFile as PeFile64};
-use pelite::resources::Name;
-use pelite::FileMap;
+use lazy_static::lazy_static;
 use round::round;
 use std::{
     cell::RefCell,
-    io::Cursor,
     path::PathBuf,
     rc::Rc,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        mpsc, Arc, Mutex,
+    },
+    thread,
 };
 
-// The name of a generic E4Button: cannot be deleted
-const GENERIC: &str = "generic";
+/// The name of a generic E4Button: cannot be deleted
+pub const GENERIC: &str = "generic";
+
+/// The seconds between two checks of a launching button, shown inactive until its launch ends.
+const LAUNCHING_CHECK_INTERVAL: f64 = 0.1;
+
+/// The type of a [E4Button], read from the TYPE key of its configuration file.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum E4ButtonType {
+    /// Launch a command: the default.
+    Command,
+    /// Keep a history of the clipboard texts: see [crate::e4widget].
+    Clipboard,
+    /// Control the media player: see [crate::e4media].
+    Media,
+    /// Switch something on and off, like the wifi: see [crate::e4toggle].
+    Toggle,
+    /// Move the dropped files to the trash: see [crate::e4trash].
+    Trash,
+    /// List the files of a directory: see [crate::e4widget].
+    Folder,
+    /// A widget provided by a plugin: see [crate::e4plugin].
+    Plugin,
+    /// Run a rhai script: see [crate::e4script].
+    Script,
+    /// Show if the process of its command is running, like a daemon, without launching it.
+    Watch,
+}
+
+impl E4ButtonType {
+    /// Return the type of a button from its TYPE key. Missing or unknown types are commands.
+    pub fn from_name(name: &str) -> Self {
+        match name.trim().to_lowercase().as_str() {
+            "clipboard" => E4ButtonType::Clipboard,
+            "media" => E4ButtonType::Media,
+            "toggle" => E4ButtonType::Toggle,
+            "trash" => E4ButtonType::Trash,
+            "folder" => E4ButtonType::Folder,
+            "plugin" => E4ButtonType::Plugin,
+            "script" => E4ButtonType::Script,
+            "watch" => E4ButtonType::Watch,
+            _ => E4ButtonType::Command,
+        }
+    }
+
+    /// Return the value of the TYPE key for this type.
+    pub fn name(&self) -> &'static str {
+        match self {
+            E4ButtonType::Command => "command",
+            E4ButtonType::Clipboard => "clipboard",
+            E4ButtonType::Media => "media",
+            E4ButtonType::Toggle => "toggle",
+            E4ButtonType::Trash => "trash",
+            E4ButtonType::Folder => "folder",
+            E4ButtonType::Plugin => "plugin",
+            E4ButtonType::Script => "script",
+            E4ButtonType::Watch => "watch",
+        }
+    }
+}
+
+/// Error raised when the configuration of a button cannot be read.
+#[derive(Debug)]
+struct ButtonError {
+    details: String,
+}
+
+impl std::fmt::Display for ButtonError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for ButtonError {}
+
+/// If true the icons are decoded in background threads: see [set_lazy_icons].
+static LAZY_ICONS: AtomicBool = AtomicBool::new(false);
+/// How the icons are recolored: see [set_icon_tint].
+static ICON_TINT: Mutex<E4IconTint> = Mutex::new(E4IconTint::None);
+/// The rounded corners and the shadow of the icons: see [set_icon_effects].
+static ICON_EFFECTS: Mutex<E4IconEffects> = Mutex::new(E4IconEffects {
+    corner_radius: 0.0,
+    shadow: false,
+});
+/// The number of icons being decoded in background threads.
+static PENDING_ICONS: AtomicUsize = AtomicUsize::new(0);
+
+/// An icon to decode: the name of its button, its path and the translations of the errors.
+type IconJob = (String, PathBuf, Arc<Mutex<Translations>>);
+
+lazy_static! {
+    /// The pool of threads decoding the lazy icons in parallel, started by the first one.
+    static ref ICON_POOL: Mutex<mpsc::Sender<IconJob>> = Mutex::new(start_icon_pool());
+}
+
+/// Start a thread for each core, decoding the icons sent to the returned channel.
+fn start_icon_pool() -> mpsc::Sender<IconJob> {
+    let (sender, receiver) = mpsc::channel::<IconJob>();
+    let receiver = Arc::new(Mutex::new(receiver));
+    let workers = thread::available_parallelism().map_or(2, |n| n.get());
+    for _ in 0..workers {
+        let receiver = receiver.clone();
+        thread::spawn(move || loop {
+            // The lock is released before decoding, letting the other threads take the next icons
+            let job = receiver.lock().unwrap().recv();
+            match job {
+                Ok((name, image_path, translations)) => {
+                    E4Button::decode_icon(name, image_path, translations)
+                }
+                Err(_) => break,
+            }
+        });
+    }
+    sender
+}
+
+/// Count an icon as decoded, reporting the startup profile after the last one.
+fn icon_done() {
+    if PENDING_ICONS.fetch_sub(1, Ordering::SeqCst) == 1 {
+        e4profile::report("icons decoded");
+    }
+}
+
+/// Decode the icons of the buttons created from now on in background threads, to show the window sooner.
+/// The generic icon is shown until they are ready: they are published as [E4Event::IconLoaded] events,
+/// applied by [crate::e4dockview::DockView].
+pub fn set_lazy_icons(lazy: bool) {
+    LAZY_ICONS.store(lazy, Ordering::Relaxed);
+}
+
+/// Recolor the icons shown from now on by tint, the one of the style of the dock.
+pub fn set_icon_tint(tint: E4IconTint) {
+    *ICON_TINT.lock().unwrap() = tint;
+}
+
+/// Give the icons shown from now on effects, the ones of the style of the dock.
+pub fn set_icon_effects(effects: E4IconEffects) {
+    *ICON_EFFECTS.lock().unwrap() = effects;
+}
+
+/// Return png_data recolored by the tint of the icons and with their effects, or as it is if it cannot be decoded.
+fn styled(png_data: &[u8]) -> Vec<u8> {
+    e4icon::styled(
+        png_data,
+        *ICON_TINT.lock().unwrap(),
+        *ICON_EFFECTS.lock().unwrap(),
+    )
+}
 
 /// The configuration for a [E4Button].
 pub struct E4ButtonConfig {
+    /// The type of the [E4Button].
+    pub button_type: E4ButtonType,
     /// The [E4Command] containing the command and the args to exec.
     pub command: E4Command,
     /// The path of the [E4Icon] image for the [E4Button].
@@ -37,23 +196,32 @@ struct E4ButtonEditUI {
     button_icon: Button,
     command: Input,
     command_button: Button,
-    arguments: Input,
+    arguments: E4ArgumentsEditor,
+    /// The button to choose a store app, only on Windows
+    store_app: Option<Button>,
+    /// The button to choose an application of the .desktop entries, neither on Windows nor on macOS
+    desktop_app: Option<Button>,
     save: Button,
+    /// The values shown once the ui is populated, to ask whether to save them once changed.
+    initial_values: Rc<RefCell<Option<Vec<String>>>>,
 }
 
 impl E4ButtonEditUI {
     /// Create a ui and return the window, the inputs, the icon button and the save button
     fn new(translations: Arc<Mutex<Translations>>) -> Result<Self, Box<dyn std::error::Error>> {
-        let mut window = Window::default().with_size(700, 300);
+        let mut window = Window::default().with_size(700, 500);
         let mut grid = fltk_grid::Grid::default()
-            .with_size(650, 250)
+            .with_size(650, 450)
             .center_of(&window);
         grid.show_grid(false);
         grid.set_gap(10, 10);
         let grid_values = ["", "", "", ""];
         // ncells = 10: Label and text for each value + Browse button + Save button
+        // The arguments take three rows; one more row holds the Store app button on Windows,
+        // the Applications button elsewhere but on macOS
+        // The row above the Save button shows the result of the Test button
         let ncols = 3;
-        let nrows = 5;
+        let nrows = if cfg!(target_os = "macos") { 8 } else { 9 };
         grid.set_layout(nrows, ncols);
 
         let labels = [
@@ -85,11 +253,44 @@ impl E4ButtonEditUI {
         grid.set_widget(&mut command_button, 2, 2)?;
 
         let mut arguments_label = fltk::frame::Frame::default().with_label(labels[3]);
-        let mut arguments_input = Input::default();
+        let mut arguments_editor = E4ArgumentsEditor::new(translations.clone())?;
         grid.set_widget(&mut arguments_label, 3, 0)?;
-        grid.set_widget(&mut arguments_input, 3, 1..3)?;
+        grid.set_widget(arguments_editor.widget(), 3..6, 1..3)?;
 
-        // Add Save button at the bottom
+        let store_app = if cfg!(target_os = "windows") {
+            let mut store_app_button = Button::default().with_label(
+                tr!(translations, get_or_default, "store-app", "Store app...").as_str(),
+            );
+            grid.set_widget(&mut store_app_button, 6, 1..3)?;
+            Some(store_app_button)
+        } else {
+            None
+        };
+        let desktop_app = if cfg!(not(any(target_os = "windows", target_os = "macos"))) {
+            let mut desktop_app_button = Button::default().with_label(
+                tr!(
+                    translations,
+                    get_or_default,
+                    "browse-applications",
+                    "Browse applications..."
+                )
+                .as_str(),
+            );
+            grid.set_widget(&mut desktop_app_button, 6, 1..3)?;
+            Some(desktop_app_button)
+        } else {
+            None
+        };
+
+        // Show the result of the test
+        let mut test_result = Frame::default();
+        test_result.set_align(fltk::enums::Align::Inside | fltk::enums::Align::Wrap);
+        grid.set_widget(&mut test_result, nrows - 2, 0..3)?;
+
+        // Add Test and Save buttons at the bottom
+        let mut test_button = Button::default()
+            .with_label(tr!(translations, get_or_default, "test", "Test").as_str());
+        grid.set_widget(&mut test_button, nrows - 1, 0)?;
         let mut save_button = fltk::button::Button::new(
             200,
             250,
@@ -97,8 +298,69 @@ impl E4ButtonEditUI {
             30,
             tr!(translations, get_or_default, "save", "Save").as_str(),
         );
-        grid.set_widget(&mut save_button, 4, 0..3)?;
+        grid.set_widget(&mut save_button, nrows - 1, 1..3)?;
+
+        // Run the command as entered, without saving it
+        test_button.set_callback({
+            let command_input = command_input.clone();
+            let arguments_editor = arguments_editor.clone();
+            let translations = translations.clone();
+            move |_| {
+                let cmd = command_input.value();
+                let mut command = E4Command::new(cmd.clone(), arguments_editor.arguments());
+                let (message, color) = match command.exec() {
+                    Ok(_) => (
+                        tr!(
+                            translations,
+                            format,
+                            "the-command-has-been-started",
+                            &[&cmd]
+                        ),
+                        Color::DarkGreen,
+                    ),
+                    Err(e) => (
+                        tr!(
+                            translations,
+                            format,
+                            "failed-to-execute-command",
+                            &[&cmd, &e.to_string()]
+                        ),
+                        Color::Red,
+                    ),
+                };
+                // @ starts a symbol in the labels
+                test_result.set_label(&message.replace('@', "@@"));
+                test_result.set_label_color(color);
+                test_result.redraw();
+            }
+        });
 
+        let initial_values: Rc<RefCell<Option<Vec<String>>>> = Rc::new(RefCell::new(None));
+        let is_dirty = {
+            let initial_values = initial_values.clone();
+            let name_input = name_input.clone();
+            let command_input = command_input.clone();
+            let arguments_editor = arguments_editor.clone();
+            move || {
+                initial_values
+                    .borrow()
+                    .as_ref()
+                    .is_some_and(|initial_values| {
+                        *initial_values
+                            != Self::values(&name_input, &command_input, &arguments_editor)
+                    })
+            }
+        };
+        // Cancelled, the staged configuration of the button is discarded
+        crate::e4dialog::set_keys(
+            &mut window,
+            &mut save_button,
+            is_dirty,
+            || {
+                let _ = std::fs::remove_file(crate::e4config::get_tmp_file());
+            },
+            translations.clone(),
+        );
         window.make_modal(true);
         window.end();
 
@@ -108,16 +370,162 @@ impl E4ButtonEditUI {
             button_icon,
             command: command_input,
             command_button,
-            arguments: arguments_input,
+            arguments: arguments_editor,
+            store_app,
+            desktop_app,
             save: save_button,
+            initial_values,
         })
     }
+
+    /// Return the values of the fields and the staged configuration file, holding the chosen icon.
+    fn values(name: &Input, command: &Input, arguments: &E4ArgumentsEditor) -> Vec<String> {
+        vec![
+            name.value(),
+            command.value(),
+            arguments.value(),
+            std::fs::read_to_string(crate::e4config::get_tmp_file()).unwrap_or_default(),
+        ]
+    }
+
+    /// Remember the values of the populated ui: the user is asked whether to save them once changed.
+    fn set_clean(&self) {
+        *self.initial_values.borrow_mut() =
+            Some(Self::values(&self.name, &self.command, &self.arguments));
+    }
+
+    /// Let the user choose an installed store app, filling the command and the icon.
+    fn set_store_app_callback(
+        &mut self,
+        assets_dir: PathBuf,
+        (w, h): (i32, i32),
+        translations: Arc<Mutex<Translations>>,
+    ) {
+        let mut command = self.command.clone();
+        let mut button_icon = self.button_icon.clone();
+        if let Some(store_app) = self.store_app.as_mut() {
+            store_app.set_callback(move |_| {
+                let uwp_app = match crate::e4uwp::choose_app(translations.clone()) {
+                    Some(uwp_app) => uwp_app,
+                    None => return,
+                };
+                command.set_value(&crate::e4uwp::shell_uri(&uwp_app.aumid));
+
+                // Use the logo of the app as icon
+                let logo = match crate::e4uwp::fetch_app_logo(&uwp_app.aumid, &assets_dir) {
+                    Some(logo) => logo,
+                    None => return,
+                };
+                match E4Button::get_fltk_image(&logo, translations.clone()) {
+                    Ok(mut image) => {
+                        image.scale(w, h, true, true);
+                        button_icon.set_image(Some(image));
+                        button_icon.redraw();
+                    }
+                    Err(e) => {
+                        let message = tr!(
+                            translations,
+                            format,
+                            "cannot-load-the-image",
+                            &[&e.to_string()]
+                        );
+                        fltk::dialog::alert_default(&message);
+                        return;
+                    }
+                }
+                let mut config = Ini::new();
+                let tmp_file_path = crate::e4config::get_tmp_file();
+                let _ = config.load(&tmp_file_path);
+                config.set(
+                    crate::e4config::BUTTON_BUTTON_SECTION,
+                    "icon",
+                    Some(logo.display().to_string()),
+                );
+                FsStorage.write_ini(&tmp_file_path, &config).expect(&tr!(
+                    translations,
+                    get_or_default,
+                    "cannot-save-the-config-file",
+                    "Cannot save the config file"
+                ));
+            });
+        }
+    }
+
+    /// Let the user choose an installed application, filling the command, the arguments, the icon and,
+    /// if with_name, the name.
+    fn set_desktop_app_callback(
+        &mut self,
+        assets_dir: PathBuf,
+        (w, h): (i32, i32),
+        with_name: bool,
+        translations: Arc<Mutex<Translations>>,
+    ) {
+        let mut name = self.name.clone();
+        let mut command = self.command.clone();
+        let mut arguments = self.arguments.clone();
+        let mut button_icon = self.button_icon.clone();
+        if let Some(desktop_app) = self.desktop_app.as_mut() {
+            desktop_app.set_callback(move |_| {
+                let app = match crate::e4desktop::choose_app(translations.clone()) {
+                    Some(app) => app,
+                    None => return,
+                };
+                if with_name {
+                    name.set_value(&app.name);
+                }
+                command.set_value(&app.command);
+                arguments.set_value(&crate::e4command::join_arguments(&app.arguments));
+
+                // Without an icon the one of the command is found once saved
+                let icon = match crate::e4desktop::fetch_app_icon(&app, &assets_dir) {
+                    Some(icon) => icon,
+                    None => return,
+                };
+                match E4Button::get_fltk_image(&icon, translations.clone()) {
+                    Ok(mut image) => {
+                        image.scale(w, h, true, true);
+                        button_icon.set_image(Some(image));
+                        button_icon.redraw();
+                    }
+                    Err(e) => {
+                        let message = tr!(
+                            translations,
+                            format,
+                            "cannot-load-the-image",
+                            &[&e.to_string()]
+                        );
+                        fltk::dialog::alert_default(&message);
+                        return;
+                    }
+                }
+                let mut config = Ini::new();
+                let tmp_file_path = crate::e4config::get_tmp_file();
+                let _ = config.load(&tmp_file_path);
+                config.set(
+                    crate::e4config::BUTTON_BUTTON_SECTION,
+                    "icon",
+                    Some(icon.display().to_string()),
+                );
+                if let Err(e) = FsStorage.write_ini(&tmp_file_path, &config) {
+                    let message = tr!(
+                        translations,
+                        format,
+                        "cannot-save",
+                        &[&tmp_file_path.display().to_string(), &e.to_string()]
+                    );
+                    fltk::dialog::alert_default(&message);
+                }
+            });
+        }
+    }
 }
 
 /// A struct for the line below the [E4Button]
 pub struct BorderIndicator {
     frame: Frame,
     is_active: bool,
+    /// The color of the line while the program is running.
+    active_color: Color,
 }
 
 impl std::clone::Clone for BorderIndicator {
@@ -125,6 +533,7 @@ impl std::clone::Clone for BorderIndicator {
         Self {
             frame: self.frame.clone(),
             is_active: self.is_active,
+            active_color: self.active_color,
         }
     }
 }
@@ -144,6 +553,16 @@ impl BorderIndicator {
         Self {
             frame,
             is_active: false,
+            active_color: Color::Blue,
+        }
+    }
+
+    /// Show the running program with color instead of blue.
+    pub fn set_active_color(&mut self, color: Color) {
+        self.active_color = color;
+        if self.is_active {
+            self.frame.set_color(color);
+            self.frame.redraw();
         }
     }
 
@@ -151,7 +570,7 @@ impl BorderIndicator {
         if active != self.is_active {
             self.is_active = active;
             if active {
-                self.frame.set_color(Color::Blue);
+                self.frame.set_color(self.active_color);
             } else {
                 self.frame.set_color(Color::White);
             }
@@ -162,6 +581,17 @@ impl BorderIndicator {
     pub fn is_active(&self) -> bool {
         self.is_active
     }
+
+    /// Draw the indicator in color, or as its state tells if None: for the blinking of a button asking for attention.
+    pub fn flash(&mut self, color: Option<Color>) {
+        let color = match color {
+            Some(color) => color,
+            None if self.is_active => self.active_color,
+            None => Color::White,
+        };
+        self.frame.set_color(color);
+        self.frame.redraw();
+    }
 }
 
 /// A struct for the position of the button
@@ -222,6 +652,8 @@ impl std::clone::Clone for Size {
 pub struct E4Button {
     /// The name of the button, corresponding to the .conf file name
     pub name: String,
+    /// The type of the button
+    pub button_type: E4ButtonType,
     /// The position of the button
     pub position: Position,
     /// The size of the button
@@ -234,6 +666,16 @@ pub struct E4Button {
     pub command: Arc<Mutex<E4Command>>,
     /// The border of the [E4Button]
     pub border: BorderIndicator,
+    /// The commands of a toggle widget, polled by the process checker
+    pub toggle: Option<E4Toggle>,
+    /// The icons of a widget showing a state
+    pub state_icons: Option<E4StateIcons>,
+    /// The badge drawn on the icon, polled by the process checker
+    pub badge: Option<E4Badge>,
+    /// The accent color of the button, if any
+    pub accent: Option<E4Accent>,
+    /// The colors of its background: at rest, hovered and pressed
+    pub colors: E4ButtonColors,
 }
 
 /// Create the [E4Button]s.
@@ -243,52 +685,142 @@ pub fn create_buttons(
     frame: &Frame,
     translations: Arc<Mutex<Translations>>,
 ) -> Result<Vec<E4Button>, Box<dyn std::error::Error>> {
+    let mut button_configs = vec![];
+    for button_name in config.button_names() {
+        // Read the button config: a broken one is reported and shown as a generic button
+        let button_config: E4ButtonConfig = match e4profile::time(E4Phase::ConfigParsing, || {
+            E4Button::read_config(config, button_name, translations.clone())
+        }) {
+            Ok(button_config) => button_config,
+            Err(e) => {
+                e4events::report_error(&e.to_string());
+                E4ButtonConfig {
+                    button_type: E4ButtonType::Command,
+                    command: E4Command::new(String::new(), vec![]),
+                    icon_path: crate::e4initialize::get_generic_icon(translations.clone())
+                        .display()
+                        .to_string(),
+                }
+            }
+        };
+        button_configs.push((button_name.clone(), button_config));
+    }
+    create_buttons_from(config, button_configs, wind, frame, translations)
+}
+
+/// Create the [E4Button]s from their names and configurations, without reading their .conf files.
+pub fn create_buttons_from(
+    config: &E4Config,
+    button_configs: Vec<(String, E4ButtonConfig)>,
+    wind: &mut Window,
+    frame: &Frame,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<Vec<E4Button>, Box<dyn std::error::Error>> {
+    set_icon_tint(config.style.icon_tint);
+    set_icon_effects(config.style.icon_effects);
     let mut buttons = vec![];
-    let mut current_e4button;
-    // Put the buttons in the window
-    let mut x = config.margin_between_buttons;
+    // The icons decoded while the buttons are created are not the last ones
+    PENDING_ICONS.fetch_add(1, Ordering::SeqCst);
+    let result = button_configs.into_iter().enumerate().try_for_each(
+        |(index, (button_name, button_config))| {
+            let current_e4button = e4profile::time(E4Phase::WidgetCreation, || {
+                create_button(
+                    config,
+                    &button_name,
+                    button_config,
+                    index,
+                    frame,
+                    translations.clone(),
+                )
+            })?;
+            // Add the button to the window
+            wind.add(&current_e4button.button);
+            buttons.push(current_e4button);
+            Ok(())
+        },
+    );
+    icon_done();
+    result.map(|_| buttons)
+}
+
+/// Create the [E4Button] button_name at index in the dock, not counting the spacers.
+pub fn create_button(
+    config: &E4Config,
+    button_name: &String,
+    button_config: E4ButtonConfig,
+    index: usize,
+    frame: &Frame,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<E4Button, Box<dyn std::error::Error>> {
+    // Put the button in the window
+    let x = config.button_x(index);
     let y: i32 = round(
         (config.window_height as f64 - config.icon_height as f64) / 2.0,
         0,
     ) as i32;
 
-    for button_name in &config.buttons {
-        // Read the button config
-        let button_config: E4ButtonConfig =
-            E4Button::read_config(config, button_name, translations.clone())?;
-        // Create the icon
-        let icon = E4Icon::new(
-            PathBuf::from(button_config.icon_path),
-            config.icon_width,
-            config.icon_height,
-        );
-        // Create the command
-        let command = Arc::new(Mutex::new(button_config.command));
-        // Create the button
-        current_e4button = E4Button::new(
-            button_name,
-            Position { x, y },
-            frame,
-            Arc::clone(&command),
-            config,
-            icon,
-            translations.clone(),
-        )?;
-        current_e4button.button.set_tooltip(
-            tr!(
-                translations,
-                format_display,
-                "right-click-to-edit-delete-or-to-create-a-new-button-after",
-                &[&button_name]
-            )
-            .as_str(),
-        );
-        // Add the button to the window
-        wind.add(&current_e4button.button);
-        buttons.push(current_e4button);
-        x += config.icon_width + config.margin_between_buttons;
+    // Create the icon
+    let icon = E4Icon::new(
+        PathBuf::from(button_config.icon_path),
+        config.icon_width,
+        config.icon_height,
+    );
+    // Create the command
+    let command = Arc::new(Mutex::new(button_config.command));
+    // Create the button
+    let mut e4button = E4Button::new(
+        button_name,
+        button_config.button_type,
+        Position { x, y },
+        frame,
+        Arc::clone(&command),
+        config,
+        icon,
+        translations.clone(),
+    )?;
+    e4button.button.set_tooltip(
+        tr!(
+            translations,
+            format_display,
+            "right-click-to-edit-delete-or-to-create-a-new-button-after",
+            &[&button_name]
+        )
+        .as_str(),
+    );
+    Ok(e4button)
+}
+
+/// Return the files dropped on a button.
+/// The dropped text holds one file for each line, as a path or as a file:// URI.
+pub(crate) fn dropped_files(text: &str) -> Vec<String> {
+    text.lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .map(|line| match line.strip_prefix("file://") {
+            Some(uri) => percent_decode(uri),
+            None => line.to_string(),
+        })
+        .collect()
+}
+
+/// Decode the %XX sequences of an URI.
+fn percent_decode(uri: &str) -> String {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' && i + 2 < bytes.len() {
+            let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).unwrap_or("");
+            if let Ok(byte) = u8::from_str_radix(hex, 16) {
+                decoded.push(byte);
+                i += 3;
+                continue;
+            }
+        }
+        decoded.push(bytes[i]);
+        i += 1;
     }
-    Ok(buttons)
+    String::from_utf8_lossy(&decoded).to_string()
 }
 
 /// Clone trait for [E4Button].
@@ -296,153 +828,135 @@ impl std::clone::Clone for E4Button {
     fn clone(&self) -> Self {
         Self {
             name: self.name.clone(),
+            button_type: self.button_type,
             position: self.position.clone(),
             size: self.size.clone(),
             button: self.button.clone(),
             icon: self.icon.clone(),
             command: self.command.clone(),
             border: self.border.clone(),
+            toggle: self.toggle.clone(),
+            state_icons: self.state_icons.clone(),
+            badge: self.badge.clone(),
+            accent: self.accent,
+            colors: self.colors,
         }
     }
 }
 
 impl E4Button {
-    /// Transform the image to a fltk PngImage
-    fn get_fltk_image(
+    /// Transform the image to a fltk PngImage.
+    /// If the image cannot be opened the error is shown and the generic icon is returned.
+    pub(crate) fn get_fltk_image(
         image_path: &PathBuf,
         translations: Arc<Mutex<Translations>>,
     ) -> Result<fltk::image::PngImage, Box<dyn std::error::Error>> {
-        match &image_path.extension().and_then(std::ffi::OsStr::to_str) {
-            Some(extension) => {
-                let image_extension = extension.to_lowercase();
-                let png_data = if image_extension != "exe" {
-                    let new_image = ImageReader::open(image_path)?.decode()?;
-                    let png_bytes: Vec<u8> = vec![];
-                    let mut cursor = Cursor::new(png_bytes);
-                    new_image.write_to(&mut cursor, image::ImageFormat::Png)?;
-                    cursor.into_inner()
-                } else {
-                    // Open and map the exe file
-                    match FileMap::open(image_path) {
-                        Ok(file_map) => {
-                            // Try as PE32
-                            match PeFile32::from_bytes(&file_map) {
-                                Ok(pe32) => {
-                                    let resources = pe32.resources()?;
-                                    // RT_ICON as Name::Id
-                                    let icon = Name::Id(3); // RT_ICON
-
-                                    // Get the first icon
-                                    let icon_data =
-                                        resources.find_resource(&[icon, Name::Id(1)])?;
-
-                                    // Convert icon raw data to an image
-                                    let img = image::load_from_memory(icon_data)?;
-
-                                    // Prepare the buffer for the PNG
-                                    let png_bytes: Vec<u8> = vec![];
-                                    let mut cursor = Cursor::new(png_bytes);
-
-                                    // Write the image as PNG
-                                    img.write_to(&mut cursor, image::ImageFormat::Png)?;
-                                    cursor.into_inner()
-                                }
-                                Err(_) => {
-                                    // If PE32 fails, try as PE64
-                                    match PeFile64::from_bytes(&file_map) {
-                                        Ok(pe64) => {
-                                            let resources = pe64.resources()?;
-                                            // RT_ICON as Name::Id
-                                            let icon = Name::Id(3); // RT_ICON
-
-                                            // Get the first icon
-                                            let icon_data =
-                                                resources.find_resource(&[icon, Name::Id(1)])?;
-
-                                            // Convert icon raw data to an image
-                                            let img = image::load_from_memory(icon_data)?;
-
-                                            // Prepare the buffer for the PNG
-                                            let png_bytes: Vec<u8> = vec![];
-                                            let mut cursor = Cursor::new(png_bytes);
-
-                                            // Write the image as PNG
-                                            img.write_to(&mut cursor, image::ImageFormat::Png)?;
-                                            cursor.into_inner()
-                                        }
-                                        Err(e) => {
-                                            // Cannot open the exe file. Return the generic icon
-                                            let message = tr!(
-                                                translations,
-                                                format,
-                                                "error-in-opening",
-                                                &[
-                                                    &image_path.display().to_string(),
-                                                    &e.to_string()
-                                                ]
-                                            );
-                                            fltk::dialog::alert_default(&message);
-                                            let new_image = ImageReader::open(
-                                                crate::e4initialize::get_generic_icon(Arc::clone(
-                                                    &translations,
-                                                )),
-                                            )?
-                                            .decode()?;
-                                            let png_bytes: Vec<u8> = vec![];
-                                            let mut cursor = Cursor::new(png_bytes);
-                                            new_image
-                                                .write_to(&mut cursor, image::ImageFormat::Png)?;
-                                            cursor.into_inner()
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                        Err(e) => {
-                            let message = tr!(
-                                translations,
-                                format,
-                                "error-in-opening",
-                                &[&image_path.display().to_string(), &e.to_string()]
-                            );
-                            fltk::dialog::alert_default(&message);
-                            vec![]
-                        }
-                    }
-                };
-                let fltk_image = if !png_data.is_empty() {
-                    fltk::image::PngImage::from_data(&png_data)?
-                } else {
-                    let new_image = ImageReader::open(crate::e4initialize::get_generic_icon(
-                        translations.clone(),
-                    ))?
-                    .decode()?;
-                    let png_bytes: Vec<u8> = vec![];
-                    let mut cursor = Cursor::new(png_bytes);
-                    new_image.write_to(&mut cursor, image::ImageFormat::Png)?;
-                    let png_data = cursor.into_inner();
-                    fltk::image::PngImage::from_data(&png_data)?
-                };
-                Ok(fltk_image)
+        match e4icon::icon_png_data(image_path, translations.clone()) {
+            Ok(png_data) if !png_data.is_empty() => {
+                Ok(fltk::image::PngImage::from_data(&styled(&png_data))?)
             }
-            None => {
-                let message = tr!(
-                    translations,
-                    format_display,
-                    "error-in-getting-the-icon-extension",
-                    &[&image_path.display()]
-                );
-                fltk::dialog::alert_default(&message);
-                let new_image =
-                    ImageReader::open(crate::e4initialize::get_generic_icon(translations.clone()))?
-                        .decode()?;
-                let png_bytes: Vec<u8> = vec![];
-                let mut cursor = Cursor::new(png_bytes);
-                new_image.write_to(&mut cursor, image::ImageFormat::Png)?;
-                let png_data = cursor.into_inner();
-                Ok(fltk::image::PngImage::from_data(&png_data)?)
+            Ok(_) => Self::generic_image(),
+            Err(e) => {
+                fltk::dialog::alert_default(&e.to_string());
+                Self::generic_image()
+            }
+        }
+    }
+
+    /// Let the user choose an icon in assets_dir and return its path. The chosen file is previewed at
+    /// the size of the buttons, (w, h), with its own dimensions and its file size.
+    fn choose_icon(
+        assets_dir: &std::path::Path,
+        (w, h): (i32, i32),
+        translations: Arc<Mutex<Translations>>,
+    ) -> Option<String> {
+        let mut chooser = fltk::dialog::FileChooser::new(
+            assets_dir,                                                       // directory
+            crate::e4icon::ICON_PATTERN,                                      // filter or pattern
+            fltk::dialog::FileChooserType::Single,                            // chooser type
+            &tr!(translations, get_or_default, "choose-icon", "Choose icon"), // title
+        );
+        // The preview of fltk does not show the icon at the size of the buttons
+        chooser.set_preview(false);
+        let mut preview =
+            fltk::group::Flex::new(0, 0, 400, h.max(app::font_size() * 2), None).row();
+        let mut image_frame = Frame::default();
+        preview.fixed(&image_frame, w);
+        let mut details = Frame::default();
+        details.set_align(fltk::enums::Align::Inside | fltk::enums::Align::Left);
+        preview.end();
+        chooser.add_extra(&preview);
+        chooser.set_callback(move |chooser| {
+            // Called when a file is selected
+            let image = chooser
+                .value(1)
+                .map(PathBuf::from)
+                .filter(|path| path.is_file())
+                .and_then(|path| {
+                    let bytes = std::fs::metadata(&path).ok()?.len();
+                    let png_data = e4icon::icon_png_data(&path, translations.clone()).ok()?;
+                    let image = fltk::image::PngImage::from_data(&styled(&png_data)).ok()?;
+                    Some((image, bytes))
+                });
+            match image {
+                Some((mut image, bytes)) => {
+                    let size = tr!(translations, format_size, bytes);
+                    details.set_label(&tr!(
+                        translations,
+                        format,
+                        "image-details",
+                        &[&image.w().to_string(), &image.h().to_string(), &size]
+                    ));
+                    image.scale(w, h, true, true);
+                    image_frame.set_image(Some(image));
+                }
+                None => {
+                    details.set_label("");
+                    image_frame.set_image(None::<fltk::image::PngImage>);
+                }
+            }
+            preview.redraw();
+        });
+        chooser.show();
+        while chooser.shown() {
+            app::wait();
+        }
+        chooser.value(1)
+    }
+
+    /// Queue the icon in image_path to the decoding pool, which publishes it for the button named name.
+    fn decode_later(name: &str, image_path: PathBuf, translations: Arc<Mutex<Translations>>) {
+        PENDING_ICONS.fetch_add(1, Ordering::SeqCst);
+        let job = (name.to_string(), image_path, translations);
+        if let Err(mpsc::SendError((name, image_path, translations))) =
+            ICON_POOL.lock().unwrap().send(job)
+        {
+            // The pool is gone: decode the icon here
+            Self::decode_icon(name, image_path, translations);
+        }
+    }
+
+    /// Decode the icon in image_path and publish it for the button named name.
+    fn decode_icon(name: String, image_path: PathBuf, translations: Arc<Mutex<Translations>>) {
+        let result = e4profile::time(E4Phase::IconDecoding, || {
+            e4icon::icon_png_data(&image_path, translations).map_err(|e| e.to_string())
+        });
+        match result {
+            Ok(png_data) if !png_data.is_empty() => {
+                e4events::publish(E4Event::IconLoaded(name, png_data))
             }
+            // The generic icon is already shown
+            Ok(_) => {}
+            Err(message) => e4events::report_error(&message),
         }
+        icon_done();
+    }
+
+    /// Return the generic icon as a fltk PngImage, from the data embedded in the program.
+    pub(crate) fn generic_image() -> Result<fltk::image::PngImage, Box<dyn std::error::Error>> {
+        Ok(fltk::image::PngImage::from_data(
+            crate::e4initialize::generic_png_data(),
+        )?)
     }
 
     /// Create a new [E4Button].
@@ -452,7 +966,7 @@ impl E4Button {
     /// Create a [E4Button] of size 64x64 at position 0, 0.
     ///
     /// ```rust
-    /// use e4docker::{e4button::E4Button, e4command::E4Command};
+    /// use e4docker::{e4button::{E4Button, E4ButtonType}, e4command::E4Command};
     /// use e4docker::{e4config::E4Config, e4icon::E4Icon};
     /// use fltk::{frame::Frame, prelude::*};
     /// use std::{sync::Arc, sync::Mutex, path::PathBuf};
@@ -470,6 +984,7 @@ impl E4Button {
     ///
     /// let my_button = E4Button::new(
     ///     name: "nano".to_string(),
+    ///     button_type: E4ButtonType::Command,
     ///     position: Position { x: 0,
     ///     y: 0},
     ///     parent: &frame,
@@ -480,6 +995,7 @@ impl E4Button {
     /// ```
     pub fn new(
         name: &String,
+        button_type: E4ButtonType,
         position: Position,
         parent: &Frame,
         command: Arc<Mutex<E4Command>>,
@@ -492,26 +1008,47 @@ impl E4Button {
             .with_size(icon.width(), icon.height())
             .center_y(parent);
         let (x, y) = (button.x(), button.y());
-        let mut frame_border = Frame::new(
-            button.x(),
-            button.y() + button.height() - 2, // 2 pixel dal fondo
-            button.width(),
-            2, // altezza della linea
-            None,
-        );
-        frame_border.set_color(Color::from_u32(0)); // Inizialmente trasparente
-        frame_border.set_frame(fltk::enums::FrameType::FlatBox);
 
         let command_clone = Arc::clone(&command);
         let translations_second_clone = translations.clone();
         let translations_third_clone = translations.clone();
-        button.set_callback(move |_| {
-            let translations_clone = Translations::get_instance();
+        let button_name = name.to_string();
+        let config_dir = config.config_dir.clone();
+        let new_instance_modifier = config.style.new_instance_modifier;
+        button.set_callback(move |button| {
+            // Ctrl+click selects the button, a slow double click renames it: see [crate::e4rename]
+            if crate::e4selection::is_select_click() || crate::e4rename::is_renaming() {
+                return;
+            }
             let mut guard = command_clone.lock().unwrap();
-            let result = guard.exec(translations_clone);
+            // A double click launches once
+            if guard.is_launching() {
+                return;
+            }
+            // The running program is shown, unless a new instance is asked with the modifier
+            let new_instance =
+                new_instance_modifier != E4Modifier::None && new_instance_modifier.is_held();
+            if guard.is_single_instance()
+                && !new_instance
+                && crate::e4window::activate(guard.get_cmd())
+            {
+                return;
+            }
+            let result = guard.exec();
+            let arguments = guard.get_arguments().to_vec();
+            let cmd = guard.get_cmd().clone();
             drop(guard);
             match result {
-                Ok(_) => (),
+                Ok(_) => {
+                    Self::show_launching(button, &command_clone);
+                    // Launched with file arguments: remember them in the recent files
+                    let files: Vec<String> = arguments
+                        .into_iter()
+                        .filter(|argument| std::path::Path::new(argument).is_file())
+                        .collect();
+                    let _ = crate::e4state::add_launch(&config_dir, &button_name, &files);
+                    Self::run_launch_hooks(&button_name, &cmd, translations_third_clone.clone());
+                }
                 Err(e) => {
                     let guard = command_clone.lock().unwrap();
                     let message = tr!(
@@ -526,78 +1063,254 @@ impl E4Button {
             };
         });
 
-        // If the icon path does not exist, search for the icon in the assets directory
-        let mut button_icon = if !icon.path().exists() {
-            match Self::get_fltk_image(
-                &config.assets_dir.join(icon.path()),
-                translations_second_clone,
-            ) {
-                Ok(image) => image,
-                Err(e) => {
-                    let message = tr!(
-                        translations,
-                        format,
-                        "cannot-find",
-                        &[
-                            &config.assets_dir.join(icon.path()).display().to_string(),
-                            &e.to_string()
-                        ]
+        // Open the files dropped on the button
+        let command_clone = Arc::clone(&command);
+        let button_name = name.to_string();
+        let config_dir = config.config_dir.clone();
+        let translations_fourth_clone = translations.clone();
+        button.handle(move |_, ev| match ev {
+            fltk::enums::Event::DndEnter
+            | fltk::enums::Event::DndDrag
+            | fltk::enums::Event::DndRelease => true,
+            fltk::enums::Event::Paste => {
+                let files = dropped_files(&app::event_text());
+                if !files.is_empty() {
+                    Self::open_files(
+                        &button_name,
+                        &command_clone,
+                        &config_dir,
+                        &files,
+                        translations_fourth_clone.clone(),
                     );
-                    fltk::dialog::alert_default(&message);
-                    let new_image = ImageReader::open(crate::e4initialize::get_generic_icon(
-                        translations.clone(),
-                    ))?
-                    .decode()?;
-                    let png_bytes: Vec<u8> = vec![];
-                    let mut cursor = Cursor::new(png_bytes);
-                    new_image.write_to(&mut cursor, image::ImageFormat::Png)?;
-                    fltk::image::PngImage::from_data(&cursor.into_inner())?
                 }
+                true
             }
-        } else {
-            match Self::get_fltk_image(icon.path(), translations.clone()) {
-                Ok(image) => image,
-                Err(e) => {
-                    let message = tr!(
-                        translations,
-                        format,
-                        "cannot-find",
-                        &[
-                            &config.assets_dir.join(icon.path()).display().to_string(),
-                            &e.to_string()
-                        ]
-                    );
-                    fltk::dialog::alert_default(&message);
+            _ => false,
+        });
 
-                    let new_image = ImageReader::open(crate::e4initialize::get_generic_icon(
-                        translations.clone(),
-                    ))?
-                    .decode()?;
-                    let png_bytes: Vec<u8> = vec![];
-                    let mut cursor = Cursor::new(png_bytes);
-                    new_image.write_to(&mut cursor, image::ImageFormat::Png)?;
-                    fltk::image::PngImage::from_data(&cursor.into_inner())?
-                }
-            }
+        // Widgets replace the callback and the event handler of the commands
+        crate::e4widget::setup(&mut button, button_type, name, config, translations.clone());
+        let toggle = match button_type {
+            E4ButtonType::Toggle => Some(E4Toggle::read(config, name)),
+            _ => None,
+        };
+        let state_icons = E4StateIcons::read(config, name, button_type);
+        let badge = E4Badge::read(config, name);
+        if let Some(badge) = &badge {
+            badge.attach(&mut button);
+        }
+
+        // If the icon path does not exist, search for the icon in the assets directory
+        let image_path = if !icon.path().exists() {
+            config.assets_dir.join(icon.path())
+        } else {
+            icon.path().clone()
+        };
+        let cmd = command.lock().unwrap().get_cmd().clone();
+        // A lazy icon shows the generic one until it is decoded.
+        // The widgets showing a state set their icons by themselves.
+        let mut button_icon = if !image_path.exists()
+            && crate::e4favicon::is_favicon(&image_path, &config.assets_dir, &cmd)
+        {
+            // The favicon is shown once downloaded
+            crate::e4favicon::fetch_later(name, &cmd, image_path.clone());
+            Self::generic_image()?
+        } else if LAZY_ICONS.load(Ordering::Relaxed) && state_icons.is_none() {
+            Self::decode_later(name, image_path.clone(), translations_second_clone);
+            Self::generic_image()?
+        } else {
+            e4profile::time(E4Phase::IconDecoding, || {
+                Self::get_fltk_image(&image_path, translations_second_clone)
+            })?
         };
         let (w, h) = (icon.width(), icon.height());
 
         button_icon.scale(w, h, true, true);
         button.set_image(Some(button_icon));
-        let border = BorderIndicator::new(x, y, w, h);
+        // The icons showing a state are not animated, nor the favicons still to download
+        if state_icons.is_none()
+            && image_path.exists()
+            && crate::e4animation::is_animated(config, name)
+        {
+            match crate::e4animation::frames(&image_path) {
+                Ok(frames) => {
+                    let frames: Vec<_> = frames
+                        .iter()
+                        .filter_map(|(png_data, delay)| {
+                            let mut frame =
+                                fltk::image::PngImage::from_data(&styled(png_data)).ok()?;
+                            frame.scale(w, h, true, true);
+                            Some((frame, *delay))
+                        })
+                        .collect();
+                    crate::e4animation::animate(name, &button, frames);
+                }
+                Err(e) => log::warn!("{}: {}", image_path.display(), e),
+            }
+        }
+        let mut border = BorderIndicator::new(x, y, w, h);
+        let accent = E4Accent::read(config, name);
+        let frame_type = match &accent {
+            Some(accent) if accent.plate => fltk::enums::FrameType::RFlatBox,
+            _ => fltk::enums::FrameType::FlatBox,
+        };
+        if let Some(accent) = &accent {
+            border.set_active_color(accent.color());
+        }
+        // The pressed button is drawn with its selection color
+        let colors = config.style.button_colors(accent.as_ref());
+        button.set_frame(frame_type);
+        button.set_down_frame(frame_type);
+        button.set_color(colors.normal);
+        button.set_selection_color(colors.pressed);
         Ok(E4Button {
             name: name.to_string(),
+            button_type,
             position: Position { x, y },
             size: Size::new(w, y),
             button,
             icon,
             command,
             border,
+            toggle,
+            state_icons,
+            badge,
+            accent,
+            colors,
         })
     }
 
+    /// Launch the command of a [E4Button] opening files, and remember them in its recent files.
+    pub fn open_files(
+        name: &str,
+        command: &Arc<Mutex<E4Command>>,
+        config_dir: &std::path::Path,
+        files: &[String],
+        translations: Arc<Mutex<Translations>>,
+    ) {
+        let mut guard = command.lock().unwrap();
+        if guard.is_launching() {
+            return;
+        }
+        let result = guard.exec_with_files(files);
+        let cmd = guard.get_cmd().clone();
+        drop(guard);
+        match result {
+            Ok(_) => {
+                let _ = crate::e4state::add_launch(config_dir, name, files);
+                Self::run_launch_hooks(name, &cmd, translations);
+            }
+            Err(e) => {
+                let message = tr!(
+                    translations,
+                    format,
+                    "failed-to-execute-command",
+                    &[&cmd, &e.to_string()]
+                );
+                fltk::dialog::alert_default(&message);
+            }
+        }
+    }
+
+    /// Show button as inactive while its command is launching: for the cooldown, or until the window
+    /// of its program appears. See [E4Command::is_launching].
+    fn show_launching(button: &mut Button, command: &Arc<Mutex<E4Command>>) {
+        if !command.lock().unwrap().is_launching() {
+            return;
+        }
+        button.deactivate();
+        let mut button = button.clone();
+        let command = command.clone();
+        app::add_timeout3(LAUNCHING_CHECK_INTERVAL, move |handle| {
+            if command.lock().unwrap().is_launching() {
+                app::repeat_timeout3(LAUNCHING_CHECK_INTERVAL, handle);
+            } else {
+                button.activate();
+            }
+        });
+    }
+
+    /// Run the launch hook of e4docker.conf and call the launch hooks of the plugins, showing their errors.
+    #[cfg_attr(not(feature = "script-widgets"), allow(unused_variables))]
+    fn run_launch_hooks(name: &str, cmd: &str, translations: Arc<Mutex<Translations>>) {
+        crate::e4hooks::run(crate::e4hooks::E4Hook::ButtonLaunched, Some((name, cmd)));
+        #[cfg(feature = "script-widgets")]
+        for error in crate::e4plugin::launched(name, cmd) {
+            let message = tr!(translations, format, "plugin-error", &[&error]);
+            fltk::dialog::alert_default(&message);
+        }
+    }
+
+    /// Move the [E4Button] and its border horizontally to x.
+    pub fn move_to(&mut self, x: i32) {
+        let dx = x - self.position.x;
+        self.position.x = x;
+        self.button.set_pos(self.button.x() + dx, self.button.y());
+        let frame = &mut self.border.frame;
+        frame.set_pos(frame.x() + dx, frame.y());
+    }
+
+    /// Show the icon decoded in png_data, scaled to the size of the [E4Button].
+    pub fn set_icon_data(&mut self, png_data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut image = fltk::image::PngImage::from_data(&styled(png_data))?;
+        image.scale(self.icon.width(), self.icon.height(), true, true);
+        self.button.set_image(Some(image));
+        self.button.redraw();
+        Ok(())
+    }
+
+    /// Highlight the background of the [E4Button] if hovered, or show it at rest.
+    pub fn set_hovered(&mut self, hovered: bool) {
+        let color = if hovered && self.button.active() {
+            self.colors.hover
+        } else {
+            self.colors.normal
+        };
+        if self.button.color() != color {
+            self.button.set_color(color);
+            self.button.redraw();
+        }
+    }
+
+    /// Draw the [E4Button] with a border if selected, or as usual.
+    pub fn set_selected(&mut self, selected: bool) {
+        // The down frame is the frame at rest
+        let frame = if selected {
+            fltk::enums::FrameType::BorderBox
+        } else {
+            self.button.down_frame()
+        };
+        if self.button.frame() != frame {
+            self.button.set_frame(frame);
+            self.button.redraw();
+        }
+    }
+
+    /// Redraw the [E4Button] and its border.
+    pub fn redraw(&mut self) {
+        self.button.redraw();
+        self.border.frame.redraw();
+    }
+
+    /// Remove the [E4Button] and its border from wind, deleting their widgets.
+    pub fn remove_from(&mut self, wind: &mut Window) {
+        wind.remove(&self.button);
+        wind.remove(&self.border.frame);
+        Button::delete(self.button.clone());
+        Frame::delete(self.border.frame.clone());
+    }
+
+    /// Return true if the process of the [E4Button] is running, or if its widget is on.
+    pub fn is_running(&self) -> bool {
+        self.border.is_active()
+            || self
+                .state_icons
+                .as_ref()
+                .is_some_and(|icons| icons.shown == Some(true))
+    }
+
     /// Set a new command for the [E4Button].
-    pub fn set_command(&self, cmd: String, arguments: String) {
+    pub fn set_command(&self, cmd: String, arguments: Vec<String>) {
         let mut guard = self.command.lock().unwrap();
         guard.set(cmd, arguments);
         drop(guard);
@@ -616,44 +1329,58 @@ impl E4Button {
             return;
         }
 
-        // Delete the button configuration file
-        let mut config_file = PathBuf::from(&self.name).with_extension("");
-        config_file.set_extension("conf");
-        config_file = config.config_dir.join(config_file);
-        match std::fs::remove_file(&config_file) {
-            Ok(_) => {}
-            Err(e) => {
-                let message = tr!(
-                    translations,
-                    format_display,
-                    "cannot-remove-the-config-file",
-                    &[&e]
-                );
-                fltk::dialog::alert_default(&message);
-            }
+        if let Err(e) = config.remove_button(&self.name, translations.clone()) {
+            fltk::dialog::alert_default(&e.to_string());
         }
+        e4events::publish(E4Event::ButtonRemoved(self.name.clone()));
+    }
 
-        // DON'T Delete the icon
-        // self.icon.delete(&config);
+    /// Rename the [E4Button] as name, saving its place: the generic button keeps its name.
+    pub fn rename(
+        &self,
+        config: &mut E4Config,
+        name: &str,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if self.name == GENERIC || name.trim() == GENERIC {
+            return Err(Box::new(ButtonError {
+                details: tr!(
+                    translations,
+                    get_or_default,
+                    "cannot-modify-the-generic-button",
+                    "Cannot modify the GENERIC button"
+                ),
+            }));
+        }
+        config.rename_button(&self.name, name, translations)
+    }
 
-        // Create a new buttons vec removing the one to be deleted
-        let mut buttons = vec![];
-        let old_buttons = config.buttons.clone();
-        for (i, button) in old_buttons.iter().enumerate() {
-            let button_number = i + 1;
-            if *button != self.name {
-                buttons.push(button.to_string());
-            }
-            let key_to_remove = format!("button{}", button_number);
-            config.remove_key(
-                crate::e4config::E4DOCKER_BUTTON_SECTION.to_string(),
-                key_to_remove,
-                translations.clone(),
+    /// Delete the [E4Button]s named names at once, except the generic one.
+    pub fn delete_all(
+        names: &[String],
+        config: &mut E4Config,
+        translations: Arc<Mutex<Translations>>,
+    ) {
+        if names.iter().any(|name| name == GENERIC) {
+            let message = tr!(
+                translations,
+                get_or_default,
+                "cannot-delete-the-generic-button",
+                "Cannot delete the GENERIC button"
             );
+            fltk::dialog::alert_default(&message);
+        }
+        let names: Vec<String> = names
+            .iter()
+            .filter(|name| *name != GENERIC)
+            .cloned()
+            .collect();
+        if let Err(e) = config.remove_buttons(&names, translations.clone()) {
+            fltk::dialog::alert_default(&e.to_string());
+        }
+        for name in names {
+            e4events::publish(E4Event::ButtonRemoved(name));
         }
-        config.set_number_of_buttons(buttons.len() as i32, translations.clone());
-        config.save_buttons(&buttons, translations.clone());
-        crate::e4config::restart_app(translations.clone());
     }
 
     /// Edit the [E4Button].
@@ -687,7 +1414,7 @@ impl E4Button {
                     &self.name,
                     &icon,
                     &command.get_cmd().clone(),
-                    &command.get_arguments().clone(),
+                    &command.arguments_line(),
                 ];
 
                 // Populate the ui
@@ -723,34 +1450,9 @@ impl E4Button {
                 let translations_second_clone = translations.clone();
                 let translations_third_clone = translations.clone();
                 ui.button_icon.set_callback(move |b| {
-                    let mut chooser = fltk::dialog::FileChooser::new(
-                        &assets_dir,                           // directory
-                        "*.png",                               // filter or pattern
-                        fltk::dialog::FileChooserType::Single, // chooser type
-                        &tr!(
-                            translations_clone,
-                            get_or_default,
-                            "choose-icon",
-                            "Choose icon"
-                        ), // title
-                    );
-                    chooser.show();
-                    while chooser.shown() {
-                        app::wait();
-                    }
-                    if chooser.value(1).is_some() {
-                        let image_path = match chooser.value(1) {
-                            Some(img) => img,
-                            None => panic!(
-                                "{}",
-                                tr!(
-                                    translations,
-                                    get_or_default,
-                                    "cannot-find-the-chosen-image",
-                                    "Cannot find the chosen image"
-                                )
-                            ),
-                        };
+                    if let Some(image_path) =
+                        Self::choose_icon(&assets_dir, (w, h), translations_clone.clone())
+                    {
                         let mut new_image = match Self::get_fltk_image(
                             &PathBuf::from(&image_path),
                             translations.clone(),
@@ -795,7 +1497,7 @@ impl E4Button {
                             "icon",
                             Some(image_path),
                         );
-                        config.write(&tmp_file_path).expect(&tr!(
+                        FsStorage.write_ini(&tmp_file_path, &config).expect(&tr!(
                             translations,
                             get_or_default,
                             "cannot-save-the-config-file",
@@ -818,6 +1520,17 @@ impl E4Button {
                 });
 
                 ui.command.set_value(grid_values[2]);
+                ui.set_store_app_callback(
+                    config.assets_dir.clone(),
+                    (w, h),
+                    translations_second_clone.clone(),
+                );
+                ui.set_desktop_app_callback(
+                    config.assets_dir.clone(),
+                    (w, h),
+                    false,
+                    translations_second_clone.clone(),
+                );
                 let mut command_clone = ui.command.clone();
 
                 ui.command_button.set_callback(move |_| {
@@ -875,7 +1588,8 @@ impl E4Button {
                     }
                 });
 
-                ui.arguments.set_value(command.get_arguments());
+                ui.arguments.set_arguments(command.get_arguments());
+                ui.set_clean();
                 drop(command);
                 // Add OK button at the bottom
                 let mut config_clone = config.clone();
@@ -884,11 +1598,24 @@ impl E4Button {
                 ui.save.set_callback({
                     let mut wind = ui.window.clone();
                     move |_| {
+                        let name = ui.name.value();
+                        // Another button with the same name would lose its .conf file
+                        if config_clone.is_button_name_taken(&name, Some(&old_name)) {
+                            let suggestion = config_clone.unique_button_name(&name);
+                            let message = tr!(
+                                translations_third_clone,
+                                format,
+                                "button-name-already-exists",
+                                &[&name, &suggestion]
+                            );
+                            fltk::dialog::alert_default(&message);
+                            ui.name.set_value(&suggestion);
+                            return;
+                        }
                         wind.hide();
                         let tmp_file_path = crate::e4config::get_tmp_file();
                         let mut tmp_config = Ini::new();
                         let _ = tmp_config.load(&tmp_file_path);
-                        let name = ui.name.value();
                         if name == GENERIC {
                             let message = tr!(
                                 translations_third_clone,
@@ -913,7 +1640,7 @@ impl E4Button {
                             "arguments",
                             Some(arguments),
                         );
-                        match tmp_config.write(&tmp_file_path) {
+                        match FsStorage.write_ini(&tmp_file_path, &tmp_config) {
                             Ok(_) => {}
                             Err(e) => {
                                 panic!(
@@ -933,12 +1660,14 @@ impl E4Button {
                                 n = i + 1;
                             }
                         }
-                        config_clone.set_value(
+                        if let Err(e) = config_clone.set_value(
                             crate::e4config::E4DOCKER_BUTTON_SECTION.to_string(),
                             format!("button{}", n),
-                            Some(name),
+                            Some(name.clone()),
                             translations_third_clone.clone(),
-                        );
+                        ) {
+                            fltk::dialog::alert_default(&e.to_string());
+                        }
                         match std::fs::copy(&tmp_file_path, &config_file) {
                             Ok(_) => {}
                             Err(e) => {
@@ -957,10 +1686,14 @@ impl E4Button {
                                 );
                             }
                         }
-                        crate::e4config::restart_app(translations_third_clone.clone());
+                        e4events::publish(E4Event::ButtonChanged {
+                            old_name: old_name.clone(),
+                            name,
+                        });
                     }
                 });
 
+                crate::e4screen::place_dialog(&mut ui.window);
                 ui.window.show();
 
                 // Run modal window
@@ -984,7 +1717,11 @@ impl E4Button {
     pub fn new_button(config: &mut E4Config, translations: Arc<Mutex<Translations>>) {
         match E4ButtonEditUI::new(translations.clone()) {
             Ok(mut ui) => {
+                // The new button starts as a copy of the generic one, under a translated name
                 let name = GENERIC;
+                let new_name = config.numbered_button_name(|n| {
+                    tr!(translations, format, "new-button-name", &[&n.to_string()])
+                });
                 let mut config_file = config.config_dir.join(name);
                 config_file.set_extension("conf");
                 let tmp_file_path = crate::e4config::get_tmp_file();
@@ -1030,7 +1767,12 @@ impl E4Button {
                 ));
                 let command = button_config.command;
                 let icon = button_config.icon_path;
-                let grid_values = [name, &icon, command.get_cmd(), command.get_arguments()];
+                let grid_values = [
+                    new_name.as_str(),
+                    &icon,
+                    command.get_cmd(),
+                    &command.arguments_line(),
+                ];
 
                 // Populate the ui
                 ui.name.set_value(grid_values[0]);
@@ -1061,34 +1803,9 @@ impl E4Button {
                 let translations_second_clone = translations.clone();
                 let translations_third_clone = translations.clone();
                 ui.button_icon.set_callback(move |b| {
-                    let mut chooser = fltk::dialog::FileChooser::new(
-                        &assets_dir,                           // directory
-                        "*.png",                               // filter or pattern
-                        fltk::dialog::FileChooserType::Single, // chooser type
-                        &tr!(
-                            translations_clone,
-                            get_or_default,
-                            "choose-icon",
-                            "Choose icon"
-                        ), // title
-                    );
-                    chooser.show();
-                    while chooser.shown() {
-                        app::wait();
-                    }
-                    if chooser.value(1).is_some() {
-                        let image_path = match chooser.value(1) {
-                            Some(img) => img,
-                            None => panic!(
-                                "{}",
-                                tr!(
-                                    translations,
-                                    get_or_default,
-                                    "cannot-find-the-chosen-image",
-                                    "Cannot find the chosen image"
-                                )
-                            ),
-                        };
+                    if let Some(image_path) =
+                        Self::choose_icon(&assets_dir, (w, h), translations_clone.clone())
+                    {
                         let mut new_image = match Self::get_fltk_image(
                             &PathBuf::from(&image_path),
                             translations.clone(),
@@ -1133,7 +1850,7 @@ impl E4Button {
                             "icon",
                             Some(image_path),
                         );
-                        config.write(&tmp_file_path).expect(&tr!(
+                        FsStorage.write_ini(&tmp_file_path, &config).expect(&tr!(
                             translations,
                             get_or_default,
                             "cannot-save-the-config-file",
@@ -1156,6 +1873,17 @@ impl E4Button {
                 });
 
                 ui.command.set_value(grid_values[2]);
+                ui.set_store_app_callback(
+                    config.assets_dir.clone(),
+                    (w, h),
+                    translations_second_clone.clone(),
+                );
+                ui.set_desktop_app_callback(
+                    config.assets_dir.clone(),
+                    (w, h),
+                    true,
+                    translations_second_clone.clone(),
+                );
                 let mut command_clone = ui.command.clone();
                 ui.command_button.set_callback(move |_| {
                     // Ottieni la directory corrente
@@ -1213,22 +1941,64 @@ impl E4Button {
                     }
                 });
 
-                ui.arguments.set_value(command.get_arguments());
+                ui.arguments.set_arguments(command.get_arguments());
+                ui.set_clean();
 
                 let mut config_clone = config.clone();
                 // Add OK button at the bottom
                 ui.save.set_callback({
                     let mut wind = ui.window.clone();
                     move |_| {
+                        let name = ui.name.value();
+                        // Another button with the same name, or the generic one, would lose its .conf file
+                        if config_clone.is_button_name_taken(&name, None)
+                            || crate::e4config::same_button_name(&name, GENERIC)
+                        {
+                            let suggestion = config_clone.unique_button_name(&name);
+                            let message = tr!(
+                                translations_third_clone,
+                                format,
+                                "button-name-already-exists",
+                                &[&name, &suggestion]
+                            );
+                            fltk::dialog::alert_default(&message);
+                            ui.name.set_value(&suggestion);
+                            return;
+                        }
                         wind.hide();
                         let tmp_file_path = crate::e4config::get_tmp_file();
                         let mut tmp_config = Ini::new();
                         let _ = tmp_config.load(&tmp_file_path);
-                        let name = ui.name.value();
                         let mut config_file = config_clone.config_dir.join(&name);
                         config_file.set_extension("conf");
                         let command = ui.command.value();
                         let arguments = ui.arguments.value();
+                        // Without a chosen icon, find the one of the command
+                        let has_icon = tmp_config
+                            .get(crate::e4config::BUTTON_BUTTON_SECTION, "icon")
+                            .is_some_and(|icon| {
+                                !crate::e4initialize::is_generic_icon(
+                                    std::path::Path::new(&icon),
+                                    &config_clone.assets_dir,
+                                )
+                            });
+                        if !has_icon && !command.trim().is_empty() {
+                            match crate::e4icon::save_resolved_icon(
+                                &config_clone,
+                                &name,
+                                &command,
+                                translations_third_clone.clone(),
+                            ) {
+                                Ok(icon) => {
+                                    tmp_config.set(
+                                        crate::e4config::BUTTON_BUTTON_SECTION,
+                                        "icon",
+                                        Some(icon),
+                                    );
+                                }
+                                Err(e) => log::warn!("{}", e),
+                            }
+                        }
                         tmp_config.set(
                             crate::e4config::BUTTON_BUTTON_SECTION,
                             "command",
@@ -1239,7 +2009,7 @@ impl E4Button {
                             "arguments",
                             Some(arguments),
                         );
-                        match tmp_config.write(&tmp_file_path) {
+                        match FsStorage.write_ini(&tmp_file_path, &tmp_config) {
                             Ok(_) => {}
                             Err(e) => {
                                 panic!(
@@ -1273,37 +2043,19 @@ impl E4Button {
                             }
                         };
 
-                        // Modify the number of buttons and the buttons list in e4docker.conf.
-                        let number_of_buttons = match config_clone
-                            .get_number_of_buttons(translations_third_clone.clone())
-                        {
-                            Ok(b) => b + 1,
-                            Err(e) => {
-                                panic!(
-                                    "{}",
-                                    tr!(
-                                        translations_third_clone,
-                                        format,
-                                        "cannot-get-the-number-of-buttons",
-                                        &[&e.to_string()]
-                                    )
-                                );
-                            }
-                        };
-                        config_clone.set_number_of_buttons(
-                            number_of_buttons,
+                        // Add the button to the list in e4docker.conf, which also saves their number.
+                        let result = config_clone.update_buttons(
+                            |buttons| buttons.push(name.to_string()),
                             translations_third_clone.clone(),
                         );
-                        let mut new_buttons = vec![];
-                        for button in &config_clone.buttons {
-                            new_buttons.push(button.clone());
+                        if let Err(e) = result {
+                            fltk::dialog::alert_default(&e.to_string());
                         }
-                        new_buttons.push(name.to_string());
-                        config_clone.save_buttons(&new_buttons, translations_third_clone.clone());
-                        crate::e4config::restart_app(translations_third_clone.clone());
+                        e4events::publish(E4Event::ButtonAdded(name.to_string()));
                     }
                 });
 
+                crate::e4screen::place_dialog(&mut ui.window);
                 ui.window.show();
 
                 // Run modal window
@@ -1331,31 +2083,46 @@ impl E4Button {
         translations: Arc<Mutex<Translations>>,
     ) -> Result<E4ButtonConfig, Box<dyn std::error::Error>> {
         // Read config.config_dir/button_name.conf
-        let mut config_file = config.config_dir.join(button_name);
-        config_file.set_extension("conf");
-        let mut config = Ini::new();
-        let result = config.load(config_file);
-
-        match result {
-            Ok(_) => (),
+        let config_file = crate::e4config::button_file(&config.config_dir, button_name);
+        let assets_dir = &config.assets_dir;
+        let config = match config.storage.load_ini(&config_file) {
+            Ok(config) => config,
             Err(e) => {
-                let message = tr!(
-                    translations,
-                    format,
-                    "cannot-load-the-button-config-file",
-                    &[&e.to_string()]
-                );
-                fltk::dialog::alert_default(&message);
+                return Err(Box::new(ButtonError {
+                    details: tr!(
+                        translations,
+                        format,
+                        "cannot-load-the-button-config-file",
+                        &[&e]
+                    ),
+                }));
             }
         };
 
         // Get the fields
+        let button_type = match config.get(crate::e4config::BUTTON_BUTTON_SECTION, "TYPE") {
+            Some(button_type) => E4ButtonType::from_name(&button_type),
+            None => E4ButtonType::Command,
+        };
         let command: String = match config.get(crate::e4config::BUTTON_BUTTON_SECTION, "COMMAND") {
             Some(command) => command,
             None => "".to_string(),
         };
         let icon_path: String = match config.get(crate::e4config::BUTTON_BUTTON_SECTION, "ICON") {
             Some(path) => path,
+            // A macOS bundle carries its own icon
+            None if crate::e4bundle::is_app_bundle(std::path::Path::new(&command)) => {
+                command.clone()
+            }
+            // A web address shows the favicon of its site, once downloaded
+            None if crate::e4favicon::is_url(&command) => {
+                match crate::e4favicon::icon_path(assets_dir, &command) {
+                    Some(favicon) => favicon.display().to_string(),
+                    None => crate::e4initialize::get_generic_icon(translations.clone())
+                        .display()
+                        .to_string(),
+                }
+            }
             None => crate::e4initialize::get_generic_icon(translations.clone())
                 .display()
                 .to_string(),
@@ -1366,9 +2133,59 @@ impl E4Button {
                 None => "".to_string(),
             };
         arguments = arguments.trim().to_string();
+        // The seconds after a launch in which the button does not launch again
+        let cooldown = match config.get(crate::e4config::BUTTON_BUTTON_SECTION, "COOLDOWN") {
+            Some(cooldown) => match cooldown.trim().parse::<f64>() {
+                Ok(seconds) if seconds.is_finite() && seconds >= 0.0 => {
+                    std::time::Duration::from_secs_f64(seconds)
+                }
+                _ => {
+                    return Err(Box::new(ButtonError {
+                        details: tr!(translations, format, "invalid-cooldown", &[&cooldown]),
+                    }));
+                }
+            },
+            None => e4command::DEFAULT_COOLDOWN,
+        };
+        // The seconds after a launch in which the program is killed, if still running
+        let timeout = match config.get(crate::e4config::BUTTON_BUTTON_SECTION, "TIMEOUT") {
+            Some(timeout) => match timeout.trim().parse::<f64>() {
+                Ok(seconds) if seconds.is_finite() && seconds > 0.0 => {
+                    Some(std::time::Duration::from_secs_f64(seconds))
+                }
+                _ => {
+                    return Err(Box::new(ButtonError {
+                        details: tr!(translations, format, "invalid-timeout", &[&timeout]),
+                    }));
+                }
+            },
+            None => None,
+        };
+
+        // A click shows the running program instead of launching it again
+        let single_instance =
+            match config.getbool(crate::e4config::BUTTON_BUTTON_SECTION, "SINGLE_INSTANCE") {
+                Ok(single_instance) => single_instance.unwrap_or(false),
+                Err(_) => {
+                    let value = config
+                        .get(crate::e4config::BUTTON_BUTTON_SECTION, "SINGLE_INSTANCE")
+                        .unwrap_or_default();
+                    return Err(Box::new(ButtonError {
+                        details: tr!(translations, format, "invalid-single-instance", &[&value]),
+                    }));
+                }
+            };
 
         // Create the E4Command
-        let command = E4Command::new(command, arguments);
-        Ok(E4ButtonConfig { command, icon_path })
+        let mut command = E4Command::new(command, e4command::split_arguments(&arguments));
+        command.set_cooldown(cooldown);
+        command.set_timeout(timeout);
+        command.set_single_instance(single_instance);
+        command.set_button(button_name);
+        Ok(E4ButtonConfig {
+            button_type,
+            command,
+            icon_path,
+        })
     }
 }
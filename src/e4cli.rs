@@ -0,0 +1,392 @@
+use crate::{
+    e4config::{button_file, config_file, is_spacer, E4Config},
+    e4diff, e4import, e4initialize, e4instance, e4lint,
+    e4list::{self, E4ListFormat},
+    e4storage::FsStorage,
+    tr,
+    translations::Translations,
+};
+use std::{
+    env,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+};
+
+/// The flag running e4docker without dialogs, on the servers without a display: see [is_quiet].
+pub const QUIET: &str = "--quiet";
+
+/// The flag checking the configuration, without dialogs, instead of starting the dock: see [run_check].
+pub const CHECK_CONFIG: &str = "--check-config";
+
+/// The option of the subcommands changing the configuration printing the changes instead of writing them.
+pub const DRY_RUN: &str = "--dry-run";
+
+/// True if e4docker runs without dialogs.
+static QUIET_MODE: AtomicBool = AtomicBool::new(false);
+
+/// The exit codes of e4docker, for the scripts.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum E4ExitCode {
+    Success = 0,
+    /// Another failure, like a file which cannot be read or written.
+    Failure = 1,
+    /// The arguments are wrong, like a missing file or an unknown format.
+    Usage = 2,
+    /// The configuration directory or e4docker.conf cannot be found or created.
+    ConfigNotFound = 3,
+    /// The configuration, or the buttons to import, are not valid.
+    ValidationFailed = 4,
+    /// A button of e4docker.conf has no configuration file.
+    ButtonMissing = 5,
+}
+
+impl E4ExitCode {
+    /// Exit e4docker with the code.
+    pub fn exit(self) -> ! {
+        std::process::exit(self as i32)
+    }
+}
+
+/// An error of a subcommand, with the exit code telling it to the scripts.
+#[derive(Debug)]
+pub struct E4CliError {
+    pub code: E4ExitCode,
+    details: String,
+}
+
+impl E4CliError {
+    /// Create an error exiting with code and showing details.
+    pub fn new(code: E4ExitCode, details: &str) -> Self {
+        Self {
+            code,
+            details: details.to_string(),
+        }
+    }
+}
+
+impl std::fmt::Display for E4CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for E4CliError {}
+
+/// Read the quiet flag in args, the arguments of the program: the check of the configuration is quiet too.
+pub fn set_quiet(args: &[String]) {
+    QUIET_MODE.store(
+        args.iter().any(|arg| arg == QUIET || arg == CHECK_CONFIG),
+        Ordering::Relaxed,
+    );
+}
+
+/// Return true if e4docker runs without dialogs: the messages are printed on the standard error and FLTK
+/// is never initialized, so the configurations can be prepared on a server without a display.
+pub fn is_quiet() -> bool {
+    QUIET_MODE.load(Ordering::Relaxed)
+}
+
+/// Show message in a dialog, or print it on the standard error in quiet mode.
+pub fn alert(message: &str) {
+    if is_quiet() {
+        eprintln!("{}", message);
+    } else {
+        fltk::dialog::alert_default(message);
+    }
+}
+
+/// Return the buttons of config, without the spacers, whose configuration file is missing.
+pub fn missing_buttons(config: &E4Config) -> Vec<String> {
+    config
+        .buttons
+        .iter()
+        .filter(|name| {
+            !is_spacer(name)
+                && !config
+                    .storage
+                    .exists(&button_file(&config.config_dir, name))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Run the subcommand in args, the arguments of the program, on the configuration in config_dir:
+/// None if there is none, and the dock starts. local_config_dir holds the lock of the running dock.
+pub fn run(
+    args: &[String],
+    config_dir: &Path,
+    local_config_dir: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Option<Result<(), E4CliError>> {
+    // e4docker import-json [--dry-run] <file> adds the buttons of the file to the dock, without starting it
+    if let Some(position) = args.iter().position(|arg| arg == "import-json") {
+        return Some(import_json(
+            &args[position + 1..],
+            config_dir,
+            local_config_dir,
+            translations,
+        ));
+    }
+    // e4docker list-buttons [--format json|tsv] prints the buttons, with their state if the dock is running
+    if args.iter().any(|arg| arg == "list-buttons") {
+        return Some(list_buttons(
+            args,
+            config_dir,
+            local_config_dir,
+            translations,
+        ));
+    }
+    None
+}
+
+/// Check the configuration if args, the arguments of the program, hold --check-config: None otherwise.
+/// It is run before the configuration of the user is created, so a missing one is reported.
+///
+/// e4docker --check-config [dir] prints the problems of the configuration in dir, like the one of a dotfiles
+/// repository in its CI; without dir, the configuration of the user, or of its profile, is checked.
+pub fn run_check(
+    args: &[String],
+    translations: Arc<Mutex<Translations>>,
+) -> Option<Result<(), E4CliError>> {
+    let position = args.iter().position(|arg| arg == CHECK_CONFIG)?;
+    let config_dir = match args.get(position + 1) {
+        Some(dir) if !dir.starts_with("--") => PathBuf::from(dir),
+        _ => match e4initialize::package_config_path(&translations) {
+            Ok(config_dir) => config_dir,
+            Err(e) => {
+                return Some(Err(E4CliError::new(
+                    E4ExitCode::ConfigNotFound,
+                    &e.to_string(),
+                )))
+            }
+        },
+    };
+    Some(check_config(&config_dir, translations))
+}
+
+/// Read the configuration in config_dir for a subcommand.
+fn read_config(
+    config_dir: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<E4Config, E4CliError> {
+    let file = config_file(config_dir);
+    if !file.is_file() {
+        return Err(E4CliError::new(
+            E4ExitCode::ConfigNotFound,
+            &tr!(
+                translations,
+                format,
+                "configuration-not-found",
+                &[&file.display().to_string()]
+            ),
+        ));
+    }
+    E4Config::read(config_dir, translations.clone()).map_err(|e| {
+        E4CliError::new(
+            E4ExitCode::ValidationFailed,
+            &tr!(
+                translations,
+                format,
+                "invalid-configuration",
+                &[&file.display().to_string(), &e.to_string()]
+            ),
+        )
+    })
+}
+
+/// Import the buttons of the JSON file in args, the arguments after import-json, in the configuration
+/// in config_dir, and tell the running dock to show them. With --dry-run the changes are printed instead.
+fn import_json(
+    args: &[String],
+    config_dir: &Path,
+    local_config_dir: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<(), E4CliError> {
+    let cannot_import = |code: E4ExitCode, e: &dyn std::error::Error| {
+        E4CliError::new(
+            code,
+            &tr!(
+                translations,
+                format,
+                "cannot-import-the-buttons",
+                &[&e.to_string()]
+            ),
+        )
+    };
+    // --dry-run prints the changes without writing them, to check a provisioning script
+    let dry_run = args.iter().any(|arg| arg == DRY_RUN);
+    let path = match args.iter().find(|arg| !arg.starts_with("--")) {
+        Some(path) => Path::new(path),
+        None => {
+            return Err(E4CliError::new(
+                E4ExitCode::Usage,
+                "e4docker import-json [--dry-run] <file>",
+            ))
+        }
+    };
+    let json = std::fs::read_to_string(path).map_err(|e| cannot_import(E4ExitCode::Failure, &e))?;
+    let buttons = e4import::buttons_from_json(&json)
+        .map_err(|e| cannot_import(E4ExitCode::ValidationFailed, e.as_ref()))?;
+    let mut config = read_config(config_dir, translations.clone())?;
+    if dry_run {
+        return import_dry_run(config_dir, &buttons, translations.clone())
+            .map_err(|e| cannot_import(E4ExitCode::Failure, e.as_ref()));
+    }
+    let names = e4import::import(&mut config, &buttons, translations.clone())
+        .map_err(|e| cannot_import(E4ExitCode::Failure, e.as_ref()))?;
+    // The running dock, if any, shows them at once
+    let _ = e4instance::request(local_config_dir, e4instance::RELOAD_REQUEST);
+    println!(
+        "{}",
+        tr!(
+            translations,
+            format,
+            "buttons-imported",
+            &[&names.len().to_string()]
+        )
+    );
+    Ok(())
+}
+
+/// Print the changes of importing buttons in the configuration of config_dir, without writing them.
+/// The icons are fetched in a temporary directory, removed afterwards.
+fn import_dry_run(
+    config_dir: &Path,
+    buttons: &[e4import::E4ImportedButton],
+    translations: Arc<Mutex<Translations>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let assets_dir = env::temp_dir().join(format!("e4docker-dry-run-{}", std::process::id()));
+    std::fs::create_dir_all(&assets_dir)?;
+    let result = e4diff::dry_run(Arc::new(FsStorage), config_dir, &assets_dir, |config| {
+        e4import::import(config, buttons, translations.clone())
+    });
+    let _ = std::fs::remove_dir_all(&assets_dir);
+    let (_, diff) = result?;
+    if diff.is_empty() {
+        println!(
+            "{}",
+            tr!(
+                translations,
+                get_or_default,
+                "no-changes",
+                "Nothing changes"
+            )
+        );
+    }
+    for line in diff.lines(translations.clone()) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+/// Print the buttons of the configuration in config_dir in the format of args, with their state if the dock
+/// is running. It fails, after printing them, if a button has no configuration file.
+fn list_buttons(
+    args: &[String],
+    config_dir: &Path,
+    local_config_dir: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<(), E4CliError> {
+    let format = match e4list::format_from_args(args) {
+        Some(name) => E4ListFormat::from_name(&name).ok_or_else(|| {
+            E4CliError::new(
+                E4ExitCode::Usage,
+                &tr!(translations, format, "invalid-list-format", &[&name]),
+            )
+        })?,
+        None => E4ListFormat::default(),
+    };
+    let config = read_config(config_dir, translations.clone())?;
+    let mut buttons = e4list::list_buttons(&config);
+    if let Ok(answer) = e4instance::request(local_config_dir, e4instance::RUNNING_BUTTONS_REQUEST) {
+        e4list::set_running(&mut buttons, &answer);
+    }
+    print!("{}", format.write(&buttons));
+    let missing = missing_buttons(&config);
+    if !missing.is_empty() {
+        return Err(E4CliError::new(
+            E4ExitCode::ButtonMissing,
+            &tr!(
+                translations,
+                format,
+                "buttons-without-configuration",
+                &[&missing.join(", ")]
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Print the problems of the configuration in config_dir, with its icons in config_dir/assets, one per line,
+/// and how many they are. It fails if any breaks the dock: see [e4lint::E4Lint::is_error].
+fn check_config(
+    config_dir: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<(), E4CliError> {
+    let file = config_file(config_dir);
+    if !file.is_file() {
+        return Err(E4CliError::new(
+            E4ExitCode::ConfigNotFound,
+            &tr!(
+                translations,
+                format,
+                "configuration-not-found",
+                &[&file.display().to_string()]
+            ),
+        ));
+    }
+    let assets_dir = config_dir.join("assets");
+    let lints =
+        e4lint::check_config(Arc::new(FsStorage), config_dir, &assets_dir).map_err(|e| {
+            E4CliError::new(
+                E4ExitCode::ValidationFailed,
+                &tr!(
+                    translations,
+                    format,
+                    "invalid-configuration",
+                    &[&file.display().to_string(), &e.to_string()]
+                ),
+            )
+        })?;
+    let errors = lints.iter().filter(|lint| lint.is_error()).count();
+    for lint in &lints {
+        let key = if lint.is_error() {
+            "check-error"
+        } else {
+            "check-warning"
+        };
+        println!(
+            "{}",
+            tr!(
+                translations,
+                format,
+                key,
+                &[&lint.message(translations.clone())]
+            )
+        );
+    }
+    println!(
+        "{}",
+        tr!(
+            translations,
+            format,
+            "check-summary",
+            &[&errors.to_string(), &(lints.len() - errors).to_string()]
+        )
+    );
+    if errors > 0 {
+        return Err(E4CliError::new(
+            E4ExitCode::ValidationFailed,
+            &tr!(
+                translations,
+                format,
+                "configuration-has-errors",
+                &[&file.display().to_string(), &errors.to_string()]
+            ),
+        ));
+    }
+    Ok(())
+}
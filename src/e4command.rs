@@ -1,10 +1,596 @@
-use std::{error, thread, process::Command, sync::{Arc, Mutex}};
-use crate::{tr, translations::Translations};
+use crate::{
+    e4bundle,
+    e4events::{self, E4Event},
+    e4favicon, e4uwp, e4window,
+};
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    env, error, fs,
+    io::{Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    process::{Child, Command, ExitStatus, Stdio},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, OnceLock,
+    },
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The time after a launch in which a [E4Command] is not launched again, so a double click opens one instance.
+pub const DEFAULT_COOLDOWN: Duration = Duration::from_secs(1);
+
+/// The time after a launch in which a failure of a [E4Command] is reported: a later exit is the program being closed.
+const EARLY_EXIT: Duration = Duration::from_secs(5);
+
+/// The time between two checks of the children and of the awaited windows by the supervisor.
+const SUPERVISOR_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The last lines of the error output of a failed launch, shown with its failure.
+const OUTPUT_LINES: usize = 3;
+
+/// The bytes at the end of the error output of a failed launch read to find its last lines.
+const OUTPUT_BYTES: u64 = 16 * 1024;
+
+/// The private directory of the files capturing the error output of the launches, if it can be created:
+/// see [output_dir].
+static OUTPUT_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+/// A process launched by a [E4Command], waited for by the supervisor.
+struct Supervised {
+    child: Child,
+    /// The name of the button which launched it, told of an early failure.
+    button: Option<String>,
+    started_at: Instant,
+    /// The time after which it is killed, if any.
+    timeout: Option<Duration>,
+    /// The file capturing its error output just after its launch, if any.
+    output: Option<PathBuf>,
+    /// Its exit status once it has ended, reported by the supervisor.
+    status: Option<ExitStatus>,
+}
+
+/// A window of the program launched by a button, awaited to end its launch.
+struct AwaitedWindow {
+    button: String,
+    class: String,
+    /// The ids of the windows of class before the launch.
+    existing: Vec<String>,
+    started_at: Instant,
+}
+
+/// The processes launched and the windows awaited, not ended yet.
+#[derive(Default)]
+struct Supervisor {
+    children: Vec<Supervised>,
+    windows: Vec<AwaitedWindow>,
+}
+
+lazy_static! {
+    /// The supervisor of the launches: a single thread, started with the first launch, reaps the processes,
+    /// kills them after their timeout and publishes their early failures and their windows as [E4Event]s.
+    static ref SUPERVISOR: Mutex<Supervisor> = {
+        thread::spawn(run_supervisor);
+        Mutex::new(Supervisor::default())
+    };
+
+    /// The number of launches of the buttons since the dock started, by name.
+    static ref LAUNCH_COUNTS: Mutex<HashMap<String, u32>> = Mutex::new(HashMap::new());
+}
+
+/// Return the number of launches of the button named name since the dock started.
+pub fn launch_count(name: &str) -> u32 {
+    LAUNCH_COUNTS
+        .lock()
+        .unwrap()
+        .get(name)
+        .copied()
+        .unwrap_or(0)
+}
+
+/// Return the private directory of the files capturing the error output of the launches, created once
+/// readable by the user only: in the runtime directory of the user, or as a new directory in the temporary one.
+fn output_dir() -> Option<&'static Path> {
+    OUTPUT_DIR
+        .get_or_init(|| {
+            // The runtime directory belongs to the user: the directory of a previous dock can be reused
+            let (dir, reusable) = match dirs::runtime_dir() {
+                Some(runtime_dir) => (runtime_dir.join("e4docker"), true),
+                None => {
+                    let now = SystemTime::now()
+                        .duration_since(UNIX_EPOCH)
+                        .map_or(0, |duration| duration.as_nanos());
+                    let name = format!("e4docker-{}-{}", std::process::id(), now);
+                    (env::temp_dir().join(name), false)
+                }
+            };
+            let mut builder = fs::DirBuilder::new();
+            #[cfg(unix)]
+            std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+            match builder.create(&dir) {
+                Ok(()) => Some(dir),
+                Err(e) if reusable && e.kind() == std::io::ErrorKind::AlreadyExists => Some(dir),
+                Err(e) => {
+                    log::warn!("Cannot create {}: {}", dir.display(), e);
+                    None
+                }
+            }
+        })
+        .as_deref()
+}
+
+/// Create a new file capturing the error output of a launch, in [output_dir].
+fn capture_file() -> Option<(PathBuf, fs::File)> {
+    static CAPTURES: AtomicUsize = AtomicUsize::new(0);
+    let path = output_dir()?.join(format!(
+        "{}-{}.log",
+        std::process::id(),
+        CAPTURES.fetch_add(1, Ordering::Relaxed)
+    ));
+    let file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(&path)
+        .ok()?;
+    Some((path, file))
+}
+
+/// Return the last lines of the error output captured in path, read from its last [OUTPUT_BYTES].
+fn captured_output(path: &Path) -> String {
+    let mut output = vec![];
+    if let Ok(mut file) = fs::File::open(path) {
+        let length = file.metadata().map_or(0, |metadata| metadata.len());
+        if file
+            .seek(SeekFrom::Start(length.saturating_sub(OUTPUT_BYTES)))
+            .is_ok()
+        {
+            let _ = file.read_to_end(&mut output);
+        }
+    }
+    let output = String::from_utf8_lossy(&output);
+    let lines: Vec<&str> = output
+        .lines()
+        .map(str::trim_end)
+        .filter(|line| !line.is_empty())
+        .collect();
+    lines[lines.len().saturating_sub(OUTPUT_LINES)..].join("\n")
+}
+
+/// Stop capturing the error output of supervised, removing its file.
+fn end_capture(supervised: &mut Supervised) {
+    if let Some(path) = supervised.output.take() {
+        // Windows cannot remove a file open by the child: try again at the next check
+        if fs::remove_file(&path).is_err() && path.exists() {
+            supervised.output = Some(path);
+        }
+    }
+}
+
+/// Tell the button of a process ended with status, if it failed just after its launch.
+fn report_exit(supervised: &Supervised, status: ExitStatus) {
+    // Killed by a signal, the process has no code
+    if let (Some(name), Some(code)) = (&supervised.button, status.code()) {
+        if code != 0 && supervised.started_at.elapsed() < EARLY_EXIT {
+            e4events::publish(E4Event::LaunchFailed {
+                name: name.clone(),
+                code,
+                output: supervised
+                    .output
+                    .as_deref()
+                    .map(captured_output)
+                    .unwrap_or_default(),
+            });
+        }
+    }
+}
+
+/// Check supervised, killing it after its timeout. Return false once it has ended, with its exit status
+/// kept in it to be reported.
+fn check(supervised: &mut Supervised) -> bool {
+    match supervised.child.try_wait() {
+        Ok(Some(status)) => {
+            supervised.status = Some(status);
+            false
+        }
+        Ok(None) => {
+            // A later failure is not told: its output is not needed anymore
+            if supervised.started_at.elapsed() >= EARLY_EXIT {
+                end_capture(supervised);
+            }
+            let expired = supervised
+                .timeout
+                .is_some_and(|timeout| supervised.started_at.elapsed() >= timeout);
+            if expired {
+                log::warn!(
+                    "Killing the process {}, running for more than its timeout",
+                    supervised.child.id()
+                );
+                let _ = supervised.child.kill();
+                let _ = supervised.child.wait();
+                end_capture(supervised);
+            }
+            !expired
+        }
+        Err(e) => {
+            log::warn!(
+                "Cannot wait for the process {}: {}",
+                supervised.child.id(),
+                e
+            );
+            end_capture(supervised);
+            false
+        }
+    }
+}
+
+/// Reap the ended processes, kill the ones past their timeout and search the awaited windows, forever.
+/// It runs in the supervisor thread.
+fn run_supervisor() {
+    loop {
+        thread::sleep(SUPERVISOR_INTERVAL);
+        let mut ended = vec![];
+        let windows: Vec<(String, String, Vec<String>)> = {
+            let mut supervisor = SUPERVISOR.lock().unwrap();
+            for mut supervised in std::mem::take(&mut supervisor.children) {
+                if check(&mut supervised) {
+                    supervisor.children.push(supervised);
+                } else {
+                    ended.push(supervised);
+                }
+            }
+            // Launched with or without a window
+            supervisor
+                .windows
+                .retain(|window| window.started_at.elapsed() < e4window::STARTUP_TIMEOUT);
+            supervisor
+                .windows
+                .iter()
+                .map(|window| {
+                    (
+                        window.button.clone(),
+                        window.class.clone(),
+                        window.existing.clone(),
+                    )
+                })
+                .collect()
+        };
+        // The error output of the failures is read without holding the lock
+        for mut supervised in ended {
+            if let Some(status) = supervised.status {
+                report_exit(&supervised, status);
+            }
+            end_capture(&mut supervised);
+        }
+        // xdotool can be slow: the windows are searched without holding the lock
+        for (button, class, existing) in windows {
+            if e4window::window_ids(&class)
+                .iter()
+                .any(|id| !existing.contains(id))
+            {
+                SUPERVISOR
+                    .lock()
+                    .unwrap()
+                    .windows
+                    .retain(|window| window.button != button);
+                e4events::publish(E4Event::WindowShown(button));
+            }
+        }
+    }
+}
+
+/// Hand child, launched by the button named button if any, to the supervisor, which waits for it to end
+/// and kills it after timeout. output is the file capturing its error output, if any.
+fn supervise(
+    child: Child,
+    button: Option<String>,
+    timeout: Option<Duration>,
+    output: Option<PathBuf>,
+) {
+    SUPERVISOR.lock().unwrap().children.push(Supervised {
+        child,
+        button,
+        started_at: Instant::now(),
+        timeout,
+        output,
+        status: None,
+    });
+}
+
+/// Hand child to the supervisor, which waits for it to end: a program launched without waiting for it
+/// leaves no zombie process.
+pub fn supervise_child(child: Child) {
+    supervise(child, None, None, None);
+}
+
+/// Wait for a new window of class, the WM_CLASS of the program launched by the button named button,
+/// then publish [E4Event::WindowShown]. existing are the ids of its windows before the launch.
+/// Nothing is published if no window appears within [e4window::STARTUP_TIMEOUT].
+fn await_window(button: &str, class: &str, existing: Vec<String>) {
+    SUPERVISOR.lock().unwrap().windows.push(AwaitedWindow {
+        button: button.to_string(),
+        class: class.to_string(),
+        existing,
+        started_at: Instant::now(),
+    });
+}
+
+/// Return the ids of the running processes launched by the button named name, as known by the supervisor.
+/// The programs which fork and exit, like the ones opening a window in a running instance, are not among them.
+pub fn supervised_pids(name: &str) -> Vec<u32> {
+    SUPERVISOR
+        .lock()
+        .unwrap()
+        .children
+        .iter()
+        .filter(|supervised| supervised.button.as_deref() == Some(name))
+        .map(|supervised| supervised.child.id())
+        .collect()
+}
+
+/// Error raised when a [E4Command] is launched again during its cooldown.
+#[derive(Debug)]
+struct LaunchError {
+    details: String,
+}
+
+impl std::fmt::Display for LaunchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl error::Error for LaunchError {}
+
+/// The creation flag of the Windows processes started without a console window.
+#[cfg(target_os = "windows")]
+pub const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+/// Return the [Command] running a command line through the shell.
+/// The dock has no console: cmd opens none either, or the polled commands would flash a window.
+#[cfg(target_os = "windows")]
+pub fn shell_command(command_line: &str) -> Command {
+    use std::os::windows::process::CommandExt;
+    let mut command = Command::new("cmd");
+    command
+        .arg("/C")
+        .raw_arg(command_line)
+        .creation_flags(CREATE_NO_WINDOW);
+    command
+}
+
+/// Return the [Command] running a command line through the shell.
+#[cfg(not(target_os = "windows"))]
+pub fn shell_command(command_line: &str) -> Command {
+    let mut command = Command::new("sh");
+    command.arg("-c").arg(command_line);
+    command
+}
+
+/// Split the arguments line of a button in its arguments, like a shell does.
+///
+/// The arguments are separated by spaces: the quoted ones can hold spaces.
+/// Inside single quotes every character is kept; inside double quotes `\"` is a double quote.
+/// Outside the quotes a backslash keeps the following quote or space; the other backslashes are kept,
+/// like in the Windows paths.
+///
+/// These are the quoting rules of a POSIX shell, as in the shell-words crate, but for the backslashes:
+/// shell-words drops the ones before the other characters too, breaking the Windows paths of the buttons,
+/// like `C:\Users`, written without quotes.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4command::split_arguments;
+///
+/// assert_eq!(
+///     split_arguments(r#"--new-window 'My Documents' "it's" C:\Users"#),
+///     vec!["--new-window", "My Documents", "it's", r"C:\Users"]
+/// );
+/// ```
+pub fn split_arguments(line: &str) -> Vec<String> {
+    let mut arguments = vec![];
+    let mut argument = String::new();
+    let mut in_argument = false;
+    let mut quote = None;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some('"'), '\\') if chars.peek() == Some(&'"') => argument.extend(chars.next()),
+            (Some(_), c) => argument.push(c),
+            (None, '\\')
+                if chars
+                    .peek()
+                    .is_some_and(|next| matches!(next, '"' | '\'') || next.is_whitespace()) =>
+            {
+                argument.extend(chars.next());
+                in_argument = true;
+            }
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_argument = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_argument {
+                    arguments.push(std::mem::take(&mut argument));
+                    in_argument = false;
+                }
+            }
+            (None, c) => {
+                argument.push(c);
+                in_argument = true;
+            }
+        }
+    }
+    if in_argument {
+        arguments.push(argument);
+    }
+    arguments
+}
+
+/// Join arguments in a line read back by [split_arguments], quoting the ones which need it.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4command::{join_arguments, split_arguments};
+///
+/// let arguments = vec!["-p".to_string(), "My Documents".to_string(), "it's".to_string()];
+/// assert_eq!(join_arguments(&arguments), r"-p 'My Documents' 'it'\''s'");
+/// assert_eq!(split_arguments(&join_arguments(&arguments)), arguments);
+/// ```
+pub fn join_arguments(arguments: &[String]) -> String {
+    arguments
+        .iter()
+        .map(|argument| {
+            let plain = !argument.is_empty()
+                && !argument.ends_with('\\')
+                && !argument
+                    .chars()
+                    .any(|c| matches!(c, '"' | '\'') || c.is_whitespace());
+            if plain {
+                argument.clone()
+            } else {
+                // A single quote closes the quotes, is escaped and opens them again
+                format!("'{}'", argument.replace('\'', r"'\''"))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Return the path of cmd: cmd itself if it exists, otherwise the first file named cmd in the PATH directories.
+pub fn locate(cmd: &str) -> Option<PathBuf> {
+    let path = Path::new(cmd);
+    if path.exists() {
+        return Some(path.to_path_buf());
+    }
+    let names = if cfg!(target_os = "windows") && path.extension().is_none() {
+        vec![PathBuf::from(cmd), path.with_extension("exe")]
+    } else {
+        vec![PathBuf::from(cmd)]
+    };
+    env::split_paths(&env::var_os("PATH")?)
+        .flat_map(|dir| names.iter().map(move |name| dir.join(name)))
+        .find(|candidate| candidate.is_file())
+}
+
+/// What spawns the [Command]s of the [E4Command]s: the processes, or a recording in the tests.
+pub trait E4Executor: Send + Sync {
+    /// Spawn command, launched by the button named button if any, to be killed after timeout.
+    /// Return the error if it cannot be started.
+    fn spawn(
+        &self,
+        command: Command,
+        button: Option<String>,
+        timeout: Option<Duration>,
+    ) -> Result<(), Box<dyn error::Error>>;
+}
+
+/// The executor starting the processes, handed to the supervisor.
+#[derive(Debug, Default)]
+pub struct ProcessExecutor;
+
+impl E4Executor for ProcessExecutor {
+    /// Spawn command, handing it to the supervisor, which waits for it to end and kills it after timeout.
+    /// If it fails just after its launch, [E4Event::LaunchFailed] is published for the button, if any,
+    /// with the end of its error output.
+    fn spawn(
+        &self,
+        mut command: Command,
+        button: Option<String>,
+        timeout: Option<Duration>,
+    ) -> Result<(), Box<dyn error::Error>> {
+        // The error output goes to a file, not to a pipe: the program outlives the dock
+        let output = match button.as_ref().and_then(|_| capture_file()) {
+            Some((path, file)) => {
+                command.stderr(Stdio::from(file));
+                Some(path)
+            }
+            None => None,
+        };
+        let child = match command.spawn() {
+            Ok(child) => child,
+            Err(e) => {
+                if let Some(path) = &output {
+                    let _ = fs::remove_file(path);
+                }
+                return Err(Box::new(e));
+            }
+        };
+        supervise(child, button, timeout, output);
+        Ok(())
+    }
+}
+
+/// A [Command] as it would be spawned, recorded by the [RecordingExecutor].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct E4Launch {
+    /// The program.
+    pub program: String,
+    /// The arguments.
+    pub args: Vec<String>,
+    /// The variables set, or removed if None, in the environment of the program.
+    pub env: Vec<(String, Option<String>)>,
+    /// The name of the button launching it, if any.
+    pub button: Option<String>,
+    /// The time after which it would be killed, if any.
+    pub timeout: Option<Duration>,
+}
+
+/// The executor recording the commands instead of spawning them, to test the launches without starting processes.
+#[derive(Debug, Default)]
+pub struct RecordingExecutor {
+    launches: Mutex<Vec<E4Launch>>,
+}
+
+impl RecordingExecutor {
+    /// Create an executor with no launches.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the launches recorded, in their order.
+    pub fn launches(&self) -> Vec<E4Launch> {
+        self.launches.lock().unwrap().clone()
+    }
+}
+
+impl E4Executor for RecordingExecutor {
+    fn spawn(
+        &self,
+        command: Command,
+        button: Option<String>,
+        timeout: Option<Duration>,
+    ) -> Result<(), Box<dyn error::Error>> {
+        let to_string = |value: &std::ffi::OsStr| value.to_string_lossy().to_string();
+        self.launches.lock().unwrap().push(E4Launch {
+            program: to_string(command.get_program()),
+            args: command.get_args().map(to_string).collect(),
+            env: command
+                .get_envs()
+                .map(|(key, value)| (to_string(key), value.map(to_string)))
+                .collect(),
+            button,
+            timeout,
+        });
+        Ok(())
+    }
+}
 
 /// A struct which holds a [Command] and its arguments.
 pub struct E4Command {
     cmd: String,
-    arguments: String,
+    /// The arguments, each passed as it is to the program: see [split_arguments] to read them from a line.
+    arguments: Vec<String>,
+    cooldown: Duration,
+    timeout: Option<Duration>,
+    launched_at: Option<Instant>,
+    waiting_for_window: bool,
+    single_instance: bool,
+    button: Option<String>,
+    executor: Arc<dyn E4Executor>,
 }
 
 impl E4Command {
@@ -12,66 +598,193 @@ impl E4Command {
     ///
     /// # Example
     ///
-    /// Create a [E4Command] to start /usr/bin/nano /tmp/myfile.txt.
+    /// Create a [E4Command] to start /usr/bin/nano "/tmp/my file.txt".
     ///
     /// ```rust
-    /// use e4docker::e4command::E4Command;
+    /// use e4docker::e4command::{split_arguments, E4Command};
     ///
     /// let command = E4Command::new(
     ///     String::from("/usr/bin/nano"),
-    ///     String::from("/tmp/myfile.txt"));
+    ///     split_arguments("'/tmp/my file.txt'"));
+    /// assert_eq!(command.get_arguments(), ["/tmp/my file.txt"]);
     /// ```
-    pub fn new(cmd: String, arguments: String) -> Self {
-        Self { cmd, arguments }
+    pub fn new(cmd: String, arguments: Vec<String>) -> Self {
+        Self {
+            cmd,
+            arguments,
+            cooldown: DEFAULT_COOLDOWN,
+            timeout: None,
+            launched_at: None,
+            waiting_for_window: false,
+            single_instance: false,
+            button: None,
+            executor: Arc::new(ProcessExecutor),
+        }
+    }
+
+    /// Return the [Command] to launch cmd.
+    /// A macOS .app bundle is launched through `open -a`, passing the following arguments to the app.
+    /// A UWP app (`shell:AppsFolder\AUMID`) is launched through explorer.exe.
+    /// A web address is opened by the default browser.
+    fn command(cmd: &str) -> Command {
+        if e4uwp::is_uwp_command(cmd) {
+            e4uwp::launch_command(cmd)
+        } else if e4favicon::is_url(cmd) {
+            let mut command = Command::new(Self::opener());
+            command.arg(cmd.trim());
+            command
+        } else if e4bundle::is_app_bundle(Path::new(cmd)) {
+            let mut command = Command::new("open");
+            command.arg("-a").arg(cmd).arg("--args");
+            command
+        } else {
+            Command::new(cmd)
+        }
+    }
+
+    /// Spawn the [Command] of the [E4Command] with args, unless it has been launched during the cooldown.
+    fn launch(&mut self, args: &[String]) -> Result<(), Box<dyn error::Error>> {
+        if self.is_launching() {
+            return Err(Box::new(LaunchError {
+                details: format!("{} is already launching", self.cmd),
+            }));
+        }
+        // explorer.exe exits with a failure code even when it launches a UWP app or opens a web address
+        let button = self
+            .button
+            .clone()
+            .filter(|_| !e4uwp::is_uwp_command(&self.cmd) && !e4favicon::is_url(&self.cmd));
+        // With the WM_CLASS of its .desktop entry, the launch ends when a new window of the program appears
+        let class = button.as_ref().and_then(|_| e4window::wm_class(&self.cmd));
+        let existing = class
+            .as_deref()
+            .map(e4window::window_ids)
+            .unwrap_or_default();
+        let mut command = Self::command(&self.cmd);
+        command.args(args);
+        self.executor.spawn(command, button.clone(), self.timeout)?;
+        self.launched_at = Some(Instant::now());
+        if let Some(name) = &self.button {
+            *LAUNCH_COUNTS
+                .lock()
+                .unwrap()
+                .entry(name.clone())
+                .or_default() += 1;
+        }
+        self.waiting_for_window = false;
+        if let (Some(name), Some(class)) = (button, class) {
+            await_window(&name, &class, existing);
+            self.waiting_for_window = true;
+        }
+        Ok(())
     }
 
     /// Exec the [Command] of the [E4Command]. Return () or the [error::Error].
-    pub fn exec(&mut self, translations: Arc<Mutex<Translations>>) -> Result<(), Box<dyn error::Error>> {
-        // With arguments
-        let cmd = self.cmd.clone();
+    /// It fails if the [E4Command] is still launching: see [E4Command::is_launching].
+    pub fn exec(&mut self) -> Result<(), Box<dyn error::Error>> {
         let args = self.arguments.clone();
-        let translations_clone = translations.clone();
-        if !self.arguments.is_empty() {
-            thread::spawn(move || {
-                let child = Command::new(&cmd)
-                    .spawn();
-                match child {
-                    Ok(mut c) => {
-                        let _ = c.wait(); // Wait nel thread separato
-                    },
-                    Err(e) => {
-                        let message = tr!(
-                            translations_clone,
-                            format,
-                            "failed-to-execute-command",
-                            &[&cmd, &e.to_string()]
-                        );
-                        fltk::dialog::alert_default(&message);
-                    }
-                }
-            });
+        self.launch(&args)
+    }
+
+    /// Exec the [Command] of the [E4Command] opening files, passed after its own arguments.
+    pub fn exec_with_files(&mut self, files: &[String]) -> Result<(), Box<dyn error::Error>> {
+        let mut args = self.arguments.clone();
+        args.extend(files.iter().cloned());
+        self.launch(&args)
+    }
+
+    /// Return true if the [E4Command] has been launched less than its cooldown ago, or if the window
+    /// of its program has not appeared yet, up to [e4window::STARTUP_TIMEOUT]: it is not launched again.
+    pub fn is_launching(&self) -> bool {
+        self.launched_at.is_some_and(|launched_at| {
+            let elapsed = launched_at.elapsed();
+            elapsed < self.cooldown
+                || (self.waiting_for_window && elapsed < e4window::STARTUP_TIMEOUT)
+        })
+    }
+
+    /// Tell the [E4Command] that the window of its program has appeared: its launch ends with the cooldown.
+    pub fn window_shown(&mut self) {
+        self.waiting_for_window = false;
+    }
+
+    /// Set the name of the button launching the [E4Command], told by [E4Event::LaunchFailed] if it fails.
+    pub fn set_button(&mut self, name: &str) {
+        self.button = Some(name.to_string());
+    }
+
+    /// Get the cooldown of the [E4Command].
+    pub fn get_cooldown(&self) -> Duration {
+        self.cooldown
+    }
+
+    /// Set the cooldown of the [E4Command]: the time after a launch in which it is not launched again.
+    /// A zero cooldown allows any number of launches.
+    pub fn set_cooldown(&mut self, cooldown: Duration) {
+        self.cooldown = cooldown;
+    }
+
+    /// Return true if a click shows the window of the running program instead of launching it again.
+    pub fn is_single_instance(&self) -> bool {
+        self.single_instance
+    }
+
+    /// Set if a click shows the window of the running program instead of launching it again:
+    /// see [e4window::activate].
+    pub fn set_single_instance(&mut self, single_instance: bool) {
+        self.single_instance = single_instance;
+    }
+
+    /// Set the executor spawning the [Command] of the [E4Command], like a [RecordingExecutor] in the tests.
+    pub fn set_executor(&mut self, executor: Arc<dyn E4Executor>) {
+        self.executor = executor;
+    }
+
+    /// Get the timeout of the [E4Command], if any.
+    pub fn get_timeout(&self) -> Option<Duration> {
+        self.timeout
+    }
+
+    /// Set the timeout of the [E4Command]: the time after a launch in which its program is killed, if still running.
+    pub fn set_timeout(&mut self, timeout: Option<Duration>) {
+        self.timeout = timeout;
+    }
+
+    /// Return the program opening a file, a directory or an URI with the default application of the desktop.
+    fn opener() -> &'static str {
+        if cfg!(target_os = "windows") {
+            "explorer.exe"
+        } else if cfg!(target_os = "macos") {
+            "open"
         } else {
-            thread::spawn(move || {
-                let child = Command::new(&cmd)
-                    .args([&args])
-                    .spawn();
-                match child {
-                    Ok(mut c) => {
-                        let _ = c.wait(); // Wait nel thread separato
-                    },
-                    Err(e) => {
-                        let message = tr!(
-                            translations_clone,
-                            format,
-                            "failed-to-execute-command",
-                            &[&cmd, &e.to_string()]
-                        );
-                        fltk::dialog::alert_default(&message);
-                    }
-                }
-            });
+            "xdg-open"
         }
-        Ok(())
+    }
+
+    /// Open a file, a directory or an URI with the default application of the desktop.
+    pub fn open_with_default_app(target: &str) -> Result<(), Box<dyn error::Error>> {
+        let mut command = Command::new(Self::opener());
+        command.arg(target);
+        ProcessExecutor.spawn(command, None, None)
+    }
+
+    /// Return the program and its arguments opening a text file with the default editor of the desktop.
+    fn editor() -> (&'static str, &'static [&'static str]) {
+        if cfg!(target_os = "windows") {
+            ("notepad.exe", &[])
+        } else if cfg!(target_os = "macos") {
+            ("open", &["-t"])
+        } else {
+            ("xdg-open", &[])
+        }
+    }
+
+    /// Open a text file, like a configuration file, with the default editor of the desktop.
+    pub fn edit_with_default_editor(path: &str) -> Result<(), Box<dyn error::Error>> {
+        let (editor, arguments) = Self::editor();
+        let mut command = Command::new(editor);
+        command.args(arguments).arg(path);
+        ProcessExecutor.spawn(command, None, None)
     }
 
     /// Get the [E4Command]
@@ -80,16 +793,33 @@ impl E4Command {
     }
 
     /// Set the [E4Command] and its args.
-    pub fn set(&mut self, cmd: String, arguments: String) {
+    pub fn set(&mut self, cmd: String, arguments: Vec<String>) {
         self.cmd = cmd;
         self.arguments = arguments;
     }
 
     /// Get the args of the [E4Command].
-    pub fn get_arguments(&self) -> &String {
+    pub fn get_arguments(&self) -> &[String] {
         &self.arguments
     }
 
+    /// Return the args of the [E4Command] in a line for a button configuration file, quoted where needed.
+    pub fn arguments_line(&self) -> String {
+        join_arguments(&self.arguments)
+    }
+
+    /// Return the command line of the [E4Command], quoted where needed, as it can be typed in a shell.
+    pub fn command_line(&self) -> String {
+        let mut parts = vec![self.cmd.clone()];
+        parts.extend(self.arguments.iter().cloned());
+        join_arguments(&parts)
+    }
+
+    /// Return the directory holding the [Command] of the [E4Command], searched in the PATH if needed.
+    pub fn location(&self) -> Option<PathBuf> {
+        locate(&self.cmd)?.parent().map(Path::to_path_buf)
+    }
+
     /// Get the [Command] of the [E4Command].
     pub fn get_cmd(&self) -> &String {
         &self.cmd
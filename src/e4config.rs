@@ -1,33 +1,64 @@
-use crate::{e4initialize, tr, translations::Translations};
+use crate::{
+    e4events::{self, E4Event},
+    e4hooks::E4Hooks,
+    e4initialize,
+    e4storage::{E4Storage, FsStorage},
+    e4style::{E4Alignment, E4Modifier, E4Style, MOVE_MODIFIER},
+    tr,
+    translations::Translations,
+};
 use configparser::ini::Ini;
 use fltk::{app, misc::Spinner, prelude::*, window::Window};
 use std::{
-    env,
     path::{Path, PathBuf},
-    process::Command,
     sync::{Arc, Mutex},
-    thread,
 };
 
 /// Sections in the configuration files.
 /// e4docker.conf.
 pub const E4DOCKER_DOCKER_SECTION: &str = "E4DOCKER";
 pub const E4DOCKER_BUTTON_SECTION: &str = "BUTTONS";
+pub const E4DOCKER_HOOKS_SECTION: &str = "HOOKS";
+
+pub(crate) const E4DOCKER_NUMBER_OF_BUTTONS: &str = "NUMBER_OF_BUTTONS";
+pub(crate) const E4DOCKER_MARGIN_BETWEEN_BUTTONS: &str = "MARGIN_BETWEEN_BUTTONS";
+pub(crate) const E4DOCKER_FRAME_MARGIN: &str = "FRAME_MARGIN";
+pub(crate) const E4DOCKER_ICON_WIDTH: &str = "ICON_WIDTH";
+pub(crate) const E4DOCKER_ICON_HEIGHT: &str = "ICON_HEIGHT";
+pub(crate) const E4DOCKER_X: &str = "X";
+pub(crate) const E4DOCKER_Y: &str = "Y";
+
+/// The smallest and the largest size of the icons, before the scale.
+const MIN_ICON_SIZE: i32 = 16;
+const MAX_ICON_SIZE: i32 = 512;
+
+/// The size of the icons when e4docker.conf does not set it.
+const DEFAULT_ICON_SIZE: i32 = 32;
 
-const E4DOCKER_MARGIN_BETWEEN_BUTTONS: &str = "MARGIN_BETWEEN_BUTTONS";
-const E4DOCKER_FRAME_MARGIN: &str = "FRAME_MARGIN";
-const E4DOCKER_ICON_WIDTH: &str = "ICON_WIDTH";
-const E4DOCKER_ICON_HEIGHT: &str = "ICON_HEIGHT";
+/// The largest margins, before the scale: wider ones push the buttons out of the screen.
+const MAX_MARGIN: i32 = 200;
+
+/// The name of the entries of [E4DOCKER_BUTTON_SECTION] making a flexible space between the buttons.
+pub const E4DOCKER_SPACER: &str = "|";
 
 /// A button configuration file.
 pub const BUTTON_BUTTON_SECTION: &str = "BUTTON";
 
 // Definisci un tipo di errore personalizzato
 #[derive(Debug)]
-struct E4Error {
+pub struct E4Error {
     details: String,
 }
 
+impl E4Error {
+    /// Create an error showing details.
+    pub fn new(details: &str) -> Self {
+        Self {
+            details: details.to_string(),
+        }
+    }
+}
+
 // Implementa il tratto `std::fmt::Display` per il tuo tipo di errore
 impl std::fmt::Display for E4Error {
     fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -37,6 +68,68 @@ impl std::fmt::Display for E4Error {
 
 impl std::error::Error for E4Error {}
 
+/// A value of e4docker.conf out of its range, replaced by the nearest value allowed when read.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct E4ClampedValue {
+    /// The key of the value in e4docker.conf.
+    pub key: String,
+    /// The value written in e4docker.conf.
+    pub value: i32,
+    /// The value used instead.
+    pub used: i32,
+}
+
+/// Read the integer of key in the E4DOCKER section of config, default if missing. A value out of range
+/// is replaced by the nearest one in range and added to clamped.
+fn read_in_range(
+    config: &Ini,
+    key: &str,
+    default: i32,
+    range: std::ops::RangeInclusive<i32>,
+    clamped: &mut Vec<E4ClampedValue>,
+) -> Result<i32, Box<dyn std::error::Error>> {
+    let value: i32 = match config.get(E4DOCKER_DOCKER_SECTION, key) {
+        Some(val) => val.trim().parse()?,
+        None => return Ok(default),
+    };
+    let used = value.clamp(*range.start(), *range.end());
+    if used != value {
+        clamped.push(E4ClampedValue {
+            key: key.to_lowercase(),
+            value,
+            used,
+        });
+    }
+    Ok(used)
+}
+
+/// Changes of e4docker.conf staged in memory and written at once by [E4Config::commit]:
+/// a failure leaves the file as it was, instead of with some of them.
+pub struct E4ConfigTransaction {
+    config: Ini,
+    buttons: Option<Vec<String>>,
+}
+
+impl E4ConfigTransaction {
+    /// Stage the value of key in section, or its removal if value is None.
+    pub fn set_value(&mut self, section: &str, key: &str, value: Option<String>) {
+        match value {
+            Some(value) => {
+                self.config.set(section, key, Some(value));
+            }
+            None => {
+                self.config.remove_key(section, key);
+            }
+        }
+    }
+
+    /// Stage the buttons, numbered from button1, and their number, removing the entries after the last one.
+    pub fn set_buttons(&mut self, buttons: &[String]) {
+        renumber_buttons(&mut self.config, buttons);
+        self.buttons = Some(buttons.to_vec());
+    }
+}
+
 /// The configuration of e4docker read from e4docker.conf.
 pub struct E4Config {
     pub config_dir: PathBuf,
@@ -50,6 +143,13 @@ pub struct E4Config {
     pub icon_height: i32,
     pub x: i32,
     pub y: i32,
+    pub hooks: E4Hooks,
+    /// The appearance of the dock
+    pub style: E4Style,
+    /// Where the configuration files are read and written
+    pub storage: Arc<dyn E4Storage>,
+    /// The values of e4docker.conf out of their range, replaced when read
+    pub clamped: Vec<E4ClampedValue>,
 }
 
 /// Create the about dialog.
@@ -81,9 +181,17 @@ pub fn create_about_dialog(message: &str, translations: Arc<Mutex<Translations>>
         let mut wind = wind.clone();
         move |_| wind.hide()
     });
+    crate::e4dialog::set_keys(
+        &mut wind,
+        &mut ok_btn,
+        || false,
+        || {},
+        translations.clone(),
+    );
 
     wind.make_modal(true);
     wind.end();
+    crate::e4screen::place_dialog(&mut wind);
     wind.show();
 
     // Run modal window
@@ -92,56 +200,133 @@ pub fn create_about_dialog(message: &str, translations: Arc<Mutex<Translations>>
     }
 }
 
-/// Restart the program.
-pub fn restart_app(translations: Arc<Mutex<Translations>>) {
-    // Get the current exe
-    let current_exe = env::current_exe().expect(&tr!(
-        translations,
-        get_or_default,
-        "failed-to-get-current-executable-path",
-        "Failed to get current executable path"
-    ));
+/// Get a temporary file name for storing temporary configuration data.
+pub fn get_tmp_file() -> PathBuf {
+    let package_name = env!("CARGO_PKG_NAME");
+    let mut tmp_file = std::env::temp_dir().join(package_name);
+    tmp_file.set_extension("conf");
+    tmp_file
+}
 
-    // Get the args
-    let args: Vec<String> = env::args().collect();
+/// Return the path of e4docker.conf in config_dir.
+pub fn config_file(config_dir: &Path) -> PathBuf {
+    let mut config_file = config_dir.join(env!("CARGO_PKG_NAME"));
+    config_file.set_extension("conf");
+    config_file
+}
 
-    let translations_clone = translations.clone();
+/// Return the path of the configuration file of the button name in config_dir.
+pub fn button_file(config_dir: &Path, name: &str) -> PathBuf {
+    let mut button_file = config_dir.join(name);
+    button_file.set_extension("conf");
+    button_file
+}
 
-    if args.len() > 1 {
-        thread::spawn(move || {
-            let _ = Command::new(&current_exe)
-                .args(&args[1..])
-                .spawn()
-                .expect(&tr!(
-                    translations_clone,
-                    get_or_default,
-                    "failed-to-restart-the-program",
-                    "Failed to restart the program"
-            ));
-            // End the current process
-            std::process::exit(0);
-        });
+/// Return true if a and b name the same button.
+/// The names of the .conf files ignore the case on Windows: so do the names of the buttons.
+pub fn same_button_name(a: &str, b: &str) -> bool {
+    if cfg!(windows) {
+        a.to_lowercase() == b.to_lowercase()
     } else {
-        thread::spawn(move || {
-            let _ = Command::new(&current_exe).spawn().expect(&tr!(
-                translations_clone,
-                get_or_default,
-                "failed-to-restart-the-program",
-                "Failed to restart the program"
-            ));
-
-            // End the current process
-            std::process::exit(0);
+        a == b
+    }
+}
+
+/// Return true if the entry name of [E4DOCKER_BUTTON_SECTION] is a spacer, not a button.
+pub fn is_spacer(name: &str) -> bool {
+    name.trim() == E4DOCKER_SPACER
+}
+
+/// Return the entries of the BUTTONS section of config, button1, button2..., with their number, in their order.
+/// The empty entries are skipped.
+fn button_entries(config: &Ini) -> Vec<(u32, String)> {
+    let mut entries: Vec<(u32, String)> = config
+        .get_map_ref()
+        .get(&E4DOCKER_BUTTON_SECTION.to_lowercase())
+        .map(|section| {
+            section
+                .iter()
+                .filter_map(|(key, value)| {
+                    let number = key.strip_prefix("button")?.parse().ok()?;
+                    match value.as_deref().map(str::trim) {
+                        Some(name) if !name.is_empty() => Some((number, name.to_string())),
+                        _ => None,
+                    }
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    entries.sort_by_key(|(number, _)| *number);
+    entries
+}
+
+/// Return the buttons of the BUTTONS section of config in the order of their entries, even if
+/// their numbers have gaps or disagree with NUMBER_OF_BUTTONS.
+pub(crate) fn numbered_buttons(config: &Ini) -> Vec<String> {
+    button_entries(config)
+        .into_iter()
+        .map(|(_, name)| name)
+        .collect()
+}
+
+/// Return true if the entries of the BUTTONS section of config are numbered from button1 to buttonN,
+/// N being NUMBER_OF_BUTTONS.
+pub(crate) fn has_consistent_buttons(config: &Ini) -> Result<bool, Box<dyn std::error::Error>> {
+    let number_of_buttons: Option<usize> =
+        match config.get(E4DOCKER_DOCKER_SECTION, E4DOCKER_NUMBER_OF_BUTTONS) {
+            Some(val) => Some(val.trim().parse()?),
+            None => None,
+        };
+    let entries = button_entries(config);
+    Ok(number_of_buttons == Some(entries.len())
+        && entries
+            .iter()
+            .enumerate()
+            .all(|(index, (number, _))| *number as usize == index + 1))
+}
+
+/// Replace the entries of the BUTTONS section of config and NUMBER_OF_BUTTONS with buttons,
+/// numbered from button1.
+pub(crate) fn renumber_buttons(config: &mut Ini, buttons: &[String]) {
+    if let Some(section) = config
+        .get_mut_map()
+        .get_mut(&E4DOCKER_BUTTON_SECTION.to_lowercase())
+    {
+        section.retain(|key, _| {
+            key.strip_prefix("button")
+                .is_none_or(|number| number.parse::<u32>().is_err())
         });
     }
+    for (i, button) in buttons.iter().enumerate() {
+        config.set(
+            E4DOCKER_BUTTON_SECTION,
+            &format!("button{}", i + 1),
+            Some(button.clone()),
+        );
+    }
+    config.set(
+        E4DOCKER_DOCKER_SECTION,
+        E4DOCKER_NUMBER_OF_BUTTONS,
+        Some(buttons.len().to_string()),
+    );
 }
 
-/// Get a temporary file name for storing temporary configuration data.
-pub fn get_tmp_file() -> PathBuf {
-    let package_name = env!("CARGO_PKG_NAME");
-    let mut tmp_file = std::env::temp_dir().join(package_name);
-    tmp_file.set_extension("conf");
-    tmp_file
+/// Return the width and the height of the window holding number_of_buttons buttons.
+pub fn window_size(
+    number_of_buttons: i32,
+    icon_width: i32,
+    icon_height: i32,
+    margin_between_buttons: i32,
+    frame_margin: i32,
+) -> (i32, i32) {
+    // Calculates the window width
+    let window_width = (number_of_buttons * icon_width)
+        + (number_of_buttons * margin_between_buttons)
+        + (frame_margin * 2);
+
+    // Calculates the window height, adding margin * 4 for the 4 sides frame margin
+    let window_height = icon_height + (frame_margin * 4);
+    (window_width, window_height)
 }
 
 impl std::clone::Clone for E4Config {
@@ -158,6 +343,10 @@ impl std::clone::Clone for E4Config {
             icon_height: self.icon_height,
             x: self.x,
             y: self.y,
+            hooks: self.hooks.clone(),
+            style: self.style.clone(),
+            storage: self.storage.clone(),
+            clamped: self.clamped.clone(),
         }
     }
 }
@@ -168,15 +357,19 @@ impl E4Config {
         &mut self,
         translations: Arc<Mutex<Translations>>,
     ) -> Result<(), Box<dyn std::error::Error>> {
-        let mut window = Window::default().with_size(700, 300);
+        let mut window = Window::default().with_size(700, 340);
         let mut grid = fltk_grid::Grid::default()
-            .with_size(650, 250)
+            .with_size(650, 290)
             .center_of(&window);
         grid.show_grid(false);
         grid.set_gap(10, 10);
-        let grid_values = [self.icon_width as f64, self.icon_height as f64];
+        // The sizes in e4docker.conf, before the scale
+        let grid_values = [
+            self.style.unscaled(self.icon_width) as f64,
+            self.style.unscaled(self.icon_height) as f64,
+        ];
         let ncols = 2;
-        let nrows = 3;
+        let nrows = 4;
         grid.set_layout(nrows, ncols);
 
         let labels = [
@@ -188,7 +381,7 @@ impl E4Config {
         let mut icon_width_label = fltk::frame::Frame::default().with_label(labels[0]);
         let mut icon_width_input = Spinner::default();
         icon_width_input.set_step(1.0);
-        icon_width_input.set_range(16.0, 512.0);
+        icon_width_input.set_range(MIN_ICON_SIZE as f64, MAX_ICON_SIZE as f64);
         icon_width_input.set_value(grid_values[0]);
         grid.set_widget(&mut icon_width_label, 0, 0)?;
         grid.set_widget(&mut icon_width_input, 0, 1)?;
@@ -196,11 +389,31 @@ impl E4Config {
         let mut icon_height_label = fltk::frame::Frame::default().with_label(labels[1]);
         let mut icon_height_input = Spinner::default();
         icon_height_input.set_step(1.0);
-        icon_height_input.set_range(16.0, 512.0);
+        icon_height_input.set_range(MIN_ICON_SIZE as f64, MAX_ICON_SIZE as f64);
         icon_height_input.set_value(grid_values[1]);
         grid.set_widget(&mut icon_height_label, 1, 0)?;
         grid.set_widget(&mut icon_height_input, 1, 1)?;
 
+        let mut move_modifier_label = fltk::frame::Frame::default().with_label(&tr!(
+            translations,
+            get_or_default,
+            "move-modifier",
+            "Move the dock by dragging"
+        ));
+        let mut move_modifier_input = fltk::menu::Choice::default();
+        for modifier in E4Modifier::ALL {
+            let key = format!("move-modifier-{}", modifier.name());
+            move_modifier_input.add_choice(&tr!(translations, get_or_default, &key, &key));
+        }
+        move_modifier_input.set_value(
+            E4Modifier::ALL
+                .iter()
+                .position(|modifier| *modifier == self.style.move_modifier)
+                .unwrap_or(0) as i32,
+        );
+        grid.set_widget(&mut move_modifier_label, 2, 0)?;
+        grid.set_widget(&mut move_modifier_input, 2, 1)?;
+
         // Add Save button at the bottom
         let mut save_button = fltk::button::Button::new(
             200,
@@ -209,33 +422,94 @@ impl E4Config {
             30,
             tr!(translations, get_or_default, "save", "Save").as_str(),
         );
-        grid.set_widget(&mut save_button, 2, 0..2)?;
+        grid.set_widget(&mut save_button, 3, 0..2)?;
 
+        // The values shown, to ask whether to save them once changed
+        let is_dirty = {
+            let icon_width_input = icon_width_input.clone();
+            let icon_height_input = icon_height_input.clone();
+            let move_modifier_input = move_modifier_input.clone();
+            let move_modifier = move_modifier_input.value();
+            move || {
+                icon_width_input.value() != grid_values[0]
+                    || icon_height_input.value() != grid_values[1]
+                    || move_modifier_input.value() != move_modifier
+            }
+        };
         save_button.set_callback({
             let mut wind = window.clone();
             let mut myself = self.clone();
+            let translations = translations.clone();
             move |_| {
-                let icon_width = (icon_width_input.value() as i32).to_string();
-                let icon_height = (icon_height_input.value() as i32).to_string();
-                wind.hide();
-                myself.set_value(
-                    E4DOCKER_DOCKER_SECTION.to_string(),
-                    E4DOCKER_ICON_WIDTH.to_string(),
-                    Some(icon_width),
-                    translations.clone(),
-                );
-                myself.set_value(
-                    E4DOCKER_DOCKER_SECTION.to_string(),
-                    E4DOCKER_ICON_HEIGHT.to_string(),
-                    Some(icon_height),
-                    translations.clone(),
+                let icon_width = icon_width_input.value().round() as i32;
+                let icon_height = icon_height_input.value().round() as i32;
+                // The spinners take any typed value
+                let sizes = MIN_ICON_SIZE..=MAX_ICON_SIZE;
+                if !sizes.contains(&icon_width) || !sizes.contains(&icon_height) {
+                    let message = tr!(
+                        translations,
+                        format,
+                        "icon-size-out-of-range",
+                        &[&MIN_ICON_SIZE.to_string(), &MAX_ICON_SIZE.to_string()]
+                    );
+                    fltk::dialog::alert_default(&message);
+                    return;
+                }
+                let (window_width, _) = window_size(
+                    myself.buttons.len() as i32,
+                    myself.style.scaled(icon_width),
+                    myself.style.scaled(icon_height),
+                    myself.margin_between_buttons,
+                    myself.frame_margin,
                 );
-                crate::e4config::restart_app(translations.clone());
+                if window_width > app::screen_size().0 as i32 {
+                    let message = tr!(
+                        translations,
+                        format,
+                        "dock-wider-than-the-screen",
+                        &[&icon_width.to_string()]
+                    );
+                    fltk::dialog::alert_default(&message);
+                    return;
+                }
+                let move_modifier = E4Modifier::ALL
+                    .get(move_modifier_input.value().max(0) as usize)
+                    .copied()
+                    .unwrap_or_default();
+                wind.hide();
+                let result = myself
+                    .save_icon_size(icon_width, icon_height, translations.clone())
+                    .and_then(|_| {
+                        myself.set_value(
+                            E4DOCKER_DOCKER_SECTION.to_string(),
+                            MOVE_MODIFIER.to_string(),
+                            Some(move_modifier.name().to_string()),
+                            translations.clone(),
+                        )
+                    });
+                if let Err(e) = result {
+                    let message = tr!(
+                        translations,
+                        format,
+                        "error-in-saving-settings",
+                        &[&e.to_string()]
+                    );
+                    fltk::dialog::alert_default(&message);
+                }
+                e4events::publish(E4Event::ConfigChanged);
             }
         });
+        crate::e4dialog::set_keys(
+            &mut window,
+            &mut save_button,
+            is_dirty,
+            || {},
+            translations.clone(),
+        );
 
         window.make_modal(true);
         window.end();
+        crate::e4screen::place_dialog(&mut window);
         window.show();
         // Run modal window
         while window.shown() {
@@ -248,82 +522,111 @@ impl E4Config {
     pub fn read(
         config_dir: &Path,
         translations: Arc<Mutex<Translations>>,
+    ) -> Result<Self, Box<dyn std::error::Error>> {
+        Self::read_from(
+            Arc::new(FsStorage),
+            config_dir,
+            &e4initialize::get_package_assets_dir(translations.clone()),
+        )
+    }
+
+    /// Read the configuration from config_dir/e4docker.conf in storage, with the icons in assets_dir.
+    pub fn read_from(
+        storage: Arc<dyn E4Storage>,
+        config_dir: &Path,
+        assets_dir: &Path,
     ) -> Result<Self, Box<dyn std::error::Error>> {
         // Read the config file
-        let package_name = env!("CARGO_PKG_NAME");
-        let mut config_file = config_dir.join(package_name);
-        config_file.set_extension("conf");
-        let mut config = Ini::new();
-        let _ = config.load(config_file)?;
+        let mut config = storage.load_ini(&config_file(config_dir))?;
 
         // Read the x position of the window
         let mut x: i32 = 0;
         let mut y: i32 = 0;
-        let mut number_of_buttons: i32 = 0;
-        let mut margin_between_buttons: i32 = 0;
-        let mut frame_margin: i32 = 0;
-        let mut icon_width: i32 = 0;
-        let mut icon_height: i32 = 0;
+        // The values breaking the window are replaced, telling the user
+        let mut clamped = vec![];
 
         // Read the x coordinate of the docker
-        if let Some(val) = config.get(E4DOCKER_DOCKER_SECTION, "X") {
+        if let Some(val) = config.get(E4DOCKER_DOCKER_SECTION, E4DOCKER_X) {
             x = val.parse()?;
         }
 
         // Read the y coordinate of the docker
-        if let Some(val) = config.get(E4DOCKER_DOCKER_SECTION, "Y") {
+        if let Some(val) = config.get(E4DOCKER_DOCKER_SECTION, E4DOCKER_Y) {
             y = val.parse()?;
         }
 
-        // Read the number of buttons
-        if let Some(val) = config.get(E4DOCKER_DOCKER_SECTION, "NUMBER_OF_BUTTONS") {
-            number_of_buttons = val.parse()?;
-        };
-
-        // Read the margin between the buttons
-        if let Some(val) = config.get(E4DOCKER_DOCKER_SECTION, E4DOCKER_MARGIN_BETWEEN_BUTTONS) {
-            margin_between_buttons = val.parse()?;
-        };
-
-        // Read the margin between the buttons
-        if let Some(val) = config.get(E4DOCKER_DOCKER_SECTION, E4DOCKER_FRAME_MARGIN) {
-            frame_margin = val.parse()?;
-        };
-
-        // Read the buttons
-        let mut buttons = vec![];
-        for n in 1..=number_of_buttons {
-            let button_key = format!("button{}", n);
-            let mut button_name: String = "".to_string();
-            if let Some(val) = config.get(E4DOCKER_BUTTON_SECTION, &button_key) {
-                button_name = val;
-            };
-            buttons.push(button_name);
+        // Read the buttons, repairing their entries if they disagree with their number, as after a crash
+        // while saving them. A read-only configuration is only repaired in memory.
+        let buttons = numbered_buttons(&config);
+        if !has_consistent_buttons(&config)? {
+            log::warn!(
+                "Renumbering the buttons of {}",
+                config_file(config_dir).display()
+            );
+            renumber_buttons(&mut config, &buttons);
+            if !e4initialize::is_read_only() {
+                if let Err(e) = storage.write_ini(&config_file(config_dir), &config) {
+                    log::warn!("{}", e);
+                }
+            }
         }
+        let number_of_buttons = buttons.len() as i32;
 
-        // Read the buttons width (the same as the icons width)
-        if let Some(val) = config.get(E4DOCKER_DOCKER_SECTION, E4DOCKER_ICON_WIDTH) {
-            icon_width = val.parse()?;
-        };
+        // Read the margin between the buttons
+        let margin_between_buttons = read_in_range(
+            &config,
+            E4DOCKER_MARGIN_BETWEEN_BUTTONS,
+            0,
+            0..=MAX_MARGIN,
+            &mut clamped,
+        )?;
 
-        // Read the buttons height (the same as the icons height)
-        if let Some(val) = config.get(E4DOCKER_DOCKER_SECTION, E4DOCKER_ICON_HEIGHT) {
-            icon_height = val.parse()?;
-        };
+        // Read the margin of the frame
+        let frame_margin = read_in_range(
+            &config,
+            E4DOCKER_FRAME_MARGIN,
+            0,
+            0..=MAX_MARGIN,
+            &mut clamped,
+        )?;
 
-        // Calculates the window width
-        let window_width = (number_of_buttons * icon_width)
-            + (number_of_buttons * margin_between_buttons)
-            + (frame_margin * 2);
+        // Read the buttons width and height (the same as the icons ones)
+        let icon_width = read_in_range(
+            &config,
+            E4DOCKER_ICON_WIDTH,
+            DEFAULT_ICON_SIZE,
+            MIN_ICON_SIZE..=MAX_ICON_SIZE,
+            &mut clamped,
+        )?;
+        let icon_height = read_in_range(
+            &config,
+            E4DOCKER_ICON_HEIGHT,
+            DEFAULT_ICON_SIZE,
+            MIN_ICON_SIZE..=MAX_ICON_SIZE,
+            &mut clamped,
+        )?;
 
-        // Calculates the window height, adding margin * 4 for the 4 sides frame margin
-        let window_height = icon_height + (frame_margin * 4);
+        // The sizes are zoomed by the scale
+        let style = E4Style::from_ini(&config)?;
+        let margin_between_buttons = style.scaled(margin_between_buttons);
+        let frame_margin = style.scaled(frame_margin);
+        let icon_width = style.scaled(icon_width);
+        let icon_height = style.scaled(icon_height);
+        let (window_width, window_height) = window_size(
+            number_of_buttons,
+            icon_width,
+            icon_height,
+            margin_between_buttons,
+            frame_margin,
+        );
+        // The room left by the buttons is shared according to the alignment
+        let window_width = window_width.max(style.scaled(style.min_width));
 
         // Return the configuration
         Ok(Self {
             config_dir: config_dir.to_path_buf(),
             buttons,
-            assets_dir: e4initialize::get_package_assets_dir(translations.clone()),
+            assets_dir: assets_dir.to_path_buf(),
             margin_between_buttons,
             frame_margin,
             window_width,
@@ -332,64 +635,342 @@ impl E4Config {
             icon_height,
             x,
             y,
+            hooks: E4Hooks::from_ini(&config),
+            style,
+            storage,
+            clamped,
         })
     }
 
+    /// Load e4docker.conf.
+    fn load(
+        &self,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<Ini, Box<dyn std::error::Error>> {
+        match self.storage.load_ini(&config_file(&self.config_dir)) {
+            Ok(config) => Ok(config),
+            Err(e) => Err(Box::new(E4Error {
+                details: tr!(
+                    translations,
+                    format,
+                    "cannot-load-e4docker-conf",
+                    &[e.as_str()]
+                ),
+            })),
+        }
+    }
+
+    /// Write e4docker.conf.
+    fn write(
+        &self,
+        config: &Ini,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if e4initialize::is_read_only() {
+            return Err(Box::new(E4Error {
+                details: tr!(
+                    translations,
+                    format,
+                    "read-only-configuration",
+                    &[&self.config_dir.display().to_string()]
+                ),
+            }));
+        }
+        match self
+            .storage
+            .write_ini(&config_file(&self.config_dir), config)
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Box::new(E4Error {
+                details: format!(
+                    "{}: {}",
+                    tr!(
+                        translations,
+                        get_or_default,
+                        "cannot-save-e4docker-conf",
+                        "Cannot save e4docker.conf"
+                    ),
+                    e
+                ),
+            })),
+        }
+    }
+
     /// Get a value from the configuration file.
     pub fn get_value(
         &mut self,
         section: String,
         key: String,
         translations: Arc<Mutex<Translations>>,
-    ) -> Option<String> {
+    ) -> Result<Option<String>, Box<dyn std::error::Error>> {
         // Read the config file
-        let package_name = env!("CARGO_PKG_NAME");
-        let mut config_file = self.config_dir.join(package_name);
-        config_file.set_extension("conf");
-        let mut config = Ini::new();
-        let result = config.load(&config_file);
-        match result {
-            Ok(_) => (),
-            Err(e) => {
-                let message = tr!(
+        let config = self.load(translations)?;
+        // Get and return the key and the value
+        Ok(config.get(&section, &key))
+    }
+
+    /// Return the names of the buttons, without the spacers.
+    pub fn button_names(&self) -> impl Iterator<Item = &String> {
+        self.buttons.iter().filter(|name| !is_spacer(name))
+    }
+
+    /// Return the x coordinate of every button, without the spacers, in the window.
+    ///
+    /// Every entry takes a slot, spacers included. The room left when the window is wider,
+    /// for its minimum width, is shared by the spacers or, without spacers, placed according to the alignment.
+    pub fn button_positions(&self) -> Vec<i32> {
+        let slot = self.icon_width + self.margin_between_buttons;
+        let (width, _) = window_size(
+            self.buttons.len() as i32,
+            self.icon_width,
+            self.icon_height,
+            self.margin_between_buttons,
+            self.frame_margin,
+        );
+        let room = (self.window_width - width).max(0);
+        let spacers = self.buttons.iter().filter(|name| is_spacer(name)).count() as i32;
+        let buttons = self.buttons.len() as i32 - spacers;
+        let (offset, gap) = match self.style.alignment {
+            _ if spacers > 0 => (0, 0),
+            E4Alignment::Start => (0, 0),
+            E4Alignment::Center => (room / 2, 0),
+            E4Alignment::End => (room, 0),
+            E4Alignment::SpaceBetween if buttons > 1 => (0, room / (buttons - 1)),
+            E4Alignment::SpaceBetween => (0, 0),
+        };
+
+        let mut x = self.margin_between_buttons + offset;
+        let mut spacer = 0;
+        let mut positions = vec![];
+        for name in &self.buttons {
+            if is_spacer(name) {
+                // The first spacers take what is left by the division
+                x += slot + room / spacers + i32::from(spacer < room % spacers);
+                spacer += 1;
+            } else {
+                positions.push(x);
+                x += slot + gap;
+            }
+        }
+        positions
+    }
+
+    /// Return the size of the icons, before the scale, filling a window width pixels wide:
+    /// the height keeps the proportion of the icons, and both stay between the sizes allowed by the settings.
+    pub fn icon_size_for_width(&self, width: i32) -> (i32, i32) {
+        let icon_width = self.style.unscaled(self.icon_width);
+        let icon_height = self.style.unscaled(self.icon_height);
+        let slots = self.buttons.len() as i32;
+        if slots == 0 || icon_width <= 0 {
+            return (icon_width, icon_height);
+        }
+        // The inverse of window_size
+        let new_width = self
+            .style
+            .unscaled((width - self.frame_margin * 2) / slots - self.margin_between_buttons)
+            .clamp(MIN_ICON_SIZE, MAX_ICON_SIZE);
+        let new_height = (icon_height as f64 * new_width as f64 / icon_width as f64).round() as i32;
+        (new_width, new_height.clamp(MIN_ICON_SIZE, MAX_ICON_SIZE))
+    }
+
+    /// Save the size of the icons, before the scale, in e4docker.conf.
+    pub fn save_icon_size(
+        &mut self,
+        width: i32,
+        height: i32,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.set_value(
+            E4DOCKER_DOCKER_SECTION.to_string(),
+            E4DOCKER_ICON_WIDTH.to_string(),
+            Some(width.to_string()),
+            translations.clone(),
+        )?;
+        self.set_value(
+            E4DOCKER_DOCKER_SECTION.to_string(),
+            E4DOCKER_ICON_HEIGHT.to_string(),
+            Some(height.to_string()),
+            translations,
+        )
+    }
+
+    /// Return the x coordinate of the button at index, without counting the spacers.
+    pub fn button_x(&self, index: usize) -> i32 {
+        self.button_positions().get(index).copied().unwrap_or(
+            self.margin_between_buttons
+                + index as i32 * (self.icon_width + self.margin_between_buttons),
+        )
+    }
+
+    /// Return true if another button is named name.
+    /// The button renamed, if any, is old_name: it does not count.
+    pub fn is_button_name_taken(&self, name: &str, old_name: Option<&str>) -> bool {
+        self.buttons
+            .iter()
+            .filter(|button| Some(button.as_str()) != old_name)
+            .any(|button| same_button_name(button, name))
+    }
+
+    /// Return name followed by the first suffix, like "-2", giving a name no button has.
+    pub fn unique_button_name(&self, name: &str) -> String {
+        (2..)
+            .map(|n| format!("{}-{}", name, n))
+            .find(|candidate| !self.is_button_name_taken(candidate, None))
+            .unwrap_or_else(|| name.to_string())
+    }
+
+    /// Return name(n) for the first n, from 1, giving a name no button has: the default name of a new button.
+    pub fn numbered_button_name<F: Fn(usize) -> String>(&self, name: F) -> String {
+        (1..)
+            .map(name)
+            .find(|candidate| !self.is_button_name_taken(candidate, None))
+            .unwrap_or_default()
+    }
+
+    /// Start staging changes of e4docker.conf, to be written at once by [E4Config::commit].
+    pub fn transaction(
+        &self,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<E4ConfigTransaction, Box<dyn std::error::Error>> {
+        Ok(E4ConfigTransaction {
+            config: self.load(translations)?,
+            buttons: None,
+        })
+    }
+
+    /// Write the changes staged in transaction to e4docker.conf, in one write.
+    /// The buttons of the configuration change only if it succeeds.
+    pub fn commit(
+        &mut self,
+        transaction: E4ConfigTransaction,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.write(&transaction.config, translations)?;
+        if let Some(buttons) = transaction.buttons {
+            self.buttons = buttons;
+        }
+        Ok(())
+    }
+
+    /// Apply change to a copy of the buttons, spacers included, and save them in config_dir/e4docker.conf,
+    /// numbered from button1, with their number. The entries after the last button are removed: the callers
+    /// do not renumber them. Starting from all the entries, a change cannot lose the ones it does not know of.
+    pub fn update_buttons<F: FnOnce(&mut Vec<String>)>(
+        &mut self,
+        change: F,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut buttons = self.buttons.clone();
+        change(&mut buttons);
+        let mut transaction = self.transaction(translations.clone())?;
+        transaction.set_buttons(&buttons);
+        self.commit(transaction, translations)
+    }
+
+    /// Add the button name at index, writing its configuration file button.
+    pub fn insert_button(
+        &mut self,
+        index: usize,
+        name: &str,
+        button: &Ini,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.storage
+            .write_ini(&button_file(&self.config_dir, name), button)
+            .map_err(|e| {
+                E4Error::new(&tr!(
                     translations,
                     format,
-                    "cannot-load-e4docker-conf",
-                    &[&e.to_string()]
-                );
-                fltk::dialog::alert_default(&message);
+                    "cannot-save",
+                    &[name, &e.to_string()]
+                ))
+            })?;
+        self.update_buttons(
+            |buttons| buttons.insert(index.min(buttons.len()), name.to_string()),
+            translations,
+        )
+    }
+
+    /// Rename the button old_name as name: its entry in e4docker.conf, in its place, and its configuration file.
+    pub fn rename_button(
+        &mut self,
+        old_name: &str,
+        name: &str,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let name = name.trim();
+        // The name is the name of the configuration file
+        if name.is_empty() || name.contains(['/', '\\']) {
+            return Err(Box::new(E4Error::new(&tr!(
+                translations,
+                format,
+                "invalid-button-name",
+                &[&name]
+            ))));
+        }
+        if name == old_name {
+            return Ok(());
+        }
+        if self.is_button_name_taken(name, Some(old_name)) {
+            return Err(Box::new(E4Error::new(&tr!(
+                translations,
+                format,
+                "button-name-already-exists",
+                &[&name, &self.unique_button_name(name)]
+            ))));
+        }
+        let old_file = button_file(&self.config_dir, old_name);
+        let new_file = button_file(&self.config_dir, name);
+        let contents = self.storage.read_to_string(&old_file).map_err(|e| {
+            E4Error::new(&tr!(
+                translations,
+                format,
+                "cannot-load-the-button-config-file",
+                &[&e.to_string()]
+            ))
+        })?;
+        self.storage.write(&new_file, &contents).map_err(|e| {
+            E4Error::new(&tr!(
+                translations,
+                format,
+                "cannot-save",
+                &[name, &e.to_string()]
+            ))
+        })?;
+        let renamed = |buttons: &mut Vec<String>| {
+            for button in buttons.iter_mut().filter(|button| *button == old_name) {
+                *button = name.to_string();
             }
         };
-        // Get and return the key and the value
-        config.get(&section, &key)
-    }
-
-    /// Save the buttons in config_dir/e4docker.conf.
-    pub fn save_buttons(&mut self, buttons: &[String], translations: Arc<Mutex<Translations>>) {
-        // Save the buttons
-        for (i, button) in buttons.iter().enumerate() {
-            let key = format!("button{}", i + 1);
-            self.set_value(
-                E4DOCKER_BUTTON_SECTION.to_string(),
-                key,
-                Some(button.to_string()),
-                translations.clone(),
-            );
+        if let Err(e) = self.update_buttons(renamed, translations) {
+            let _ = self.storage.remove(&new_file);
+            return Err(e);
         }
+        // Only the case changes: on Windows it is the same file
+        if !same_button_name(old_name, name) {
+            let _ = self.storage.remove(&old_file);
+        }
+        Ok(())
     }
 
+    /// Swap the buttons first and second, in their entries of e4docker.conf, and save their order: the spacers
+    /// stay where they are. Publishing [E4Event::ConfigChanged] then draws them again in the same window.
     pub fn swap_buttons(
         &mut self,
-        buttons: &mut [String],
-        first_button_index: usize,
-        second_button_index: usize,
+        first: &str,
+        second: &str,
         translations: Arc<Mutex<Translations>>,
-    ) {
-        let temp_button = buttons[first_button_index].clone();
-        buttons[first_button_index] = buttons[second_button_index].clone();
-        buttons[second_button_index] = temp_button;
-        self.save_buttons(buttons, translations.clone());
-        crate::e4config::restart_app(translations.clone())
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let position = |name: &str| {
+            self.buttons
+                .iter()
+                .position(|button| button == name)
+                .ok_or_else(|| {
+                    E4Error::new(&tr!(translations, format, "button-not-found", &[name]))
+                })
+        };
+        let (first, second) = (position(first)?, position(second)?);
+        self.update_buttons(|buttons| buttons.swap(first, second), translations)
     }
 
     /// Set a value in the configuration file.
@@ -399,33 +980,12 @@ impl E4Config {
         key: String,
         value: Option<String>,
         translations: Arc<Mutex<Translations>>,
-    ) {
+    ) -> Result<(), Box<dyn std::error::Error>> {
         // Read the config file
-        let package_name = env!("CARGO_PKG_NAME");
-        let mut config_file = self.config_dir.join(package_name);
-        config_file.set_extension("conf");
-        let mut config = Ini::new();
-        let result = config.load(&config_file);
-        match result {
-            Ok(_) => (),
-            Err(e) => {
-                let message = tr!(
-                    translations,
-                    format,
-                    "cannot-load-e4docker-conf",
-                    &[&e.to_string()]
-                );
-                fltk::dialog::alert_default(&message);
-            }
-        };
+        let mut config = self.load(translations.clone())?;
         // Set the key and the value
         config.set(&section, &key, value);
-        config.write(config_file).expect(&tr!(
-            translations,
-            get_or_default,
-            "cannot-save-e4docker-conf",
-            "Cannot save e4docker.conf"
-        ));
+        self.write(&config, translations)
     }
 
     /// Get the number of buttons in the configuration file
@@ -436,9 +996,9 @@ impl E4Config {
         let number_of_buttons: i32;
         if let Some(val) = self.get_value(
             E4DOCKER_DOCKER_SECTION.to_string(),
-            String::from("NUMBER_OF_BUTTONS"),
+            E4DOCKER_NUMBER_OF_BUTTONS.to_string(),
             translations.clone(),
-        ) {
+        )? {
             number_of_buttons = val.parse()?;
         } else {
             return Err(Box::new(E4Error {
@@ -454,13 +1014,54 @@ impl E4Config {
     }
 
     /// Set the number of buttons in the configuration file
-    pub fn set_number_of_buttons(&mut self, number: i32, translations: Arc<Mutex<Translations>>) {
+    pub fn set_number_of_buttons(
+        &mut self,
+        number: i32,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         self.set_value(
             E4DOCKER_DOCKER_SECTION.to_string(),
-            String::from("NUMBER_OF_BUTTONS"),
+            E4DOCKER_NUMBER_OF_BUTTONS.to_string(),
             Some(number.to_string()),
             translations.clone(),
-        );
+        )
+    }
+
+    /// Remove the button name: its entry in e4docker.conf and its configuration file.
+    pub fn remove_button(
+        &mut self,
+        name: &str,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        self.remove_buttons(&[name.to_string()], translations)
+    }
+
+    /// Remove the buttons names: their entries in e4docker.conf, written at once, and their configuration files.
+    pub fn remove_buttons(
+        &mut self,
+        names: &[String],
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        // Remove the entries of the buttons to be deleted
+        self.update_buttons(
+            |buttons| buttons.retain(|button| !names.contains(button)),
+            translations.clone(),
+        )?;
+
+        // DON'T Delete the icons
+        for name in names {
+            if let Err(e) = self.storage.remove(&button_file(&self.config_dir, name)) {
+                return Err(Box::new(E4Error {
+                    details: tr!(
+                        translations,
+                        format,
+                        "cannot-remove-the-config-file",
+                        &[&e.to_string()]
+                    ),
+                }));
+            }
+        }
+        Ok(())
     }
 
     /// Delete a key from the configuratio file.
@@ -469,30 +1070,9 @@ impl E4Config {
         section: String,
         key: String,
         translations: Arc<Mutex<Translations>>,
-    ) {
-        let package_name = env!("CARGO_PKG_NAME");
-        let mut config_file = self.config_dir.join(package_name);
-        config_file.set_extension("conf");
-        let mut config = Ini::new();
-        let result = config.load(&config_file);
-        match result {
-            Ok(_) => (),
-            Err(e) => {
-                let message = tr!(
-                    translations,
-                    format,
-                    "cannot-load-e4docker-conf",
-                    &[&e.to_string()]
-                );
-                fltk::dialog::alert_default(&message);
-            }
-        };
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut config = self.load(translations.clone())?;
         config.remove_key(&section, &key);
-        config.write(config_file).expect(&tr!(
-            translations,
-            get_or_default,
-            "cannot-save-e4docker-conf",
-            "Cannot save e4docker.conf"
-        ));
+        self.write(&config, translations)
     }
 }
@@ -0,0 +1,210 @@
+use crate::{e4command::split_arguments, e4icon, e4window, tr, translations::Translations};
+use fltk::{app, browser::HoldBrowser, button::Button, prelude::*, window::Window};
+use std::{
+    cell::RefCell,
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+/// The field codes of Exec, replaced by the files or the URLs opened by the application: the dock opens none.
+const FIELD_CODES: [&str; 13] = [
+    "%f", "%F", "%u", "%U", "%d", "%D", "%n", "%N", "%i", "%c", "%k", "%v", "%m",
+];
+
+/// An application installed by a .desktop entry.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DesktopApp {
+    /// The Name of the application, without the / and \ a button name cannot hold.
+    pub name: String,
+    /// The program launched by Exec.
+    pub command: String,
+    /// The other arguments of Exec, without its field codes.
+    pub arguments: Vec<String>,
+    /// The Icon of the application: the name of an icon of the theme or the path of an image.
+    pub icon: Option<String>,
+}
+
+/// Return the application launched by a .desktop entry, or None if it is hidden from the menus or it is
+/// not an application.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4desktop::desktop_app;
+///
+/// let entry = "[Desktop Entry]\nType=Application\nName=Firefox\nExec=firefox --new-window %u\nIcon=firefox\n";
+/// let app = desktop_app(entry).unwrap();
+/// assert_eq!((app.name.as_str(), app.command.as_str()), ("Firefox", "firefox"));
+/// assert_eq!(app.arguments, vec!["--new-window".to_string()]);
+/// assert_eq!(app.icon, Some("firefox".to_string()));
+/// assert!(desktop_app(&format!("{}NoDisplay=true\n", entry)).is_none());
+/// ```
+pub fn desktop_app(entry: &str) -> Option<DesktopApp> {
+    let mut in_entry = false;
+    let (mut name, mut exec, mut icon) = (None, None, None);
+    for line in entry.lines().map(str::trim) {
+        if line.starts_with('[') {
+            // The actions have their own Name and Exec
+            in_entry = line == "[Desktop Entry]";
+        } else if let (true, Some((key, value))) = (in_entry, line.split_once('=')) {
+            let value = value.trim().to_string();
+            match key.trim() {
+                "Type" if value != "Application" => return None,
+                "NoDisplay" | "Hidden" if value == "true" => return None,
+                "Name" => name = Some(value),
+                "Exec" => exec = Some(value),
+                "Icon" => icon = Some(value).filter(|icon| !icon.is_empty()),
+                _ => {}
+            }
+        }
+    }
+    let name = name
+        .filter(|name| !name.is_empty())?
+        .replace(['/', '\\'], "-");
+    let mut arguments: Vec<String> = split_arguments(&exec?)
+        .into_iter()
+        .filter(|argument| !FIELD_CODES.contains(&argument.as_str()))
+        .map(|argument| argument.replace("%%", "%"))
+        .collect();
+    if arguments.is_empty() {
+        return None;
+    }
+    let command = arguments.remove(0);
+    Some(DesktopApp {
+        name,
+        command,
+        arguments,
+        icon,
+    })
+}
+
+/// Return the applications of the .desktop entries in the applications directories, like
+/// ~/.local/share/applications and /usr/share/applications, sorted by name. The entries of the user
+/// override the ones of the system with the same file name.
+pub fn installed_apps() -> Vec<DesktopApp> {
+    let mut file_names = HashSet::new();
+    let mut apps = vec![];
+    for dir in e4window::data_dirs()
+        .into_iter()
+        .map(|dir| dir.join("applications"))
+    {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().and_then(|extension| extension.to_str()) != Some("desktop")
+                || !file_names.insert(path.file_name().map(|name| name.to_os_string()))
+            {
+                continue;
+            }
+            if let Some(app) = fs::read_to_string(&path)
+                .ok()
+                .and_then(|entry| desktop_app(&entry))
+            {
+                apps.push(app);
+            }
+        }
+    }
+    apps.sort_by_key(|app| app.name.to_lowercase());
+    apps
+}
+
+/// Convert the icon of app to PNG in the assets directory and return its path: None without an icon,
+/// or if it is not a PNG icon of the theme or an image.
+pub fn fetch_app_icon(app: &DesktopApp, assets_dir: &Path) -> Option<PathBuf> {
+    let icon = app.icon.as_ref()?;
+    let path = PathBuf::from(icon);
+    let path = if path.is_absolute() {
+        path
+    } else {
+        e4icon::theme_icon(icon)?
+    };
+    let png_data = e4icon::to_png(&path)
+        .and_then(|png_data| {
+            e4icon::scale_to_fit(&png_data, e4icon::SAVED_ICON_SIZE, e4icon::SAVED_ICON_SIZE)
+        })
+        .ok()?;
+    let mut destination = assets_dir.join(path.file_stem()?);
+    destination.set_extension("png");
+    fs::write(&destination, png_data).ok()?;
+    Some(destination)
+}
+
+/// Show a dialog listing the installed applications and return the chosen one.
+pub fn choose_app(translations: Arc<Mutex<Translations>>) -> Option<DesktopApp> {
+    let mut apps = installed_apps();
+    if apps.is_empty() {
+        fltk::dialog::alert_default(&tr!(
+            translations,
+            get_or_default,
+            "no-applications-found",
+            "No applications found"
+        ));
+        return None;
+    }
+
+    let mut window = Window::default().with_size(400, 450).with_label(&tr!(
+        translations,
+        get_or_default,
+        "choose-an-application",
+        "Choose an application"
+    ));
+    let mut browser = HoldBrowser::new(10, 10, 380, 390, None);
+    // The names are shown as they are, without the @ formatting of the browser
+    browser.set_format_char('\0');
+    for desktop_app in &apps {
+        browser.add(&desktop_app.name);
+    }
+
+    let selected = Rc::new(RefCell::new(None));
+    let mut ok_button = Button::new(
+        150,
+        410,
+        100,
+        30,
+        tr!(translations, get_or_default, "ok", "OK").as_str(),
+    );
+    ok_button.set_callback({
+        let mut window = window.clone();
+        let browser = browser.clone();
+        let selected = selected.clone();
+        move |_| {
+            if browser.value() > 0 {
+                *selected.borrow_mut() = Some(browser.value() as usize - 1);
+            }
+            window.hide();
+        }
+    });
+    // A double click chooses the application
+    browser.set_callback({
+        let mut ok_button = ok_button.clone();
+        move |_| {
+            if app::event_clicks() {
+                ok_button.do_callback();
+            }
+        }
+    });
+    crate::e4dialog::set_keys(
+        &mut window,
+        &mut ok_button,
+        || false,
+        || {},
+        translations.clone(),
+    );
+
+    window.make_modal(true);
+    window.end();
+    crate::e4screen::place_dialog(&mut window);
+    window.show();
+    // Run modal window
+    while window.shown() {
+        app::wait();
+    }
+
+    let index = *selected.borrow();
+    index.map(|i| apps.swap_remove(i))
+}
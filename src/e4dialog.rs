@@ -0,0 +1,53 @@
+use crate::{tr, translations::Translations};
+use fltk::{
+    button::Button,
+    enums::{Key, Shortcut},
+    prelude::*,
+    window::Window,
+};
+use std::sync::{Arc, Mutex};
+
+/// Give dialog the keys of all the dialogs: Enter presses ok, Esc or closing the window calls cancel
+/// and hides it, leaving what is edited as it was. If is_dirty tells that something changed, the user
+/// is asked whether to save it first, with ok, discard it or keep editing.
+pub fn set_keys<D, F>(
+    dialog: &mut Window,
+    ok: &mut Button,
+    is_dirty: D,
+    mut cancel: F,
+    translations: Arc<Mutex<Translations>>,
+) where
+    D: Fn() -> bool + 'static,
+    F: FnMut() + 'static,
+{
+    // A field using Enter, like a list of arguments, takes it first
+    ok.set_shortcut(Shortcut::None | Key::Enter);
+    let mut ok = ok.clone();
+    dialog.set_callback(move |dialog| {
+        if is_dirty() {
+            let message = tr!(
+                translations,
+                get_or_default,
+                "save-the-changes",
+                "Save the changes?"
+            );
+            let choice = fltk::dialog::choice2_default(
+                &message,
+                &tr!(translations, get_or_default, "cancel", "Cancel"),
+                &tr!(translations, get_or_default, "discard", "Discard"),
+                &tr!(translations, get_or_default, "save", "Save"),
+            );
+            match choice {
+                // The save hides the dialog, unless a value is wrong
+                Some(2) => {
+                    ok.do_callback();
+                    return;
+                }
+                Some(1) => {}
+                _ => return,
+            }
+        }
+        cancel();
+        dialog.hide();
+    });
+}
@@ -0,0 +1,266 @@
+use crate::{
+    e4config::{
+        button_file, config_file, is_spacer, numbered_buttons, same_button_name, E4Config,
+        E4DOCKER_BUTTON_SECTION,
+    },
+    e4storage::{DryRunStorage, E4Storage},
+    tr,
+    translations::Translations,
+};
+use configparser::ini::Ini;
+use fltk::{app, browser::Browser, button::Button, frame::Frame, prelude::*, window::Window};
+use std::{
+    cell::Cell,
+    collections::BTreeSet,
+    path::Path,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+/// A configuration as read from its files: e4docker.conf and the configuration files of the buttons.
+#[derive(Clone, Debug, Default)]
+pub struct E4ConfigSnapshot {
+    /// The content of e4docker.conf.
+    pub config: Ini,
+    /// The names of the buttons, in their order and without the spacers, with their configuration files.
+    pub buttons: Vec<(String, Ini)>,
+}
+
+impl E4ConfigSnapshot {
+    /// Read the configuration in config_dir from storage.
+    pub fn read(storage: &dyn E4Storage, config_dir: &Path) -> Result<Self, String> {
+        Self::read_with(|path| storage.load_ini(&config_dir.join(path)))
+    }
+
+    /// Read the configuration with load_ini, which loads a file by its path relative to the configuration directory.
+    /// A button whose configuration file cannot be read is kept, with no keys.
+    pub fn read_with<F: Fn(&Path) -> Result<Ini, String>>(load_ini: F) -> Result<Self, String> {
+        let config = load_ini(&config_file(Path::new("")))?;
+        let buttons = numbered_buttons(&config)
+            .into_iter()
+            .filter(|name| !is_spacer(name))
+            .map(|name| {
+                let ini = load_ini(&button_file(Path::new(""), &name)).unwrap_or_default();
+                (name, ini)
+            })
+            .collect();
+        Ok(Self { config, buttons })
+    }
+
+    /// Return the configuration file of the button name.
+    fn button(&self, name: &str) -> Option<&Ini> {
+        self.buttons
+            .iter()
+            .find(|(other, _)| same_button_name(other, name))
+            .map(|(_, ini)| ini)
+    }
+}
+
+/// How a button differs between two configurations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum E4Change {
+    /// The button is only in the new configuration.
+    Added,
+    /// The button is only in the current configuration.
+    Removed,
+    /// The configuration file of the button is different.
+    Changed,
+}
+
+/// A key of e4docker.conf with a different value in the new configuration.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct E4SettingChange {
+    pub section: String,
+    pub key: String,
+    /// The current value, if set.
+    pub old: Option<String>,
+    /// The new value, if set.
+    pub new: Option<String>,
+}
+
+/// What changes when a configuration replaces the current one.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct E4ConfigDiff {
+    /// The buttons added, removed or changed: the removed and changed ones in the current order,
+    /// then the added ones in the new order.
+    pub buttons: Vec<(String, E4Change)>,
+    /// True if the buttons in both the configurations are in a different order.
+    pub reordered: bool,
+    /// The changed keys of e4docker.conf, by section and key, without the buttons.
+    pub settings: Vec<E4SettingChange>,
+}
+
+impl E4ConfigDiff {
+    /// Compare the current configuration with the new one replacing it.
+    pub fn new(current: &E4ConfigSnapshot, new: &E4ConfigSnapshot) -> Self {
+        let mut diff = Self::default();
+        for (name, ini) in &current.buttons {
+            match new.button(name) {
+                None => diff.buttons.push((name.clone(), E4Change::Removed)),
+                Some(new_ini) if new_ini.get_map_ref() != ini.get_map_ref() => {
+                    diff.buttons.push((name.clone(), E4Change::Changed))
+                }
+                Some(_) => {}
+            }
+        }
+        for (name, _) in &new.buttons {
+            if current.button(name).is_none() {
+                diff.buttons.push((name.clone(), E4Change::Added));
+            }
+        }
+
+        let kept = |from: &E4ConfigSnapshot, other: &E4ConfigSnapshot| -> Vec<String> {
+            from.buttons
+                .iter()
+                .map(|(name, _)| name.clone())
+                .filter(|name| other.button(name).is_some())
+                .collect()
+        };
+        let current_order = kept(current, new);
+        let new_order = kept(new, current);
+        diff.reordered = current_order
+            .iter()
+            .zip(&new_order)
+            .any(|(a, b)| !same_button_name(a, b));
+
+        // The buttons and their number are compared above
+        let buttons_section = E4DOCKER_BUTTON_SECTION.to_lowercase();
+        let keys: BTreeSet<(&String, &String)> = [&current.config, &new.config]
+            .into_iter()
+            .flat_map(|config| config.get_map_ref())
+            .filter(|(section, _)| **section != buttons_section)
+            .flat_map(|(section, keys)| keys.keys().map(move |key| (section, key)))
+            .filter(|(_, key)| *key != "number_of_buttons")
+            .collect();
+        for (section, key) in keys {
+            let old = current.config.get(section, key);
+            let new = new.config.get(section, key);
+            if old != new {
+                diff.settings.push(E4SettingChange {
+                    section: section.clone(),
+                    key: key.clone(),
+                    old,
+                    new,
+                });
+            }
+        }
+        diff
+    }
+
+    /// Return true if nothing changes.
+    pub fn is_empty(&self) -> bool {
+        self.buttons.is_empty() && !self.reordered && self.settings.is_empty()
+    }
+
+    /// Return the changes, one per line.
+    pub fn lines(&self, translations: Arc<Mutex<Translations>>) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .buttons
+            .iter()
+            .map(|(name, change)| {
+                let key = match change {
+                    E4Change::Added => "button-added",
+                    E4Change::Removed => "button-removed",
+                    E4Change::Changed => "button-changed",
+                };
+                tr!(translations, format, key, &[name.as_str()])
+            })
+            .collect();
+        if self.reordered {
+            lines.push(tr!(
+                translations,
+                get_or_default,
+                "buttons-reordered",
+                "The order of the buttons changes"
+            ));
+        }
+        let not_set = tr!(translations, get_or_default, "not-set", "(not set)");
+        for setting in &self.settings {
+            lines.push(tr!(
+                translations,
+                format,
+                "setting-changed",
+                &[
+                    &format!("[{}] {}", setting.section, setting.key).as_str(),
+                    setting.old.as_deref().unwrap_or(&not_set),
+                    setting.new.as_deref().unwrap_or(&not_set),
+                ]
+            ));
+        }
+        lines
+    }
+}
+
+/// Apply change to the configuration in config_dir of storage, with the icons in assets_dir, without writing it:
+/// return the result of change and what it changes. The files change writes through the storage of the
+/// configuration stay in memory, see [DryRunStorage].
+pub fn dry_run<T, F>(
+    storage: Arc<dyn E4Storage>,
+    config_dir: &Path,
+    assets_dir: &Path,
+    change: F,
+) -> Result<(T, E4ConfigDiff), Box<dyn std::error::Error>>
+where
+    F: FnOnce(&mut E4Config) -> Result<T, Box<dyn std::error::Error>>,
+{
+    let current = E4ConfigSnapshot::read(storage.as_ref(), config_dir)?;
+    let dry_run = Arc::new(DryRunStorage::new(storage));
+    let mut config = E4Config::read_from(dry_run.clone(), config_dir, assets_dir)?;
+    let result = change(&mut config)?;
+    let new = E4ConfigSnapshot::read(dry_run.as_ref(), config_dir)?;
+    Ok((result, E4ConfigDiff::new(&current, &new)))
+}
+
+/// Show message and the changes of diff, and return true if the user applies them.
+/// Nothing is asked when nothing changes.
+pub fn confirm(message: &str, diff: &E4ConfigDiff, translations: Arc<Mutex<Translations>>) -> bool {
+    if diff.is_empty() {
+        return true;
+    }
+    let mut window = Window::default().with_size(600, 400).with_label(&tr!(
+        translations,
+        get_or_default,
+        "review-the-changes",
+        "Review the changes"
+    ));
+    let mut frame = Frame::new(10, 10, 580, 40, None);
+    frame.set_label(&message.replace('@', "@@"));
+    frame.set_align(
+        fltk::enums::Align::Inside | fltk::enums::Align::Left | fltk::enums::Align::Wrap,
+    );
+
+    let mut browser = Browser::new(10, 60, 580, 280, None);
+    // The names and the values are shown as they are, without the @ formatting of the browser
+    browser.set_format_char('\0');
+    for line in diff.lines(translations.clone()) {
+        browser.add(&line);
+    }
+
+    let applied = Rc::new(Cell::new(false));
+    let mut apply_button = Button::new(190, 355, 100, 30, None);
+    apply_button.set_label(&tr!(translations, get_or_default, "apply", "Apply"));
+    apply_button.set_callback({
+        let mut window = window.clone();
+        let applied = applied.clone();
+        move |_| {
+            applied.set(true);
+            window.hide();
+        }
+    });
+    let mut cancel_button = Button::new(310, 355, 100, 30, None);
+    cancel_button.set_label(&tr!(translations, get_or_default, "cancel", "Cancel"));
+    cancel_button.set_callback({
+        let mut window = window.clone();
+        move |_| window.hide()
+    });
+
+    window.make_modal(true);
+    window.end();
+    crate::e4screen::place_dialog(&mut window);
+    window.show();
+    // Run modal window
+    while window.shown() {
+        app::wait();
+    }
+    applied.get()
+}
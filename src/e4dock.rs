@@ -0,0 +1,343 @@
+use crate::{
+    e4button::{self, E4Button, E4ButtonConfig, E4ButtonType},
+    e4command::{self, E4Command},
+    e4config::{
+        self, button_file, config_file, E4Config, BUTTON_BUTTON_SECTION, E4DOCKER_BUTTON_SECTION,
+        E4DOCKER_DOCKER_SECTION, E4DOCKER_FRAME_MARGIN, E4DOCKER_ICON_HEIGHT, E4DOCKER_ICON_WIDTH,
+        E4DOCKER_MARGIN_BETWEEN_BUTTONS, E4DOCKER_NUMBER_OF_BUTTONS, E4DOCKER_X, E4DOCKER_Y,
+    },
+    e4hooks::E4Hooks,
+    e4storage::{E4Storage, FsStorage},
+    e4style::E4Style,
+    translations::Translations,
+};
+use configparser::ini::Ini;
+use fltk::{frame::Frame, prelude::*, window::Window};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+/// The sizes of a new [Dock], the same as the e4docker.conf written on the first run.
+const DEFAULT_FRAME_MARGIN: i32 = 10;
+const DEFAULT_MARGIN_BETWEEN_BUTTONS: i32 = 20;
+const DEFAULT_ICON_SIZE: i32 = 32;
+
+/// A button of a [Dock], described in code instead of in a .conf file.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4dock::E4ButtonBuilder;
+///
+/// let firefox = E4ButtonBuilder::new("firefox")
+///     .command("/usr/bin/firefox")
+///     .arguments("https://www.rust-lang.org")
+///     .icon("firefox.png");
+/// ```
+#[derive(Clone, Debug)]
+pub struct E4ButtonBuilder {
+    name: String,
+    button_type: E4ButtonType,
+    command: String,
+    arguments: String,
+    icon: Option<PathBuf>,
+    cooldown: Duration,
+    timeout: Option<Duration>,
+}
+
+impl E4ButtonBuilder {
+    /// Start a command button named name, with no command.
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            button_type: E4ButtonType::Command,
+            command: String::new(),
+            arguments: String::new(),
+            icon: None,
+            cooldown: e4command::DEFAULT_COOLDOWN,
+            timeout: None,
+        }
+    }
+
+    /// Set the type of the button, to make it a widget.
+    pub fn button_type(mut self, button_type: E4ButtonType) -> Self {
+        self.button_type = button_type;
+        self
+    }
+
+    /// Set the program launched by the button.
+    pub fn command(mut self, command: &str) -> Self {
+        self.command = command.to_string();
+        self
+    }
+
+    /// Set the arguments of the command.
+    pub fn arguments(mut self, arguments: &str) -> Self {
+        self.arguments = arguments.trim().to_string();
+        self
+    }
+
+    /// Set the icon, as a path or as a file name in the assets directory.
+    /// Without an icon the button shows the generic one.
+    pub fn icon<P: AsRef<Path>>(mut self, icon: P) -> Self {
+        self.icon = Some(icon.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set the time after a launch in which the button does not launch again.
+    pub fn cooldown(mut self, cooldown: Duration) -> Self {
+        self.cooldown = cooldown;
+        self
+    }
+
+    /// Set the time after a launch in which the program of the button is killed, if still running.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Return the name of the button.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Return the name and the configuration of the button.
+    pub fn build(self, translations: Arc<Mutex<Translations>>) -> (String, E4ButtonConfig) {
+        let icon_path = match self.icon {
+            Some(icon) => icon.display().to_string(),
+            // A macOS bundle carries its own icon
+            None if crate::e4bundle::is_app_bundle(Path::new(&self.command)) => {
+                self.command.clone()
+            }
+            None => crate::e4initialize::get_generic_icon(translations)
+                .display()
+                .to_string(),
+        };
+        let mut command = E4Command::new(self.command, e4command::split_arguments(&self.arguments));
+        command.set_cooldown(self.cooldown);
+        command.set_timeout(self.timeout);
+        command.set_button(&self.name);
+        let config = E4ButtonConfig {
+            button_type: self.button_type,
+            command,
+            icon_path,
+        };
+        (self.name, config)
+    }
+}
+
+/// A dock built in code, for the programs embedding e4docker.
+///
+/// The buttons are drawn from the [E4ButtonBuilder]s, without writing the .conf files first:
+/// [Dock::save] writes them if the dock should be opened by e4docker later.
+///
+/// # Example
+///
+/// ```rust,no_run
+/// use e4docker::{
+///     e4dock::{Dock, E4ButtonBuilder},
+///     translations::Translations,
+/// };
+/// use fltk::{app, prelude::*, window::Window};
+///
+/// let app = app::App::default();
+/// let mut dock = Dock::new(std::path::Path::new("/tmp/mydock")).icon_size(48, 48);
+/// dock.add_button(E4ButtonBuilder::new("firefox").command("/usr/bin/firefox"))
+///     .add_button(E4ButtonBuilder::new("terminal").command("/usr/bin/xterm"));
+/// let mut wind = Window::default().with_label("My dock");
+/// let _buttons = dock.draw(&mut wind, Translations::get_instance()).unwrap();
+/// wind.show();
+/// app.run().unwrap();
+/// ```
+#[derive(Clone, Debug)]
+pub struct Dock {
+    config_dir: PathBuf,
+    x: i32,
+    y: i32,
+    icon_width: i32,
+    icon_height: i32,
+    margin_between_buttons: i32,
+    frame_margin: i32,
+    buttons: Vec<E4ButtonBuilder>,
+}
+
+impl Dock {
+    /// Create a dock with no buttons.
+    /// config_dir holds the state of the buttons, like their recent files, and the assets directory of their icons.
+    pub fn new(config_dir: &Path) -> Self {
+        Self {
+            config_dir: config_dir.to_path_buf(),
+            x: 0,
+            y: 0,
+            icon_width: DEFAULT_ICON_SIZE,
+            icon_height: DEFAULT_ICON_SIZE,
+            margin_between_buttons: DEFAULT_MARGIN_BETWEEN_BUTTONS,
+            frame_margin: DEFAULT_FRAME_MARGIN,
+            buttons: vec![],
+        }
+    }
+
+    /// Set the size of the icons.
+    pub fn icon_size(mut self, width: i32, height: i32) -> Self {
+        self.icon_width = width;
+        self.icon_height = height;
+        self
+    }
+
+    /// Set the margin between the buttons and the margin of the frame.
+    pub fn margins(mut self, between_buttons: i32, frame: i32) -> Self {
+        self.margin_between_buttons = between_buttons;
+        self.frame_margin = frame;
+        self
+    }
+
+    /// Set the position of the window.
+    pub fn position(mut self, x: i32, y: i32) -> Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Add a button at the end of the dock.
+    pub fn add_button(&mut self, button: E4ButtonBuilder) -> &mut Self {
+        self.buttons.push(button);
+        self
+    }
+
+    /// Return the [E4Config] of the dock, as if it was read from e4docker.conf.
+    pub fn config(&self) -> E4Config {
+        let (window_width, window_height) = e4config::window_size(
+            self.buttons.len() as i32,
+            self.icon_width,
+            self.icon_height,
+            self.margin_between_buttons,
+            self.frame_margin,
+        );
+        E4Config {
+            config_dir: self.config_dir.clone(),
+            buttons: self.buttons.iter().map(|b| b.name.clone()).collect(),
+            assets_dir: self.config_dir.join("assets"),
+            margin_between_buttons: self.margin_between_buttons,
+            frame_margin: self.frame_margin,
+            window_width,
+            window_height,
+            icon_width: self.icon_width,
+            icon_height: self.icon_height,
+            x: self.x,
+            y: self.y,
+            hooks: E4Hooks::default(),
+            style: E4Style::default(),
+            storage: Arc::new(FsStorage),
+            clamped: vec![],
+        }
+    }
+
+    /// Draw the dock in wind, resizing it, and return its buttons.
+    pub fn draw(
+        &self,
+        wind: &mut Window,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Result<Vec<E4Button>, Box<dyn std::error::Error>> {
+        let config = self.config();
+        wind.begin();
+        wind.set_size(config.window_width, config.window_height);
+        if config.x != 0 {
+            wind.set_pos(config.x, config.y);
+        }
+        let mut frame = Frame::default()
+            .with_size(
+                config.window_width - config.frame_margin,
+                config.window_height - config.frame_margin,
+            )
+            .center_of(wind);
+        frame.set_frame(config.style.frame_style.frame_type());
+
+        let button_configs = self
+            .buttons
+            .iter()
+            .map(|button| button.clone().build(translations.clone()))
+            .collect();
+        let buttons =
+            e4button::create_buttons_from(&config, button_configs, wind, &frame, translations)?;
+        wind.end();
+        Ok(buttons)
+    }
+
+    /// Write e4docker.conf and the .conf files of the buttons in the configuration directory.
+    pub fn save(&self) -> Result<(), Box<dyn std::error::Error>> {
+        std::fs::create_dir_all(&self.config_dir)?;
+        self.save_to(&FsStorage)
+    }
+
+    /// Write e4docker.conf and the .conf files of the buttons in the configuration directory of storage.
+    /// The comments of the files already there are kept.
+    pub fn save_to(&self, storage: &dyn E4Storage) -> Result<(), Box<dyn std::error::Error>> {
+        let mut e4docker_conf = Ini::new();
+        let values = [
+            (E4DOCKER_NUMBER_OF_BUTTONS, self.buttons.len() as i32),
+            (E4DOCKER_FRAME_MARGIN, self.frame_margin),
+            (E4DOCKER_MARGIN_BETWEEN_BUTTONS, self.margin_between_buttons),
+            (E4DOCKER_ICON_WIDTH, self.icon_width),
+            (E4DOCKER_ICON_HEIGHT, self.icon_height),
+            (E4DOCKER_X, self.x),
+            (E4DOCKER_Y, self.y),
+        ];
+        for (key, value) in values {
+            e4docker_conf.set(E4DOCKER_DOCKER_SECTION, key, Some(value.to_string()));
+        }
+
+        for (i, button) in self.buttons.iter().enumerate() {
+            e4docker_conf.set(
+                E4DOCKER_BUTTON_SECTION,
+                &format!("button{}", i + 1),
+                Some(button.name.clone()),
+            );
+
+            let mut button_conf = Ini::new();
+            button_conf.set(
+                BUTTON_BUTTON_SECTION,
+                "command",
+                Some(button.command.clone()),
+            );
+            button_conf.set(
+                BUTTON_BUTTON_SECTION,
+                "arguments",
+                Some(button.arguments.clone()),
+            );
+            if let Some(icon) = &button.icon {
+                button_conf.set(
+                    BUTTON_BUTTON_SECTION,
+                    "icon",
+                    Some(icon.display().to_string()),
+                );
+            }
+            if button.cooldown != e4command::DEFAULT_COOLDOWN {
+                button_conf.set(
+                    BUTTON_BUTTON_SECTION,
+                    "cooldown",
+                    Some(button.cooldown.as_secs_f64().to_string()),
+                );
+            }
+            if let Some(timeout) = button.timeout {
+                button_conf.set(
+                    BUTTON_BUTTON_SECTION,
+                    "timeout",
+                    Some(timeout.as_secs_f64().to_string()),
+                );
+            }
+            if button.button_type != E4ButtonType::Command {
+                button_conf.set(
+                    BUTTON_BUTTON_SECTION,
+                    "type",
+                    Some(button.button_type.name().to_string()),
+                );
+            }
+            storage.write_ini(&button_file(&self.config_dir, &button.name), &button_conf)?;
+        }
+
+        storage.write_ini(&config_file(&self.config_dir), &e4docker_conf)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,245 @@
+use crate::{
+    e4attention,
+    e4button::{self, E4Button},
+    e4config::E4Config,
+    e4initialize, e4processes, e4toast, tr,
+    translations::Translations,
+};
+use fltk::{frame::Frame, menu::MenuBar, prelude::*, window::Window};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+/// The widgets of the dock in the window, known by the names of their buttons.
+///
+/// A change of a button, like its process starting or its configuration being edited,
+/// updates only the widgets of that button instead of drawing the whole window again.
+pub struct DockView {
+    wind: Window,
+    frame: Frame,
+    menubar: MenuBar,
+    config: Rc<RefCell<E4Config>>,
+    buttons: Arc<Mutex<Vec<E4Button>>>,
+    translations: Arc<Mutex<Translations>>,
+}
+
+impl DockView {
+    /// Create the view of the buttons drawn in the frame of wind, under the menubar.
+    /// config is shared with the menus of the window: the view keeps it up to date.
+    pub fn new(
+        wind: &Window,
+        frame: &Frame,
+        menubar: &MenuBar,
+        config: Rc<RefCell<E4Config>>,
+        buttons: Arc<Mutex<Vec<E4Button>>>,
+        translations: Arc<Mutex<Translations>>,
+    ) -> Self {
+        Self {
+            wind: wind.clone(),
+            frame: frame.clone(),
+            menubar: menubar.clone(),
+            config,
+            buttons,
+            translations,
+        }
+    }
+
+    /// Return the buttons of the dock, shared with the process checker.
+    pub fn buttons(&self) -> Arc<Mutex<Vec<E4Button>>> {
+        self.buttons.clone()
+    }
+
+    /// Return true if the configuration is in use, like by an open dialog: the changes have to wait.
+    pub fn is_busy(&self) -> bool {
+        self.config.try_borrow_mut().is_err()
+    }
+
+    /// Show the state of the process of the button named name, redrawing only its border or icon.
+    pub fn set_state(&self, name: &str, is_running: bool) {
+        e4processes::show_state(&mut self.buttons.lock().unwrap(), name, is_running);
+    }
+
+    /// Show the badge of the button named name, redrawing only that button.
+    pub fn set_badge(&self, name: &str, text: Option<String>) {
+        e4processes::show_badge(&mut self.buttons.lock().unwrap(), name, text);
+    }
+
+    /// Make the button named name blink until it is clicked.
+    pub fn request_attention(&self, name: &str) {
+        e4attention::request(&self.buttons, name);
+    }
+
+    /// Show the icon of the button named name, decoded in background.
+    pub fn set_icon(&self, name: &str, png_data: &[u8]) -> Result<(), Box<dyn std::error::Error>> {
+        let mut buttons = self.buttons.lock().unwrap();
+        match buttons.iter_mut().find(|button| button.name == name) {
+            Some(button) => button.set_icon_data(png_data),
+            None => Ok(()),
+        }
+    }
+
+    /// End the launch of the button named name, whose window has appeared, and show it running at once
+    /// instead of at the next check of the processes.
+    pub fn end_launch(&self, name: &str) {
+        let mut buttons = self.buttons.lock().unwrap();
+        if let Some(button) = buttons.iter_mut().find(|button| button.name == name) {
+            button.command.lock().unwrap().window_shown();
+        }
+        e4processes::show_state(&mut buttons, name, true);
+    }
+
+    /// Tell that the command of the button named name has exited with code just after its launch, printing
+    /// output, offering to launch it again or to edit it: a typo in the command shows at once.
+    pub fn show_launch_failure(&self, name: &str, code: i32, output: &str) {
+        let button = match self.buttons.lock().unwrap().iter().find(|b| b.name == name) {
+            Some(button) => button.clone(),
+            None => return,
+        };
+        let mut message = tr!(
+            self.translations,
+            format,
+            "the-command-has-failed",
+            &[name, &code.to_string()]
+        );
+        // Why it failed, as printed by the command
+        if !output.is_empty() {
+            message = format!("{}\n\n{}", message, output);
+        }
+
+        let mut actions: Vec<(String, Box<dyn FnOnce()>)> = vec![];
+        let mut widget = button.button.clone();
+        actions.push((
+            tr!(self.translations, get_or_default, "retry", "Retry"),
+            Box::new(move || widget.do_callback()),
+        ));
+        if !e4initialize::is_read_only() {
+            let mut button = button;
+            let config = self.config.clone();
+            let translations = self.translations.clone();
+            actions.push((
+                tr!(self.translations, get_or_default, "edit-menu", "Edit"),
+                Box::new(move || {
+                    // Another dialog is open
+                    if let Ok(mut config) = config.try_borrow_mut() {
+                        button.edit(&mut config, translations);
+                    }
+                }),
+            ));
+        }
+        e4toast::show_with_actions(&message, actions);
+    }
+
+    /// Draw the button named name, just added to the configuration, and widen the window.
+    pub fn add_button(&mut self, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let config = self.reload()?;
+        let index = match config.button_names().position(|button| button == name) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+        let e4button = self.create_button(&config, name, index)?;
+
+        let mut buttons = self.buttons.lock().unwrap();
+        buttons.insert(index.min(buttons.len()), e4button);
+        drop(buttons);
+        self.place(&config);
+        self.resize(&config);
+        Ok(())
+    }
+
+    /// Draw again the button named old_name, whose configuration has been edited and saved as name.
+    pub fn update_button(
+        &mut self,
+        old_name: &str,
+        name: &str,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let config = self.reload()?;
+        let index = match self.position(old_name) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+        let mut e4button = self.create_button(&config, name, index)?;
+
+        let mut buttons = self.buttons.lock().unwrap();
+        buttons[index].remove_from(&mut self.wind);
+        e4button.redraw();
+        buttons[index] = e4button;
+        Ok(())
+    }
+
+    /// Remove the widgets of the button named name, moving the other buttons and narrowing the window.
+    pub fn remove_button(&mut self, name: &str) -> Result<(), Box<dyn std::error::Error>> {
+        let config = self.reload()?;
+        let index = match self.position(name) {
+            Some(index) => index,
+            None => return Ok(()),
+        };
+
+        let mut buttons = self.buttons.lock().unwrap();
+        buttons.remove(index).remove_from(&mut self.wind);
+        drop(buttons);
+        self.place(&config);
+        self.resize(&config);
+        Ok(())
+    }
+
+    /// Return the index of the button named name.
+    fn position(&self, name: &str) -> Option<usize> {
+        self.buttons
+            .lock()
+            .unwrap()
+            .iter()
+            .position(|button| button.name == name)
+    }
+
+    /// Read e4docker.conf again, sharing it with the menus of the window, and return it.
+    fn reload(&self) -> Result<E4Config, Box<dyn std::error::Error>> {
+        let config_dir = self.config.borrow().config_dir.clone();
+        let config = E4Config::read(&config_dir, self.translations.clone())?;
+        *self.config.borrow_mut() = config.clone();
+        Ok(config)
+    }
+
+    /// Create the button named name at index, reading its .conf file.
+    fn create_button(
+        &mut self,
+        config: &E4Config,
+        name: &str,
+        index: usize,
+    ) -> Result<E4Button, Box<dyn std::error::Error>> {
+        let name = name.to_string();
+        let button_config = E4Button::read_config(config, &name, self.translations.clone())?;
+        self.wind.begin();
+        let e4button = e4button::create_button(
+            config,
+            &name,
+            button_config,
+            index,
+            &self.frame,
+            self.translations.clone(),
+        );
+        self.wind.end();
+        e4button
+    }
+
+    /// Move the buttons where config places them, after one has been added or removed.
+    fn place(&self, config: &E4Config) {
+        let positions = config.button_positions();
+        let mut buttons = self.buttons.lock().unwrap();
+        for (button, x) in buttons.iter_mut().zip(positions) {
+            button.move_to(x);
+        }
+    }
+
+    /// Resize the window, the frame and the menu bar to the number of buttons of config.
+    fn resize(&mut self, config: &E4Config) {
+        // The buttons keep their position and size
+        self.wind.make_resizable(false);
+        self.wind.set_size(config.window_width, self.wind.h());
+        self.frame
+            .set_size(config.window_width - config.frame_margin, self.frame.h());
+        self.menubar.set_size(config.window_width, self.menubar.h());
+        self.wind.redraw();
+    }
+}
@@ -0,0 +1,98 @@
+use crate::{e4button::E4Button, e4command, e4profile, e4share::quote};
+use lazy_static::lazy_static;
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+lazy_static! {
+    /// The buttons of the running dock, with the time it started: see [watch].
+    static ref DOCK: Mutex<Option<(Arc<Mutex<Vec<E4Button>>>, Instant)>> = Mutex::new(None);
+}
+
+/// The state of a button of the running dock.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct E4ButtonState {
+    pub name: String,
+    /// The value of its TYPE key.
+    pub button_type: &'static str,
+    pub command: String,
+    /// True if its process is running, or if its widget is on.
+    pub running: bool,
+    /// The number of its launches since the dock started.
+    pub launches: u32,
+}
+
+/// Return the state of the dock as JSON, on one line: its buttons, the seconds since it started
+/// and the times of the phases of its startup, if profiled with --profile-startup.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4dump::{state_to_json, E4ButtonState};
+/// use std::time::Duration;
+///
+/// let firefox = E4ButtonState {
+///     name: "firefox".to_string(),
+///     button_type: "command",
+///     command: "/usr/bin/firefox".to_string(),
+///     running: true,
+///     launches: 2,
+/// };
+/// assert_eq!(
+///     state_to_json(&[firefox], Duration::from_secs(60), None),
+///     "{\"uptime_secs\": 60, \"startup\": null, \"buttons\": [{\"name\": \"firefox\", \"type\": \"command\", \
+///      \"command\": \"/usr/bin/firefox\", \"running\": true, \"launches\": 2}]}"
+/// );
+/// ```
+pub fn state_to_json(
+    buttons: &[E4ButtonState],
+    uptime: Duration,
+    startup: Option<String>,
+) -> String {
+    let buttons: Vec<String> = buttons
+        .iter()
+        .map(|button| {
+            format!(
+                "{{\"name\": {}, \"type\": {}, \"command\": {}, \"running\": {}, \"launches\": {}}}",
+                quote(&button.name),
+                quote(button.button_type),
+                quote(&button.command),
+                button.running,
+                button.launches
+            )
+        })
+        .collect();
+    format!(
+        "{{\"uptime_secs\": {}, \"startup\": {}, \"buttons\": [{}]}}",
+        uptime.as_secs(),
+        startup.map_or("null".to_string(), |startup| quote(&startup)),
+        buttons.join(", ")
+    )
+}
+
+/// Dump the state of buttons, the buttons of the running dock, when asked by `e4docker --dump-state`.
+pub fn watch(buttons: Arc<Mutex<Vec<E4Button>>>) {
+    *DOCK.lock().unwrap() = Some((buttons, Instant::now()));
+}
+
+/// Return the state of the running dock as JSON: see [state_to_json].
+pub fn dump() -> String {
+    let (buttons, started) = match DOCK.lock().unwrap().clone() {
+        Some(dock) => dock,
+        None => (Arc::new(Mutex::new(vec![])), Instant::now()),
+    };
+    let states: Vec<E4ButtonState> = buttons
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|button| E4ButtonState {
+            name: button.name.clone(),
+            button_type: button.button_type.name(),
+            command: button.command.lock().unwrap().command_line(),
+            running: button.is_running(),
+            launches: e4command::launch_count(&button.name),
+        })
+        .collect();
+    state_to_json(&states, started.elapsed(), e4profile::summary("dump"))
+}
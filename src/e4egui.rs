@@ -0,0 +1,306 @@
+use crate::{
+    e4button::E4ButtonType,
+    e4command::{self, E4Command, E4Executor},
+    e4config::{button_file, is_spacer, E4Config, BUTTON_BUTTON_SECTION},
+    e4icon, e4initialize, e4state, tr,
+    translations::Translations,
+};
+use eframe::egui;
+use std::{
+    error,
+    path::Path,
+    process::Command,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+/// The time after a launch in which a program exiting with a failure code is reported, as by the FLTK dock.
+const EARLY_EXIT: Duration = Duration::from_secs(5);
+
+/// The time between two checks of a program just launched.
+const EXIT_CHECK_INTERVAL: Duration = Duration::from_millis(200);
+
+/// The executor of a button of the egui dock. The FLTK dock hears of the early failures through
+/// [crate::e4events], whose channel is drained by the FLTK event loop: this one keeps them for the next frame.
+struct E4EguiExecutor {
+    /// The name of the button.
+    name: String,
+    ctx: egui::Context,
+    /// The failures to show, by button name and exit code.
+    failures: Arc<Mutex<Vec<(String, i32)>>>,
+}
+
+impl E4Executor for E4EguiExecutor {
+    /// Spawn command and watch it for [EARLY_EXIT], then hand it to the supervisor of e4command.
+    fn spawn(
+        &self,
+        mut command: Command,
+        _button: Option<String>,
+        _timeout: Option<Duration>,
+    ) -> Result<(), Box<dyn error::Error>> {
+        let mut child = command.spawn()?;
+        let name = self.name.clone();
+        let ctx = self.ctx.clone();
+        let failures = self.failures.clone();
+        std::thread::spawn(move || {
+            let started_at = Instant::now();
+            while started_at.elapsed() < EARLY_EXIT {
+                match child.try_wait() {
+                    Ok(Some(status)) => {
+                        // Killed by a signal, the process has no code
+                        if let Some(code) = status.code().filter(|code| *code != 0) {
+                            failures.lock().unwrap().push((name, code));
+                            ctx.request_repaint();
+                        }
+                        return;
+                    }
+                    Ok(None) => std::thread::sleep(EXIT_CHECK_INTERVAL),
+                    Err(_) => break,
+                }
+            }
+            e4command::supervise_child(child);
+        });
+        Ok(())
+    }
+}
+
+/// A command button of the egui dock.
+struct E4EguiButton {
+    name: String,
+    command: E4Command,
+    /// The icon, decoded once when the dock starts: None if it cannot be read.
+    icon: Option<egui::TextureHandle>,
+}
+
+/// The dock drawn by egui: the command buttons of e4docker.conf in a row, with their spacers.
+struct E4EguiDock {
+    config: E4Config,
+    /// The buttons in the order of the dock, None being a spacer.
+    buttons: Vec<Option<E4EguiButton>>,
+    /// The last error of a launch, shown under the buttons until the next launch.
+    error: Option<String>,
+    /// The launches failed just after starting, reported by the [E4EguiExecutor].
+    failures: Arc<Mutex<Vec<(String, i32)>>>,
+    translations: Arc<Mutex<Translations>>,
+}
+
+impl E4EguiDock {
+    /// Create the dock of config, decoding the icons in the pixels of the screen of ctx.
+    fn new(ctx: &egui::Context, config: E4Config, translations: Arc<Mutex<Translations>>) -> Self {
+        let failures = Arc::new(Mutex::new(vec![]));
+        let buttons = config
+            .buttons
+            .iter()
+            .filter_map(|name| {
+                if is_spacer(name) {
+                    Some(None)
+                } else {
+                    command_button(ctx, &config, name, translations.clone()).map(|mut button| {
+                        button.command.set_executor(Arc::new(E4EguiExecutor {
+                            name: name.clone(),
+                            ctx: ctx.clone(),
+                            failures: failures.clone(),
+                        }));
+                        Some(button)
+                    })
+                }
+            })
+            .collect();
+        Self {
+            config,
+            buttons,
+            error: None,
+            failures,
+            translations,
+        }
+    }
+
+    /// Show the last launch failed just after starting, if any.
+    fn show_failures(&mut self) {
+        if let Some((name, code)) = self.failures.lock().unwrap().drain(..).last() {
+            self.error = Some(tr!(
+                self.translations,
+                format,
+                "the-command-has-failed",
+                &[&name, &code.to_string()]
+            ));
+        }
+    }
+
+    /// Launch the command of the button at index, remembering the launch as the FLTK dock does.
+    fn launch(&mut self, index: usize) {
+        let button = match self.buttons.get_mut(index) {
+            Some(Some(button)) => button,
+            _ => return,
+        };
+        match button.command.exec() {
+            Ok(_) => {
+                self.error = None;
+                let _ = e4state::add_launch(&self.config.config_dir, &button.name, &[]);
+            }
+            Err(e) => {
+                self.error = Some(tr!(
+                    self.translations,
+                    format,
+                    "failed-to-execute-command",
+                    &[button.command.get_cmd(), &e.to_string()]
+                ))
+            }
+        }
+    }
+}
+
+impl eframe::App for E4EguiDock {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.show_failures();
+        let icon_size = egui::vec2(
+            self.config.icon_width as f32,
+            self.config.icon_height as f32,
+        );
+        let mut clicked = None;
+        egui::CentralPanel::default()
+            .frame(egui::Frame::none().inner_margin(self.config.frame_margin as f32))
+            .show(ctx, |ui| {
+                // The dock is moved by dragging it between the buttons
+                let background = ui.interact(
+                    ui.max_rect(),
+                    egui::Id::new("e4docker-background"),
+                    egui::Sense::click_and_drag(),
+                );
+                if background.drag_started() {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::StartDrag);
+                }
+                background.context_menu(|ui| {
+                    if ui
+                        .button(tr!(self.translations, get_or_default, "quit", "Quit"))
+                        .clicked()
+                    {
+                        ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                    }
+                });
+                ui.horizontal(|ui| {
+                    ui.spacing_mut().item_spacing.x = self.config.margin_between_buttons as f32;
+                    for (index, button) in self.buttons.iter().enumerate() {
+                        let response = match button {
+                            Some(E4EguiButton {
+                                icon: Some(icon), ..
+                            }) => {
+                                ui.add(egui::ImageButton::new((icon.id(), icon_size)).frame(false))
+                            }
+                            Some(button) => {
+                                ui.add_sized(icon_size, egui::Button::new(&button.name))
+                            }
+                            None => {
+                                ui.add_space(icon_size.x);
+                                continue;
+                            }
+                        };
+                        let name = button.as_ref().map_or("", |button| button.name.as_str());
+                        if response.on_hover_text(name).clicked() {
+                            clicked = Some(index);
+                        }
+                    }
+                });
+                if let Some(error) = &self.error {
+                    ui.colored_label(egui::Color32::RED, error);
+                }
+            });
+        if let Some(index) = clicked {
+            self.launch(index);
+        }
+    }
+}
+
+/// Return the button name of config, or None if it is a widget: the widgets are drawn by FLTK only.
+fn command_button(
+    ctx: &egui::Context,
+    config: &E4Config,
+    name: &str,
+    translations: Arc<Mutex<Translations>>,
+) -> Option<E4EguiButton> {
+    let ini = config
+        .storage
+        .load_ini(&button_file(&config.config_dir, name))
+        .ok()?;
+    let value = |key: &str| {
+        ini.get(BUTTON_BUTTON_SECTION, key)
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    };
+    let button_type = ini
+        .get(BUTTON_BUTTON_SECTION, "TYPE")
+        .map_or(E4ButtonType::Command, |button_type| {
+            E4ButtonType::from_name(&button_type)
+        });
+    if button_type != E4ButtonType::Command {
+        return None;
+    }
+    let icon = match value("ICON") {
+        icon if icon.is_empty() => e4initialize::get_generic_icon(translations.clone()),
+        icon => config.assets_dir.join(icon),
+    };
+    Some(E4EguiButton {
+        name: name.to_string(),
+        command: E4Command::new(
+            value("COMMAND"),
+            e4command::split_arguments(&value("ARGUMENTS")),
+        ),
+        icon: load_icon(ctx, config, name, &icon, translations).ok(),
+    })
+}
+
+/// Decode the icon in path of the button name of config, styled as in the FLTK dock, to a texture of ctx.
+/// It is decoded in the pixels of the screen, so it stays sharp on the HiDPI displays.
+fn load_icon(
+    ctx: &egui::Context,
+    config: &E4Config,
+    name: &str,
+    path: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<egui::TextureHandle, Box<dyn std::error::Error>> {
+    let pixels = |size: i32| (size as f32 * ctx.pixels_per_point()).round() as u32;
+    let png_data = e4icon::scale_to_fit(
+        &e4icon::icon_png_data(path, translations)?,
+        pixels(config.icon_width),
+        pixels(config.icon_height),
+    )?;
+    let png_data = e4icon::styled(&png_data, config.style.icon_tint, config.style.icon_effects);
+    let image = image::load_from_memory(&png_data)?.to_rgba8();
+    let image = egui::ColorImage::from_rgba_unmultiplied(
+        [image.width() as usize, image.height() as usize],
+        image.as_raw(),
+    );
+    Ok(ctx.load_texture(name, image, egui::TextureOptions::LINEAR))
+}
+
+/// Show the dock of the configuration in config_dir with egui instead of FLTK, until it is closed.
+/// It launches the commands of the buttons: the widgets, the menus and the dialogs are left to the FLTK dock.
+pub fn run(
+    config_dir: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let config = E4Config::read(config_dir, translations.clone())?;
+    let options = eframe::NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_title("E4 Docker")
+            .with_position([config.x as f32, config.y as f32])
+            .with_inner_size([config.window_width as f32, config.window_height as f32])
+            .with_decorations(false)
+            .with_always_on_top()
+            .with_resizable(false),
+        ..Default::default()
+    };
+    eframe::run_native(
+        "e4docker",
+        options,
+        Box::new(|creation| {
+            Ok(Box::new(E4EguiDock::new(
+                &creation.egui_ctx,
+                config,
+                translations,
+            )))
+        }),
+    )?;
+    Ok(())
+}
@@ -0,0 +1,93 @@
+use fltk::app;
+use lazy_static::lazy_static;
+use std::cell::RefCell;
+
+/// The seconds between two dispatches of the published events.
+const DISPATCH_INTERVAL: f64 = 0.1;
+
+lazy_static! {
+    /// The channel carrying the events to the main thread.
+    static ref BUS: (app::Sender<E4Event>, app::Receiver<E4Event>) = app::channel();
+}
+
+thread_local! {
+    /// The handlers of the events. The widgets live in the main thread: so do they.
+    static SUBSCRIBERS: RefCell<Vec<Box<dyn FnMut(&E4Event)>>> = RefCell::new(vec![]);
+}
+
+/// An event of the dock, published by the code changing the configuration or watching the processes
+/// and applied by the subscribers, like the window.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum E4Event {
+    /// e4docker.conf or the configuration of a button has changed.
+    ConfigChanged,
+    /// A button has been added, with its name.
+    ButtonAdded(String),
+    /// A button has been removed, with its name.
+    ButtonRemoved(String),
+    /// The configuration of a button has been edited: its old name and its name.
+    ButtonChanged { old_name: String, name: String },
+    /// The process (or the state, for the widgets) of a button, by name, is running or not.
+    ProcessStateChanged(String, bool),
+    /// The badge command of a button, by name, has printed a new text, or nothing.
+    BadgeChanged(String, Option<String>),
+    /// The badge command of a button, by name, asks for attention: the button blinks until clicked.
+    AttentionRequested(String),
+    /// The window of the program of a hovered button, by name, has been captured: its PNG thumbnail.
+    PreviewCaptured(String, Vec<u8>),
+    /// The icon of a button, by name, has been decoded in background: its PNG data.
+    IconLoaded(String, Vec<u8>),
+    /// A new window of the program launched by a button, by name, has appeared: its launch has ended.
+    WindowShown(String),
+    /// The command of a button, by name, has exited with a failure code just after its launch,
+    /// printing output as the last lines of its error output.
+    LaunchFailed {
+        name: String,
+        code: i32,
+        output: String,
+    },
+    /// Another dock has been started with the same profile: the running one shows itself instead.
+    FocusRequested,
+    /// An error to show, found where no dialog can be shown, like in a background thread.
+    Error(String),
+}
+
+/// Publish an event. It can be called from any thread: the event is handled by [dispatch] in the main thread.
+pub fn publish(event: E4Event) {
+    BUS.0.send(event);
+}
+
+/// Publish an event at the next dispatch, for the subscribers that cannot apply it yet.
+/// It must be called in the main thread.
+pub fn publish_later(event: E4Event) {
+    app::add_timeout3(DISPATCH_INTERVAL, move |_| publish(event.clone()));
+}
+
+/// Report an error from where it cannot be shown, like a background thread: the window shows it.
+pub fn report_error(message: &str) {
+    publish(E4Event::Error(message.to_string()));
+}
+
+/// Call handler on every event dispatched from now on.
+pub fn subscribe<F: FnMut(&E4Event) + 'static>(handler: F) {
+    SUBSCRIBERS.with(|subscribers| subscribers.borrow_mut().push(Box::new(handler)));
+}
+
+/// Pass the published events to the subscribers. It must be called in the main thread, see [start_dispatching].
+pub fn dispatch() {
+    while let Some(event) = BUS.1.recv() {
+        SUBSCRIBERS.with(|subscribers| {
+            for handler in subscribers.borrow_mut().iter_mut() {
+                handler(&event);
+            }
+        });
+    }
+}
+
+/// Dispatch the published events periodically from the FLTK event loop, run by `app.run()`.
+pub fn start_dispatching() {
+    app::add_timeout3(DISPATCH_INTERVAL, |handle| {
+        dispatch();
+        app::repeat_timeout3(DISPATCH_INTERVAL, handle);
+    });
+}
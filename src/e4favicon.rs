@@ -0,0 +1,205 @@
+use crate::e4events::{self, E4Event};
+use regex::Regex;
+use std::{
+    fs,
+    io::Cursor,
+    path::{Path, PathBuf},
+    process::Command,
+    thread,
+};
+
+/// The directory of the assets holding the downloaded favicons, named by their host.
+const FAVICONS_DIR: &str = "favicons";
+
+/// The seconds to wait for a download.
+const DOWNLOAD_TIMEOUT: &str = "10";
+
+/// Error raised when no favicon of a site can be downloaded.
+#[derive(Debug)]
+struct FaviconError {
+    details: String,
+}
+
+impl std::fmt::Display for FaviconError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for FaviconError {}
+
+/// Return true if command is a web address, opened by a URL button.
+pub fn is_url(command: &str) -> bool {
+    let command = command.trim().to_lowercase();
+    command.starts_with("http://") || command.starts_with("https://")
+}
+
+/// Return the scheme and the host of url, like ("https", "www.rust-lang.org").
+fn scheme_and_host(url: &str) -> Option<(&str, &str)> {
+    let (scheme, rest) = url.trim().split_once("://")?;
+    let authority = rest.split(['/', '?', '#']).next()?;
+    // Without the user and the port
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    if host.is_empty() {
+        None
+    } else {
+        Some((scheme, host))
+    }
+}
+
+/// Return the path of the favicon of the site of url in assets_dir, downloaded or not.
+pub fn icon_path(assets_dir: &Path, url: &str) -> Option<PathBuf> {
+    let (_, host) = scheme_and_host(url)?;
+    let file_name: String = host
+        .to_lowercase()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '.' || c == '-' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    // The host has dots: the extension is added, not set
+    Some(
+        assets_dir
+            .join(FAVICONS_DIR)
+            .join(format!("{}.png", file_name)),
+    )
+}
+
+/// Return true if path is where the favicon of the site of url is kept in assets_dir.
+pub fn is_favicon(path: &Path, assets_dir: &Path, url: &str) -> bool {
+    icon_path(assets_dir, url).is_some_and(|favicon| favicon == path)
+}
+
+/// Return the address of href, a link of the page in url.
+fn resolve(url: &str, href: &str) -> Option<String> {
+    let href = href.trim();
+    if is_url(href) {
+        return Some(href.to_string());
+    }
+    let (scheme, host) = scheme_and_host(url)?;
+    if let Some(path) = href.strip_prefix("//") {
+        return Some(format!("{}://{}", scheme, path));
+    }
+    if href.starts_with('/') {
+        return Some(format!("{}://{}{}", scheme, host, href));
+    }
+    // Relative to the directory of the page
+    let page = url.split(['?', '#']).next()?;
+    let (_, rest) = page.split_once("://")?;
+    let directory = match rest.rfind('/') {
+        Some(index) => &page[..page.len() - rest.len() + index],
+        None => page,
+    };
+    Some(format!("{}/{}", directory, href))
+}
+
+/// Return the addresses of the icons linked by the html of the page in url: the apple-touch-icons first,
+/// being the largest ones, then the icons, then /favicon.ico.
+pub fn icon_links(url: &str, html: &str) -> Vec<String> {
+    let link = Regex::new(r"(?is)<link\b[^>]*>").unwrap();
+    let attribute = |tag: &str, name: &str| {
+        let pattern = format!(r#"(?is)\b{}\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+))"#, name);
+        Regex::new(&pattern)
+            .unwrap()
+            .captures(tag)
+            .and_then(|captures| {
+                (1..=3)
+                    .find_map(|i| captures.get(i))
+                    .map(|value| value.as_str().to_string())
+            })
+    };
+
+    let mut touch_icons = vec![];
+    let mut icons = vec![];
+    for tag in link.find_iter(html).map(|tag| tag.as_str()) {
+        let rel = attribute(tag, "rel").unwrap_or_default().to_lowercase();
+        let href = match attribute(tag, "href").and_then(|href| resolve(url, &href)) {
+            Some(href) => href,
+            None => continue,
+        };
+        let rels: Vec<&str> = rel.split_whitespace().collect();
+        if rels.iter().any(|rel| rel.starts_with("apple-touch-icon")) {
+            touch_icons.push(href);
+        } else if rels.contains(&"icon") {
+            icons.push(href);
+        }
+    }
+    touch_icons.extend(icons);
+    if let Some(favicon) = resolve(url, "/favicon.ico") {
+        touch_icons.push(favicon);
+    }
+    touch_icons
+}
+
+/// Download url with curl, found on Linux, macOS and Windows 10 and later.
+fn download(url: &str) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let output = Command::new("curl")
+        .args(["-fsSL", "--max-time", DOWNLOAD_TIMEOUT])
+        .arg(url)
+        .output()?;
+    if !output.status.success() {
+        return Err(Box::new(FaviconError {
+            details: format!(
+                "Cannot download {}: {}",
+                url,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        }));
+    }
+    Ok(output.stdout)
+}
+
+/// Download the favicon of the site of url and save it as PNG in path. Return the PNG data.
+/// The icons that cannot be decoded, like the SVG ones, are skipped.
+pub fn fetch(url: &str, path: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let html = download(url)
+        .map(|html| String::from_utf8_lossy(&html).to_string())
+        .unwrap_or_default();
+    for link in icon_links(url, &html) {
+        let image = match download(&link).map(|data| image::load_from_memory(&data)) {
+            Ok(Ok(image)) => image,
+            _ => continue,
+        };
+        return save_png(&image, path);
+    }
+    Err(Box::new(FaviconError {
+        details: format!("{} has no favicon", url),
+    }))
+}
+
+/// Download the image at url and save it as PNG in path. Return the PNG data.
+pub fn fetch_icon(url: &str, path: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let image = image::load_from_memory(&download(url.trim())?)?;
+    save_png(&image, path)
+}
+
+/// Save image as PNG in path, creating its directory. Return the PNG data.
+fn save_png(
+    image: &image::DynamicImage,
+    path: &Path,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let mut cursor = Cursor::new(vec![]);
+    image.write_to(&mut cursor, image::ImageFormat::Png)?;
+    let png_data = cursor.into_inner();
+    if let Some(directory) = path.parent() {
+        fs::create_dir_all(directory)?;
+    }
+    fs::write(path, &png_data)?;
+    Ok(png_data)
+}
+
+/// Fetch the favicon of url in a separate thread, then show it on the button named name.
+/// The button keeps the generic icon if the site cannot be reached: the favicon is fetched again
+/// at the next start.
+pub fn fetch_later(name: &str, url: &str, path: PathBuf) {
+    let name = name.to_string();
+    let url = url.trim().to_string();
+    thread::spawn(move || match fetch(&url, &path) {
+        Ok(png_data) => e4events::publish(E4Event::IconLoaded(name, png_data)),
+        Err(e) => log::warn!("{}", e),
+    });
+}
@@ -0,0 +1,164 @@
+use crate::e4style::E4Edge;
+use std::{
+    collections::VecDeque,
+    time::{Duration, Instant},
+};
+
+/// The time in which the scroll events of a swipe are added up.
+const SWIPE_SPAN: Duration = Duration::from_millis(300);
+
+/// The shortest swipe, in scroll units along its direction.
+const SWIPE_DISTANCE: i32 = 8;
+
+/// The lowest speed of a swipe, in scroll units per second: a slower scroll is not a swipe.
+const SWIPE_VELOCITY: f64 = 40.0;
+
+/// The shortest time between the clicks of a slow double click: the faster ones are double clicks.
+const SLOW_DOUBLE_CLICK_MIN: Duration = Duration::from_millis(500);
+
+/// The longest time between the clicks of a slow double click: the slower ones are single clicks.
+const SLOW_DOUBLE_CLICK_MAX: Duration = Duration::from_millis(1500);
+
+/// The direction of a swipe, as the scroll events report it: down for a positive vertical scroll.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum E4Swipe {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl E4Swipe {
+    /// Return the swipe in the opposite direction.
+    pub fn opposite(&self) -> Self {
+        match self {
+            E4Swipe::Up => E4Swipe::Down,
+            E4Swipe::Down => E4Swipe::Up,
+            E4Swipe::Left => E4Swipe::Right,
+            E4Swipe::Right => E4Swipe::Left,
+        }
+    }
+
+    /// Return true if the swipe goes toward edge: toward one of its sides for a corner.
+    pub fn is_toward(&self, edge: E4Edge) -> bool {
+        match self {
+            E4Swipe::Up => matches!(edge, E4Edge::Top | E4Edge::TopLeft | E4Edge::TopRight),
+            E4Swipe::Down => matches!(
+                edge,
+                E4Edge::Bottom | E4Edge::BottomLeft | E4Edge::BottomRight
+            ),
+            E4Swipe::Left => matches!(edge, E4Edge::Left | E4Edge::TopLeft | E4Edge::BottomLeft),
+            E4Swipe::Right => {
+                matches!(edge, E4Edge::Right | E4Edge::TopRight | E4Edge::BottomRight)
+            }
+        }
+    }
+
+    /// Return true if the swipe goes away from edge.
+    pub fn is_away_from(&self, edge: E4Edge) -> bool {
+        self.opposite().is_toward(edge)
+    }
+}
+
+/// Recognize the swipes from the scroll events of a touchpad or a touchscreen.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4gesture::{E4Swipe, E4SwipeTracker};
+/// use std::time::{Duration, Instant};
+///
+/// let mut tracker = E4SwipeTracker::default();
+/// let start = Instant::now();
+/// assert_eq!(tracker.add(start, 0, 4), None);
+/// assert_eq!(tracker.add(start + Duration::from_millis(50), 1, 5), Some(E4Swipe::Down));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct E4SwipeTracker {
+    /// The scroll events in the last [SWIPE_SPAN]: their time and their horizontal and vertical scroll.
+    samples: VecDeque<(Instant, i32, i32)>,
+}
+
+impl E4SwipeTracker {
+    /// Add a scroll event happened at, and return the swipe it completes, if any.
+    /// Once recognized, a swipe is forgotten, so the same events do not make another one.
+    pub fn add(&mut self, at: Instant, dx: i32, dy: i32) -> Option<E4Swipe> {
+        while let Some((first, _, _)) = self.samples.front() {
+            if at.duration_since(*first) > SWIPE_SPAN {
+                self.samples.pop_front();
+            } else {
+                break;
+            }
+        }
+        self.samples.push_back((at, dx, dy));
+
+        let (dx, dy) = self
+            .samples
+            .iter()
+            .fold((0, 0), |(x, y), (_, dx, dy)| (x + dx, y + dy));
+        // The swipe goes mostly along one direction
+        let (distance, swipe) = if dx.abs() >= dy.abs() * 2 {
+            (
+                dx.abs(),
+                if dx > 0 {
+                    E4Swipe::Right
+                } else {
+                    E4Swipe::Left
+                },
+            )
+        } else if dy.abs() >= dx.abs() * 2 {
+            (dy.abs(), if dy > 0 { E4Swipe::Down } else { E4Swipe::Up })
+        } else {
+            return None;
+        };
+        let elapsed = match self.samples.front() {
+            Some((first, _, _)) => at.duration_since(*first).as_secs_f64(),
+            None => 0.0,
+        };
+        // A single event is as fast as it gets
+        let velocity = distance as f64 / elapsed.max(f64::EPSILON);
+        if distance >= SWIPE_DISTANCE && velocity >= SWIPE_VELOCITY {
+            self.samples.clear();
+            Some(swipe)
+        } else {
+            None
+        }
+    }
+}
+
+/// Recognize the slow double clicks on a button, renaming it: the second click comes after a double click,
+/// but not much later.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4gesture::E4SlowClickTracker;
+/// use std::time::{Duration, Instant};
+///
+/// let mut tracker = E4SlowClickTracker::default();
+/// let start = Instant::now();
+/// assert!(!tracker.add(start, "firefox"));
+/// assert!(tracker.add(start + Duration::from_millis(800), "firefox"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct E4SlowClickTracker {
+    /// The last click: its time and the name of its button.
+    last: Option<(Instant, String)>,
+}
+
+impl E4SlowClickTracker {
+    /// Add a click on the button name happened at, and return true if it completes a slow double click.
+    /// Once recognized, a slow double click is forgotten, so a third click does not make another one.
+    pub fn add(&mut self, at: Instant, name: &str) -> bool {
+        let is_slow_double_click = match self.last.take() {
+            Some((last, last_name)) if last_name == name => {
+                (SLOW_DOUBLE_CLICK_MIN..=SLOW_DOUBLE_CLICK_MAX).contains(&at.duration_since(last))
+            }
+            _ => false,
+        };
+        if !is_slow_double_click {
+            self.last = Some((at, name.to_string()));
+        }
+        is_slow_double_click
+    }
+}
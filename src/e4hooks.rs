@@ -0,0 +1,111 @@
+use crate::{e4command, e4config::E4DOCKER_HOOKS_SECTION};
+use configparser::ini::Ini;
+use lazy_static::lazy_static;
+use std::{collections::HashMap, process::Command, sync::Mutex};
+
+lazy_static! {
+    /// The hooks of the current configuration.
+    static ref HOOKS: Mutex<E4Hooks> = Mutex::new(E4Hooks::default());
+}
+
+/// An event of the dock which can run a command.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum E4Hook {
+    /// The dock has started.
+    Startup,
+    /// The dock is quitting.
+    Quit,
+    /// A button has launched its command.
+    ButtonLaunched,
+    /// The process of a button has started, from the dock or not.
+    ProcessStarted,
+}
+
+impl E4Hook {
+    /// All the hooks.
+    pub const ALL: [E4Hook; 4] = [
+        E4Hook::Startup,
+        E4Hook::Quit,
+        E4Hook::ButtonLaunched,
+        E4Hook::ProcessStarted,
+    ];
+
+    /// Return the key of the hook in the HOOKS section of e4docker.conf.
+    pub fn key(&self) -> &'static str {
+        match self {
+            E4Hook::Startup => "on_startup",
+            E4Hook::Quit => "on_quit",
+            E4Hook::ButtonLaunched => "on_button_launched",
+            E4Hook::ProcessStarted => "on_process_started",
+        }
+    }
+}
+
+/// The commands run on the dock events, read from the HOOKS section of e4docker.conf:
+///
+/// ```ini
+/// [hooks]
+/// on_startup=notify-send "The dock has started"
+/// on_button_launched=echo "$E4DOCKER_BUTTON: $E4DOCKER_COMMAND" >> ~/launches.log
+/// ```
+///
+/// The commands are run by the shell, with the E4DOCKER_EVENT environment variable set to the key of the hook
+/// and, for the button events, E4DOCKER_BUTTON and E4DOCKER_COMMAND set to the button name and its command.
+#[derive(Clone, Debug, Default)]
+pub struct E4Hooks {
+    commands: HashMap<E4Hook, String>,
+}
+
+impl E4Hooks {
+    /// Read the hooks from the loaded e4docker.conf.
+    pub fn from_ini(ini: &Ini) -> Self {
+        let mut commands = HashMap::new();
+        for hook in E4Hook::ALL {
+            if let Some(command) = ini.get(E4DOCKER_HOOKS_SECTION, hook.key()) {
+                if !command.trim().is_empty() {
+                    commands.insert(hook, command.trim().to_string());
+                }
+            }
+        }
+        Self { commands }
+    }
+
+    /// Return the command run on an event.
+    pub fn command(&self, hook: E4Hook) -> Option<&String> {
+        self.commands.get(&hook)
+    }
+}
+
+/// Set the hooks of the current configuration.
+pub fn set(hooks: E4Hooks) {
+    *HOOKS.lock().unwrap() = hooks;
+}
+
+/// Return the shell command of a hook, if set, with its environment.
+fn hook_command(hook: E4Hook, button: Option<(&str, &str)>) -> Option<Command> {
+    let hooks = HOOKS.lock().unwrap();
+    let mut command = e4command::shell_command(hooks.command(hook)?);
+    command.env("E4DOCKER_EVENT", hook.key());
+    if let Some((name, cmd)) = button {
+        command.env("E4DOCKER_BUTTON", name);
+        command.env("E4DOCKER_COMMAND", cmd);
+    }
+    Some(command)
+}
+
+/// Run the command of a hook, without waiting for it.
+/// button holds the name and the command of the button, for the button events.
+pub fn run(hook: E4Hook, button: Option<(&str, &str)>) {
+    if let Some(mut command) = hook_command(hook, button) {
+        if let Ok(child) = command.spawn() {
+            e4command::supervise_child(child);
+        }
+    }
+}
+
+/// Run the command of a hook and wait for it, e.g. before quitting.
+pub fn run_and_wait(hook: E4Hook) {
+    if let Some(mut command) = hook_command(hook, None) {
+        let _ = command.status();
+    }
+}
@@ -1,9 +1,179 @@
-use crate::{e4config::E4Config, tr, translations::Translations};
+use crate::{
+    e4bundle,
+    e4config::E4Config,
+    e4favicon,
+    e4style::{E4IconEffects, E4IconTint},
+    e4window, tr,
+    translations::Translations,
+};
+use image::{imageops::FilterType, Rgba, RgbaImage};
 use std::{
-    path::PathBuf,
+    io::Cursor,
+    path::{Path, PathBuf},
     sync::{Arc, Mutex},
 };
 
+/// The sizes of the icons of the hicolor theme searched by [theme_icon], from the largest.
+const THEME_SIZES: [&str; 7] = [
+    "512x512", "256x256", "128x128", "96x96", "64x64", "48x48", "32x32",
+];
+
+/// The pattern of the files chosen as icons: the formats decoded by [to_png], and the exe files.
+pub const ICON_PATTERN: &str = "*.{png,gif,bmp,webp,jpg,jpeg,ico,icns,exe}";
+
+/// The largest side of the icons saved in the assets directory, in pixels: the larger ones, like the 512x512
+/// icons of the theme, are scaled down by [scale_to_fit] to be decoded faster when the dock starts.
+pub const SAVED_ICON_SIZE: u32 = 256;
+
+/// The side of the monograms, in pixels.
+const MONOGRAM_SIZE: u32 = 128;
+
+/// The radius of the corners of the monograms, in pixels.
+const MONOGRAM_RADIUS: u32 = 24;
+
+/// The pixels of a cell of the font of the monograms.
+const MONOGRAM_SCALE: u32 = 12;
+
+/// The backgrounds of the monograms, chosen by the name of the button.
+const MONOGRAM_COLORS: [(u8, u8, u8); 8] = [
+    (0xd3, 0x2f, 0x2f),
+    (0xc2, 0x18, 0x5b),
+    (0x7b, 0x1f, 0xa2),
+    (0x30, 0x3f, 0x9f),
+    (0x02, 0x88, 0xd1),
+    (0x00, 0x79, 0x6b),
+    (0x38, 0x8e, 0x3c),
+    (0xe6, 0x4a, 0x19),
+];
+
+/// The characters drawn by the monograms, in the order of [MONOGRAM_GLYPHS].
+const MONOGRAM_CHARS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ0123456789";
+
+/// The glyphs of [MONOGRAM_CHARS], 5x7 cells: a row for each byte, whose bit 4 is the leftmost cell.
+const MONOGRAM_GLYPHS: [[u8; 7]; 36] = [
+    [
+        0b01110, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001,
+    ],
+    [
+        0b11110, 0b10001, 0b10001, 0b11110, 0b10001, 0b10001, 0b11110,
+    ],
+    [
+        0b01110, 0b10001, 0b10000, 0b10000, 0b10000, 0b10001, 0b01110,
+    ],
+    [
+        0b11110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b11110,
+    ],
+    [
+        0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b11111,
+    ],
+    [
+        0b11111, 0b10000, 0b10000, 0b11110, 0b10000, 0b10000, 0b10000,
+    ],
+    [
+        0b01110, 0b10001, 0b10000, 0b10111, 0b10001, 0b10001, 0b01111,
+    ],
+    [
+        0b10001, 0b10001, 0b10001, 0b11111, 0b10001, 0b10001, 0b10001,
+    ],
+    [
+        0b01110, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+    ],
+    [
+        0b00111, 0b00010, 0b00010, 0b00010, 0b00010, 0b10010, 0b01100,
+    ],
+    [
+        0b10001, 0b10010, 0b10100, 0b11000, 0b10100, 0b10010, 0b10001,
+    ],
+    [
+        0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b10000, 0b11111,
+    ],
+    [
+        0b10001, 0b11011, 0b10101, 0b10101, 0b10001, 0b10001, 0b10001,
+    ],
+    [
+        0b10001, 0b10001, 0b11001, 0b10101, 0b10011, 0b10001, 0b10001,
+    ],
+    [
+        0b01110, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+    ],
+    [
+        0b11110, 0b10001, 0b10001, 0b11110, 0b10000, 0b10000, 0b10000,
+    ],
+    [
+        0b01110, 0b10001, 0b10001, 0b10001, 0b10101, 0b10010, 0b01101,
+    ],
+    [
+        0b11110, 0b10001, 0b10001, 0b11110, 0b10100, 0b10010, 0b10001,
+    ],
+    [
+        0b01111, 0b10000, 0b10000, 0b01110, 0b00001, 0b00001, 0b11110,
+    ],
+    [
+        0b11111, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100, 0b00100,
+    ],
+    [
+        0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01110,
+    ],
+    [
+        0b10001, 0b10001, 0b10001, 0b10001, 0b10001, 0b01010, 0b00100,
+    ],
+    [
+        0b10001, 0b10001, 0b10001, 0b10101, 0b10101, 0b10101, 0b01010,
+    ],
+    [
+        0b10001, 0b10001, 0b01010, 0b00100, 0b01010, 0b10001, 0b10001,
+    ],
+    [
+        0b10001, 0b10001, 0b01010, 0b00100, 0b00100, 0b00100, 0b00100,
+    ],
+    [
+        0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b10000, 0b11111,
+    ],
+    [
+        0b01110, 0b10001, 0b10011, 0b10101, 0b11001, 0b10001, 0b01110,
+    ],
+    [
+        0b00100, 0b01100, 0b00100, 0b00100, 0b00100, 0b00100, 0b01110,
+    ],
+    [
+        0b01110, 0b10001, 0b00001, 0b00010, 0b00100, 0b01000, 0b11111,
+    ],
+    [
+        0b11111, 0b00010, 0b00100, 0b00010, 0b00001, 0b10001, 0b01110,
+    ],
+    [
+        0b00010, 0b00110, 0b01010, 0b10010, 0b11111, 0b00010, 0b00010,
+    ],
+    [
+        0b11111, 0b10000, 0b11110, 0b00001, 0b00001, 0b10001, 0b01110,
+    ],
+    [
+        0b00110, 0b01000, 0b10000, 0b11110, 0b10001, 0b10001, 0b01110,
+    ],
+    [
+        0b11111, 0b00001, 0b00010, 0b00100, 0b01000, 0b01000, 0b01000,
+    ],
+    [
+        0b01110, 0b10001, 0b10001, 0b01110, 0b10001, 0b10001, 0b01110,
+    ],
+    [
+        0b01110, 0b10001, 0b10001, 0b01111, 0b00001, 0b00010, 0b01100,
+    ],
+];
+
+#[derive(Debug)]
+struct IconError {
+    details: String,
+}
+
+impl std::fmt::Display for IconError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for IconError {}
+
 /// The icon on a [crate::e4button::E4Button].
 pub struct E4Icon {
     path: PathBuf,
@@ -71,3 +241,375 @@ impl E4Icon {
         self.path = path;
     }
 }
+
+/// A way to find the icon of a button given only its command, tried in turn by [resolve_icon].
+pub trait E4IconResolver {
+    /// Return the icon of the button named name launching cmd as PNG data, or None to try the next resolver.
+    fn resolve(&self, name: &str, cmd: &str) -> Option<Vec<u8>>;
+}
+
+/// Find the icon in the Icon key of the .desktop entry of the command.
+pub struct E4DesktopIconResolver;
+
+impl E4IconResolver for E4DesktopIconResolver {
+    fn resolve(&self, _name: &str, cmd: &str) -> Option<Vec<u8>> {
+        let icon = e4window::desktop_icon(cmd)?;
+        let path = PathBuf::from(&icon);
+        if path.is_absolute() {
+            png_data(&path)
+        } else {
+            png_data(&theme_icon(&icon)?)
+        }
+    }
+}
+
+/// Find the icon embedded in the command: the resource of an exe file or the icns of a macOS bundle.
+pub struct E4ResourceIconResolver {
+    pub translations: Arc<Mutex<Translations>>,
+}
+
+impl E4IconResolver for E4ResourceIconResolver {
+    fn resolve(&self, _name: &str, cmd: &str) -> Option<Vec<u8>> {
+        let path = Path::new(cmd.trim());
+        // The executable of a bundle is in its Contents/MacOS directory
+        if let Some(bundle) = path.ancestors().find(|path| e4bundle::is_app_bundle(path)) {
+            return e4bundle::bundle_icon_png(bundle).ok();
+        }
+        let is_exe = path
+            .extension()
+            .and_then(std::ffi::OsStr::to_str)
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("exe"));
+        if !is_exe || !path.exists() {
+            return None;
+        }
+        icon_png_data(path, self.translations.clone()).ok()
+    }
+}
+
+/// Find the icon of the theme named as the program of the command, like firefox for /usr/bin/firefox.
+pub struct E4ThemeIconResolver;
+
+impl E4IconResolver for E4ThemeIconResolver {
+    fn resolve(&self, _name: &str, cmd: &str) -> Option<Vec<u8>> {
+        png_data(&theme_icon(&e4window::program_name(cmd)?)?)
+    }
+}
+
+/// Download the favicon of the site opened by a URL button, keeping it in the assets directory.
+pub struct E4FaviconResolver {
+    pub assets_dir: PathBuf,
+}
+
+impl E4IconResolver for E4FaviconResolver {
+    fn resolve(&self, _name: &str, cmd: &str) -> Option<Vec<u8>> {
+        if !e4favicon::is_url(cmd) {
+            return None;
+        }
+        let path = e4favicon::icon_path(&self.assets_dir, cmd)?;
+        match e4favicon::fetch(cmd, &path) {
+            Ok(png_data) => Some(png_data),
+            Err(e) => {
+                log::warn!("{}", e);
+                None
+            }
+        }
+    }
+}
+
+/// Draw the first letter or digit of the name of the button: it always finds an icon.
+pub struct E4MonogramResolver;
+
+impl E4IconResolver for E4MonogramResolver {
+    fn resolve(&self, name: &str, cmd: &str) -> Option<Vec<u8>> {
+        let name = match name.trim() {
+            "" => e4window::program_name(cmd).unwrap_or_default(),
+            name => name.to_string(),
+        };
+        Some(monogram(&name))
+    }
+}
+
+/// Return the resolvers of the icons in the order they are tried: the Icon of the .desktop entry,
+/// the resource of an exe or a bundle, the icon of the theme named as the program, the favicon of a URL
+/// and at last a monogram. Push or insert others to change how the icons are found.
+pub fn default_resolvers(
+    assets_dir: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Vec<Box<dyn E4IconResolver>> {
+    vec![
+        Box::new(E4DesktopIconResolver),
+        Box::new(E4ResourceIconResolver { translations }),
+        Box::new(E4ThemeIconResolver),
+        Box::new(E4FaviconResolver {
+            assets_dir: assets_dir.to_path_buf(),
+        }),
+        Box::new(E4MonogramResolver),
+    ]
+}
+
+/// Return the icon of the button named name launching cmd as PNG data, found by the first of resolvers finding one.
+pub fn resolve_icon(
+    resolvers: &[Box<dyn E4IconResolver>],
+    name: &str,
+    cmd: &str,
+) -> Option<Vec<u8>> {
+    resolvers
+        .iter()
+        .find_map(|resolver| resolver.resolve(name, cmd))
+}
+
+/// Find the icon of the button named name launching cmd with the [default_resolvers], save it in the assets
+/// directory of config and return its file name, to be the icon of the button.
+pub fn save_resolved_icon(
+    config: &E4Config,
+    name: &str,
+    cmd: &str,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<String, Box<dyn std::error::Error>> {
+    // A configuration in memory has no assets on disk
+    if !config.assets_dir.is_dir() {
+        return Err(Box::new(IconError {
+            details: format!("{} is not a directory", config.assets_dir.display()),
+        }));
+    }
+    let resolvers = default_resolvers(&config.assets_dir, translations);
+    let png_data = match resolve_icon(&resolvers, name, cmd) {
+        Some(png_data) => png_data,
+        None => {
+            return Err(Box::new(IconError {
+                details: format!("{} has no icon", cmd),
+            }))
+        }
+    };
+    // Do not replace the icon of another button
+    let mut file_name = format!("{}.png", name);
+    let mut copy = 1;
+    while config.assets_dir.join(&file_name).exists() {
+        copy += 1;
+        file_name = format!("{}-{}.png", name, copy);
+    }
+    let png_data = scale_to_fit(&png_data, SAVED_ICON_SIZE, SAVED_ICON_SIZE)?;
+    std::fs::write(config.assets_dir.join(&file_name), png_data)?;
+    Ok(file_name)
+}
+
+/// Convert the image in path to PNG: a PNG, JPEG, GIF, BMP, WebP or ICO file, recognized by its content
+/// whatever its extension. An animated GIF gives its first frame.
+pub fn to_png(path: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    decode_to_png(&std::fs::read(path)?)
+}
+
+/// Decode data, an image in one of the formats of [to_png], to PNG data.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4icon::{decode_to_png, monogram};
+///
+/// let png_data = monogram("Firefox");
+/// let image = image::load_from_memory(&decode_to_png(&png_data).unwrap()).unwrap();
+/// assert_eq!((image.width(), image.height()), (128, 128));
+/// assert!(decode_to_png(b"not an image").is_err());
+/// ```
+pub fn decode_to_png(data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let image = image::load_from_memory(data)?;
+    let mut cursor = Cursor::new(vec![]);
+    image.write_to(&mut cursor, image::ImageFormat::Png)?;
+    Ok(cursor.into_inner())
+}
+
+/// Return the PNG image in png_data scaled down, keeping its proportions, to fit in width x height.
+/// A smaller image is returned as it is: the icons are never enlarged.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4icon::{monogram, scale_to_fit};
+///
+/// let scaled = scale_to_fit(&monogram("Firefox"), 64, 32).unwrap();
+/// let image = image::load_from_memory(&scaled).unwrap();
+/// assert_eq!((image.width(), image.height()), (32, 32));
+/// assert_eq!(scale_to_fit(&scaled, 64, 64).unwrap(), scaled);
+/// ```
+pub fn scale_to_fit(
+    png_data: &[u8],
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let image = image::load_from_memory(png_data)?;
+    if image.width() <= width && image.height() <= height {
+        return Ok(png_data.to_vec());
+    }
+    let image = image.resize(width, height, FilterType::Lanczos3);
+    let mut cursor = Cursor::new(vec![]);
+    image.write_to(&mut cursor, image::ImageFormat::Png)?;
+    Ok(cursor.into_inner())
+}
+
+/// Return png_data recolored by tint and with effects, or as it is if it cannot be decoded.
+pub fn styled(png_data: &[u8], tint: E4IconTint, effects: E4IconEffects) -> Vec<u8> {
+    tint.apply(png_data)
+        .and_then(|png_data| effects.apply(&png_data))
+        .unwrap_or_else(|_| png_data.to_vec())
+}
+
+/// Decode the image in image_path to PNG data: an image, the icon of a macOS bundle or of an icns file,
+/// or the first icon of an exe file.
+/// It shows no dialogs and needs no display, so it can run in a background thread or in a benchmark.
+pub fn icon_png_data(
+    image_path: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    // The generic icon does not need to be on disk
+    if !image_path.exists()
+        && crate::e4initialize::is_generic_icon(
+            image_path,
+            &crate::e4initialize::get_package_assets_dir(translations.clone()),
+        )
+    {
+        return Ok(crate::e4initialize::generic_png_data().to_vec());
+    }
+    let opening_error = |e: &dyn std::fmt::Display| IconError {
+        details: tr!(
+            translations,
+            format,
+            "error-in-opening",
+            &[&image_path.display().to_string(), &e.to_string()]
+        ),
+    };
+    let image_extension = match &image_path.extension().and_then(std::ffi::OsStr::to_str) {
+        Some(extension) => extension.to_lowercase(),
+        None => {
+            return Err(Box::new(IconError {
+                details: tr!(
+                    translations,
+                    format_display,
+                    "error-in-getting-the-icon-extension",
+                    &[&image_path.display()]
+                ),
+            }))
+        }
+    };
+    if image_extension == "app" || image_extension == "icns" {
+        // macOS bundle or icns file: use the largest embedded PNG
+        let icns_result = if image_extension == "app" {
+            e4bundle::bundle_icon_png(image_path)
+        } else {
+            e4bundle::icns_to_png(image_path)
+        };
+        return Ok(icns_result.map_err(|e| opening_error(&e))?);
+    }
+    if image_extension != "exe" {
+        return to_png(image_path);
+    }
+
+    Ok(exe_icon(image_path).map_err(|e| opening_error(&e))?)
+}
+
+/// Return the first icon of the exe file in path as PNG data.
+#[cfg(feature = "exe-icons")]
+fn exe_icon(path: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    use pelite::pe32::{Pe as Pe32, PeFile as PeFile32};
+    use pelite::pe64::{Pe as Pe64, PeFile as PeFile64};
+    use pelite::resources::Name;
+    use pelite::FileMap;
+
+    // Open and map the exe file
+    let file_map = FileMap::open(path)?;
+    // RT_ICON as Name::Id
+    let icon = Name::Id(3);
+    // Try as PE32
+    let icon_data = match PeFile32::from_bytes(&file_map) {
+        Ok(pe32) => {
+            let resources = pe32.resources()?;
+            // Get the first icon
+            resources.find_resource(&[icon, Name::Id(1)])?
+        }
+        Err(_) => {
+            // If PE32 fails, try as PE64
+            let pe64 = PeFile64::from_bytes(&file_map)?;
+            let resources = pe64.resources()?;
+            // Get the first icon
+            resources.find_resource(&[icon, Name::Id(1)])?
+        }
+    };
+
+    // Convert the raw data of the icon to PNG
+    decode_to_png(icon_data)
+}
+
+/// Return the first icon of the exe file in path as PNG data: never without the exe-icons feature.
+#[cfg(not(feature = "exe-icons"))]
+fn exe_icon(path: &Path) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    Err(Box::new(IconError {
+        details: format!(
+            "{} is an exe file: this build does not read their icons",
+            path.display()
+        ),
+    }))
+}
+
+/// Return the PNG data of the image in path, or None if it cannot be decoded, like an SVG icon.
+fn png_data(path: &Path) -> Option<Vec<u8>> {
+    to_png(path).ok()
+}
+
+/// Return the path of the PNG icon named name in the hicolor theme, the largest first, or in the pixmaps.
+pub fn theme_icon(name: &str) -> Option<PathBuf> {
+    // Some .desktop entries name the file of the icon
+    let file_name = format!("{}.png", name.strip_suffix(".png").unwrap_or(name));
+    let dirs = e4window::data_dirs();
+    let themed = dirs.iter().flat_map(|dir| {
+        THEME_SIZES
+            .iter()
+            .map(move |size| dir.join("icons/hicolor").join(size).join("apps"))
+    });
+    themed
+        .chain(dirs.iter().map(|dir| dir.join("pixmaps")))
+        .map(|dir| dir.join(&file_name))
+        .find(|path| path.is_file())
+}
+
+/// Return the monogram of name as PNG data: its first letter or digit, in white on a rounded square
+/// whose color depends on name.
+pub fn monogram(name: &str) -> Vec<u8> {
+    let seed = name.bytes().fold(0usize, |seed, byte| {
+        seed.wrapping_mul(31).wrapping_add(byte as usize)
+    });
+    let (r, g, b) = MONOGRAM_COLORS[seed % MONOGRAM_COLORS.len()];
+    let glyph = name
+        .chars()
+        .find(char::is_ascii_alphanumeric)
+        .and_then(|c| MONOGRAM_CHARS.find(c.to_ascii_uppercase()))
+        .map(|index| MONOGRAM_GLYPHS[index]);
+    let (width, height) = (5 * MONOGRAM_SCALE, 7 * MONOGRAM_SCALE);
+    let (left, top) = ((MONOGRAM_SIZE - width) / 2, (MONOGRAM_SIZE - height) / 2);
+    let image = RgbaImage::from_fn(MONOGRAM_SIZE, MONOGRAM_SIZE, |x, y| {
+        // The distance from the center of the nearest corner, outside the rounded square
+        let corner = |v: u32| {
+            let inner = v.clamp(MONOGRAM_RADIUS, MONOGRAM_SIZE - 1 - MONOGRAM_RADIUS);
+            v.abs_diff(inner)
+        };
+        let (dx, dy) = (corner(x), corner(y));
+        if dx * dx + dy * dy > MONOGRAM_RADIUS * MONOGRAM_RADIUS {
+            return Rgba([0, 0, 0, 0]);
+        }
+        let is_letter = glyph.is_some_and(|glyph| {
+            (left..left + width).contains(&x)
+                && (top..top + height).contains(&y)
+                && glyph[((y - top) / MONOGRAM_SCALE) as usize]
+                    & (0b10000 >> ((x - left) / MONOGRAM_SCALE))
+                    != 0
+        });
+        if is_letter {
+            Rgba([255, 255, 255, 255])
+        } else {
+            Rgba([r, g, b, 255])
+        }
+    });
+    let mut cursor = Cursor::new(vec![]);
+    image
+        .write_to(&mut cursor, image::ImageFormat::Png)
+        .expect("A monogram can be encoded as PNG");
+    cursor.into_inner()
+}
@@ -0,0 +1,345 @@
+use crate::{
+    e4button::E4ButtonType,
+    e4command,
+    e4config::{button_file, E4Config, BUTTON_BUTTON_SECTION},
+    e4favicon, e4icon,
+    e4share::{parse_json, quote, Json},
+    translations::Translations,
+};
+use configparser::ini::Ini;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+/// The format of a list of buttons to import, in the "format" key of its JSON.
+pub const BUTTONS_JSON_FORMAT: &str = "e4docker-buttons";
+
+/// The icon of the imported buttons without one.
+const GENERIC_ICON: &str = "generic.png";
+
+#[derive(Debug)]
+struct ImportError {
+    details: String,
+}
+
+impl std::fmt::Display for ImportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for ImportError {}
+
+/// Return the error of the value at path, like buttons[2].command, not matching the schema.
+fn schema_error(path: &str, expected: &str) -> Box<dyn std::error::Error> {
+    Box::new(ImportError {
+        details: format!("{}: {}", path, expected),
+    })
+}
+
+/// A button read from the JSON to import.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct E4ImportedButton {
+    pub name: String,
+    /// The type of the button, a command if None.
+    pub button_type: Option<E4ButtonType>,
+    pub command: String,
+    pub arguments: Vec<String>,
+    /// The path of the icon, relative to the assets directory, or its URL.
+    pub icon: Option<String>,
+    /// The other keys of its configuration file, like badge_command or accent_color.
+    pub flags: Vec<(String, String)>,
+}
+
+impl E4ImportedButton {
+    /// Return the configuration file of the button, whose icon is icon.
+    pub fn to_ini(&self, icon: &str) -> Ini {
+        let mut ini = Ini::new();
+        let mut set = |key: &str, value: String| {
+            ini.set(BUTTON_BUTTON_SECTION, key, Some(value));
+        };
+        set("command", self.command.clone());
+        set("arguments", e4command::join_arguments(&self.arguments));
+        set("icon", icon.to_string());
+        if let Some(button_type) = self.button_type {
+            set("type", button_type.name().to_string());
+        }
+        for (key, value) in &self.flags {
+            set(key, value.clone());
+        }
+        ini
+    }
+}
+
+/// Return the text of value, at path.
+fn text(value: &Json, path: &str) -> Result<String, Box<dyn std::error::Error>> {
+    match value {
+        Json::Text(text) => Ok(text.clone()),
+        _ => Err(schema_error(path, "expected a string")),
+    }
+}
+
+/// Return the button described by value, the one at index in the list.
+fn button_from_value(
+    value: &Json,
+    index: usize,
+) -> Result<E4ImportedButton, Box<dyn std::error::Error>> {
+    let path = format!("buttons[{}]", index);
+    let fields = match value {
+        Json::Object(fields) => fields,
+        _ => return Err(schema_error(&path, "expected an object")),
+    };
+    let mut button = E4ImportedButton::default();
+    for (key, value) in fields {
+        let path = format!("{}.{}", path, key);
+        match key.as_str() {
+            "name" => button.name = text(value, &path)?.trim().to_string(),
+            "type" => {
+                let name = text(value, &path)?;
+                let button_type = E4ButtonType::from_name(&name);
+                // Unknown types are read as commands: here they are a mistake
+                if button_type.name() != name.trim().to_lowercase() {
+                    return Err(schema_error(&path, "unknown type"));
+                }
+                button.button_type = Some(button_type);
+            }
+            "command" => button.command = text(value, &path)?.trim().to_string(),
+            "args" => {
+                button.arguments = match value {
+                    Json::Text(arguments) => e4command::split_arguments(arguments),
+                    Json::Array(arguments) => arguments
+                        .iter()
+                        .enumerate()
+                        .map(|(i, argument)| text(argument, &format!("{}[{}]", path, i)))
+                        .collect::<Result<_, _>>()?,
+                    _ => return Err(schema_error(&path, "expected a string or an array")),
+                }
+            }
+            "icon" => button.icon = Some(text(value, &path)?.trim().to_string()),
+            "flags" => {
+                let flags = match value {
+                    Json::Object(flags) => flags,
+                    _ => return Err(schema_error(&path, "expected an object")),
+                };
+                for (flag, value) in flags {
+                    let value = match value {
+                        Json::Text(value) | Json::Number(value) => value.clone(),
+                        Json::Bool(value) => value.to_string(),
+                        _ => {
+                            return Err(schema_error(
+                                &format!("{}.{}", path, flag),
+                                "expected a string, a number or a boolean",
+                            ))
+                        }
+                    };
+                    button.flags.push((flag.to_lowercase(), value));
+                }
+            }
+            _ => return Err(schema_error(&path, "unknown key")),
+        }
+    }
+    // The name is the name of the configuration file
+    if button.name.is_empty() || button.name.contains(['/', '\\']) {
+        return Err(schema_error(
+            &format!("{}.name", path),
+            "expected a name without / or \\",
+        ));
+    }
+    let needs_command = matches!(
+        button.button_type,
+        None | Some(E4ButtonType::Command) | Some(E4ButtonType::Watch)
+    );
+    if needs_command && button.command.is_empty() {
+        return Err(schema_error(&format!("{}.command", path), "missing"));
+    }
+    Ok(button)
+}
+
+/// Read the buttons from json, checking its schema:
+///
+/// ```json
+/// {
+///   "format": "e4docker-buttons",
+///   "buttons": [
+///     {
+///       "name": "firefox",
+///       "command": "/usr/bin/firefox",
+///       "args": ["--new-window", "https://www.rust-lang.org"],
+///       "icon": "https://www.mozilla.org/media/img/favicons/firefox/browser/favicon-196x196.png",
+///       "flags": { "accent_color": "#e66000", "cooldown": 2 }
+///     },
+///     { "name": "trash", "type": "trash", "icon": "trash.png" }
+///   ]
+/// }
+/// ```
+///
+/// Only name is required, and command for the command and watch buttons. args can also be a command line,
+/// icon a path relative to the assets directory, and flags holds the other keys of the configuration file
+/// of the button. The errors tell the value not matching the schema, like `buttons[1].command: missing`.
+pub fn buttons_from_json(json: &str) -> Result<Vec<E4ImportedButton>, Box<dyn std::error::Error>> {
+    let fields = match parse_json(json)? {
+        Json::Object(fields) => fields,
+        _ => return Err(schema_error("the JSON", "expected an object")),
+    };
+    let field = |key: &str| {
+        fields
+            .iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    };
+    match field("format") {
+        Some(Json::Text(format)) if format == BUTTONS_JSON_FORMAT => {}
+        _ => {
+            return Err(schema_error(
+                "format",
+                &format!("expected {}", BUTTONS_JSON_FORMAT),
+            ))
+        }
+    }
+    if let Some((key, _)) = fields
+        .iter()
+        .find(|(key, _)| key != "format" && key != "buttons")
+    {
+        return Err(schema_error(key, "unknown key"));
+    }
+    match field("buttons") {
+        Some(Json::Array(buttons)) => buttons
+            .iter()
+            .enumerate()
+            .map(|(index, button)| button_from_value(button, index))
+            .collect(),
+        _ => Err(schema_error("buttons", "expected an array")),
+    }
+}
+
+/// Return the JSON of buttons, with their names and their configuration files, in the format read by
+/// [buttons_from_json]: the keys of the configuration files other than type, command, arguments and icon
+/// are its flags.
+pub fn buttons_to_json(buttons: &[(String, Ini)]) -> String {
+    let buttons: Vec<String> = buttons
+        .iter()
+        .map(|(name, ini)| {
+            let mut fields = vec![format!("\"name\": {}", quote(name))];
+            let mut flags = vec![];
+            if let Some(keys) = ini.get_map_ref().get(BUTTON_BUTTON_SECTION) {
+                // Sorted, to export the same button in the same way
+                let mut keys: Vec<_> = keys.iter().collect();
+                keys.sort();
+                for (key, value) in keys {
+                    let value = value.clone().unwrap_or_default();
+                    match key.as_str() {
+                        "type" => fields.push(format!(
+                            "\"type\": {}",
+                            quote(E4ButtonType::from_name(&value).name())
+                        )),
+                        "command" if !value.is_empty() => {
+                            fields.push(format!("\"command\": {}", quote(&value)))
+                        }
+                        "arguments" => {
+                            let arguments: Vec<String> = e4command::split_arguments(&value)
+                                .iter()
+                                .map(|argument| quote(argument))
+                                .collect();
+                            fields.push(format!("\"args\": [{}]", arguments.join(", ")));
+                        }
+                        "icon" if !value.is_empty() => {
+                            fields.push(format!("\"icon\": {}", quote(&value)))
+                        }
+                        "command" | "icon" => {}
+                        _ => flags.push(format!("{}: {}", quote(key), quote(&value))),
+                    }
+                }
+            }
+            if !flags.is_empty() {
+                fields.push(format!("\"flags\": {{ {} }}", flags.join(", ")));
+            }
+            format!("    {{ {} }}", fields.join(", "))
+        })
+        .collect();
+    format!(
+        "{{\n  \"format\": {},\n  \"buttons\": [\n{}\n  ]\n}}\n",
+        quote(BUTTONS_JSON_FORMAT),
+        buttons.join(",\n")
+    )
+}
+
+/// Write the buttons names of config to the JSON file at path, to be imported later: see [buttons_to_json].
+pub fn export_file(
+    config: &E4Config,
+    names: &[String],
+    path: &Path,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut buttons = vec![];
+    for name in names {
+        let ini = config
+            .storage
+            .load_ini(&button_file(&config.config_dir, name))
+            .map_err(|e| ImportError {
+                details: format!("{}: {}", name, e),
+            })?;
+        buttons.push((name.clone(), ini));
+    }
+    config.storage.write(path, &buttons_to_json(&buttons))?;
+    Ok(())
+}
+
+/// Add buttons at the end of the dock, renaming the ones whose name is taken, and return their names.
+/// The icons given by URL are downloaded in the assets directory, and the buttons without one get the icon
+/// of their command: see [e4icon::save_resolved_icon]. The generic icon replaces the ones that cannot be found.
+pub fn import(
+    config: &mut E4Config,
+    buttons: &[E4ImportedButton],
+    translations: Arc<Mutex<Translations>>,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let mut names = vec![];
+    for button in buttons {
+        let name = if config.is_button_name_taken(&button.name, None) {
+            config.unique_button_name(&button.name)
+        } else {
+            button.name.clone()
+        };
+        let icon = match &button.icon {
+            Some(url) if e4favicon::is_url(url) => {
+                let icon = format!("{}.png", name);
+                match e4favicon::fetch_icon(url, &config.assets_dir.join(&icon)) {
+                    Ok(_) => icon,
+                    Err(e) => {
+                        log::warn!("{}", e);
+                        GENERIC_ICON.to_string()
+                    }
+                }
+            }
+            Some(icon) if !icon.is_empty() => icon.clone(),
+            _ if !button.command.is_empty() => {
+                match e4icon::save_resolved_icon(
+                    config,
+                    &name,
+                    &button.command,
+                    translations.clone(),
+                ) {
+                    Ok(icon) => icon,
+                    Err(e) => {
+                        log::warn!("{}", e);
+                        GENERIC_ICON.to_string()
+                    }
+                }
+            }
+            _ => GENERIC_ICON.to_string(),
+        };
+        let index = config.buttons.len();
+        config.insert_button(index, &name, &button.to_ini(&icon), translations.clone())?;
+        names.push(name);
+    }
+    Ok(names)
+}
+
+/// Import the buttons of the JSON file at path: see [buttons_from_json] and [import].
+pub fn import_file(
+    config: &mut E4Config,
+    path: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<Vec<String>, Box<dyn std::error::Error>> {
+    let buttons = buttons_from_json(&std::fs::read_to_string(path)?)?;
+    import(config, &buttons, translations)
+}
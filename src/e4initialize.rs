@@ -1,9 +1,17 @@
-use crate::{tr, translations::Translations};
+use crate::{
+    e4config::E4Error,
+    e4storage::{E4Storage, FsStorage},
+    tr,
+    translations::Translations,
+};
 use base64::{engine::general_purpose, Engine};
 use std::{
     io::Write,
-    path::PathBuf,
-    sync::{Arc, Mutex},
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, OnceLock,
+    },
 };
 
 const GENERIC_PNG: &str =
@@ -26,128 +34,413 @@ OH0ABr93O2dy7tjECslfgbt1tCiFb8CEoZtexZ9Dtj6cSNBlpLn07itw/XBRKmvKtAo4iKc9cmJQ
 9/e1qpJn/wKUDujnkcHdPXl1PLZhcKcdtT5kmoyGGc36xarzM4yWvuQazf6rMtwBbWiehsGr5+cA
 AAAASUVORK5CYII=";
 
+/// The name of the generic button, of its .conf file and of its icon.
+const GENERIC: &str = "generic";
+
+/// The name of the configuration file of this app.
+const CONFIG_FILE: &str = concat!(env!("CARGO_PKG_NAME"), ".conf");
+
+/// If true the configuration directory cannot be written: see [get_package_config_dir].
+static READ_ONLY: AtomicBool = AtomicBool::new(false);
+
+/// The generic icon, decoded from [GENERIC_PNG] on the first use.
+static GENERIC_PNG_DATA: OnceLock<Vec<u8>> = OnceLock::new();
+
+/// The profile of the dock, with its own configuration directory: see [set_profile].
+static PROFILE: OnceLock<String> = OnceLock::new();
+
+/// The directory of the configuration directories of the profiles.
+const PROFILES_DIR: &str = "profiles";
+
+/// A button written in the configuration directory on the first run.
+struct StarterButton {
+    /// The name of the button and of its .conf file.
+    name: &'static str,
+    /// The program to launch.
+    command: PathBuf,
+    /// The arguments of the program.
+    arguments: String,
+}
+
+/// Return the command of the generic button for the current OS.
+#[cfg(target_os = "windows")]
+fn generic_command() -> PathBuf {
+    windows_dir().join("System32").join("notepad.exe")
+}
+
+/// Return the command of the generic button for the current OS.
+#[cfg(target_os = "macos")]
+fn generic_command() -> PathBuf {
+    PathBuf::from("/usr/bin/open")
+}
+
+/// Return the command of the generic button for the current OS.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn generic_command() -> PathBuf {
+    PathBuf::from("/usr/bin/xdg-open")
+}
+
+/// Return the Windows directory, usually C:\Windows.
+#[cfg(target_os = "windows")]
+fn windows_dir() -> PathBuf {
+    match std::env::var_os("SystemRoot") {
+        Some(dir) => PathBuf::from(dir),
+        None => PathBuf::from("C:\\Windows"),
+    }
+}
+
+/// Return the user home directory as a button argument.
+fn home_argument() -> String {
+    match dirs::home_dir() {
+        Some(home) => home.display().to_string(),
+        None => String::new(),
+    }
+}
+
+/// Return the buttons created on the first run on Windows.
+#[cfg(target_os = "windows")]
+fn starter_buttons() -> Vec<StarterButton> {
+    vec![
+        StarterButton {
+            name: "notepad",
+            command: windows_dir().join("System32").join("notepad.exe"),
+            arguments: String::new(),
+        },
+        StarterButton {
+            name: "explorer",
+            command: windows_dir().join("explorer.exe"),
+            arguments: home_argument(),
+        },
+    ]
+}
+
+/// Return the buttons created on the first run on macOS.
+#[cfg(target_os = "macos")]
+fn starter_buttons() -> Vec<StarterButton> {
+    vec![
+        StarterButton {
+            name: "finder",
+            command: PathBuf::from("/usr/bin/open"),
+            arguments: home_argument(),
+        },
+        StarterButton {
+            name: "terminal",
+            command: PathBuf::from("/usr/bin/open"),
+            arguments: String::from("/System/Applications/Utilities/Terminal.app"),
+        },
+    ]
+}
+
+/// Return the buttons created on the first run on Linux and the other unixes.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn starter_buttons() -> Vec<StarterButton> {
+    vec![
+        StarterButton {
+            name: "files",
+            command: PathBuf::from("/usr/bin/xdg-open"),
+            arguments: home_argument(),
+        },
+        StarterButton {
+            name: "terminal",
+            command: PathBuf::from("/usr/bin/x-terminal-emulator"),
+            arguments: String::new(),
+        },
+    ]
+}
+
+/// Return the content of a button .conf file using the generic icon.
+fn button_conf(command: &std::path::Path, arguments: &str) -> String {
+    format!(
+        "[button]
+arguments={}
+icon={}.png
+command={}",
+        arguments,
+        GENERIC,
+        command.display()
+    )
+}
+
+/// Write the command of the generic button for the current OS in generic_conf, if it has none,
+/// keeping its comments.
+fn set_generic_command(generic_conf: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    let mut ini = FsStorage.load_ini(generic_conf)?;
+    let has_command = ini
+        .get("button", "command")
+        .is_some_and(|command| !command.trim().is_empty());
+    if !has_command {
+        ini.set(
+            "button",
+            "command",
+            Some(generic_command().display().to_string()),
+        );
+        FsStorage.write_ini(generic_conf, &ini)?;
+    }
+    Ok(())
+}
+
+/// Return the PNG data of the generic icon, embedded in the program.
+pub fn generic_png_data() -> &'static [u8] {
+    GENERIC_PNG_DATA.get_or_init(|| {
+        general_purpose::STANDARD
+            .decode(GENERIC_PNG.replace("\n", ""))
+            .unwrap_or_default()
+    })
+}
+
+/// Return true if path, absolute or relative to assets_dir, is the generic icon of assets_dir:
+/// when it does not exist, the embedded one is used.
+pub fn is_generic_icon(path: &Path, assets_dir: &Path) -> bool {
+    let mut generic = assets_dir.join(GENERIC);
+    generic.set_extension("png");
+    assets_dir.join(path) == generic
+}
+
 pub fn create_generic_button(
     destination: &std::path::Path,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let img64 = GENERIC_PNG.replace("\n", "");
-    let bytes = general_purpose::STANDARD.decode(img64)?;
-
     // Create a file on destination
     let mut file = std::fs::File::create(destination)?;
 
     // Write the bytes on file
-    file.write_all(&bytes)?;
+    file.write_all(generic_png_data())?;
 
     Ok(())
 }
 
-pub fn get_package_config_dir(translations: Arc<Mutex<Translations>>) -> PathBuf {
-    // Get the package name
-    let package_name = env!("CARGO_PKG_NAME");
+/// Return an [E4Error] with the translation of key, followed by its cause.
+fn init_error(
+    translations: &Arc<Mutex<Translations>>,
+    key: &str,
+    default: &str,
+    cause: &dyn std::fmt::Display,
+) -> E4Error {
+    E4Error::new(&format!(
+        "{}: {}",
+        tr!(translations, get_or_default, key, default),
+        cause
+    ))
+}
 
-    let config_dir = dirs::config_dir().expect(&tr!(
-        translations,
-        get_or_default,
-        "cannot-create-the-configuration-directory",
-        "Cannot create the configuration directory"
-    ));
+/// Return the path of the configuration directory of this app, or of its profile, without creating it.
+pub fn package_config_path(translations: &Arc<Mutex<Translations>>) -> Result<PathBuf, E4Error> {
+    match dirs::config_dir() {
+        // Create the path of the configuration directory for this app
+        Some(config_dir) => {
+            let config_dir = config_dir.join(env!("CARGO_PKG_NAME"));
+            Ok(match profile() {
+                Some(profile) => config_dir.join(PROFILES_DIR).join(profile),
+                None => config_dir,
+            })
+        }
+        None => Err(E4Error::new(&tr!(
+            translations,
+            get_or_default,
+            "cannot-find-the-configuration-directory",
+            "Cannot find the configuration directory of the user"
+        ))),
+    }
+}
+
+/// Return the configuration directory of this app, creating it with the starter buttons on the first run.
+/// If it cannot be written but e4docker.conf can be read, the app runs read-only: see [is_read_only].
+pub fn get_package_config_dir(translations: Arc<Mutex<Translations>>) -> Result<PathBuf, E4Error> {
+    let project_config_dir = package_config_path(&translations)?;
+    match create_config_files(&project_config_dir, &translations) {
+        Ok(_) => {
+            // The files can be there, on a read-only file system like a live CD
+            if !is_writable(&project_config_dir) {
+                READ_ONLY.store(true, Ordering::Relaxed);
+            }
+            Ok(project_config_dir)
+        }
+        Err(_) if project_config_dir.join(CONFIG_FILE).is_file() => {
+            READ_ONLY.store(true, Ordering::Relaxed);
+            Ok(project_config_dir)
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Use the configuration directory of profile, in the profiles directory, instead of the default one.
+/// It must be called once, before [get_package_config_dir].
+pub fn set_profile(profile: &str) {
+    let _ = PROFILE.set(profile.to_string());
+}
+
+/// Return the profile of the dock, None for the default configuration.
+pub fn profile() -> Option<&'static str> {
+    PROFILE.get().map(|profile| profile.as_str())
+}
+
+/// Return true if the configuration directory cannot be written: the changes are not saved.
+pub fn is_read_only() -> bool {
+    READ_ONLY.load(Ordering::Relaxed)
+}
 
-    // Create the path of the configuration directory for this app
-    let project_config_dir = config_dir.join(package_name);
+/// Return true if a file can be created in dir.
+fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!(".e4docker-write-test-{}", std::process::id()));
+    match std::fs::write(&probe, "") {
+        Ok(_) => {
+            let _ = std::fs::remove_file(&probe);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Create the missing directories and files of the configuration in project_config_dir.
+fn create_config_files(
+    project_config_dir: &Path,
+    translations: &Arc<Mutex<Translations>>,
+) -> Result<(), E4Error> {
     let assets_dir = project_config_dir.join("assets");
 
     // Create this app configuration directory if it does not exist
     if !project_config_dir.exists() {
         // Create the project configuration directory
-        std::fs::create_dir_all(&project_config_dir).expect(&tr!(
-            translations,
-            get_or_default,
-            "cannot-create-the-project-config-directory",
-            "Cannot create the project config directory"
-        ));
+        std::fs::create_dir_all(project_config_dir).map_err(|e| {
+            init_error(
+                translations,
+                "cannot-create-the-project-config-directory",
+                "Cannot create the project config directory",
+                &e,
+            )
+        })?;
         // Create the assets directory
-        std::fs::create_dir_all(&assets_dir).expect(&tr!(
-            translations,
-            get_or_default,
-            "cannot-create-assets-config-directory",
-            "Cannot create assets config directory"
-        ));
+        std::fs::create_dir_all(&assets_dir).map_err(|e| {
+            init_error(
+                translations,
+                "cannot-create-assets-config-directory",
+                "Cannot create assets config directory",
+                &e,
+            )
+        })?;
     }
 
     // Generic button png file
-    let mut generic_png = assets_dir.join("generic");
+    let mut generic_png = assets_dir.join(GENERIC);
     generic_png.set_extension("png");
     if !generic_png.exists() {
-        match create_generic_button(&generic_png) {
-            Ok(_) => {}
-            Err(e) => {
-                panic!(
-                    "{}",
-                    &tr!(
-                        translations,
-                        format,
-                        "cannot-create",
-                        &[&generic_png.display().to_string(), &e.to_string()]
-                    )
-                );
-            }
-        }
+        create_generic_button(&generic_png).map_err(|e| {
+            E4Error::new(&tr!(
+                translations,
+                format,
+                "cannot-create",
+                &[&generic_png.display().to_string(), &e.to_string()]
+            ))
+        })?;
     }
 
     // Generic button conf file
-    let mut generic_conf = project_config_dir.join("generic");
+    let mut generic_conf = project_config_dir.join(GENERIC);
     generic_conf.set_extension("conf");
     if !generic_conf.exists() {
         // Create generic.conf
-        let mut file = std::fs::File::create(&generic_conf).expect(&tr!(
-            translations,
-            get_or_default,
-            "cannot-create-generic-conf",
-            "Cannot create generic.conf"
-        ));
-        file.write_all(
-            b"[button]
-arguments=
-icon=generic.png
-command=/usr/bin/generic",
-        )
-        .expect("Cannot write on generic.conf");
+        std::fs::write(&generic_conf, button_conf(&generic_command(), "")).map_err(|e| {
+            init_error(
+                translations,
+                "cannot-write-on-generic-conf",
+                "Cannot write on generic.conf",
+                &e,
+            )
+        })?;
     }
 
     // App conf file
-    let mut e4docker_conf = project_config_dir.join(package_name);
-    e4docker_conf.set_extension("conf");
+    let e4docker_conf = project_config_dir.join(CONFIG_FILE);
     if !e4docker_conf.exists() {
-        // Create generic.conf
-        let mut file = std::fs::File::create(&e4docker_conf).expect(&tr!(
-            translations,
-            get_or_default,
-            "cannot-create-e4docker-conf",
-            "Cannot create e4docker.conf"
-        ));
-        file.write_all(
-            b"[e4docker]
-number_of_buttons=1
+        // The generic.conf installed with e4docker leaves the command to the platform, written on the first run:
+        // a command left empty later is left so
+        if let Err(e) = set_generic_command(&generic_conf) {
+            log::warn!("Cannot write on {}: {}", generic_conf.display(), e);
+        }
+        // Create the starter buttons conf files
+        let starters = starter_buttons();
+        for starter in &starters {
+            let mut starter_conf = project_config_dir.join(starter.name);
+            starter_conf.set_extension("conf");
+            if starter_conf.exists() {
+                continue;
+            }
+            std::fs::write(
+                &starter_conf,
+                button_conf(&starter.command, &starter.arguments),
+            )
+            .map_err(|e| {
+                E4Error::new(&tr!(
+                    translations,
+                    format,
+                    "cannot-create",
+                    &[&starter_conf.display().to_string(), &e.to_string()]
+                ))
+            })?;
+        }
+
+        // The generic button comes first, followed by the starter buttons
+        let mut buttons = format!("button1={}\n", GENERIC);
+        for (i, starter) in starters.iter().enumerate() {
+            buttons.push_str(&format!("button{}={}\n", i + 2, starter.name));
+        }
+
+        // Create e4docker.conf
+        std::fs::write(
+            &e4docker_conf,
+            format!(
+                "[e4docker]
+number_of_buttons={}
 frame_margin=10
 margin_between_buttons=20
 icon_width=32
 icon_height=32
+frame_style=engraved
+show_frame=true
+window_border=false
+alignment=start
+min_width=0
+scale=1
+hover_label=false
+hover_label_delay=0.5
+hover_preview=false
+icon_tint=none
+icon_corner_radius=0
+icon_shadow=false
+animate_icons=false
+reveal_edge=bottom
+autohide=0
+reserve_space=false
+move_modifier=none
+quick_launch_modifier=alt
+quick_launch_overlay=true
+new_instance_modifier=shift
+theme=light
+dialog_position=default
+clean_assets_on_start=false
 [buttons]
-button1=generic",
+{}",
+                starters.len() + 1,
+                buttons
+            ),
         )
-        .expect(&tr!(
-            translations,
-            get_or_default,
-            "cannot-write-on-e4docker-conf",
-            "Cannot write on e4docker.conf"
-        ));
+        .map_err(|e| {
+            init_error(
+                translations,
+                "cannot-write-on-e4docker-conf",
+                "Cannot write on e4docker.conf",
+                &e,
+            )
+        })?;
     }
 
-    project_config_dir
+    Ok(())
 }
 
 pub fn get_package_assets_dir(translations: Arc<Mutex<Translations>>) -> PathBuf {
-    get_package_config_dir(translations.clone()).join("assets")
+    // The directory is created by get_package_config_dir, at startup
+    package_config_path(&translations)
+        .unwrap_or_default()
+        .join("assets")
 }
 
 pub fn get_generic_icon(translations: Arc<Mutex<Translations>>) -> PathBuf {
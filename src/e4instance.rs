@@ -0,0 +1,147 @@
+use crate::e4events::{self, E4Event};
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+/// The file, in the configuration directory of a profile, with the port of its running dock.
+const LOCK_FILE: &str = "e4docker.lock";
+
+/// The request asking the running dock to show itself.
+const FOCUS_REQUEST: &str = "focus";
+
+/// The request asking the running dock to read its configuration again, changed by another program.
+pub const RELOAD_REQUEST: &str = "reload";
+
+/// The request asking the running dock for the names of its running buttons.
+pub const RUNNING_BUTTONS_REQUEST: &str = "running-buttons";
+
+/// The request asking the running dock for its state as JSON, with the dump-state feature.
+#[cfg(feature = "dump-state")]
+pub const DUMP_STATE_REQUEST: &str = "dump-state";
+
+/// The answer of the running dock to a request it knows.
+const OK_ANSWER: &str = "ok";
+
+/// How long a dock waits for the running one to answer.
+const ANSWER_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Return the profile in args, the arguments of the program: --profile name or --profile=name.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4instance::profile_from_args;
+///
+/// let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+/// assert_eq!(profile_from_args(&args(&["e4docker", "--profile", "work"])), Some("work".to_string()));
+/// assert_eq!(profile_from_args(&args(&["e4docker", "--profile=media"])), Some("media".to_string()));
+/// assert_eq!(profile_from_args(&args(&["e4docker"])), None);
+/// ```
+pub fn profile_from_args(args: &[String]) -> Option<String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            return args.next().cloned();
+        }
+        if let Some(profile) = arg.strip_prefix("--profile=") {
+            return Some(profile.to_string());
+        }
+    }
+    None
+}
+
+/// Return true if name can be the name of a profile, naming its directory: letters, digits, - and _.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4instance::is_valid_profile;
+///
+/// assert!(is_valid_profile("work-2"));
+/// assert!(!is_valid_profile("../work"));
+/// assert!(!is_valid_profile(""));
+/// ```
+pub fn is_valid_profile(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+}
+
+/// The running dock of a profile, answering the requests of the docks started later with the same profile.
+/// Dropping it removes its lock file.
+pub struct E4Instance {
+    lock_file: PathBuf,
+}
+
+impl Drop for E4Instance {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_file(&self.lock_file);
+    }
+}
+
+/// Become the running dock of the profile with config_dir, its local configuration directory.
+/// If another dock of the profile is running, ask it to show itself and return None: this one must quit.
+/// The docks of different profiles have different directories, so they run side by side.
+pub fn acquire(config_dir: &Path) -> std::io::Result<Option<E4Instance>> {
+    let lock_file = config_dir.join(LOCK_FILE);
+    // The port can be used by another program, after a crash
+    if matches!(send(&lock_file, FOCUS_REQUEST).as_deref(), Ok(OK_ANSWER)) {
+        return Ok(None);
+    }
+    // No dock answers: the lock file, if any, is left by a dock that crashed
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0))?;
+    std::fs::write(&lock_file, listener.local_addr()?.port().to_string())?;
+    thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            let _ = answer(stream);
+        }
+    });
+    Ok(Some(E4Instance { lock_file }))
+}
+
+/// Send a request to the running dock of the profile with config_dir and return its answer, on one line.
+pub fn request(config_dir: &Path, request: &str) -> std::io::Result<String> {
+    send(&config_dir.join(LOCK_FILE), request)
+}
+
+/// Send a request to the running dock with lock_file and return its answer.
+fn send(lock_file: &Path, request: &str) -> std::io::Result<String> {
+    let port: u16 = std::fs::read_to_string(lock_file)?
+        .trim()
+        .parse()
+        .map_err(|_| std::io::Error::from(std::io::ErrorKind::InvalidData))?;
+    let address = SocketAddr::from((Ipv4Addr::LOCALHOST, port));
+    let mut stream = TcpStream::connect_timeout(&address, ANSWER_TIMEOUT)?;
+    stream.set_read_timeout(Some(ANSWER_TIMEOUT))?;
+    writeln!(stream, "{}", request)?;
+    let mut answer = String::new();
+    BufReader::new(stream).read_line(&mut answer)?;
+    Ok(answer.trim_end().to_string())
+}
+
+/// Read a request from another dock and answer it.
+fn answer(stream: TcpStream) -> std::io::Result<()> {
+    stream.set_read_timeout(Some(ANSWER_TIMEOUT))?;
+    let mut line = String::new();
+    BufReader::new(&stream).read_line(&mut line)?;
+    let answer = match line.trim() {
+        FOCUS_REQUEST => {
+            e4events::publish(E4Event::FocusRequested);
+            OK_ANSWER.to_string()
+        }
+        RELOAD_REQUEST => {
+            e4events::publish(E4Event::ConfigChanged);
+            OK_ANSWER.to_string()
+        }
+        RUNNING_BUTTONS_REQUEST => crate::e4list::running_buttons(),
+        #[cfg(feature = "dump-state")]
+        DUMP_STATE_REQUEST => crate::e4dump::dump(),
+        _ => String::new(),
+    };
+    writeln!(&stream, "{}", answer)
+}
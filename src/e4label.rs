@@ -0,0 +1,199 @@
+use crate::{
+    e4preview,
+    e4state::{self, E4State},
+    e4style::E4Style,
+};
+use fltk::{
+    app, draw,
+    enums::{Color, FrameType},
+    frame::Frame,
+    image::PngImage,
+    prelude::*,
+    window::Window,
+};
+use std::{
+    cell::{Cell, RefCell},
+    path::Path,
+};
+
+/// The space around the name in the label.
+const LABEL_PADDING: i32 = 6;
+
+/// The distance of the label from the top of the button.
+const LABEL_GAP: i32 = 4;
+
+/// The height of the sparkline of the launches, under the name.
+const SPARKLINE_HEIGHT: i32 = 16;
+
+/// The width of a bar of the sparkline, a day, and the space between two bars.
+const SPARKLINE_BAR: (i32, i32) = (3, 1);
+
+thread_local! {
+    /// The name of the hovered button, whose label is shown or waits for the delay, and its top center.
+    static HOVERED: RefCell<Option<(String, i32, i32)>> = const { RefCell::new(None) };
+    /// Counts the hovers, so the delay of a button left meanwhile shows nothing.
+    static GENERATION: Cell<u64> = const { Cell::new(0) };
+    /// The window of the label, when shown.
+    static LABEL: RefCell<Option<Window>> = const { RefCell::new(None) };
+    /// The window of the preview, when shown.
+    static PREVIEW: RefCell<Option<Window>> = const { RefCell::new(None) };
+}
+
+/// Show the name of the button hovered by the mouse in a floating label, after the delay of style.
+/// (x, y) is the top center of the button on the screen. It must be called in the main thread.
+///
+/// The label shows under the name a sparkline of the launches of the button in the last days, read from
+/// the state in config_dir. running_command is the command of the button if its program is running: with
+/// the hover_preview option a thumbnail of its window is captured meanwhile, then shown by [show_preview].
+pub fn hover(
+    style: &E4Style,
+    config_dir: &Path,
+    name: &str,
+    x: i32,
+    y: i32,
+    running_command: Option<String>,
+) {
+    let running_command = running_command.filter(|_| style.hover_preview);
+    if !style.hover_label && running_command.is_none() {
+        return;
+    }
+    if HOVERED.with(|hovered| {
+        hovered
+            .borrow()
+            .as_ref()
+            .is_some_and(|(hovered, _, _)| hovered == name)
+    }) {
+        return;
+    }
+    hide();
+    HOVERED.with(|hovered| *hovered.borrow_mut() = Some((name.to_string(), x, y)));
+    let generation = GENERATION.with(|generation| generation.get());
+
+    let style = style.clone();
+    let config_dir = config_dir.to_path_buf();
+    let name = name.to_string();
+    app::add_timeout3(style.hover_label_delay, move |_| {
+        if GENERATION.with(|current| current.get()) != generation {
+            return;
+        }
+        if style.hover_label {
+            show(&style, &config_dir, &name, x, y);
+        }
+        if let Some(cmd) = &running_command {
+            e4preview::capture_later(&name, cmd, style.preview_size());
+        }
+    });
+}
+
+/// Show png_data, the thumbnail of the window of the button named name, above its label.
+/// Nothing is shown if the button is no longer hovered.
+pub fn show_preview(name: &str, png_data: &[u8]) {
+    let (x, y) = match HOVERED.with(|hovered| hovered.borrow().clone()) {
+        Some((hovered, x, y)) if hovered == name => (x, y),
+        _ => return,
+    };
+    let mut image = match PngImage::from_data(png_data) {
+        Ok(image) => image,
+        Err(_) => return,
+    };
+    // Above the label, if shown
+    let top = LABEL.with(|label| label.borrow().as_ref().map(|label| label.y()));
+    let bottom = top.unwrap_or(y) - LABEL_GAP;
+    let (w, h) = (image.w() + 2 * LABEL_PADDING, image.h() + 2 * LABEL_PADDING);
+
+    if let Some(mut window) = PREVIEW.with(|preview| preview.borrow_mut().take()) {
+        window.hide();
+        Window::delete(window);
+    }
+    let mut window = Window::new(x - w / 2, bottom - h, w, h, None);
+    window.set_border(false);
+    let mut frame = Frame::new(0, 0, w, h, None);
+    frame.set_frame(FrameType::BorderBox);
+    image.scale(image.w(), image.h(), true, true);
+    frame.set_image(Some(image));
+    window.end();
+    window.set_override();
+    window.show();
+    PREVIEW.with(|preview| *preview.borrow_mut() = Some(window));
+}
+
+/// Hide the label, if any, and forget the hovered button.
+pub fn hide() {
+    GENERATION.with(|generation| generation.set(generation.get() + 1));
+    HOVERED.with(|hovered| *hovered.borrow_mut() = None);
+    for windows in [&LABEL, &PREVIEW] {
+        if let Some(mut window) = windows.with(|window| window.borrow_mut().take()) {
+            window.hide();
+            Window::delete(window);
+        }
+    }
+}
+
+/// Return the heights of the bars of a sparkline of counts, the tallest height high: a count above zero
+/// is at least one pixel high, to tell it from none.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4label::sparkline_heights;
+///
+/// assert_eq!(sparkline_heights(&[0, 1, 4, 100], 16), vec![0, 1, 1, 16]);
+/// assert_eq!(sparkline_heights(&[0, 0], 16), vec![0, 0]);
+/// ```
+pub fn sparkline_heights(counts: &[u32], height: i32) -> Vec<i32> {
+    let max = counts.iter().copied().max().unwrap_or(0).max(1) as i64;
+    counts
+        .iter()
+        .map(|&count| match count {
+            0 => 0,
+            count => ((count as i64 * height as i64 / max) as i32).max(1),
+        })
+        .collect()
+}
+
+/// Show name in a label centered above (x, y), with the sparkline of the launches of the button
+/// in the last [e4state::ACTIVITY_DAYS] days.
+fn show(style: &E4Style, config_dir: &Path, name: &str, x: i32, y: i32) {
+    let font = style.hover_label_font();
+    let size = style.hover_label_font_size();
+    // @ starts a symbol in the labels
+    let text = name.replace('@', "@@");
+    draw::set_font(font, size);
+    let (text_width, text_height) = draw::measure(&text, false);
+    let launches = E4State::load(config_dir).launches_per_day(name, e4state::today());
+    let (bar, gap) = SPARKLINE_BAR;
+    let sparkline_width = launches.len() as i32 * (bar + gap) - gap;
+    let w = text_width.max(sparkline_width) + 2 * LABEL_PADDING;
+    let h = text_height + SPARKLINE_HEIGHT + 2 * LABEL_PADDING;
+
+    let mut window = Window::new(x - w / 2, y - h - LABEL_GAP, w, h, None);
+    window.set_border(false);
+    let mut frame = Frame::new(0, 0, w, h, None);
+    frame.set_frame(FrameType::BorderBox);
+    let mut name_frame = Frame::new(0, LABEL_PADDING / 2, w, text_height, None);
+    name_frame.set_label_font(font);
+    name_frame.set_label_size(size);
+    name_frame.set_label(&text);
+    let mut sparkline = Frame::new(
+        (w - sparkline_width) / 2,
+        h - LABEL_PADDING - SPARKLINE_HEIGHT,
+        sparkline_width,
+        SPARKLINE_HEIGHT,
+        None,
+    );
+    let heights = sparkline_heights(&launches, SPARKLINE_HEIGHT);
+    sparkline.draw(move |f| {
+        // The days without launches are a line, not a hole
+        draw::set_draw_color(Color::Inactive);
+        draw::draw_xyline(f.x(), f.y() + f.h() - 1, f.x() + f.w() - 1);
+        draw::set_draw_color(Color::Selection);
+        for (i, height) in heights.iter().enumerate() {
+            let bx = f.x() + i as i32 * (bar + gap);
+            draw::draw_rectf(bx, f.y() + f.h() - height, bar, *height);
+        }
+    });
+    window.end();
+    window.set_override();
+    window.show();
+    LABEL.with(|label| *label.borrow_mut() = Some(window));
+}
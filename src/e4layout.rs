@@ -0,0 +1,236 @@
+use crate::{
+    e4command::{join_arguments, split_arguments},
+    e4config::{
+        button_file, config_file, is_spacer, same_button_name, E4Config, E4ConfigTransaction,
+        E4Error, E4DOCKER_DOCKER_SECTION,
+    },
+    tr,
+    translations::Translations,
+};
+use std::sync::{Arc, Mutex};
+
+/// The section of e4docker.conf holding the layouts.
+pub const E4DOCKER_LAYOUTS_SECTION: &str = "LAYOUTS";
+
+/// The key of e4docker.conf holding the name of the layout shown.
+const LAYOUT: &str = "LAYOUT";
+
+/// The key of e4docker.conf holding the buttons hidden by [hide_button], which can be shown again.
+const HIDDEN_BUTTONS: &str = "HIDDEN_BUTTONS";
+
+/// Return the name of a layout as it is saved: the keys of e4docker.conf are lowercase.
+fn key(name: &str) -> String {
+    name.trim().to_lowercase()
+}
+
+/// Return the layouts of config, sorted by name, with their buttons in their order.
+///
+/// A layout is an order of the buttons, saved in the LAYOUTS section of e4docker.conf: the buttons missing
+/// in it are hidden while it is shown, see [hide_button] and [show_button]. The names with spaces are quoted,
+/// like in the arguments of a button.
+///
+/// ```ini
+/// [E4DOCKER]
+/// layout=coding
+///
+/// [LAYOUTS]
+/// coding=terminal code firefox
+/// streaming=obs | firefox 'Stream Deck'
+/// ```
+pub fn layouts(config: &E4Config) -> Vec<(String, Vec<String>)> {
+    let ini = match config.storage.load_ini(&config_file(&config.config_dir)) {
+        Ok(ini) => ini,
+        Err(_) => return vec![],
+    };
+    let mut layouts: Vec<(String, Vec<String>)> = ini
+        .get_map_ref()
+        .get(&E4DOCKER_LAYOUTS_SECTION.to_lowercase())
+        .map(|section| {
+            section
+                .iter()
+                .map(|(name, buttons)| {
+                    let buttons = buttons.as_deref().map(split_arguments).unwrap_or_default();
+                    (name.clone(), buttons)
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    layouts.sort();
+    layouts
+}
+
+/// Return the name of the layout shown, if any.
+pub fn current_layout(config: &E4Config) -> Option<String> {
+    config
+        .storage
+        .load_ini(&config_file(&config.config_dir))
+        .ok()?
+        .get(E4DOCKER_DOCKER_SECTION, LAYOUT)
+        .map(|name| key(&name))
+        .filter(|name| !name.is_empty())
+}
+
+/// Save the order of the buttons of config as the layout name, replacing it if it exists, and show it.
+pub fn save_layout(
+    config: &mut E4Config,
+    name: &str,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let name = key(name);
+    // The name is a key of e4docker.conf
+    if name.is_empty() || name.contains(['=', ':', '[', ']', ';', '#']) {
+        return Err(Box::new(E4Error::new(&tr!(
+            translations,
+            format,
+            "invalid-layout-name",
+            &[&name]
+        ))));
+    }
+    let mut transaction = config.transaction(translations.clone())?;
+    transaction.set_value(
+        E4DOCKER_LAYOUTS_SECTION,
+        &name,
+        Some(join_arguments(&config.buttons)),
+    );
+    transaction.set_value(E4DOCKER_DOCKER_SECTION, LAYOUT, Some(name));
+    config.commit(transaction, translations)
+}
+
+/// Show the layout name: the buttons of config take its order, the others are hidden.
+/// The order of the layout shown before is saved first, keeping the changes made meanwhile.
+/// The buttons of the layout removed since are skipped.
+pub fn apply_layout(
+    config: &mut E4Config,
+    name: &str,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let name = key(name);
+    let buttons = match layouts(config)
+        .into_iter()
+        .find(|(layout, _)| *layout == name)
+    {
+        Some((_, buttons)) => buttons,
+        None => {
+            return Err(Box::new(E4Error::new(&tr!(
+                translations,
+                format,
+                "unknown-layout",
+                &[&name]
+            ))))
+        }
+    };
+    let mut transaction = config.transaction(translations.clone())?;
+    // The order of the layout shown before, written with the new one
+    if let Some(current) = current_layout(config) {
+        transaction.set_value(
+            E4DOCKER_LAYOUTS_SECTION,
+            &key(&current),
+            Some(join_arguments(&config.buttons)),
+        );
+    }
+    let buttons: Vec<String> = buttons
+        .into_iter()
+        .filter(|button| {
+            is_spacer(button)
+                || config
+                    .storage
+                    .exists(&button_file(&config.config_dir, button))
+        })
+        .collect();
+
+    transaction.set_buttons(&buttons);
+    transaction.set_value(E4DOCKER_DOCKER_SECTION, LAYOUT, Some(name));
+    config.commit(transaction, translations)
+}
+
+/// Return the buttons hidden by [hide_button], as saved.
+fn saved_hidden_buttons(config: &E4Config) -> Vec<String> {
+    config
+        .storage
+        .load_ini(&config_file(&config.config_dir))
+        .ok()
+        .and_then(|ini| ini.get(E4DOCKER_DOCKER_SECTION, HIDDEN_BUTTONS))
+        .map(|buttons| split_arguments(&buttons))
+        .unwrap_or_default()
+}
+
+/// Return the buttons not shown, sorted by name: the ones of the other layouts and the ones hidden.
+pub fn hidden_buttons(config: &E4Config) -> Vec<String> {
+    let mut hidden: Vec<String> = layouts(config)
+        .into_iter()
+        .flat_map(|(_, buttons)| buttons)
+        .chain(saved_hidden_buttons(config))
+        .filter(|button| {
+            !is_spacer(button)
+                && !config
+                    .buttons
+                    .iter()
+                    .any(|shown| same_button_name(shown, button))
+                && config
+                    .storage
+                    .exists(&button_file(&config.config_dir, button))
+        })
+        .collect();
+    hidden.sort();
+    hidden.dedup_by(|a, b| same_button_name(a, b));
+    hidden
+}
+
+/// Stage buttons as the buttons hidden by [hide_button].
+fn set_hidden_buttons(transaction: &mut E4ConfigTransaction, buttons: &[String]) {
+    let value = (!buttons.is_empty()).then(|| join_arguments(buttons));
+    transaction.set_value(E4DOCKER_DOCKER_SECTION, HIDDEN_BUTTONS, value);
+}
+
+/// Hide the button name from the layout shown: unlike a removed button, its configuration file is kept,
+/// and it can be shown again.
+pub fn hide_button(
+    config: &mut E4Config,
+    name: &str,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    hide_buttons(config, &[name.to_string()], translations)
+}
+
+/// Hide the buttons names from the layout shown, at once: see [hide_button].
+pub fn hide_buttons(
+    config: &mut E4Config,
+    names: &[String],
+    translations: Arc<Mutex<Translations>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let is_hidden = |button: &str| names.iter().any(|name| same_button_name(button, name));
+    let buttons: Vec<String> = config
+        .buttons
+        .iter()
+        .filter(|button| !is_hidden(button))
+        .cloned()
+        .collect();
+    let mut hidden = saved_hidden_buttons(config);
+    for name in names {
+        if !hidden.iter().any(|button| same_button_name(button, name)) {
+            hidden.push(name.to_string());
+        }
+    }
+    let mut transaction = config.transaction(translations.clone())?;
+    transaction.set_buttons(&buttons);
+    set_hidden_buttons(&mut transaction, &hidden);
+    config.commit(transaction, translations)
+}
+
+/// Show the hidden button name at the end of the layout shown.
+pub fn show_button(
+    config: &mut E4Config,
+    name: &str,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let mut buttons = config.buttons.clone();
+    buttons.push(name.to_string());
+    let hidden: Vec<String> = saved_hidden_buttons(config)
+        .into_iter()
+        .filter(|button| !same_button_name(button, name))
+        .collect();
+    let mut transaction = config.transaction(translations.clone())?;
+    transaction.set_buttons(&buttons);
+    set_hidden_buttons(&mut transaction, &hidden);
+    config.commit(transaction, translations)
+}
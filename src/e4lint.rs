@@ -0,0 +1,308 @@
+use crate::{
+    e4assets, e4bundle,
+    e4button::{E4Button, E4ButtonType},
+    e4command,
+    e4config::{
+        self, button_file, config_file, is_spacer, same_button_name, E4Config,
+        BUTTON_BUTTON_SECTION,
+    },
+    e4events::{self, E4Event},
+    e4favicon,
+    e4storage::{DryRunStorage, E4Storage},
+    e4uwp, tr,
+    translations::Translations,
+};
+use std::{
+    path::Path,
+    sync::{Arc, Mutex},
+};
+
+/// A likely mistake in the configuration of the dock, found by [lint].
+#[derive(Clone, Debug, PartialEq)]
+pub enum E4Lint {
+    /// Buttons launching the same program with the same arguments, as after importing the same buttons twice:
+    /// the command line, with the resolved path of the program, and the buttons in the order of the dock.
+    DuplicateCommand {
+        command: String,
+        buttons: Vec<String>,
+    },
+    /// The entries of the BUTTONS section of e4docker.conf are not numbered from button1 to the
+    /// NUMBER_OF_BUTTONS: the dock numbers them again when it starts.
+    MisnumberedButtons,
+    /// A button is listed more than once in the BUTTONS section of e4docker.conf.
+    RepeatedButton { button: String },
+    /// A button of e4docker.conf has no configuration file.
+    MissingConfiguration { button: String },
+    /// An icon of a button cannot be found.
+    MissingIcon { button: String, icon: String },
+    /// The program of a button cannot be found, as it is or in the PATH directories.
+    CommandNotFound { button: String, command: String },
+}
+
+impl E4Lint {
+    /// Return the translated description of the lint.
+    pub fn message(&self, translations: Arc<Mutex<Translations>>) -> String {
+        match self {
+            E4Lint::DuplicateCommand { command, buttons } => tr!(
+                translations,
+                format,
+                "duplicate-buttons",
+                &[&buttons.join(", "), command]
+            ),
+            E4Lint::MisnumberedButtons => tr!(
+                translations,
+                get_or_default,
+                "misnumbered-buttons",
+                "The buttons of e4docker.conf are not numbered from button1 to NUMBER_OF_BUTTONS"
+            ),
+            E4Lint::RepeatedButton { button } => {
+                tr!(translations, format, "repeated-button", &[button])
+            }
+            E4Lint::MissingConfiguration { button } => {
+                tr!(
+                    translations,
+                    format,
+                    "missing-button-configuration",
+                    &[button]
+                )
+            }
+            E4Lint::MissingIcon { button, icon } => {
+                tr!(translations, format, "missing-button-icon", &[button, icon])
+            }
+            E4Lint::CommandNotFound { button, command } => {
+                tr!(
+                    translations,
+                    format,
+                    "command-not-found",
+                    &[button, command]
+                )
+            }
+        }
+    }
+
+    /// Return true if the lint breaks the dock, false if it is only a likely mistake.
+    pub fn is_error(&self) -> bool {
+        !matches!(
+            self,
+            E4Lint::DuplicateCommand { .. } | E4Lint::MisnumberedButtons
+        )
+    }
+}
+
+/// Return the command line of cmd with arguments, with the program resolved in the PATH directories and
+/// without its links, to compare it with the ones of the other buttons.
+pub fn command_line(cmd: &str, arguments: &str) -> String {
+    let cmd = cmd.trim();
+    let program = e4command::locate(cmd)
+        .filter(|path| path.is_file())
+        .and_then(|path| std::fs::canonicalize(path).ok())
+        .map(|path| path.display().to_string())
+        .unwrap_or_else(|| cmd.to_string());
+    let arguments = e4command::split_arguments(arguments);
+    if arguments.is_empty() {
+        program
+    } else {
+        format!("{} {}", program, e4command::join_arguments(&arguments))
+    }
+}
+
+/// Check the buttons of config for likely mistakes.
+pub fn lint(config: &E4Config) -> Vec<E4Lint> {
+    let mut commands: Vec<(String, Vec<String>)> = vec![];
+    for name in config.buttons.iter().filter(|name| !is_spacer(name)) {
+        let ini = match config
+            .storage
+            .load_ini(&button_file(&config.config_dir, name))
+        {
+            Ok(ini) => ini,
+            Err(_) => continue,
+        };
+        // The widgets, like the trash, have no command of their own
+        let button_type = ini
+            .get(BUTTON_BUTTON_SECTION, "TYPE")
+            .map_or(E4ButtonType::Command, |button_type| {
+                E4ButtonType::from_name(&button_type)
+            });
+        let cmd = ini
+            .get(BUTTON_BUTTON_SECTION, "COMMAND")
+            .unwrap_or_default();
+        if button_type != E4ButtonType::Command || cmd.trim().is_empty() {
+            continue;
+        }
+        let arguments = ini
+            .get(BUTTON_BUTTON_SECTION, "ARGUMENTS")
+            .unwrap_or_default();
+        let command = command_line(&cmd, &arguments);
+        match commands.iter_mut().find(|(line, _)| *line == command) {
+            Some((_, buttons)) => buttons.push(name.clone()),
+            None => commands.push((command, vec![name.clone()])),
+        }
+    }
+    commands
+        .into_iter()
+        .filter(|(_, buttons)| buttons.len() > 1)
+        .map(|(command, buttons)| E4Lint::DuplicateCommand { command, buttons })
+        .collect()
+}
+
+/// Check the configuration in config_dir of storage, with the icons in assets_dir, without changing it:
+/// the order of the buttons, their configuration files, their icons and their programs, then the [lint]s.
+pub fn check_config(
+    storage: Arc<dyn E4Storage>,
+    config_dir: &Path,
+    assets_dir: &Path,
+) -> Result<Vec<E4Lint>, Box<dyn std::error::Error>> {
+    let ini = storage.load_ini(&config_file(config_dir))?;
+    let mut lints = vec![];
+    if !e4config::has_consistent_buttons(&ini).unwrap_or(false) {
+        lints.push(E4Lint::MisnumberedButtons);
+    }
+    let names = e4config::numbered_buttons(&ini);
+    for (index, name) in names.iter().enumerate() {
+        let repeated = !is_spacer(name)
+            && names[..index]
+                .iter()
+                .any(|other| same_button_name(other, name));
+        if repeated {
+            lints.push(E4Lint::RepeatedButton {
+                button: name.clone(),
+            });
+        }
+    }
+
+    // The configuration is repaired when it is read: the repairs stay in memory
+    let config = E4Config::read_from(
+        Arc::new(DryRunStorage::new(storage)),
+        config_dir,
+        assets_dir,
+    )?;
+    for name in config.buttons.iter().filter(|name| !is_spacer(name)) {
+        let ini = match config
+            .storage
+            .load_ini(&button_file(&config.config_dir, name))
+        {
+            Ok(ini) => ini,
+            Err(_) => {
+                lints.push(E4Lint::MissingConfiguration {
+                    button: name.clone(),
+                });
+                continue;
+            }
+        };
+        let cmd = ini
+            .get(BUTTON_BUTTON_SECTION, "COMMAND")
+            .unwrap_or_default()
+            .trim()
+            .to_string();
+        let button_type = ini
+            .get(BUTTON_BUTTON_SECTION, "TYPE")
+            .map_or(E4ButtonType::Command, |button_type| {
+                E4ButtonType::from_name(&button_type)
+            });
+        // The web addresses, the UWP apps and the macOS bundles are not in the PATH directories
+        let found = cmd.is_empty()
+            || e4favicon::is_url(&cmd)
+            || e4uwp::is_uwp_command(&cmd)
+            || e4bundle::is_app_bundle(Path::new(&cmd))
+            || e4command::locate(&cmd).is_some();
+        if button_type == E4ButtonType::Command && !found {
+            lints.push(E4Lint::CommandNotFound {
+                button: name.clone(),
+                command: cmd,
+            });
+        }
+    }
+    for (button, _, icon) in e4assets::button_icons(&config) {
+        if !config.assets_dir.join(&icon).exists() {
+            lints.push(E4Lint::MissingIcon { button, icon });
+        }
+    }
+    lints.extend(lint(&config));
+    Ok(lints)
+}
+
+/// Merge the buttons others into the button keep: keep takes the keys of their configuration files it has not,
+/// like an accent color or a badge, the first button setting them first. The configuration file of keep is
+/// written, the others are left to be removed.
+pub fn merge_buttons(
+    config: &E4Config,
+    keep: &str,
+    others: &[String],
+) -> Result<(), Box<dyn std::error::Error>> {
+    let keep_file = button_file(&config.config_dir, keep);
+    let mut ini = config.storage.load_ini(&keep_file)?;
+    for other in others {
+        let other_ini = config
+            .storage
+            .load_ini(&button_file(&config.config_dir, other))?;
+        let keys = match other_ini
+            .get_map_ref()
+            .get(&BUTTON_BUTTON_SECTION.to_lowercase())
+        {
+            Some(keys) => keys.clone(),
+            None => continue,
+        };
+        for (key, value) in keys {
+            if ini.get(BUTTON_BUTTON_SECTION, &key).is_none() {
+                ini.set(BUTTON_BUTTON_SECTION, &key, value);
+            }
+        }
+    }
+    config.storage.write_ini(&keep_file, &ini)?;
+    Ok(())
+}
+
+/// Offer to merge or to remove the buttons of config launching the same command, keeping the first of them.
+/// If quiet, nothing is shown without duplicates, as after an import.
+pub fn resolve_duplicates(
+    config: &mut E4Config,
+    quiet: bool,
+    translations: Arc<Mutex<Translations>>,
+) {
+    let lints = lint(config);
+    if lints.is_empty() && !quiet {
+        fltk::dialog::message_default(&tr!(
+            translations,
+            get_or_default,
+            "no-duplicate-buttons",
+            "No buttons launch the same command"
+        ));
+    }
+    for lint in lints {
+        let buttons = match &lint {
+            E4Lint::DuplicateCommand { buttons, .. } => buttons,
+            _ => continue,
+        };
+        let (keep, others) = (&buttons[0], &buttons[1..]);
+        let message = format!(
+            "{}\n\n{}",
+            lint.message(translations.clone()),
+            tr!(translations, format, "merge-duplicate-buttons", &[keep])
+        );
+        let choice = fltk::dialog::choice2_default(
+            &message,
+            &tr!(translations, get_or_default, "ignore", "Ignore"),
+            &tr!(
+                translations,
+                get_or_default,
+                "remove-the-others",
+                "Remove the others"
+            ),
+            &tr!(translations, get_or_default, "merge", "Merge"),
+        );
+        match choice {
+            Some(1) => E4Button::delete_all(others, config, translations.clone()),
+            Some(2) => match merge_buttons(config, keep, others) {
+                Ok(_) => {
+                    E4Button::delete_all(others, config, translations.clone());
+                    e4events::publish(E4Event::ButtonChanged {
+                        old_name: keep.clone(),
+                        name: keep.clone(),
+                    });
+                }
+                Err(e) => fltk::dialog::alert_default(&e.to_string()),
+            },
+            _ => {}
+        }
+    }
+}
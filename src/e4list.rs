@@ -0,0 +1,225 @@
+use crate::{
+    e4button::E4Button,
+    e4command::{join_arguments, split_arguments},
+    e4config::{button_file, is_spacer, E4Config, BUTTON_BUTTON_SECTION},
+    e4layout,
+    e4share::quote,
+};
+use lazy_static::lazy_static;
+use std::sync::{Arc, Mutex};
+
+/// The separator of the names of the running buttons answered by the running dock.
+const RUNNING_SEPARATOR: char = '\t';
+
+lazy_static! {
+    /// The buttons of the running dock, asked for the running ones: see [watch].
+    static ref DOCK: Mutex<Option<Arc<Mutex<Vec<E4Button>>>>> = Mutex::new(None);
+}
+
+/// How `e4docker list-buttons` prints the buttons.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum E4ListFormat {
+    /// A line of tab-separated values per button, after a line with the names of the columns.
+    #[default]
+    Tsv,
+    /// An array of objects, on one line.
+    Json,
+}
+
+impl E4ListFormat {
+    /// Return the format named name, json or tsv, or None if it is unknown.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "tsv" => Some(Self::Tsv),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// Return buttons in the format.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use e4docker::e4list::{E4ListFormat, E4ListedButton};
+    ///
+    /// let firefox = E4ListedButton {
+    ///     name: "firefox".to_string(),
+    ///     command: "/usr/bin/firefox".to_string(),
+    ///     arguments: vec!["--new-window".to_string(), "My Page.html".to_string()],
+    ///     icon: "firefox.png".to_string(),
+    ///     enabled: true,
+    ///     running: None,
+    /// };
+    /// assert_eq!(
+    ///     E4ListFormat::Tsv.write(&[firefox.clone()]),
+    ///     "name\tcommand\targs\ticon\tenabled\trunning\n\
+    ///      firefox\t/usr/bin/firefox\t--new-window 'My Page.html'\tfirefox.png\ttrue\t\n"
+    /// );
+    /// assert_eq!(
+    ///     E4ListFormat::Json.write(&[firefox]),
+    ///     "[{\"name\": \"firefox\", \"command\": \"/usr/bin/firefox\", \"args\": [\"--new-window\", \
+    ///      \"My Page.html\"], \"icon\": \"firefox.png\", \"enabled\": true, \"running\": null}]\n"
+    /// );
+    /// ```
+    pub fn write(&self, buttons: &[E4ListedButton]) -> String {
+        match self {
+            Self::Tsv => {
+                let mut lines = vec!["name\tcommand\targs\ticon\tenabled\trunning".to_string()];
+                lines.extend(buttons.iter().map(|button| {
+                    [
+                        escape_tsv(&button.name),
+                        escape_tsv(&button.command),
+                        escape_tsv(&join_arguments(&button.arguments)),
+                        escape_tsv(&button.icon),
+                        button.enabled.to_string(),
+                        button
+                            .running
+                            .map(|running| running.to_string())
+                            .unwrap_or_default(),
+                    ]
+                    .join("\t")
+                }));
+                format!("{}\n", lines.join("\n"))
+            }
+            Self::Json => {
+                let buttons: Vec<String> = buttons
+                    .iter()
+                    .map(|button| {
+                        let arguments: Vec<String> =
+                            button.arguments.iter().map(|argument| quote(argument)).collect();
+                        format!(
+                            "{{\"name\": {}, \"command\": {}, \"args\": [{}], \"icon\": {}, \"enabled\": {}, \"running\": {}}}",
+                            quote(&button.name),
+                            quote(&button.command),
+                            arguments.join(", "),
+                            quote(&button.icon),
+                            button.enabled,
+                            button
+                                .running
+                                .map_or("null".to_string(), |running| running.to_string())
+                        )
+                    })
+                    .collect();
+                format!("[{}]\n", buttons.join(", "))
+            }
+        }
+    }
+}
+
+/// Return value as a field of a line of tab-separated values: the tabs, the new lines and the backslashes
+/// are escaped with a backslash.
+fn escape_tsv(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('\t', r"\t")
+        .replace('\n', r"\n")
+        .replace('\r', r"\r")
+}
+
+/// A button of the configuration, as listed by `e4docker list-buttons`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct E4ListedButton {
+    pub name: String,
+    pub command: String,
+    pub arguments: Vec<String>,
+    /// The icon, as named in the configuration file of the button.
+    pub icon: String,
+    /// True if the button is on the dock, false if it is hidden or in another layout.
+    pub enabled: bool,
+    /// True if its process is running, or if its widget is on, in the running dock: None without a running dock.
+    pub running: Option<bool>,
+}
+
+/// Return the buttons of config, the ones on the dock in their order and then the hidden ones,
+/// without the spacers.
+pub fn list_buttons(config: &E4Config) -> Vec<E4ListedButton> {
+    let shown = config
+        .buttons
+        .iter()
+        .filter(|name| !is_spacer(name))
+        .map(|name| (name.clone(), true));
+    let hidden = e4layout::hidden_buttons(config)
+        .into_iter()
+        .map(|name| (name, false));
+    shown
+        .chain(hidden)
+        .map(|(name, enabled)| {
+            let ini = config
+                .storage
+                .load_ini(&button_file(&config.config_dir, &name))
+                .unwrap_or_default();
+            let value = |key: &str| {
+                ini.get(BUTTON_BUTTON_SECTION, key)
+                    .unwrap_or_default()
+                    .trim()
+                    .to_string()
+            };
+            E4ListedButton {
+                command: value("COMMAND"),
+                arguments: split_arguments(&value("ARGUMENTS")),
+                icon: value("ICON"),
+                name,
+                enabled,
+                running: None,
+            }
+        })
+        .collect()
+}
+
+/// Tell buttons which ones are running, from the answer of the running dock: see [running_buttons].
+pub fn set_running(buttons: &mut [E4ListedButton], answer: &str) {
+    let running: Vec<&str> = answer
+        .split(RUNNING_SEPARATOR)
+        .filter(|name| !name.is_empty())
+        .collect();
+    for button in buttons {
+        button.running = Some(running.contains(&button.name.as_str()));
+    }
+}
+
+/// Answer with buttons, the buttons of the running dock, when asked for the running ones.
+pub fn watch(buttons: Arc<Mutex<Vec<E4Button>>>) {
+    *DOCK.lock().unwrap() = Some(buttons);
+}
+
+/// Return the names of the running buttons of the running dock, on one line.
+pub fn running_buttons() -> String {
+    let buttons = match DOCK.lock().unwrap().clone() {
+        Some(buttons) => buttons,
+        None => return String::new(),
+    };
+    let running: Vec<String> = buttons
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|button| button.is_running())
+        .map(|button| button.name.clone())
+        .collect();
+    running.join(&RUNNING_SEPARATOR.to_string())
+}
+
+/// Return the format in args, the arguments of the program: --format name or --format=name.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4list::format_from_args;
+///
+/// let args = |args: &[&str]| args.iter().map(|arg| arg.to_string()).collect::<Vec<_>>();
+/// assert_eq!(format_from_args(&args(&["e4docker", "list-buttons", "--format", "json"])), Some("json".to_string()));
+/// assert_eq!(format_from_args(&args(&["e4docker", "list-buttons", "--format=tsv"])), Some("tsv".to_string()));
+/// assert_eq!(format_from_args(&args(&["e4docker", "list-buttons"])), None);
+/// ```
+pub fn format_from_args(args: &[String]) -> Option<String> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if arg == "--format" {
+            return args.next().cloned();
+        }
+        if let Some(format) = arg.strip_prefix("--format=") {
+            return Some(format.to_string());
+        }
+    }
+    None
+}
@@ -0,0 +1,258 @@
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use regex::Regex;
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+use std::{process::Command, sync::OnceLock};
+use std::{
+    sync::Mutex,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The time a track title is shown before being asked again to the media player.
+const TITLE_REFRESH: Duration = Duration::from_secs(2);
+
+/// The title of the track last read from the media player, in background: see [cached_track_title].
+static TITLE: Mutex<TitleCache> = Mutex::new(TitleCache {
+    title: None,
+    read_at: None,
+    reading: false,
+});
+
+/// The prefix of the D-Bus names of the MPRIS media players.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const MPRIS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+
+/// The D-Bus object path of the MPRIS media players.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+
+/// The D-Bus interface controlling the playback.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+const MPRIS_PLAYER: &str = "org.mpris.MediaPlayer2.Player";
+
+/// The track title last read from the media player.
+struct TitleCache {
+    title: Option<String>,
+    /// When it has been read, if ever.
+    read_at: Option<Instant>,
+    /// True while a thread is reading it again.
+    reading: bool,
+}
+
+/// Error raised when no media player can be controlled.
+#[derive(Debug)]
+struct MediaError {
+    details: String,
+}
+
+impl std::fmt::Display for MediaError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for MediaError {}
+
+/// A command sent to the media player.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MediaCommand {
+    PlayPause,
+    Next,
+    Previous,
+}
+
+impl MediaCommand {
+    /// Return the MPRIS method implementing the command.
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    fn mpris_method(&self) -> &'static str {
+        match self {
+            MediaCommand::PlayPause => "PlayPause",
+            MediaCommand::Next => "Next",
+            MediaCommand::Previous => "Previous",
+        }
+    }
+
+    /// Return the virtual key code of the media key implementing the command:
+    /// VK_MEDIA_PLAY_PAUSE, VK_MEDIA_NEXT_TRACK or VK_MEDIA_PREV_TRACK.
+    #[cfg(target_os = "windows")]
+    fn virtual_key(&self) -> u8 {
+        match self {
+            MediaCommand::PlayPause => 0xB3,
+            MediaCommand::Next => 0xB0,
+            MediaCommand::Previous => 0xB1,
+        }
+    }
+}
+
+/// Run dbus-send on the session bus and return its output.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn dbus_send(args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("dbus-send")
+        .arg("--session")
+        .arg("--print-reply")
+        .args(args)
+        .output()?;
+    if !output.status.success() {
+        return Err(Box::new(MediaError {
+            details: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        }));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Return the D-Bus names of the running MPRIS media players.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn players() -> Vec<String> {
+    let output = match dbus_send(&[
+        "--dest=org.freedesktop.DBus",
+        "/org/freedesktop/DBus",
+        "org.freedesktop.DBus.ListNames",
+    ]) {
+        Ok(output) => output,
+        Err(_) => return vec![],
+    };
+    static PLAYER: OnceLock<Regex> = OnceLock::new();
+    PLAYER
+        .get_or_init(|| {
+            Regex::new(&format!(
+                r#"string "({}[^"]+)""#,
+                regex::escape(MPRIS_PREFIX)
+            ))
+            .expect("Invalid MPRIS regex")
+        })
+        .captures_iter(&output)
+        .filter_map(|captures| captures.get(1).map(|m| m.as_str().to_string()))
+        .collect()
+}
+
+/// Get a property of a media player.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn player_property(player: &str, property: &str) -> Option<String> {
+    dbus_send(&[
+        &format!("--dest={}", player),
+        MPRIS_PATH,
+        "org.freedesktop.DBus.Properties.Get",
+        &format!("string:{}", MPRIS_PLAYER),
+        &format!("string:{}", property),
+    ])
+    .ok()
+}
+
+/// Return the media player to control: the playing one, or the first one.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn active_player() -> Option<String> {
+    let players = players();
+    for player in &players {
+        if let Some(status) = player_property(player, "PlaybackStatus") {
+            if status.contains("\"Playing\"") {
+                return Some(player.clone());
+            }
+        }
+    }
+    players.into_iter().next()
+}
+
+/// Send a command to the active media player.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn send(command: MediaCommand) -> Result<(), Box<dyn std::error::Error>> {
+    let player = match active_player() {
+        Some(player) => player,
+        None => {
+            return Err(Box::new(MediaError {
+                details: String::from("No MPRIS media player is running"),
+            }))
+        }
+    };
+    dbus_send(&[
+        &format!("--dest={}", player),
+        MPRIS_PATH,
+        &format!("{}.{}", MPRIS_PLAYER, command.mpris_method()),
+    ])?;
+    Ok(())
+}
+
+/// Send a command to the media session by pressing its media key, routed by the
+/// System Media Transport Controls to the current session.
+#[cfg(target_os = "windows")]
+pub fn send(command: MediaCommand) -> Result<(), Box<dyn std::error::Error>> {
+    /// The media keys are extended keys, told to keybd_event.
+    const KEYEVENTF_EXTENDEDKEY: u32 = 0x0001;
+    /// Release the key, told to keybd_event.
+    const KEYEVENTF_KEYUP: u32 = 0x0002;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn keybd_event(virtual_key: u8, scan_code: u8, flags: u32, extra_info: usize);
+    }
+
+    let virtual_key = command.virtual_key();
+    unsafe {
+        keybd_event(virtual_key, 0, KEYEVENTF_EXTENDEDKEY, 0);
+        keybd_event(virtual_key, 0, KEYEVENTF_EXTENDEDKEY | KEYEVENTF_KEYUP, 0);
+    }
+    Ok(())
+}
+
+/// Media players cannot be controlled on this OS.
+#[cfg(target_os = "macos")]
+pub fn send(_command: MediaCommand) -> Result<(), Box<dyn std::error::Error>> {
+    Err(Box::new(MediaError {
+        details: String::from("Media control is not supported on this OS"),
+    }))
+}
+
+/// Return the title, and the artist if known, of the track played by the active media player.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn track_title() -> Option<String> {
+    // dbus-send does not escape the quotes: the strings end at the end of the line
+    static TITLE_REGEX: OnceLock<Regex> = OnceLock::new();
+    static ARTIST_REGEX: OnceLock<Regex> = OnceLock::new();
+    let metadata = player_property(&active_player()?, "Metadata")?;
+    let title = TITLE_REGEX
+        .get_or_init(|| {
+            Regex::new(r#"(?m)string "xesam:title"\s*variant\s+string "(.*)"$"#)
+                .expect("Invalid title regex")
+        })
+        .captures(&metadata)?
+        .get(1)?
+        .as_str()
+        .to_string();
+    let artist = ARTIST_REGEX
+        .get_or_init(|| {
+            Regex::new(r#"(?m)string "xesam:artist"\s*variant\s+array \[\s*string "(.*)"$"#)
+                .expect("Invalid artist regex")
+        })
+        .captures(&metadata)
+        .and_then(|captures| captures.get(1))
+        .map(|m| m.as_str().to_string());
+    match artist {
+        Some(artist) if !artist.is_empty() => Some(format!("{} - {}", artist, title)),
+        _ => Some(title),
+    }
+}
+
+/// The track title is not available on this OS.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub fn track_title() -> Option<String> {
+    None
+}
+
+/// Return the title of the track as last read from the media player, reading it again in background
+/// once older than [TITLE_REFRESH]: asking the player takes several processes, too slow for the UI thread.
+pub fn cached_track_title() -> Option<String> {
+    let mut cache = TITLE.lock().unwrap();
+    let is_old = cache
+        .read_at
+        .is_none_or(|read_at| read_at.elapsed() >= TITLE_REFRESH);
+    if is_old && !cache.reading {
+        cache.reading = true;
+        thread::spawn(|| {
+            let title = track_title();
+            let mut cache = TITLE.lock().unwrap();
+            cache.title = title;
+            cache.read_at = Some(Instant::now());
+            cache.reading = false;
+        });
+    }
+    cache.title.clone()
+}
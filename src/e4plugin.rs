@@ -0,0 +1,165 @@
+use crate::e4script;
+use lazy_static::lazy_static;
+use rhai::{Dynamic, Engine, Scope, AST};
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// The directory, in the configuration directory, holding the plugins.
+pub const PLUGINS_DIR: &str = "plugins";
+
+/// The extension of the plugin scripts.
+const PLUGIN_EXTENSION: &str = "rhai";
+
+/// The function of a plugin called when one of its widgets is clicked, with the button name.
+const ON_CLICK: &str = "on_click";
+
+/// The function of a plugin called when a button launches its command, with the button name and the command.
+const ON_LAUNCH: &str = "on_launch";
+
+lazy_static! {
+    /// The plugins loaded from the configuration directory.
+    /// The lock is only held to get them: a plugin can show a dialog, running the event loop.
+    static ref PLUGINS: Mutex<Arc<E4Plugins>> = Mutex::new(Arc::new(E4Plugins::new()));
+}
+
+/// Error raised when a plugin cannot be found.
+#[derive(Debug)]
+struct PluginError {
+    details: String,
+}
+
+impl std::fmt::Display for PluginError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for PluginError {}
+
+/// A plugin: a rhai script from the plugins directory.
+///
+/// A plugin adds a widget type, used by the buttons with `type=plugin` and `plugin=<name of the script>`,
+/// by defining `fn on_click(button) { ... }`, and a launch hook by defining `fn on_launch(button, command) { ... }`.
+/// Plugins can call the functions of [e4script::register_api].
+pub struct E4Plugin {
+    /// The name of the plugin: the file name of its script without extension.
+    pub name: String,
+    /// The path of the script.
+    pub path: PathBuf,
+    ast: AST,
+}
+
+impl E4Plugin {
+    /// Return true if the plugin defines the function name with arity parameters.
+    fn defines(&self, name: &str, arity: usize) -> bool {
+        self.ast
+            .iter_functions()
+            .any(|f| f.name == name && f.params.len() == arity)
+    }
+}
+
+/// The rhai engine and the loaded plugins.
+pub struct E4Plugins {
+    engine: Engine,
+    plugins: Vec<E4Plugin>,
+}
+
+impl E4Plugins {
+    /// Create the engine, with the plugin API, and no plugins.
+    fn new() -> Self {
+        Self {
+            engine: e4script::new_engine(),
+            plugins: vec![],
+        }
+    }
+
+    /// Call a function of a plugin.
+    fn call(
+        &self,
+        plugin: &E4Plugin,
+        name: &str,
+        args: impl rhai::FuncArgs,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        match self
+            .engine
+            .call_fn::<Dynamic>(&mut Scope::new(), &plugin.ast, name, args)
+        {
+            Ok(_) => Ok(()),
+            Err(e) => Err(Box::new(PluginError {
+                details: format!("{}: {}", plugin.name, e),
+            })),
+        }
+    }
+}
+
+/// Return the loaded plugins.
+fn plugins() -> Arc<E4Plugins> {
+    PLUGINS.lock().unwrap().clone()
+}
+
+/// Load the plugins from the plugins directory of config_dir, replacing the loaded ones.
+/// Return the errors of the plugins that cannot be compiled.
+pub fn load(config_dir: &Path) -> Vec<String> {
+    let mut errors = vec![];
+    let mut plugins = E4Plugins::new();
+
+    // No plugins directory: no plugins
+    if let Ok(entries) = std::fs::read_dir(config_dir.join(PLUGINS_DIR)) {
+        let mut paths: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.extension().and_then(std::ffi::OsStr::to_str) == Some(PLUGIN_EXTENSION)
+            })
+            .collect();
+        paths.sort();
+
+        for path in paths {
+            let name = match path.file_stem().and_then(std::ffi::OsStr::to_str) {
+                Some(name) => name.to_string(),
+                None => continue,
+            };
+            match plugins.engine.compile_file(path.clone()) {
+                Ok(ast) => plugins.plugins.push(E4Plugin { name, path, ast }),
+                Err(e) => errors.push(format!("{}: {}", path.display(), e)),
+            }
+        }
+    }
+
+    *PLUGINS.lock().unwrap() = Arc::new(plugins);
+    errors
+}
+
+/// Call the on_click function of the plugin plugin_name for the widget button_name.
+pub fn click(plugin_name: &str, button_name: &str) -> Result<(), Box<dyn std::error::Error>> {
+    let plugins = plugins();
+    let plugin = plugins
+        .plugins
+        .iter()
+        .find(|p| p.name == plugin_name && p.defines(ON_CLICK, 1))
+        .ok_or_else(|| {
+            Box::new(PluginError {
+                details: format!("No plugin {} with a {} function", plugin_name, ON_CLICK),
+            })
+        })?;
+    plugins.call(plugin, ON_CLICK, (button_name.to_string(),))
+}
+
+/// Call the on_launch function of every plugin defining it.
+/// Return the errors of the failed calls.
+pub fn launched(button_name: &str, command: &str) -> Vec<String> {
+    let plugins = plugins();
+    plugins
+        .plugins
+        .iter()
+        .filter(|p| p.defines(ON_LAUNCH, 2))
+        .filter_map(|p| {
+            plugins
+                .call(p, ON_LAUNCH, (button_name.to_string(), command.to_string()))
+                .err()
+                .map(|e| e.to_string())
+        })
+        .collect()
+}
@@ -0,0 +1,36 @@
+use crate::{
+    e4events::{self, E4Event},
+    e4window,
+};
+use std::{process::Command, thread};
+
+/// Return a PNG thumbnail, width pixels wide at most, of the first visible window of cmd.
+/// The window is found by [e4window::window_class] and captured by ImageMagick's import.
+pub fn capture(cmd: &str, width: i32) -> Option<Vec<u8>> {
+    let class = e4window::window_class(cmd)?;
+    e4window::window_ids(&class).into_iter().find_map(|id| {
+        let output = Command::new("import")
+            .args(["-silent", "-window", &id, "-thumbnail"])
+            .arg(format!("{}x{}", width, width))
+            .arg("png:-")
+            .output()
+            .ok()?;
+        if output.status.success() && !output.stdout.is_empty() {
+            Some(output.stdout)
+        } else {
+            None
+        }
+    })
+}
+
+/// Capture the window of cmd in a separate thread, then publish it for the button named name
+/// as a [E4Event::PreviewCaptured] event. Nothing is published if no window is found.
+pub fn capture_later(name: &str, cmd: &str, width: i32) {
+    let name = name.to_string();
+    let cmd = cmd.to_string();
+    thread::spawn(move || {
+        if let Some(png_data) = capture(&cmd, width) {
+            e4events::publish(E4Event::PreviewCaptured(name, png_data));
+        }
+    });
+}
@@ -1,68 +1,234 @@
-use crate::e4button::E4Button;
-use fltk::app;
+use crate::{
+    e4bundle,
+    e4button::{E4Button, E4ButtonType},
+    e4command,
+    e4events::{self, E4Event},
+    e4hooks::{self, E4Hook},
+    e4trash,
+};
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Duration;
-use sysinfo::System;
+use std::time::{Duration, Instant};
 
-/// Check if a process is running by using sysinfo
-fn is_process_running(sys: &System, process_path: &str) -> bool {
-    // Extract the file name from the full path
-    let process_name = Path::new(process_path)
-        .file_name()
-        .and_then(|n| n.to_str())
-        .unwrap_or(process_path);
+/// The running processes, as last refreshed: none is known without the process-checking feature,
+/// and no button is shown as running.
+struct E4Processes {
+    #[cfg(feature = "process-checking")]
+    system: sysinfo::System,
+    /// The names of the executables of the macOS bundles, by command: Info.plist is read once.
+    bundle_executables: HashMap<String, Option<String>>,
+}
+
+impl E4Processes {
+    /// Return the processes, to be refreshed.
+    fn new() -> Self {
+        Self {
+            #[cfg(feature = "process-checking")]
+            system: sysinfo::System::new(),
+            bundle_executables: HashMap::new(),
+        }
+    }
+
+    /// Return the name of the executable of the macOS bundle process_path, if it is one.
+    fn bundle_executable(&mut self, process_path: &str) -> Option<String> {
+        self.bundle_executables
+            .entry(process_path.to_string())
+            .or_insert_with(|| e4bundle::bundle_executable(Path::new(process_path)))
+            .clone()
+    }
+
+    /// Forget the executables of the bundles which are no longer commands, after the buttons changed.
+    fn retain_bundle_executables(&mut self, commands: &HashSet<String>) {
+        self.bundle_executables
+            .retain(|process_path, _| commands.contains(process_path));
+    }
+
+    /// Read the running processes again.
+    fn refresh(&mut self) {
+        #[cfg(feature = "process-checking")]
+        self.system
+            .refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+    }
+
+    /// Return the ids of the processes whose name or command line holds process_name.
+    #[cfg(feature = "process-checking")]
+    fn matching(&self, process_name: &str) -> Vec<u32> {
+        self.system
+            .processes()
+            .iter()
+            .filter(|(_, process)| {
+                // Compare both the full path and the file name
+                process.name().to_str().unwrap().contains(process_name)
+                    || process
+                        .cmd()
+                        .iter()
+                        .any(|cmd| cmd.to_str().unwrap().contains(process_name))
+            })
+            .map(|(pid, _)| pid.as_u32())
+            .collect()
+    }
+
+    /// Return the ids of the processes whose name or command line holds process_name: none without
+    /// the process-checking feature.
+    #[cfg(not(feature = "process-checking"))]
+    fn matching(&self, _process_name: &str) -> Vec<u32> {
+        vec![]
+    }
+}
+
+/// Check if a process is running
+fn is_process_running(processes: &mut E4Processes, process_path: &str) -> bool {
+    !running_processes(processes, process_path).is_empty()
+}
+
+/// Return the ids of the running processes of process_path, found like [is_process_running].
+pub fn process_ids(process_path: &str) -> Vec<u32> {
+    let mut processes = E4Processes::new();
+    processes.refresh();
+    running_processes(&mut processes, process_path)
+}
+
+/// Return the ids of the processes whose name or command line holds the name of process_path.
+fn running_processes(processes: &mut E4Processes, process_path: &str) -> Vec<u32> {
+    // An empty name would match every process
+    if process_path.is_empty() {
+        return vec![];
+    }
+
+    // Extract the file name from the full path, or the executable name of a macOS bundle
+    let bundle_executable = processes.bundle_executable(process_path);
+    let process_name = match &bundle_executable {
+        Some(executable) => executable.as_str(),
+        None => Path::new(process_path)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(process_path),
+    };
 
     // Search among all processes
-    sys.processes().values().any(|process| {
-        // Compare both the full path and the file name
-        process.name().to_str().unwrap().contains(process_name)
-            || process
-                .cmd()
-                .iter()
-                .any(|cmd| cmd.to_str().unwrap().contains(process_name))
-    })
+    processes.matching(process_name)
 }
 
-/// Start a thread to check periodically all processes
-pub fn start_process_checker(buttons: Arc<Mutex<Vec<E4Button>>>, app: &app::App) {
+/// Start a thread to check periodically all processes.
+/// The states are published as [E4Event::ProcessStateChanged] events.
+/// The badge commands are run too, each at its interval: their texts are published as [E4Event::BadgeChanged] events,
+/// their requests of attention as [E4Event::AttentionRequested] events.
+pub fn start_process_checker(buttons: Arc<Mutex<Vec<E4Button>>>) {
     let interval = 2;
-    // Modifichiamo il channel per inviare l'indice invece del riferimento al button
-    let (sender, receiver) = app::channel::<(usize, bool)>();
-    let app_clone = *app;
-
-    let buttons_for_thread = buttons.clone();
 
     thread::spawn(move || {
-        let mut sys = System::new_all();
+        let mut processes = E4Processes::new();
+        // The state of the processes at the previous check, to detect when they start.
+        // The buttons can be added, removed and moved: they are known by name.
+        let mut was_running = HashMap::new();
+        // The last run of the badge commands, by name
+        let mut badge_runs: HashMap<String, Instant> = HashMap::new();
+        // Whether the badge commands asked for attention at their last run, by name
+        let mut was_asking = HashMap::new();
         loop {
-            sys.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
+            processes.refresh();
 
-            let buttons = buttons_for_thread.lock().unwrap();
-            for (index, button) in buttons.iter().enumerate() {
-                let cmd = button.command.lock().unwrap();
-                let is_running = is_process_running(&sys, cmd.get());
-                sender.send((index, is_running));
+            let buttons = buttons.lock().unwrap();
+            let mut widgets = vec![];
+            let mut badges = vec![];
+            let mut commands = HashSet::new();
+            for button in buttons.iter() {
+                if let Some(badge) = &button.badge {
+                    let is_due = badge_runs
+                        .get(&button.name)
+                        .is_none_or(|last_run| last_run.elapsed() >= badge.interval);
+                    if is_due {
+                        badges.push((button.name.clone(), badge.clone()));
+                    }
+                }
+                match button.button_type {
+                    // A watch button shows its process like a command button
+                    E4ButtonType::Command | E4ButtonType::Watch => {
+                        let cmd = button.command.lock().unwrap();
+                        commands.insert(cmd.get().clone());
+                        // The processes launched by the button are known by the supervisor, the others by name
+                        let is_running = !e4command::supervised_pids(&button.name).is_empty()
+                            || is_process_running(&mut processes, cmd.get());
+                        if was_running.insert(button.name.clone(), is_running) == Some(false)
+                            && is_running
+                        {
+                            e4hooks::run(
+                                E4Hook::ProcessStarted,
+                                Some((button.name.as_str(), cmd.get().as_str())),
+                            );
+                        }
+                        e4events::publish(E4Event::ProcessStateChanged(
+                            button.name.clone(),
+                            is_running,
+                        ));
+                    }
+                    E4ButtonType::Toggle | E4ButtonType::Trash => {
+                        widgets.push((
+                            button.name.clone(),
+                            button.button_type,
+                            button.toggle.clone(),
+                        ));
+                    }
+                    // Other widgets do not launch processes
+                    _ => {}
+                }
             }
             drop(buttons);
+            processes.retain_bundle_executables(&commands);
+
+            // The state commands may be slow: run them without holding the lock
+            for (name, button_type, toggle) in widgets {
+                let state = match button_type {
+                    E4ButtonType::Toggle => toggle.and_then(|toggle| toggle.is_on()),
+                    // The trash is on when full
+                    _ => e4trash::is_empty().map(|is_empty| !is_empty),
+                };
+                if let Some(state) = state {
+                    e4events::publish(E4Event::ProcessStateChanged(name, state));
+                }
+            }
+            for (name, badge) in badges {
+                badge_runs.insert(name.clone(), Instant::now());
+                let (text, is_asking) = badge.poll();
+                // Asking again and again is asking once: the attention ends with a click
+                if was_asking.insert(name.clone(), is_asking) != Some(true) && is_asking {
+                    e4events::publish(E4Event::AttentionRequested(name.clone()));
+                }
+                // A button drawn again shows no badge until it is published
+                if badge.text() != text {
+                    e4events::publish(E4Event::BadgeChanged(name, text));
+                }
+            }
 
             thread::sleep(Duration::from_secs(interval));
         }
     });
+}
 
-    while app_clone.wait() {
-        if let Some((index, is_running)) = receiver.recv() {
-            let mut buttons = buttons.lock().unwrap();
-            if let Some(button) = buttons.get_mut(index) {
-                button.border.set_active(is_running);
-            }
+/// Show the state of the button named name: the border of the command buttons, the icon of the widgets.
+/// Only the widgets of that button are redrawn, and only if the state changed.
+pub fn show_state(buttons: &mut [E4Button], name: &str, is_running: bool) {
+    if let Some(button) = buttons.iter_mut().find(|button| button.name == name) {
+        let size = (button.icon.width(), button.icon.height());
+        match button.state_icons.as_mut() {
+            Some(state_icons) => state_icons.show(&mut button.button, is_running, size),
+            None => button.border.set_active(is_running),
+        }
+    }
+}
+
+/// Show text in the badge of the button named name.
+pub fn show_badge(buttons: &mut [E4Button], name: &str, text: Option<String>) {
+    if let Some(button) = buttons.iter_mut().find(|button| button.name == name) {
+        if let Some(badge) = &button.badge {
+            badge.show(&mut button.button, text);
         }
     }
 }
 
 /// Setup of the process checker
-pub fn setup_process_checker(buttons: Vec<E4Button>, app: &app::App) {
-    let buttons = Arc::new(Mutex::new(buttons));
-    start_process_checker(buttons.clone(), app);
+pub fn setup_process_checker(buttons: Arc<Mutex<Vec<E4Button>>>) {
+    start_process_checker(buttons);
 }
@@ -0,0 +1,108 @@
+use lazy_static::lazy_static;
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+/// A phase of the startup, timed by the --profile-startup flag.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum E4Phase {
+    /// Reading e4docker.conf and the .conf files of the buttons.
+    ConfigParsing,
+    /// Decoding the icons of the buttons.
+    IconDecoding,
+    /// Creating the widgets of the buttons.
+    WidgetCreation,
+}
+
+impl E4Phase {
+    /// The phases, in the order of the report.
+    const ALL: [E4Phase; 3] = [
+        E4Phase::ConfigParsing,
+        E4Phase::IconDecoding,
+        E4Phase::WidgetCreation,
+    ];
+
+    /// Return the name of the phase in the report.
+    pub fn name(&self) -> &'static str {
+        match self {
+            E4Phase::ConfigParsing => "config parsing",
+            E4Phase::IconDecoding => "icon decoding",
+            E4Phase::WidgetCreation => "widget creation",
+        }
+    }
+}
+
+/// The time spent in the phases since the profiling started.
+struct E4Profile {
+    start: Instant,
+    phases: HashMap<E4Phase, Duration>,
+}
+
+lazy_static! {
+    /// The profile, when the profiling is enabled.
+    static ref PROFILE: Mutex<Option<E4Profile>> = Mutex::new(None);
+}
+
+thread_local! {
+    /// The time spent in the phases nested in the running one, to count it only once.
+    static NESTED: Cell<Duration> = const { Cell::new(Duration::ZERO) };
+}
+
+/// Start profiling the startup.
+pub fn enable() {
+    *PROFILE.lock().unwrap() = Some(E4Profile {
+        start: Instant::now(),
+        phases: HashMap::new(),
+    });
+}
+
+/// Return true if the startup is being profiled.
+pub fn is_enabled() -> bool {
+    PROFILE.lock().unwrap().is_some()
+}
+
+/// Run f, adding its time to phase if the profiling is enabled.
+/// The time of the phases nested in f is counted in them only.
+pub fn time<T, F: FnOnce() -> T>(phase: E4Phase, f: F) -> T {
+    if !is_enabled() {
+        return f();
+    }
+    let outer_nested = NESTED.with(|nested| nested.replace(Duration::ZERO));
+    let start = Instant::now();
+    let result = f();
+    let elapsed = start.elapsed();
+    let nested = NESTED.with(|nested| nested.replace(outer_nested + elapsed));
+    if let Some(profile) = PROFILE.lock().unwrap().as_mut() {
+        *profile.phases.entry(phase).or_default() += elapsed.saturating_sub(nested);
+    }
+    result
+}
+
+/// Return the time spent in every phase until milestone, if the profiling is enabled.
+pub fn summary(milestone: &str) -> Option<String> {
+    let profile = PROFILE.lock().unwrap();
+    let profile = profile.as_ref()?;
+    let phases: Vec<String> = E4Phase::ALL
+        .iter()
+        .map(|phase| {
+            let duration = profile.phases.get(phase).copied().unwrap_or_default();
+            format!("{} {} ms", phase.name(), duration.as_millis())
+        })
+        .collect();
+    Some(format!(
+        "{} after {} ms: {}",
+        milestone,
+        profile.start.elapsed().as_millis(),
+        phases.join(", ")
+    ))
+}
+
+/// Print the time spent in every phase until milestone, if the profiling is enabled.
+pub fn report(milestone: &str) {
+    if let Some(summary) = summary(milestone) {
+        println!("{}", summary);
+    }
+}
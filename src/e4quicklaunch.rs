@@ -0,0 +1,101 @@
+use crate::e4button::E4Button;
+use fltk::{
+    app,
+    enums::{Color, FrameType, Key},
+    frame::Frame,
+    prelude::*,
+    window::Window,
+};
+use std::cell::RefCell;
+
+/// The seconds the numbers stay over the buttons.
+const OVERLAY_SECONDS: f64 = 1.5;
+
+/// The buttons launched by a number: the first nine.
+const QUICK_LAUNCH_BUTTONS: usize = 9;
+
+thread_local! {
+    /// The numbers shown over the buttons, and the timeout hiding them.
+    static OVERLAY: RefCell<(Vec<Frame>, Option<app::TimeoutHandle>)> = const { RefCell::new((vec![], None)) };
+}
+
+/// Return the index of the button launched by key, from 0 for 1 to 8 for 9.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4quicklaunch::button_index;
+/// use fltk::enums::Key;
+///
+/// assert_eq!(button_index(Key::from_char('1')), Some(0));
+/// assert_eq!(button_index(Key::from_char('9')), Some(8));
+/// assert_eq!(button_index(Key::from_char('0')), None);
+/// ```
+pub fn button_index(key: Key) -> Option<usize> {
+    (1..=QUICK_LAUNCH_BUTTONS as u32)
+        .position(|n| char::from_digit(n, 10).map(Key::from_char) == Some(key))
+}
+
+/// Return the buttons launched by a number, in their order.
+fn numbered(buttons: &[E4Button]) -> impl Iterator<Item = &E4Button> {
+    buttons
+        .iter()
+        .filter(|button| button.button.visible() && button.button.active())
+        .take(QUICK_LAUNCH_BUTTONS)
+}
+
+/// Launch the button at index among the visible ones, as if clicked. Return false if there is none.
+pub fn launch(buttons: &[E4Button], index: usize) -> bool {
+    match numbered(buttons).nth(index) {
+        Some(button) => {
+            button.button.clone().do_callback();
+            true
+        }
+        None => false,
+    }
+}
+
+/// Show the numbers launching the buttons over them, in wind, for a moment. It must be called in the main thread.
+pub fn show_numbers(wind: &mut Window, buttons: &[E4Button]) {
+    hide_numbers();
+    wind.begin();
+    let frames: Vec<Frame> = numbered(buttons)
+        .enumerate()
+        .map(|(index, button)| {
+            let b = &button.button;
+            // In the bottom left corner, not to cover the badge
+            let size = (b.h() / 3).max(14);
+            let mut frame = Frame::new(b.x(), b.y() + b.h() - size, size, size, None);
+            frame.set_frame(FrameType::FlatBox);
+            frame.set_color(Color::Black);
+            frame.set_label_color(Color::White);
+            frame.set_label_size(size * 2 / 3);
+            frame.set_label(&(index + 1).to_string());
+            frame
+        })
+        .collect();
+    wind.end();
+    wind.redraw();
+
+    let timeout = app::add_timeout3(OVERLAY_SECONDS, |_| {
+        // The timeout running cannot be removed
+        OVERLAY.with(|overlay| overlay.borrow_mut().1 = None);
+        hide_numbers();
+    });
+    OVERLAY.with(|overlay| *overlay.borrow_mut() = (frames, Some(timeout)));
+}
+
+/// Remove the numbers shown by [show_numbers], if any.
+pub fn hide_numbers() {
+    let (frames, timeout) = OVERLAY.with(|overlay| std::mem::take(&mut *overlay.borrow_mut()));
+    if let Some(timeout) = timeout {
+        app::remove_timeout3(timeout);
+    }
+    for frame in frames {
+        if let Some(mut parent) = frame.parent() {
+            parent.remove(&frame);
+            parent.redraw();
+        }
+        Frame::delete(frame);
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{
+    e4button::E4Button,
+    e4config::E4Config,
+    e4events::{self, E4Event},
+    translations::Translations,
+};
+use fltk::{
+    app,
+    enums::{CallbackTrigger, Event, Key},
+    input::Input,
+    prelude::*,
+    window::Window,
+};
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+/// The narrowest field renaming a button: a button is narrower than most names.
+const MIN_WIDTH: i32 = 120;
+
+thread_local! {
+    /// True while a button is renamed: the clicks on it do not launch it.
+    static RENAMING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Return true if a button is renamed on the dock.
+pub fn is_renaming() -> bool {
+    RENAMING.with(Cell::get)
+}
+
+/// Show a field over button, in wind, to rename it on the dock: Enter saves the name, Esc or a click
+/// elsewhere leaves it as it was. It must be called in the main thread, from the click starting the rename.
+pub fn start(
+    wind: &Window,
+    button: &E4Button,
+    config: Rc<RefCell<E4Config>>,
+    translations: Arc<Mutex<Translations>>,
+) {
+    if RENAMING.with(|renaming| renaming.replace(true)) {
+        return;
+    }
+    let mut wind = wind.clone();
+    let button = button.clone();
+    // The clicked button takes the focus: the field is shown once the click is handled
+    app::add_timeout3(0.0, move |_| {
+        show_field(&mut wind, &button, config.clone(), translations.clone())
+    });
+}
+
+/// Show the field renaming button, centered on it.
+fn show_field(
+    wind: &mut Window,
+    button: &E4Button,
+    config: Rc<RefCell<E4Config>>,
+    translations: Arc<Mutex<Translations>>,
+) {
+    let b = &button.button;
+    let width = b.w().max(MIN_WIDTH).min(wind.w());
+    let height = app::font_size() + 10;
+    let x = (b.x() + b.w() / 2 - width / 2).clamp(0, wind.w() - width);
+    let y = (b.y() + b.h() / 2 - height / 2).max(0);
+    wind.begin();
+    let mut input = Input::new(x, y, width, height, None);
+    wind.end();
+    input.set_value(&button.name);
+    // The whole name is selected, to be replaced by typing
+    let _ = input.set_position(0);
+    let _ = input.set_mark(input.value().len() as i32);
+    input.set_trigger(CallbackTrigger::EnterKeyAlways);
+
+    let button = button.clone();
+    input.set_callback(move |input| {
+        let name = input.value().trim().to_string();
+        finish(input);
+        // Another dialog is open
+        let result = match config.try_borrow_mut() {
+            Ok(mut config) => button.rename(&mut config, &name, translations.clone()),
+            Err(_) => return,
+        };
+        match result {
+            Ok(_) if name != button.name => e4events::publish(E4Event::ButtonChanged {
+                old_name: button.name.clone(),
+                name,
+            }),
+            Ok(_) => {}
+            Err(e) => fltk::dialog::alert_default(&e.to_string()),
+        }
+    });
+    input.handle(|input, ev| match ev {
+        Event::KeyDown if app::event_key() == Key::Escape => {
+            finish(input);
+            true
+        }
+        Event::Unfocus => {
+            finish(input);
+            false
+        }
+        _ => false,
+    });
+    let _ = input.take_focus();
+    wind.redraw();
+}
+
+/// Remove the field renaming a button, once.
+fn finish(input: &Input) {
+    if !RENAMING.with(|renaming| renaming.replace(false)) {
+        return;
+    }
+    let input = input.clone();
+    // The field cannot be deleted in its own callback
+    app::add_timeout3(0.0, move |_| {
+        if let Some(mut parent) = input.parent() {
+            parent.remove(&input);
+            parent.redraw();
+        }
+        Input::delete(input.clone());
+    });
+}
@@ -0,0 +1,240 @@
+use crate::{e4gesture::E4SwipeTracker, e4rename, e4screen::E4Area, e4style::E4Edge};
+use fltk::{app, enums::Event, prelude::*, window::Window};
+use std::{
+    cell::{Cell, RefCell},
+    sync::Once,
+    time::Instant,
+};
+
+/// The thickness of the trigger along an edge, and the side of the trigger in a corner.
+pub const TRIGGER_SIZE: i32 = 2;
+
+/// The opacity of the trigger: a fully transparent window lets the pointer through on Windows.
+const TRIGGER_OPACITY: f64 = 0.01;
+
+/// The seconds the dock takes to slide off the screen, or back.
+const SLIDE_DURATION: f64 = 0.2;
+
+/// The moves of the dock while sliding.
+const SLIDE_STEPS: i32 = 10;
+
+/// The seconds between two checks of the pointer, with the auto-hide.
+const AUTOHIDE_INTERVAL: f64 = 0.25;
+
+/// Starts the timer of the auto-hide once.
+static AUTOHIDE_TIMER: Once = Once::new();
+
+thread_local! {
+    /// The window waiting for the pointer on the edge of the screen, while the dock is hidden.
+    static TRIGGER: RefCell<Option<Window>> = const { RefCell::new(None) };
+    /// The position of the dock slid off the screen, where it slides back when revealed.
+    static HOME: Cell<Option<(i32, i32)>> = const { Cell::new(None) };
+    /// True while the dock slides.
+    static SLIDING: Cell<bool> = const { Cell::new(false) };
+    /// The seconds without the pointer over the dock before it slides off the screen, 0 to keep it,
+    /// and the edge it slides to.
+    static AUTOHIDE: Cell<(f64, E4Edge)> = const { Cell::new((0.0, E4Edge::Bottom)) };
+    /// When the pointer was last over the dock.
+    static LAST_HOVER: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Return the trigger of edge on the screen (x, y, w, h): a strip along a side, or a square in a corner.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::{e4reveal::trigger_area, e4style::E4Edge};
+///
+/// assert_eq!(trigger_area(E4Edge::Bottom, (0, 0, 1920, 1080)), (0, 1078, 1920, 2));
+/// assert_eq!(trigger_area(E4Edge::TopRight, (0, 0, 1920, 1080)), (1918, 0, 2, 2));
+/// ```
+pub fn trigger_area(edge: E4Edge, screen: (i32, i32, i32, i32)) -> (i32, i32, i32, i32) {
+    let (x, y, w, h) = screen;
+    let right = x + w - TRIGGER_SIZE;
+    let bottom = y + h - TRIGGER_SIZE;
+    let size = TRIGGER_SIZE;
+    match edge {
+        E4Edge::Top => (x, y, w, size),
+        E4Edge::Bottom => (x, bottom, w, size),
+        E4Edge::Left => (x, y, size, h),
+        E4Edge::Right => (right, y, size, h),
+        E4Edge::TopLeft => (x, y, size, size),
+        E4Edge::TopRight => (right, y, size, size),
+        E4Edge::BottomLeft => (x, bottom, size, size),
+        E4Edge::BottomRight => (right, bottom, size, size),
+    }
+}
+
+/// Return the position of the dock (x, y, w, h) slid off the screen (x, y, w, h) beyond edge:
+/// the corners slide to their top or bottom edge.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::{e4reveal::hidden_position, e4style::E4Edge};
+///
+/// let dock = (100, 1000, 400, 80);
+/// assert_eq!(hidden_position(E4Edge::Bottom, dock, (0, 0, 1920, 1080)), (100, 1080));
+/// assert_eq!(hidden_position(E4Edge::TopLeft, dock, (0, 0, 1920, 1080)), (100, -80));
+/// assert_eq!(hidden_position(E4Edge::Right, dock, (0, 0, 1920, 1080)), (1920, 1000));
+/// ```
+pub fn hidden_position(edge: E4Edge, dock: E4Area, screen: E4Area) -> (i32, i32) {
+    let (x, y, w, h) = dock;
+    let (screen_x, screen_y, screen_w, screen_h) = screen;
+    match edge {
+        E4Edge::Top | E4Edge::TopLeft | E4Edge::TopRight => (x, screen_y - h),
+        E4Edge::Bottom | E4Edge::BottomLeft | E4Edge::BottomRight => (x, screen_y + screen_h),
+        E4Edge::Left => (screen_x - w, y),
+        E4Edge::Right => (screen_x + screen_w, y),
+    }
+}
+
+/// Return true if the dock is hidden, waiting for the pointer on the edge.
+pub fn is_hidden() -> bool {
+    TRIGGER.with(|trigger| trigger.borrow().is_some())
+}
+
+/// Hide wind, the dock, until the pointer touches edge of its screen, or swipes away from it.
+/// It must be called in the main thread.
+pub fn hide(wind: &Window, edge: E4Edge) {
+    if is_hidden() {
+        return;
+    }
+    let screen = app::screen_xywh(app::screen_num(wind.x(), wind.y()));
+    let (x, y, w, h) = trigger_area(edge, screen);
+    let mut trigger = Window::new(x, y, w, h, None);
+    trigger.set_border(false);
+    trigger.end();
+    trigger.set_override();
+    trigger.handle({
+        let wind = wind.clone();
+        let mut swipes = E4SwipeTracker::default();
+        move |_, event| {
+            let revealed = match event {
+                Event::Enter => true,
+                Event::MouseWheel => swipes
+                    .add(Instant::now(), app::event_dx_value(), app::event_dy_value())
+                    .is_some_and(|swipe| swipe.is_away_from(edge)),
+                _ => false,
+            };
+            if revealed {
+                // The trigger cannot be deleted in its own handler
+                let mut wind = wind.clone();
+                app::add_timeout3(0.0, move |_| reveal(&mut wind));
+            }
+            revealed
+        }
+    });
+    trigger.show();
+    trigger.set_opacity(TRIGGER_OPACITY);
+
+    let mut wind = wind.clone();
+    wind.hide();
+    TRIGGER.with(|current| *current.borrow_mut() = Some(trigger));
+}
+
+/// Show wind, the dock, hidden by [hide], sliding it back if it slid off the screen.
+/// It must be called in the main thread.
+pub fn reveal(wind: &mut Window) {
+    let trigger = TRIGGER.with(|trigger| trigger.borrow_mut().take());
+    if let Some(mut trigger) = trigger {
+        trigger.hide();
+        Window::delete(trigger);
+        LAST_HOVER.with(|last_hover| last_hover.set(Some(Instant::now())));
+        match HOME.with(Cell::take) {
+            Some(home) => {
+                let (_, edge) = AUTOHIDE.with(Cell::get);
+                let screen = app::screen_xywh(app::screen_num(home.0, home.1));
+                let (x, y) = hidden_position(edge, (home.0, home.1, wind.w(), wind.h()), screen);
+                wind.set_pos(x, y);
+                wind.show();
+                slide(wind, home, || {});
+            }
+            None => wind.show(),
+        }
+    }
+}
+
+/// Move wind to (x, y) in [SLIDE_DURATION], then call done.
+fn slide<F: FnOnce() + 'static>(wind: &Window, (x, y): (i32, i32), done: F) {
+    SLIDING.with(|sliding| sliding.set(true));
+    let mut wind = wind.clone();
+    let (from_x, from_y) = (wind.x(), wind.y());
+    let mut step = 0;
+    let mut done = Some(done);
+    let interval = SLIDE_DURATION / SLIDE_STEPS as f64;
+    app::add_timeout3(interval, move |handle| {
+        step += 1;
+        wind.set_pos(
+            from_x + (x - from_x) * step / SLIDE_STEPS,
+            from_y + (y - from_y) * step / SLIDE_STEPS,
+        );
+        if step < SLIDE_STEPS {
+            app::repeat_timeout3(interval, handle);
+            return;
+        }
+        SLIDING.with(|sliding| sliding.set(false));
+        if let Some(done) = done.take() {
+            done();
+        }
+    });
+}
+
+/// Slide wind, the dock, off the screen beyond edge, then hide it as [hide] does.
+/// It must be called in the main thread.
+pub fn slide_out(wind: &Window, edge: E4Edge) {
+    if is_hidden() || SLIDING.with(Cell::get) {
+        return;
+    }
+    let home = (wind.x(), wind.y());
+    let screen = app::screen_xywh(app::screen_num(home.0, home.1));
+    let target = hidden_position(edge, (home.0, home.1, wind.w(), wind.h()), screen);
+    let mut hidden = wind.clone();
+    slide(wind, target, move || {
+        // The trigger is put on the screen of the dock
+        hidden.hide();
+        hidden.set_pos(home.0, home.1);
+        hide(&hidden, edge);
+        HOME.with(|current| current.set(Some(home)));
+    });
+}
+
+/// Slide the dock off the screen beyond edge after seconds without the pointer over it, 0 not to.
+pub fn set_autohide(seconds: f64, edge: E4Edge) {
+    AUTOHIDE.with(|autohide| autohide.set((seconds, edge)));
+}
+
+/// Check the pointer from time to time, sliding wind, the dock, off the screen as asked by [set_autohide].
+/// It must be called in the main thread.
+pub fn watch_autohide(wind: &Window) {
+    let wind = wind.clone();
+    AUTOHIDE_TIMER.call_once(move || {
+        app::add_timeout3(AUTOHIDE_INTERVAL, move |handle| {
+            check_autohide(&wind);
+            app::repeat_timeout3(AUTOHIDE_INTERVAL, handle);
+        });
+    });
+}
+
+/// Slide wind off the screen if the pointer left it long enough ago.
+fn check_autohide(wind: &Window) {
+    let (seconds, edge) = AUTOHIDE.with(Cell::get);
+    let now = Instant::now();
+    let (mouse_x, mouse_y) = app::get_mouse();
+    let hovered = (wind.x()..wind.x() + wind.w()).contains(&mouse_x)
+        && (wind.y()..wind.y() + wind.h()).contains(&mouse_y);
+    // A menu, a dialog or a rename in progress keeps the dock
+    let busy = app::grab().is_some() || app::modal().is_some() || e4rename::is_renaming();
+    if seconds <= 0.0 || !wind.shown() || hovered || busy {
+        LAST_HOVER.with(|last_hover| last_hover.set(Some(now)));
+        return;
+    }
+    let last_hover = LAST_HOVER.with(|last_hover| {
+        let value = last_hover.get().unwrap_or(now);
+        last_hover.set(Some(value));
+        value
+    });
+    if now.duration_since(last_hover).as_secs_f64() >= seconds {
+        slide_out(wind, edge);
+    }
+}
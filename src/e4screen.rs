@@ -0,0 +1,177 @@
+use crate::e4style::E4DialogPosition;
+use fltk::{app, enums::Event, prelude::*, window::Window};
+use std::cell::{Cell, RefCell};
+
+/// An area of the screen: x, y, width and height.
+pub type E4Area = (i32, i32, i32, i32);
+
+/// How near, in pixels, the dock must be to an edge of the work area to stay on it when the area changes.
+const ANCHOR_DISTANCE: i32 = 8;
+
+/// The seconds between two checks of the work area: a panel resized sends no event.
+const WATCH_INTERVAL: f64 = 1.0;
+
+thread_local! {
+    /// The dock followed by [watch], with its screen and its work area at the last check.
+    static WATCHED: RefCell<Option<(Window, i32, E4Area)>> = const { RefCell::new(None) };
+    /// Where the dialogs are opened by [place_dialog].
+    static DIALOG_POSITION: Cell<E4DialogPosition> = const { Cell::new(E4DialogPosition::Default) };
+}
+
+/// Return the position of a side of the dock at pos, size long, in a work area from old_start, old_length long,
+/// when it changes to start from new_start, new_length long.
+fn follow_axis(
+    pos: i32,
+    size: i32,
+    (old_start, old_length): (i32, i32),
+    (new_start, new_length): (i32, i32),
+) -> i32 {
+    let new_end = new_start + new_length - size;
+    let pos = if (pos - old_start).abs() <= ANCHOR_DISTANCE {
+        new_start
+    } else if (pos + size - (old_start + old_length)).abs() <= ANCHOR_DISTANCE {
+        new_end
+    } else {
+        pos
+    };
+    // A dock larger than the area starts with it
+    pos.min(new_end).max(new_start)
+}
+
+/// Return the position of the dock (x, y, w, h) when the work area changes from old to new:
+/// the dock stays on the edges it touched and inside the work area.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4screen::follow;
+///
+/// // On the bottom edge of a 1080 pixels high screen, with a 40 pixels high panel
+/// let dock = (100, 1000, 400, 40);
+/// // The panel is removed: the dock goes down with the edge
+/// assert_eq!(follow(dock, (0, 0, 1920, 1040), (0, 0, 1920, 1080)), (100, 1040));
+/// // The resolution is reduced: the dock is moved inside the screen
+/// assert_eq!(follow((1700, 500, 400, 40), (0, 0, 1920, 1080), (0, 0, 1280, 720)), (880, 500));
+/// ```
+pub fn follow(dock: E4Area, old: E4Area, new: E4Area) -> (i32, i32) {
+    let (x, y, w, h) = dock;
+    (
+        follow_axis(x, w, (old.0, old.2), (new.0, new.2)),
+        follow_axis(y, h, (old.1, old.3), (new.1, new.3)),
+    )
+}
+
+/// Return the top left corner of a dialog, (width, height) large, opened at position from the dock,
+/// in the work area of the screen of the dock: None where fltk opens it. Beside the dock, it is opened
+/// on the side with more room, above or below.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::{e4screen::dialog_position, e4style::E4DialogPosition};
+///
+/// // On the bottom edge of the second screen, right of a 1920 pixels wide one
+/// let dock = (2400, 1000, 400, 80);
+/// let area = (1920, 0, 1920, 1080);
+/// assert_eq!(dialog_position(E4DialogPosition::Beside, dock, (600, 400), area), Some((2300, 600)));
+/// assert_eq!(dialog_position(E4DialogPosition::Center, dock, (600, 400), area), Some((2300, 680)));
+/// assert_eq!(dialog_position(E4DialogPosition::Default, dock, (600, 400), area), None);
+/// ```
+pub fn dialog_position(
+    position: E4DialogPosition,
+    dock: E4Area,
+    (width, height): (i32, i32),
+    area: E4Area,
+) -> Option<(i32, i32)> {
+    let (x, y, w, h) = dock;
+    let (area_x, area_y, area_w, area_h) = area;
+    let y = match position {
+        E4DialogPosition::Default => return None,
+        E4DialogPosition::Beside if y - area_y >= area_y + area_h - (y + h) => y - height,
+        E4DialogPosition::Beside => y + h,
+        E4DialogPosition::Center => y + h / 2 - height / 2,
+    };
+    let x = x + w / 2 - width / 2;
+    // A dialog larger than the area starts with it
+    Some((
+        x.min(area_x + area_w - width).max(area_x),
+        y.min(area_y + area_h - height).max(area_y),
+    ))
+}
+
+/// Open the dialogs created from now on at position: see [place_dialog].
+pub fn set_dialog_position(position: E4DialogPosition) {
+    DIALOG_POSITION.with(|dialog_position| dialog_position.set(position));
+}
+
+/// Move dialog, before it is shown, where the dialogs are opened from the dock followed by [watch]:
+/// see [dialog_position]. It must be called in the main thread.
+pub fn place_dialog(dialog: &mut Window) {
+    let position = DIALOG_POSITION.with(Cell::get);
+    let place = WATCHED.with(|watched| {
+        let watched = watched.borrow();
+        let (wind, _, _) = watched.as_ref()?;
+        // The dock can be on another screen since the last check
+        let (_, area) = work_area(wind);
+        dialog_position(
+            position,
+            (wind.x(), wind.y(), wind.w(), wind.h()),
+            (dialog.w(), dialog.h()),
+            area,
+        )
+    });
+    if let Some((x, y)) = place {
+        dialog.set_pos(x, y);
+    }
+}
+
+/// Return the screen showing the center of wind and its work area: the screen without the panels of the desktop.
+pub fn work_area(wind: &Window) -> (i32, E4Area) {
+    let screen = app::screen_num(wind.x() + wind.w() / 2, wind.y() + wind.h() / 2);
+    (screen, app::screen_work_area(screen))
+}
+
+/// Move wind inside the work area of its screen, e.g. when its saved position is out of it.
+pub fn clamp(wind: &mut Window) {
+    let (_, area) = work_area(wind);
+    let (x, y) = follow((wind.x(), wind.y(), wind.w(), wind.h()), area, area);
+    if (x, y) != (wind.x(), wind.y()) {
+        wind.set_pos(x, y);
+    }
+}
+
+/// Move the watched dock if the work area of its screen has changed.
+fn check() {
+    // The dock reserving its space is outside the work area: following it would move the dock away
+    if crate::e4x11::is_reserving_space() {
+        return;
+    }
+    WATCHED.with(|watched| {
+        if let Some((wind, screen, area)) = watched.borrow_mut().as_mut() {
+            let (new_screen, new_area) = work_area(wind);
+            // Dragged to another screen: the dock is where the user put it
+            if new_screen == *screen && new_area != *area {
+                let (x, y) = follow((wind.x(), wind.y(), wind.w(), wind.h()), *area, new_area);
+                wind.set_pos(x, y);
+            }
+            (*screen, *area) = (new_screen, new_area);
+        }
+    });
+}
+
+/// Keep wind, the dock, inside the work area of its screen, following its changes: a new resolution,
+/// a panel added or resized. It must be called once, in the main thread.
+pub fn watch(wind: &Window) {
+    let (screen, area) = work_area(wind);
+    WATCHED.with(|watched| *watched.borrow_mut() = Some((wind.clone(), screen, area)));
+    app::add_handler(|event| {
+        if event == Event::ScreenConfigChanged {
+            check();
+        }
+        false
+    });
+    app::add_timeout3(WATCH_INTERVAL, |handle| {
+        check();
+        app::repeat_timeout3(WATCH_INTERVAL, handle);
+    });
+}
@@ -0,0 +1,154 @@
+use crate::{
+    e4command::{self, E4Command},
+    e4toast,
+};
+use rhai::{Array, Engine};
+use std::{path::Path, process::Command};
+
+/// The operations a script can run before it is stopped, so an endless loop does not freeze the dock.
+const MAX_OPERATIONS: u64 = 10_000_000;
+
+/// The depth of the function calls a script can reach, so an endless recursion does not overflow the stack.
+const MAX_CALL_LEVELS: usize = 64;
+
+/// Return a rhai engine with the API of [register_api], stopping the scripts running too long:
+/// the script fails then, and its error is shown as the others.
+pub fn new_engine() -> Engine {
+    let mut engine = Engine::new();
+    engine.set_max_operations(MAX_OPERATIONS);
+    engine.set_max_call_levels(MAX_CALL_LEVELS);
+    register_api(&mut engine);
+    engine
+}
+
+/// Register the functions that the scripts and the plugins can call:
+/// - `run(command)` and `run(command, [arguments])` launch a program, returning false if it cannot be started;
+/// - `output(command, [arguments])` runs a program and returns what it prints;
+/// - `open(target)` opens a file, a directory or an URI with its default application, returning false if it cannot;
+/// - `alert(message)` shows a message, `toast(message)` shows a message closing by itself;
+/// - `clipboard()` returns the text in the clipboard, `copy(text)` puts a text in the clipboard;
+/// - `selection()` returns the selected text, where the desktop has a primary selection.
+pub fn register_api(engine: &mut Engine) {
+    engine.register_fn("run", |command: &str| run(command, vec![]));
+    engine.register_fn("run", |command: &str, arguments: Array| {
+        run(command, to_strings(arguments))
+    });
+    engine.register_fn("output", |command: &str, arguments: Array| {
+        output(command, &to_strings(arguments))
+    });
+    engine.register_fn("open", |target: &str| {
+        E4Command::open_with_default_app(target).is_ok()
+    });
+    engine.register_fn("alert", |message: &str| {
+        fltk::dialog::alert_default(message);
+    });
+    engine.register_fn("toast", |message: &str| {
+        e4toast::show(message);
+    });
+    engine.register_fn("clipboard", clipboard);
+    engine.register_fn("copy", |text: &str| {
+        fltk::app::copy(text);
+    });
+    engine.register_fn("selection", selection);
+}
+
+/// Run the script in path.
+pub fn run_script(path: &Path) -> Result<(), Box<dyn std::error::Error>> {
+    new_engine().run_file(path.to_path_buf())?;
+    Ok(())
+}
+
+/// Convert the items of a rhai array to strings.
+fn to_strings(array: Array) -> Vec<String> {
+    array.into_iter().map(|item| item.to_string()).collect()
+}
+
+/// Launch a program without waiting for it. Return false if it cannot be started.
+fn run(command: &str, arguments: Vec<String>) -> bool {
+    match Command::new(command).args(arguments).spawn() {
+        Ok(child) => {
+            e4command::supervise_child(child);
+            true
+        }
+        Err(_) => false,
+    }
+}
+
+/// Run a program and return its standard output, or an empty string if it cannot be run.
+fn output(command: &str, arguments: &[String]) -> String {
+    match Command::new(command).args(arguments).output() {
+        Ok(output) => String::from_utf8_lossy(&output.stdout).to_string(),
+        Err(_) => String::new(),
+    }
+}
+
+/// Return true on a Wayland session, where the X11 clipboard tools do not work.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn is_wayland() -> bool {
+    std::env::var_os("WAYLAND_DISPLAY").is_some()
+}
+
+/// Return the text in the clipboard.
+/// The FLTK clipboard is read asynchronously through paste events: ask the desktop tools instead.
+#[cfg(target_os = "windows")]
+fn clipboard() -> String {
+    output(
+        "powershell",
+        &[
+            String::from("-NoProfile"),
+            String::from("-Command"),
+            String::from("Get-Clipboard -Raw"),
+        ],
+    )
+}
+
+/// Return the text in the clipboard.
+/// The FLTK clipboard is read asynchronously through paste events: ask the desktop tools instead.
+#[cfg(target_os = "macos")]
+fn clipboard() -> String {
+    output("pbpaste", &[])
+}
+
+/// Return the text in the clipboard.
+/// The FLTK clipboard is read asynchronously through paste events: ask the desktop tools instead.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn clipboard() -> String {
+    if is_wayland() {
+        output("wl-paste", &[String::from("--no-newline")])
+    } else {
+        output(
+            "xclip",
+            &[
+                String::from("-o"),
+                String::from("-selection"),
+                String::from("clipboard"),
+            ],
+        )
+    }
+}
+
+/// Return the selected text. Only the X11 and Wayland desktops have a primary selection.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+fn selection() -> String {
+    String::new()
+}
+
+/// Return the selected text. Only the X11 and Wayland desktops have a primary selection.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn selection() -> String {
+    if is_wayland() {
+        output(
+            "wl-paste",
+            &[String::from("--primary"), String::from("--no-newline")],
+        )
+    } else {
+        output(
+            "xclip",
+            &[
+                String::from("-o"),
+                String::from("-selection"),
+                String::from("primary"),
+            ],
+        )
+    }
+}
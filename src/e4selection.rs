@@ -0,0 +1,58 @@
+use crate::e4button::E4Button;
+use fltk::{app, enums::Event};
+use std::cell::RefCell;
+
+thread_local! {
+    /// The names of the selected buttons, in the order they were selected.
+    static SELECTION: RefCell<Vec<String>> = const { RefCell::new(vec![]) };
+}
+
+/// Return true if the event is a click with Ctrl held, selecting a button instead of launching it.
+pub fn is_select_click() -> bool {
+    matches!(app::event(), Event::Push | Event::Released)
+        && app::event_mouse_button() == app::MouseButton::Left
+        && app::is_event_ctrl()
+}
+
+/// Select the button named name, among buttons, or deselect it if selected.
+/// It must be called in the main thread.
+pub fn toggle(buttons: &mut [E4Button], name: &str) {
+    let selected = SELECTION.with(|selection| {
+        let mut selection = selection.borrow_mut();
+        match selection.iter().position(|selected| selected == name) {
+            Some(index) => {
+                selection.remove(index);
+                false
+            }
+            None => {
+                selection.push(name.to_string());
+                true
+            }
+        }
+    });
+    if let Some(button) = buttons.iter_mut().find(|button| button.name == name) {
+        button.set_selected(selected);
+    }
+}
+
+/// Return true if the button named name is selected.
+pub fn is_selected(name: &str) -> bool {
+    SELECTION.with(|selection| selection.borrow().iter().any(|selected| selected == name))
+}
+
+/// Return the selected buttons among buttons, in their order in the dock: the removed ones are skipped.
+pub fn selected(buttons: &[E4Button]) -> Vec<String> {
+    buttons
+        .iter()
+        .filter(|button| is_selected(&button.name))
+        .map(|button| button.name.clone())
+        .collect()
+}
+
+/// Deselect the buttons, showing them as usual.
+pub fn clear(buttons: &mut [E4Button]) {
+    SELECTION.with(|selection| selection.borrow_mut().clear());
+    for button in buttons.iter_mut() {
+        button.set_selected(false);
+    }
+}
@@ -0,0 +1,287 @@
+use configparser::ini::Ini;
+use std::{collections::BTreeMap, iter::Peekable, str::Chars};
+
+/// The format of a button in the clipboard, in the "format" key of its JSON.
+pub const BUTTON_JSON_FORMAT: &str = "e4docker-button";
+
+#[derive(Debug)]
+struct ShareError {
+    details: String,
+}
+
+impl ShareError {
+    fn new(details: &str) -> Box<Self> {
+        Box::new(Self {
+            details: details.to_string(),
+        })
+    }
+}
+
+impl std::fmt::Display for ShareError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for ShareError {}
+
+/// The values of a JSON. The small JSON of a button holds only objects of texts, or of other objects.
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    /// A number, as written.
+    Number(String),
+    Text(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+/// Parse the whole of json, failing on the text after its value.
+pub(crate) fn parse_json(json: &str) -> Result<Json, Box<dyn std::error::Error>> {
+    let mut chars = json.chars().peekable();
+    let root = parse_value(&mut chars)?;
+    skip_whitespace(&mut chars);
+    if chars.next().is_some() {
+        return Err(ShareError::new("Unexpected text after the JSON"));
+    }
+    Ok(root)
+}
+
+/// Return the JSON of the button name, whose configuration file holds ini, to be copied to the clipboard:
+///
+/// ```json
+/// {
+///   "format": "e4docker-button",
+///   "name": "firefox",
+///   "sections": {
+///     "button": {
+///       "command": "/usr/bin/firefox",
+///       "icon": "firefox.png"
+///     }
+///   }
+/// }
+/// ```
+///
+/// The icon is a reference to its file, like in the configuration file.
+pub fn button_to_json(name: &str, ini: &Ini) -> String {
+    // Sorted, to copy the same button in the same way
+    let sections: BTreeMap<_, BTreeMap<_, _>> = ini
+        .get_map_ref()
+        .iter()
+        .map(|(section, keys)| (section, keys.iter().collect()))
+        .collect();
+    let sections: Vec<String> = sections
+        .into_iter()
+        .map(|(section, keys)| {
+            let keys: Vec<String> = keys
+                .into_iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Some(value) => quote(value),
+                        None => "null".to_string(),
+                    };
+                    format!("      {}: {}", quote(key), value)
+                })
+                .collect();
+            format!("    {}: {{\n{}\n    }}", quote(section), keys.join(",\n"))
+        })
+        .collect();
+    format!(
+        "{{\n  \"format\": {},\n  \"name\": {},\n  \"sections\": {{\n{}\n  }}\n}}",
+        quote(BUTTON_JSON_FORMAT),
+        quote(name),
+        sections.join(",\n")
+    )
+}
+
+/// Read the name and the configuration file of a button from the JSON written by [button_to_json].
+pub fn button_from_json(json: &str) -> Result<(String, Ini), Box<dyn std::error::Error>> {
+    let root = match parse_json(json)? {
+        Json::Object(root) => root,
+        _ => return Err(ShareError::new("The JSON of the button is not an object")),
+    };
+    let field = |key: &str| {
+        root.iter()
+            .find(|(name, _)| name == key)
+            .map(|(_, value)| value)
+    };
+
+    match field("format") {
+        Some(Json::Text(format)) if format == BUTTON_JSON_FORMAT => {}
+        _ => return Err(ShareError::new("The clipboard does not hold a button")),
+    }
+    let name = match field("name") {
+        Some(Json::Text(name)) if !name.trim().is_empty() => name.trim().to_string(),
+        _ => return Err(ShareError::new("The button has no name")),
+    };
+    let mut ini = Ini::new();
+    if let Some(Json::Object(sections)) = field("sections") {
+        for (section, keys) in sections {
+            let keys = match keys {
+                Json::Object(keys) => keys,
+                _ => return Err(ShareError::new(&format!("{} is not a section", section))),
+            };
+            for (key, value) in keys {
+                let value = match value {
+                    Json::Text(value) => Some(value.clone()),
+                    Json::Null => None,
+                    _ => return Err(ShareError::new(&format!("{} is not a value", key))),
+                };
+                ini.set(section, key, value);
+            }
+        }
+    }
+    Ok((name, ini))
+}
+
+/// Return text as a JSON string.
+pub(crate) fn quote(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04x}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn skip_whitespace(chars: &mut Peekable<Chars>) {
+    while chars.peek().is_some_and(|c| c.is_whitespace()) {
+        chars.next();
+    }
+}
+
+/// Parse a null, a boolean, a number, a string, an array or an object.
+fn parse_value(chars: &mut Peekable<Chars>) -> Result<Json, Box<dyn std::error::Error>> {
+    skip_whitespace(chars);
+    match chars.peek().copied() {
+        Some('"') => Ok(Json::Text(parse_string(chars)?)),
+        Some('{') => parse_object(chars),
+        Some('[') => parse_array(chars),
+        Some('n') => parse_keyword(chars, "null", Json::Null),
+        Some('t') => parse_keyword(chars, "true", Json::Bool(true)),
+        Some('f') => parse_keyword(chars, "false", Json::Bool(false)),
+        Some(c) if c == '-' || c.is_ascii_digit() => {
+            let mut number = String::new();
+            while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || "+-.eE".contains(*c)) {
+                number.push(c);
+            }
+            match number.parse::<f64>() {
+                Ok(_) => Ok(Json::Number(number)),
+                Err(_) => Err(ShareError::new(&format!(
+                    "Invalid number {} in the JSON",
+                    number
+                ))),
+            }
+        }
+        Some(c) => Err(ShareError::new(&format!("Unexpected {} in the JSON", c))),
+        None => Err(ShareError::new("The JSON ends too early")),
+    }
+}
+
+fn parse_object(chars: &mut Peekable<Chars>) -> Result<Json, Box<dyn std::error::Error>> {
+    chars.next(); // {
+    let mut fields = vec![];
+    skip_whitespace(chars);
+    if chars.peek() == Some(&'}') {
+        chars.next();
+        return Ok(Json::Object(fields));
+    }
+    loop {
+        skip_whitespace(chars);
+        let key = parse_string(chars)?;
+        skip_whitespace(chars);
+        if chars.next() != Some(':') {
+            return Err(ShareError::new("Missing : in the JSON"));
+        }
+        fields.push((key, parse_value(chars)?));
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(',') => continue,
+            Some('}') => return Ok(Json::Object(fields)),
+            _ => return Err(ShareError::new("Missing } in the JSON")),
+        }
+    }
+}
+
+/// Parse word, a keyword of JSON, returning value.
+fn parse_keyword(
+    chars: &mut Peekable<Chars>,
+    word: &str,
+    value: Json,
+) -> Result<Json, Box<dyn std::error::Error>> {
+    if chars.by_ref().take(word.len()).collect::<String>() == word {
+        Ok(value)
+    } else {
+        Err(ShareError::new("Invalid JSON value"))
+    }
+}
+
+fn parse_array(chars: &mut Peekable<Chars>) -> Result<Json, Box<dyn std::error::Error>> {
+    chars.next(); // [
+    let mut values = vec![];
+    skip_whitespace(chars);
+    if chars.peek() == Some(&']') {
+        chars.next();
+        return Ok(Json::Array(values));
+    }
+    loop {
+        values.push(parse_value(chars)?);
+        skip_whitespace(chars);
+        match chars.next() {
+            Some(',') => continue,
+            Some(']') => return Ok(Json::Array(values)),
+            _ => return Err(ShareError::new("Missing ] in the JSON")),
+        }
+    }
+}
+
+fn parse_string(chars: &mut Peekable<Chars>) -> Result<String, Box<dyn std::error::Error>> {
+    if chars.next() != Some('"') {
+        return Err(ShareError::new("Missing \" in the JSON"));
+    }
+    let mut text = String::new();
+    loop {
+        match chars.next() {
+            Some('"') => return Ok(text),
+            Some('\\') => match chars.next() {
+                Some('n') => text.push('\n'),
+                Some('r') => text.push('\r'),
+                Some('t') => text.push('\t'),
+                Some('b') => text.push('\u{8}'),
+                Some('f') => text.push('\u{c}'),
+                Some('u') => text.push(parse_unicode(chars)?),
+                Some(c) => text.push(c),
+                None => return Err(ShareError::new("The JSON ends too early")),
+            },
+            Some(c) => text.push(c),
+            None => return Err(ShareError::new("The JSON ends too early")),
+        }
+    }
+}
+
+/// Parse the 4 hex digits after \u, and the ones of the second half of a surrogate pair.
+fn parse_unicode(chars: &mut Peekable<Chars>) -> Result<char, Box<dyn std::error::Error>> {
+    let hex = |chars: &mut Peekable<Chars>| {
+        let digits: String = chars.by_ref().take(4).collect();
+        u32::from_str_radix(&digits, 16).map_err(|_| ShareError::new("Invalid \\u in the JSON"))
+    };
+    let first = hex(chars)?;
+    let code = if (0xd800..0xdc00).contains(&first) {
+        if chars.next() != Some('\\') || chars.next() != Some('u') {
+            return Err(ShareError::new("Invalid \\u in the JSON"));
+        }
+        let second = hex(chars)?;
+        0x10000 + ((first - 0xd800) << 10) + (second.wrapping_sub(0xdc00) & 0x3ff)
+    } else {
+        first
+    };
+    char::from_u32(code).ok_or_else(|| ShareError::new("Invalid \\u in the JSON").into())
+}
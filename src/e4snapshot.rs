@@ -0,0 +1,114 @@
+use crate::{e4config::E4Error, tr, translations::Translations};
+use fltk::{draw, enums::Color, prelude::*, surface::ImageSurface, window::Window};
+use std::{
+    io::Cursor,
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Return the PNG image of width x height pixels, from their data: channels bytes per pixel, as gray,
+/// gray and alpha, RGB or RGBA.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4snapshot::png_from_pixels;
+///
+/// let png_data = png_from_pixels(&[255, 0, 0, 0, 0, 255], 2, 1, 3).unwrap();
+/// assert!(png_data.starts_with(b"\x89PNG"));
+/// assert!(png_from_pixels(&[255, 0, 0], 2, 1, 3).is_err());
+/// ```
+pub fn png_from_pixels(
+    data: &[u8],
+    width: u32,
+    height: u32,
+    channels: u8,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let color_type = match channels {
+        1 => image::ExtendedColorType::L8,
+        2 => image::ExtendedColorType::La8,
+        3 => image::ExtendedColorType::Rgb8,
+        4 => image::ExtendedColorType::Rgba8,
+        _ => {
+            return Err(Box::new(E4Error::new(&format!(
+                "{} channels per pixel",
+                channels
+            ))))
+        }
+    };
+    if data.len() != width as usize * height as usize * channels as usize {
+        return Err(Box::new(E4Error::new(&format!(
+            "{} bytes for {}x{} pixels",
+            data.len(),
+            width,
+            height
+        ))));
+    }
+    let mut cursor = Cursor::new(vec![]);
+    image::write_buffer_with_format(
+        &mut cursor,
+        data,
+        width,
+        height,
+        color_type,
+        image::ImageFormat::Png,
+    )?;
+    Ok(cursor.into_inner())
+}
+
+/// Draw wind, the dock, offscreen and return it as a PNG image.
+pub fn capture(
+    wind: &Window,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let (width, height) = (wind.w(), wind.h());
+    let surface = ImageSurface::new(width, height, false);
+    ImageSurface::push_current(&surface);
+    draw::set_draw_color(Color::Background);
+    draw::draw_rectf(0, 0, width, height);
+    surface.draw(wind, 0, 0);
+    let image = surface.image();
+    ImageSurface::pop_current();
+
+    let image = match image {
+        Some(image) if width > 0 && height > 0 => image,
+        _ => {
+            return Err(Box::new(E4Error::new(&tr!(
+                translations,
+                get_or_default,
+                "cannot-capture-the-dock",
+                "Cannot capture the dock"
+            ))))
+        }
+    };
+    let data = image.to_rgb_data();
+    let (width, height) = (image.data_w() as u32, image.data_h() as u32);
+    // The surface gives RGB, but the data tells the channels
+    let channels = data.len() / (width as usize * height as usize).max(1);
+    png_from_pixels(&data, width, height, channels as u8)
+}
+
+/// Return path with the .png extension, added if missing.
+pub fn png_path(path: &Path) -> PathBuf {
+    match path.extension().and_then(|extension| extension.to_str()) {
+        Some(extension) if extension.eq_ignore_ascii_case("png") => path.to_path_buf(),
+        _ => {
+            let mut path = path.as_os_str().to_owned();
+            path.push(".png");
+            PathBuf::from(path)
+        }
+    }
+}
+
+/// Save the PNG image of wind, the dock, in path, adding the .png extension if missing.
+/// Return the path written.
+pub fn save(
+    wind: &Window,
+    path: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<PathBuf, Box<dyn std::error::Error>> {
+    let png_data = capture(wind, translations)?;
+    let path = png_path(path);
+    std::fs::write(&path, png_data)?;
+    Ok(path)
+}
@@ -0,0 +1,290 @@
+use configparser::ini::Ini;
+use std::{
+    path::{Path, PathBuf},
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The file, in the configuration directory, holding the runtime state.
+const STATE_FILE: &str = "e4docker.state";
+
+/// The maximum number of recent files remembered for each [crate::e4button::E4Button].
+pub const MAX_RECENT_FILES: usize = 10;
+
+/// The maximum number of texts remembered by the clipboard widget.
+pub const MAX_CLIPBOARD_ENTRIES: usize = 20;
+
+/// The state section holding the clipboard history.
+const CLIPBOARD_SECTION: &str = "CLIPBOARD";
+
+/// The state section holding the launches of all the buttons by hour of the day, in UTC.
+const LAUNCH_HOURS_SECTION: &str = "LAUNCH_HOURS";
+
+/// The days of launches remembered for each [crate::e4button::E4Button], shown when it is hovered.
+pub const ACTIVITY_DAYS: u64 = 14;
+
+/// The runtime state of e4docker (recent files, clipboard history...), kept apart from the configuration files
+/// so that the files edited by the user are not rewritten at every launch.
+pub struct E4State {
+    path: PathBuf,
+    ini: Ini,
+}
+
+impl E4State {
+    /// Load the state from config_dir. A missing state file gives an empty state.
+    pub fn load(config_dir: &Path) -> Self {
+        let path = config_dir.join(STATE_FILE);
+        // Values are paths and free text: keep their case and do not strip ; and #
+        let mut ini = Ini::new_cs();
+        ini.set_inline_comment_symbols(Some(&[]));
+        let _ = ini.load(&path);
+        Self { path, ini }
+    }
+
+    /// Save the state in its file.
+    pub fn save(&self) -> std::io::Result<()> {
+        self.ini.write(&self.path)
+    }
+
+    /// Get a list stored as item1..itemN in section.
+    pub fn get_list(&self, section: &str) -> Vec<String> {
+        let mut values = vec![];
+        while let Some(value) = self.ini.get(section, &format!("item{}", values.len() + 1)) {
+            values.push(unescape(&value));
+        }
+        values
+    }
+
+    /// Replace the list stored in section.
+    pub fn set_list(&mut self, section: &str, values: &[String]) {
+        self.ini.remove_section(section);
+        for (i, value) in values.iter().enumerate() {
+            self.ini
+                .set(section, &format!("item{}", i + 1), Some(escape(value)));
+        }
+    }
+
+    /// Put value at the top of the list stored in section, removing its duplicates and keeping at most max values.
+    pub fn push_to_list(&mut self, section: &str, value: &str, max: usize) {
+        let mut values = self.get_list(section);
+        values.retain(|v| v != value);
+        values.insert(0, value.to_string());
+        values.truncate(max);
+        self.set_list(section, &values);
+    }
+
+    /// Get the files recently opened through a button, the most recent first.
+    pub fn recent_files(&self, button_name: &str) -> Vec<String> {
+        self.get_list(&recent_section(button_name))
+    }
+
+    /// Remember a file opened through a button.
+    pub fn add_recent_file(&mut self, button_name: &str, file: &str) {
+        self.push_to_list(&recent_section(button_name), file, MAX_RECENT_FILES);
+    }
+
+    /// Get the clipboard history, the most recent first.
+    pub fn clipboard_history(&self) -> Vec<String> {
+        self.get_list(CLIPBOARD_SECTION)
+    }
+
+    /// Remember a text copied in the clipboard.
+    pub fn add_clipboard_entry(&mut self, text: &str) {
+        self.push_to_list(CLIPBOARD_SECTION, text, MAX_CLIPBOARD_ENTRIES);
+    }
+
+    /// Forget the clipboard history.
+    pub fn clear_clipboard_history(&mut self) {
+        self.ini.remove_section(CLIPBOARD_SECTION);
+    }
+
+    /// Get the launches of a button in each of the last [ACTIVITY_DAYS] days up to today, the oldest first.
+    /// The days are counted since the Unix epoch: see [today].
+    pub fn launches_per_day(&self, button_name: &str, today: u64) -> Vec<u32> {
+        let section = launches_section(button_name);
+        (today + 1 - ACTIVITY_DAYS.min(today + 1)..=today)
+            .map(|day| {
+                self.ini
+                    .get(&section, &day.to_string())
+                    .and_then(|count| count.parse().ok())
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    /// Count a launch of a button on day, forgetting the days older than [ACTIVITY_DAYS].
+    pub fn add_launch(&mut self, button_name: &str, day: u64) {
+        let section = launches_section(button_name);
+        let launches = self.launches_per_day(button_name, day);
+        self.ini.remove_section(&section);
+        let first_day = day + 1 - launches.len() as u64;
+        for (i, count) in launches.iter().enumerate() {
+            let count = if i + 1 == launches.len() {
+                count + 1
+            } else {
+                *count
+            };
+            if count > 0 {
+                self.ini.set(
+                    &section,
+                    &(first_day + i as u64).to_string(),
+                    Some(count.to_string()),
+                );
+            }
+        }
+    }
+
+    /// Get the launches of all the buttons in each hour of the day, from 0 to 23 in UTC.
+    pub fn launches_per_hour(&self) -> Vec<u32> {
+        (0..24)
+            .map(|hour: u32| {
+                self.ini
+                    .get(LAUNCH_HOURS_SECTION, &hour.to_string())
+                    .and_then(|count| count.parse().ok())
+                    .unwrap_or(0)
+            })
+            .collect()
+    }
+
+    /// Count a launch in hour of the day, from 0 to 23.
+    pub fn add_launch_hour(&mut self, hour: u32) {
+        let count = self
+            .launches_per_hour()
+            .get(hour as usize)
+            .copied()
+            .unwrap_or(0);
+        self.ini.set(
+            LAUNCH_HOURS_SECTION,
+            &hour.to_string(),
+            Some((count + 1).to_string()),
+        );
+    }
+}
+
+/// Escape the backslashes and the line breaks of a value, which must fit in one line.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Restore a value escaped by [escape].
+fn unescape(value: &str) -> String {
+    let mut result = String::new();
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            result.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => result.push('\n'),
+            Some('r') => result.push('\r'),
+            Some(next) => result.push(next),
+            None => result.push('\\'),
+        }
+    }
+    result
+}
+
+/// Return the state section holding the recent files of a button.
+fn recent_section(button_name: &str) -> String {
+    format!("RECENT_{}", button_name)
+}
+
+/// Return the state section holding the launches of a button, by day.
+fn launches_section(button_name: &str) -> String {
+    format!("LAUNCHES_{}", button_name)
+}
+
+/// Return the seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
+
+/// Return the days since the Unix epoch, in UTC.
+pub fn today() -> u64 {
+    now() / 86_400
+}
+
+/// Return the offset of the local time from UTC, in seconds, read from the system once:
+/// 0 if it cannot be read.
+pub fn utc_offset() -> i64 {
+    static UTC_OFFSET: OnceLock<i64> = OnceLock::new();
+    *UTC_OFFSET.get_or_init(|| read_utc_offset().unwrap_or(0))
+}
+
+/// Read the offset of the local time from UTC, in seconds, printed by date as +hhmm or -hhmm.
+#[cfg(not(target_os = "windows"))]
+fn read_utc_offset() -> Option<i64> {
+    let output = std::process::Command::new("date")
+        .arg("+%z")
+        .output()
+        .ok()?;
+    parse_utc_offset(String::from_utf8_lossy(&output.stdout).trim())
+}
+
+/// Read the offset of the local time from UTC, in seconds, from the time zone of Windows.
+#[cfg(target_os = "windows")]
+fn read_utc_offset() -> Option<i64> {
+    /// TIME_ZONE_INFORMATION, the dates being SYSTEMTIMEs of 8 words.
+    #[repr(C)]
+    #[derive(Default)]
+    struct TimeZoneInformation {
+        bias: i32,
+        standard_name: [u16; 32],
+        standard_date: [u16; 8],
+        standard_bias: i32,
+        daylight_name: [u16; 32],
+        daylight_date: [u16; 8],
+        daylight_bias: i32,
+    }
+
+    /// Returned by GetTimeZoneInformation when the daylight saving time is in effect.
+    const TIME_ZONE_ID_DAYLIGHT: u32 = 2;
+    /// Returned by GetTimeZoneInformation when it fails.
+    const TIME_ZONE_ID_INVALID: u32 = 0xFFFF_FFFF;
+
+    #[link(name = "kernel32")]
+    extern "system" {
+        fn GetTimeZoneInformation(information: *mut TimeZoneInformation) -> u32;
+    }
+
+    let mut information = TimeZoneInformation::default();
+    // The bias, in minutes, is added to the local time to get UTC
+    let bias = match unsafe { GetTimeZoneInformation(&mut information) } {
+        TIME_ZONE_ID_INVALID => return None,
+        TIME_ZONE_ID_DAYLIGHT => information.bias + information.daylight_bias,
+        _ => information.bias + information.standard_bias,
+    };
+    Some(-(bias as i64) * 60)
+}
+
+/// Return the seconds of an offset from UTC written as +hhmm or -hhmm.
+pub fn parse_utc_offset(offset: &str) -> Option<i64> {
+    let (sign, digits) = match offset.split_at_checked(1)? {
+        ("+", digits) => (1, digits),
+        ("-", digits) => (-1, digits),
+        _ => return None,
+    };
+    if digits.len() != 4 || !digits.bytes().all(|digit| digit.is_ascii_digit()) {
+        return None;
+    }
+    let hours: i64 = digits[..2].parse().ok()?;
+    let minutes: i64 = digits[2..].parse().ok()?;
+    Some(sign * (hours * 3_600 + minutes * 60))
+}
+
+/// Count a launch of a button today, remember the files it opened and save the state.
+pub fn add_launch(config_dir: &Path, button_name: &str, files: &[String]) -> std::io::Result<()> {
+    let mut state = E4State::load(config_dir);
+    state.add_launch(button_name, today());
+    state.add_launch_hour((now() % 86_400 / 3_600) as u32);
+    for file in files {
+        state.add_recent_file(button_name, file);
+    }
+    state.save()
+}
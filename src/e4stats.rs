@@ -0,0 +1,197 @@
+use crate::{
+    e4button::E4Button,
+    e4config::E4Config,
+    e4state::{self, E4State, ACTIVITY_DAYS},
+    tr,
+    translations::Translations,
+};
+use fltk::{app, browser::Browser, button::Button, prelude::*, window::Window};
+use std::{
+    cell::Cell,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+/// The most active hours shown in the report.
+const MOST_ACTIVE_HOURS: usize = 3;
+
+/// The usage of the dock, from the launches counted in the state.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct E4Stats {
+    /// The buttons with their launches in the last [ACTIVITY_DAYS] days, the most launched first.
+    pub launches: Vec<(String, u32)>,
+    /// The launches of all the buttons in each hour of the day, from 0 to 23 in the local time.
+    pub hours: Vec<u32>,
+}
+
+impl E4Stats {
+    /// Read the usage of buttons, in the order of the dock, from state up to today: see [e4state::today].
+    /// The hours of the launches, counted in UTC, are moved to the local time by utc_offset, in seconds:
+    /// see [e4state::utc_offset].
+    pub fn new(state: &E4State, buttons: &[String], today: u64, utc_offset: i64) -> Self {
+        let mut launches: Vec<(String, u32)> = buttons
+            .iter()
+            .map(|name| {
+                let count = state.launches_per_day(name, today).iter().sum();
+                (name.clone(), count)
+            })
+            .collect();
+        // Stable: the buttons launched as often stay in the order of the dock
+        launches.sort_by(|(_, a), (_, b)| b.cmp(a));
+        // With an offset of half an hour, a local hour counts the UTC hour starting half an hour before it
+        let utc_hours = state.launches_per_hour();
+        let hours = (0..24)
+            .map(|hour: i64| {
+                let utc_hour = (hour * 3_600 - utc_offset).rem_euclid(86_400) / 3_600;
+                utc_hours[utc_hour as usize]
+            })
+            .collect();
+        Self { launches, hours }
+    }
+
+    /// Return at most count hours with launches, the most active first.
+    pub fn most_active_hours(&self, count: usize) -> Vec<u32> {
+        let mut hours: Vec<u32> = (0..self.hours.len() as u32)
+            .filter(|hour| self.hours[*hour as usize] > 0)
+            .collect();
+        hours.sort_by(|a, b| self.hours[*b as usize].cmp(&self.hours[*a as usize]));
+        hours.truncate(count);
+        hours
+    }
+
+    /// Return the buttons never launched in the last [ACTIVITY_DAYS] days, in the order of the dock.
+    pub fn unused(&self) -> Vec<String> {
+        self.launches
+            .iter()
+            .filter(|(_, count)| *count == 0)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
+/// Show the usage of buttons, the buttons of config launching a command, and offer to remove the unused ones.
+/// read_only disables the removal.
+pub fn show(
+    config: &mut E4Config,
+    buttons: &[String],
+    read_only: bool,
+    translations: Arc<Mutex<Translations>>,
+) {
+    let stats = E4Stats::new(
+        &E4State::load(&config.config_dir),
+        buttons,
+        e4state::today(),
+        e4state::utc_offset(),
+    );
+    let days = ACTIVITY_DAYS.to_string();
+    let none = tr!(translations, get_or_default, "none", "none");
+
+    let mut window = Window::default().with_size(400, 450).with_label(&tr!(
+        translations,
+        get_or_default,
+        "statistics",
+        "Statistics"
+    ));
+    let mut browser = Browser::new(10, 10, 380, 385, None);
+    // The names are shown as they are, without the @ formatting of the browser
+    browser.set_format_char('\0');
+    browser.set_column_char('\t');
+    browser.set_column_widths(&[300, 60]);
+    browser.add(&tr!(
+        translations,
+        format,
+        "launches-in-the-last-days",
+        &[&days]
+    ));
+    for (name, count) in &stats.launches {
+        browser.add(&format!("    {}\t{}", name, count));
+    }
+    browser.add("");
+    browser.add(&tr!(
+        translations,
+        get_or_default,
+        "most-active-hours",
+        "Most active hours"
+    ));
+    let hours = stats.most_active_hours(MOST_ACTIVE_HOURS);
+    if hours.is_empty() {
+        browser.add(&format!("    {}", none));
+    }
+    for hour in hours {
+        browser.add(&format!(
+            "    {:02}:00 - {:02}:00\t{}",
+            hour,
+            (hour + 1) % 24,
+            stats.hours[hour as usize]
+        ));
+    }
+    browser.add("");
+    browser.add(&tr!(translations, format, "never-used-buttons", &[&days]));
+    let unused = stats.unused();
+    if unused.is_empty() {
+        browser.add(&format!("    {}", none));
+    }
+    for name in &unused {
+        browser.add(&format!("    {}", name));
+    }
+
+    let remove = Rc::new(Cell::new(false));
+    let mut remove_button = Button::new(90, 405, 150, 30, None);
+    remove_button.set_label(&tr!(
+        translations,
+        get_or_default,
+        "remove-unused-buttons",
+        "Remove unused"
+    ));
+    if unused.is_empty() || read_only {
+        remove_button.deactivate();
+    }
+    remove_button.set_callback({
+        let mut window = window.clone();
+        let remove = remove.clone();
+        move |_| {
+            remove.set(true);
+            window.hide();
+        }
+    });
+    let mut close_button = Button::new(250, 405, 100, 30, None);
+    close_button.set_label(&tr!(translations, get_or_default, "close", "Close"));
+    close_button.set_callback({
+        let mut window = window.clone();
+        move |_| window.hide()
+    });
+    crate::e4dialog::set_keys(
+        &mut window,
+        &mut close_button,
+        || false,
+        || {},
+        translations.clone(),
+    );
+
+    window.make_modal(true);
+    window.end();
+    crate::e4screen::place_dialog(&mut window);
+    window.show();
+    // Run modal window
+    while window.shown() {
+        app::wait();
+    }
+    if !remove.get() {
+        return;
+    }
+    let message = tr!(
+        translations,
+        format,
+        "confirm-remove-unused-buttons",
+        &[&unused.len().to_string(), &days]
+    );
+    let choice = fltk::dialog::choice2_default(
+        &message,
+        &tr!(translations, get_or_default, "cancel", "Cancel"),
+        &tr!(translations, get_or_default, "delete", "Delete"),
+        "",
+    );
+    if choice == Some(1) {
+        E4Button::delete_all(&unused, config, translations);
+    }
+}
@@ -0,0 +1,347 @@
+use configparser::ini::Ini;
+use std::{
+    collections::{HashMap, HashSet},
+    io::{Error, ErrorKind},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+};
+
+/// Where the configuration files are read and written: the file system, or the memory in the tests.
+pub trait E4Storage: Send + Sync {
+    /// Read the content of the file in path.
+    fn read_to_string(&self, path: &Path) -> std::io::Result<String>;
+
+    /// Write contents in the file in path, replacing it.
+    fn write(&self, path: &Path, contents: &str) -> std::io::Result<()>;
+
+    /// Remove the file in path.
+    fn remove(&self, path: &Path) -> std::io::Result<()>;
+
+    /// Return true if the file in path exists.
+    fn exists(&self, path: &Path) -> bool;
+
+    /// Load the ini file in path.
+    fn load_ini(&self, path: &Path) -> Result<Ini, String> {
+        let contents = self.read_to_string(path).map_err(|e| e.to_string())?;
+        let mut ini = Ini::new();
+        ini.read(contents)?;
+        Ok(ini)
+    }
+
+    /// Write ini in the file in path.
+    /// The comments and the order of the keys of the file, if it exists, are kept: see [merge_ini].
+    fn write_ini(&self, path: &Path, ini: &Ini) -> std::io::Result<()> {
+        let contents = match self.read_to_string(path) {
+            Ok(original) => merge_ini(&original, ini),
+            Err(_) => ini.writes(),
+        };
+        self.write(path, &contents)
+    }
+}
+
+/// Return the ini file original with the sections and the values of ini.
+///
+/// The comments, the blank lines and the order of the keys of original are kept. The keys and the sections
+/// missing in ini are removed; the new keys are added after the last key of their section and the new
+/// sections at the end.
+pub fn merge_ini(original: &str, ini: &Ini) -> String {
+    let defaults = ini.defaults();
+    let normalize = |name: &str| {
+        let name = name.trim();
+        if defaults.case_sensitive {
+            name.to_string()
+        } else {
+            name.to_lowercase()
+        }
+    };
+    let map = ini.get_map_ref();
+
+    let mut lines: Vec<String> = vec![];
+    // The keys written, by section
+    let mut written: HashMap<String, HashSet<String>> = HashMap::new();
+    // The keys before the first section header belong to the default section
+    let mut section = defaults.default_section.clone();
+    written.insert(section.clone(), HashSet::new());
+    // Where the new keys of the section go: after its last key
+    let mut section_end = 0;
+    // True in a section missing in ini, whose lines are removed
+    let mut removed = false;
+
+    for line in original.lines() {
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') && trimmed.ends_with(']') {
+            if !removed {
+                insert_new_keys(
+                    &mut lines,
+                    section_end,
+                    map.get(&section),
+                    &written[&section],
+                );
+            }
+            section = normalize(&trimmed[1..trimmed.len() - 1]);
+            removed = !map.contains_key(&section);
+            if !removed {
+                written.entry(section.clone()).or_default();
+                lines.push(line.to_string());
+            }
+            section_end = lines.len();
+            continue;
+        }
+        if removed {
+            continue;
+        }
+        if trimmed.is_empty()
+            || defaults
+                .comment_symbols
+                .iter()
+                .any(|symbol| trimmed.starts_with(*symbol))
+        {
+            lines.push(line.to_string());
+            continue;
+        }
+
+        let delimiter = line.find(|c| defaults.delimiters.contains(&c));
+        let key = normalize(match delimiter {
+            Some(i) => &line[..i],
+            None => line,
+        });
+        let keys = written.entry(section.clone()).or_default();
+        // A key missing in ini is removed, a repeated one is written once
+        if let Some(value) = map.get(&section).and_then(|values| values.get(&key)) {
+            if keys.insert(key) {
+                lines.push(merge_line(line, delimiter, value));
+                section_end = lines.len();
+            }
+        }
+    }
+    if !removed {
+        insert_new_keys(
+            &mut lines,
+            section_end,
+            map.get(&section),
+            &written[&section],
+        );
+    }
+
+    // The new sections
+    let mut new_sections: Vec<&String> = map
+        .keys()
+        .filter(|section| !written.contains_key(*section))
+        .collect();
+    new_sections.sort_by_key(|section| natural_order(section));
+    for section in new_sections {
+        lines.push(format!("[{}]", section));
+        let end = lines.len();
+        insert_new_keys(&mut lines, end, map.get(section), &HashSet::new());
+    }
+
+    let mut contents = lines.join("\n");
+    contents.push('\n');
+    contents
+}
+
+/// Return line, the key of an ini file separated by delimiter from its value, with value.
+/// The line is kept as is if the value did not change.
+fn merge_line(line: &str, delimiter: Option<usize>, value: &Option<String>) -> String {
+    match (delimiter, value) {
+        (Some(i), Some(value)) => {
+            let old_value = &line[i + 1..];
+            if old_value.trim() == value {
+                return line.to_string();
+            }
+            // Keep the spaces after the delimiter
+            let spaces = old_value.len() - old_value.trim_start().len();
+            format!("{}{}", &line[..i + 1 + spaces], value)
+        }
+        (Some(i), None) => line[..i].trim_end().to_string(),
+        (None, Some(value)) => format!("{}={}", line.trim_end(), value),
+        (None, None) => line.to_string(),
+    }
+}
+
+/// Insert in lines at index the keys of values not written yet.
+fn insert_new_keys(
+    lines: &mut Vec<String>,
+    index: usize,
+    values: Option<&HashMap<String, Option<String>>>,
+    written: &HashSet<String>,
+) {
+    let values = match values {
+        Some(values) => values,
+        None => return,
+    };
+    let mut new_keys: Vec<&String> = values
+        .keys()
+        .filter(|key| !written.contains(*key))
+        .collect();
+    new_keys.sort_by_key(|key| natural_order(key));
+    let new_lines = new_keys.into_iter().map(|key| match &values[key] {
+        Some(value) => format!("{}={}", key, value),
+        None => key.to_string(),
+    });
+    lines.splice(index..index, new_lines);
+}
+
+/// Return the sort key of a name putting button2 before button10.
+fn natural_order(name: &str) -> (&str, u64) {
+    let prefix = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    (prefix, name[prefix.len()..].parse().unwrap_or(0))
+}
+
+/// The configuration files on the file system.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FsStorage;
+
+impl E4Storage for FsStorage {
+    fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> std::io::Result<()> {
+        // Written beside it and renamed: an interrupted write leaves the file as it was
+        let mut temp = path.as_os_str().to_owned();
+        temp.push(".tmp");
+        std::fs::write(&temp, contents)?;
+        std::fs::rename(&temp, path).inspect_err(|_| {
+            let _ = std::fs::remove_file(&temp);
+        })
+    }
+
+    fn remove(&self, path: &Path) -> std::io::Result<()> {
+        std::fs::remove_file(path)
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        path.exists()
+    }
+}
+
+/// The configuration files in memory, to test the configuration without touching the real one.
+#[derive(Debug, Default)]
+pub struct MemoryStorage {
+    files: Mutex<HashMap<PathBuf, String>>,
+}
+
+impl MemoryStorage {
+    /// Create a storage with no files.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the file path with contents.
+    pub fn with_file<P: AsRef<Path>>(self, path: P, contents: &str) -> Self {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(path.as_ref().to_path_buf(), contents.to_string());
+        self
+    }
+
+    /// Return the paths of the files, sorted.
+    pub fn paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.files.lock().unwrap().keys().cloned().collect();
+        paths.sort();
+        paths
+    }
+}
+
+impl E4Storage for MemoryStorage {
+    fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
+        match self.files.lock().unwrap().get(path) {
+            Some(contents) => Ok(contents.clone()),
+            None => Err(Error::new(
+                ErrorKind::NotFound,
+                format!("{} not found", path.display()),
+            )),
+        }
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> std::io::Result<()> {
+        self.files
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), contents.to_string());
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> std::io::Result<()> {
+        match self.files.lock().unwrap().remove(path) {
+            Some(_) => Ok(()),
+            None => Err(Error::new(
+                ErrorKind::NotFound,
+                format!("{} not found", path.display()),
+            )),
+        }
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        self.files.lock().unwrap().contains_key(path)
+    }
+}
+
+/// The configuration files of another storage, written in memory: a dry run reads them as they would be
+/// without changing them.
+pub struct DryRunStorage {
+    storage: Arc<dyn E4Storage>,
+    /// The files written, or None for the removed ones.
+    changes: Mutex<HashMap<PathBuf, Option<String>>>,
+}
+
+impl DryRunStorage {
+    /// Create a storage reading the files of storage.
+    pub fn new(storage: Arc<dyn E4Storage>) -> Self {
+        Self {
+            storage,
+            changes: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Return the paths of the files written or removed, sorted.
+    pub fn changed_paths(&self) -> Vec<PathBuf> {
+        let mut paths: Vec<PathBuf> = self.changes.lock().unwrap().keys().cloned().collect();
+        paths.sort();
+        paths
+    }
+}
+
+impl E4Storage for DryRunStorage {
+    fn read_to_string(&self, path: &Path) -> std::io::Result<String> {
+        match self.changes.lock().unwrap().get(path) {
+            Some(Some(contents)) => Ok(contents.clone()),
+            Some(None) => Err(Error::new(
+                ErrorKind::NotFound,
+                format!("{} not found", path.display()),
+            )),
+            None => self.storage.read_to_string(path),
+        }
+    }
+
+    fn write(&self, path: &Path, contents: &str) -> std::io::Result<()> {
+        self.changes
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), Some(contents.to_string()));
+        Ok(())
+    }
+
+    fn remove(&self, path: &Path) -> std::io::Result<()> {
+        if !self.exists(path) {
+            return Err(Error::new(
+                ErrorKind::NotFound,
+                format!("{} not found", path.display()),
+            ));
+        }
+        self.changes
+            .lock()
+            .unwrap()
+            .insert(path.to_path_buf(), None);
+        Ok(())
+    }
+
+    fn exists(&self, path: &Path) -> bool {
+        match self.changes.lock().unwrap().get(path) {
+            Some(contents) => contents.is_some(),
+            None => self.storage.exists(path),
+        }
+    }
+}
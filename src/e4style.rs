@@ -0,0 +1,855 @@
+use crate::e4config::{button_file, E4Config, BUTTON_BUTTON_SECTION, E4DOCKER_DOCKER_SECTION};
+use configparser::ini::Ini;
+use fltk::{
+    app,
+    enums::{Color, Font, FrameType, Key},
+};
+use image::{imageops, Rgba, RgbaImage};
+use std::io::Cursor;
+
+/// The keys of the style in the E4DOCKER section of e4docker.conf.
+const FRAME_STYLE: &str = "FRAME_STYLE";
+const SHOW_FRAME: &str = "SHOW_FRAME";
+const WINDOW_BORDER: &str = "WINDOW_BORDER";
+const ALIGNMENT: &str = "ALIGNMENT";
+const MIN_WIDTH: &str = "MIN_WIDTH";
+const SCALE: &str = "SCALE";
+const HOVER_LABEL: &str = "HOVER_LABEL";
+const HOVER_LABEL_DELAY: &str = "HOVER_LABEL_DELAY";
+const HOVER_LABEL_FONT: &str = "HOVER_LABEL_FONT";
+const HOVER_LABEL_FONT_SIZE: &str = "HOVER_LABEL_FONT_SIZE";
+const HOVER_PREVIEW: &str = "HOVER_PREVIEW";
+const ICON_TINT: &str = "ICON_TINT";
+const ICON_CORNER_RADIUS: &str = "ICON_CORNER_RADIUS";
+const ICON_SHADOW: &str = "ICON_SHADOW";
+const ANIMATE_ICONS: &str = "ANIMATE_ICONS";
+const REVEAL_EDGE: &str = "REVEAL_EDGE";
+const AUTOHIDE: &str = "AUTOHIDE";
+const RESERVE_SPACE: &str = "RESERVE_SPACE";
+pub(crate) const MOVE_MODIFIER: &str = "MOVE_MODIFIER";
+const QUICK_LAUNCH_MODIFIER: &str = "QUICK_LAUNCH_MODIFIER";
+const QUICK_LAUNCH_OVERLAY: &str = "QUICK_LAUNCH_OVERLAY";
+const NEW_INSTANCE_MODIFIER: &str = "NEW_INSTANCE_MODIFIER";
+const HOVER_COLOR: &str = "HOVER_COLOR";
+const PRESSED_COLOR: &str = "PRESSED_COLOR";
+const THEME: &str = "THEME";
+const DIALOG_POSITION: &str = "DIALOG_POSITION";
+
+/// The keys of the accent in the BUTTON section of the configuration file of a button.
+const ACCENT_COLOR: &str = "ACCENT_COLOR";
+const ACCENT_PLATE: &str = "ACCENT_PLATE";
+
+/// The size of the fonts of fltk, scaled by [E4Style::scale].
+const DEFAULT_FONT_SIZE: i32 = 14;
+
+/// The largest side of the previews of the windows, scaled by [E4Style::scale].
+const PREVIEW_SIZE: i32 = 200;
+
+/// The margin left to the shadow of the icons, as a fraction of their shorter side.
+const SHADOW_MARGIN: f64 = 0.08;
+
+/// The opacity of the shadow of the icons under an opaque pixel.
+const SHADOW_OPACITY: f64 = 0.4;
+
+/// How much of the selection color of the theme is mixed with its background for a hovered and a pressed button.
+const HOVER_WEIGHT: f32 = 0.25;
+const PRESSED_WEIGHT: f32 = 0.5;
+
+#[derive(Debug)]
+struct StyleError {
+    details: String,
+}
+
+impl std::fmt::Display for StyleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for StyleError {}
+
+/// The box of the frame around the buttons.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum E4FrameStyle {
+    /// A sunken border: the default.
+    #[default]
+    Engraved,
+    /// A flat box, without a border.
+    Flat,
+    /// No box: the window shows through.
+    None,
+}
+
+impl E4FrameStyle {
+    /// All the frame styles.
+    pub const ALL: [E4FrameStyle; 3] = [
+        E4FrameStyle::Engraved,
+        E4FrameStyle::Flat,
+        E4FrameStyle::None,
+    ];
+
+    /// Return the name of the style in e4docker.conf.
+    pub fn name(&self) -> &'static str {
+        match self {
+            E4FrameStyle::Engraved => "engraved",
+            E4FrameStyle::Flat => "flat",
+            E4FrameStyle::None => "none",
+        }
+    }
+
+    /// Return the style named name, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|style| style.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Return the fltk [FrameType] drawing the style.
+    pub fn frame_type(&self) -> FrameType {
+        match self {
+            E4FrameStyle::Engraved => FrameType::EngravedBox,
+            E4FrameStyle::Flat => FrameType::FlatBox,
+            E4FrameStyle::None => FrameType::NoBox,
+        }
+    }
+}
+
+/// Where the buttons are placed in the frame, when the window is wider than them.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum E4Alignment {
+    /// Packed on the left: the default.
+    #[default]
+    Start,
+    /// Packed in the middle.
+    Center,
+    /// Packed on the right.
+    End,
+    /// The first on the left, the last on the right and the others evenly spaced between them.
+    SpaceBetween,
+}
+
+impl E4Alignment {
+    /// All the alignments.
+    pub const ALL: [E4Alignment; 4] = [
+        E4Alignment::Start,
+        E4Alignment::Center,
+        E4Alignment::End,
+        E4Alignment::SpaceBetween,
+    ];
+
+    /// Return the name of the alignment in e4docker.conf.
+    pub fn name(&self) -> &'static str {
+        match self {
+            E4Alignment::Start => "start",
+            E4Alignment::Center => "center",
+            E4Alignment::End => "end",
+            E4Alignment::SpaceBetween => "space-between",
+        }
+    }
+
+    /// Return the alignment named name, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|alignment| alignment.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// The colors of the dock: light, dark or following the preference of the system.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum E4Theme {
+    /// The colors of fltk: the default.
+    #[default]
+    Light,
+    Dark,
+    /// Light or dark as the system prefers, switching when the preference changes.
+    Auto,
+}
+
+impl E4Theme {
+    /// All the themes.
+    pub const ALL: [E4Theme; 3] = [E4Theme::Light, E4Theme::Dark, E4Theme::Auto];
+
+    /// Return the name of the theme in e4docker.conf.
+    pub fn name(&self) -> &'static str {
+        match self {
+            E4Theme::Light => "light",
+            E4Theme::Dark => "dark",
+            E4Theme::Auto => "auto",
+        }
+    }
+
+    /// Return the theme named name, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|theme| theme.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// Where the dialogs, like Settings or the edit of a button, are opened.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum E4DialogPosition {
+    /// Where fltk opens them: the default.
+    #[default]
+    Default,
+    /// Next to the dock, above or below it.
+    Beside,
+    /// Centered over the dock.
+    Center,
+}
+
+impl E4DialogPosition {
+    /// All the positions.
+    pub const ALL: [E4DialogPosition; 3] = [
+        E4DialogPosition::Default,
+        E4DialogPosition::Beside,
+        E4DialogPosition::Center,
+    ];
+
+    /// Return the name of the position in e4docker.conf.
+    pub fn name(&self) -> &'static str {
+        match self {
+            E4DialogPosition::Default => "default",
+            E4DialogPosition::Beside => "beside",
+            E4DialogPosition::Center => "center",
+        }
+    }
+
+    /// Return the position named name, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|position| position.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// The edge or the corner of the screen revealing the hidden dock when the pointer touches it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum E4Edge {
+    Top,
+    /// The bottom edge: the default.
+    #[default]
+    Bottom,
+    Left,
+    Right,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+impl E4Edge {
+    /// All the edges and the corners.
+    pub const ALL: [E4Edge; 8] = [
+        E4Edge::Top,
+        E4Edge::Bottom,
+        E4Edge::Left,
+        E4Edge::Right,
+        E4Edge::TopLeft,
+        E4Edge::TopRight,
+        E4Edge::BottomLeft,
+        E4Edge::BottomRight,
+    ];
+
+    /// Return the name of the edge in e4docker.conf.
+    pub fn name(&self) -> &'static str {
+        match self {
+            E4Edge::Top => "top",
+            E4Edge::Bottom => "bottom",
+            E4Edge::Left => "left",
+            E4Edge::Right => "right",
+            E4Edge::TopLeft => "top-left",
+            E4Edge::TopRight => "top-right",
+            E4Edge::BottomLeft => "bottom-left",
+            E4Edge::BottomRight => "bottom-right",
+        }
+    }
+
+    /// Return the edge named name, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|edge| edge.name().eq_ignore_ascii_case(name.trim()))
+    }
+}
+
+/// A key held with a drag or another key: the one held to move the dock by dragging its background,
+/// as the menu bar always moves it as a grip, the one held with a number to launch a button, or the one held
+/// with a click to launch a new instance of a single instance button.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum E4Modifier {
+    /// No key is held: the default.
+    #[default]
+    None,
+    Alt,
+    Ctrl,
+    Shift,
+}
+
+impl E4Modifier {
+    /// All the modifiers.
+    pub const ALL: [E4Modifier; 4] = [
+        E4Modifier::None,
+        E4Modifier::Alt,
+        E4Modifier::Ctrl,
+        E4Modifier::Shift,
+    ];
+
+    /// Return the name of the modifier in e4docker.conf.
+    pub fn name(&self) -> &'static str {
+        match self {
+            E4Modifier::None => "none",
+            E4Modifier::Alt => "alt",
+            E4Modifier::Ctrl => "ctrl",
+            E4Modifier::Shift => "shift",
+        }
+    }
+
+    /// Return the modifier named name, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|modifier| modifier.name().eq_ignore_ascii_case(name.trim()))
+    }
+
+    /// Return true if key is the modifier itself, on the left or on the right of the keyboard.
+    pub fn is_key(&self, key: Key) -> bool {
+        match self {
+            E4Modifier::None => false,
+            E4Modifier::Alt => key == Key::AltL || key == Key::AltR,
+            E4Modifier::Ctrl => key == Key::ControlL || key == Key::ControlR,
+            E4Modifier::Shift => key == Key::ShiftL || key == Key::ShiftR,
+        }
+    }
+
+    /// Return true if the modifier is held during the current event of fltk.
+    pub fn is_held(&self) -> bool {
+        match self {
+            E4Modifier::None => true,
+            E4Modifier::Alt => app::is_event_alt(),
+            E4Modifier::Ctrl => app::is_event_ctrl(),
+            E4Modifier::Shift => app::is_event_shift(),
+        }
+    }
+}
+
+/// Return the red, green and blue components of a color written like #3daee9.
+pub fn parse_color(name: &str) -> Option<(u8, u8, u8)> {
+    let hex = name.trim().strip_prefix('#')?;
+    if hex.len() != 6 || !hex.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let component = |i: usize| u8::from_str_radix(&hex[i..i + 2], 16).ok();
+    Some((component(0)?, component(2)?, component(4)?))
+}
+
+/// The accent of a button, telling it apart from the buttons with similar icons, read from its configuration file:
+///
+/// ```ini
+/// [button]
+/// command=/usr/bin/firefox
+/// accent_color=#e66000
+/// accent_plate=true
+/// ```
+///
+/// The accent color is the color of the indicator of the running program and, with accent_plate,
+/// of a plate behind the icon. A button without a valid accent_color has no accent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct E4Accent {
+    /// The red, green and blue components of the accent color.
+    pub color: (u8, u8, u8),
+    /// True to draw a plate of a light shade of the color behind the icon.
+    pub plate: bool,
+}
+
+impl E4Accent {
+    /// Read the accent of the button button_name, if it has one.
+    pub fn read(config: &E4Config, button_name: &str) -> Option<Self> {
+        let ini = config
+            .storage
+            .load_ini(&button_file(&config.config_dir, button_name))
+            .ok()?;
+        let color = parse_color(&ini.get(BUTTON_BUTTON_SECTION, ACCENT_COLOR)?)?;
+        let plate = ini
+            .getbool(BUTTON_BUTTON_SECTION, ACCENT_PLATE)
+            .ok()
+            .flatten()
+            .unwrap_or(false);
+        Some(Self { color, plate })
+    }
+
+    /// Return the accent color.
+    pub fn color(&self) -> Color {
+        let (r, g, b) = self.color;
+        Color::from_rgb(r, g, b)
+    }
+
+    /// Return the accent color mixed with white: weight is the share of the accent color, from 0 to 1.
+    fn shade(&self, weight: f64) -> Color {
+        let (r, g, b) = self.color;
+        let light =
+            |component: u8| (component as f64 * weight + 255.0 * (1.0 - weight)).round() as u8;
+        Color::from_rgb(light(r), light(g), light(b))
+    }
+
+    /// Return the color of the plate: the accent color mixed with white, light enough for the icon to stand out.
+    pub fn plate_color(&self) -> Color {
+        self.shade(0.4)
+    }
+}
+
+/// The colors of the background of a button: at rest, under the mouse and pressed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct E4ButtonColors {
+    pub normal: Color,
+    pub hover: Color,
+    pub pressed: Color,
+}
+
+/// How the icons are recolored when they are loaded, for a uniform look.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum E4IconTint {
+    /// The icons keep their colors: the default.
+    #[default]
+    None,
+    /// The icons are turned to grays, keeping their transparency.
+    Monochrome,
+    /// The icons are turned to shades of a color, keeping their transparency.
+    Color(u8, u8, u8),
+}
+
+impl E4IconTint {
+    /// Return the name of the tint in e4docker.conf: none, monochrome or a color like #3daee9.
+    pub fn name(&self) -> String {
+        match self {
+            E4IconTint::None => "none".to_string(),
+            E4IconTint::Monochrome => "monochrome".to_string(),
+            E4IconTint::Color(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        }
+    }
+
+    /// Return the tint named name, ignoring the case.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = name.trim();
+        if name.eq_ignore_ascii_case("none") || name.is_empty() {
+            return Some(E4IconTint::None);
+        }
+        if name.eq_ignore_ascii_case("monochrome") {
+            return Some(E4IconTint::Monochrome);
+        }
+        let (r, g, b) = parse_color(name)?;
+        Some(E4IconTint::Color(r, g, b))
+    }
+
+    /// Return the PNG image in png_data recolored by the tint.
+    /// The shades follow the brightness of the pixels: from half the tint for black to the tint for white.
+    pub fn apply(&self, png_data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        if *self == E4IconTint::None {
+            return Ok(png_data.to_vec());
+        }
+        let mut image = image::load_from_memory(png_data)?.to_rgba8();
+        for pixel in image.pixels_mut() {
+            let [r, g, b, a] = pixel.0;
+            let brightness = (0.299 * r as f64 + 0.587 * g as f64 + 0.114 * b as f64) / 255.0;
+            let shade = |tint: u8| (tint as f64 * (0.5 + brightness / 2.0)).round() as u8;
+            pixel.0 = match self {
+                E4IconTint::Color(tr, tg, tb) => [shade(*tr), shade(*tg), shade(*tb), a],
+                _ => {
+                    let gray = (brightness * 255.0).round() as u8;
+                    [gray, gray, gray, a]
+                }
+            };
+        }
+        let mut cursor = Cursor::new(vec![]);
+        image.write_to(&mut cursor, image::ImageFormat::Png)?;
+        Ok(cursor.into_inner())
+    }
+}
+
+/// The rounded corners and the drop shadow given to the icons when they are loaded, for a uniform look.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct E4IconEffects {
+    /// The radius of the corners, as a fraction of the shorter side of the icon: 0 keeps the corners,
+    /// 0.5 makes the icon round.
+    pub corner_radius: f64,
+    /// True to draw a soft shadow under the icon, shrunk to leave it room.
+    pub shadow: bool,
+}
+
+impl E4IconEffects {
+    /// Return the PNG image in png_data with the effects, of the same size.
+    pub fn apply(&self, png_data: &[u8]) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+        if *self == E4IconEffects::default() {
+            return Ok(png_data.to_vec());
+        }
+        let mut image = image::load_from_memory(png_data)?.to_rgba8();
+        if self.corner_radius > 0.0 {
+            round_corners(&mut image, self.corner_radius.min(0.5));
+        }
+        if self.shadow {
+            image = drop_shadow(&image);
+        }
+        let mut cursor = Cursor::new(vec![]);
+        image.write_to(&mut cursor, image::ImageFormat::Png)?;
+        Ok(cursor.into_inner())
+    }
+}
+
+/// Make the pixels of image outside the rounded corners, of radius times its shorter side, transparent.
+/// The pixels on the edge of a corner keep a part of their opacity, smoothing it.
+fn round_corners(image: &mut RgbaImage, radius: f64) {
+    let (width, height) = (image.width() as f64, image.height() as f64);
+    let radius = radius * width.min(height);
+    for (x, y, pixel) in image.enumerate_pixels_mut() {
+        // The distance of the center of the pixel from the center of the nearest corner, outside the inner rectangle
+        let outside = |v: u32, size: f64| {
+            let v = v as f64 + 0.5;
+            (radius - v).max(v - (size - radius)).max(0.0)
+        };
+        let (dx, dy) = (outside(x, width), outside(y, height));
+        if dx == 0.0 || dy == 0.0 {
+            continue;
+        }
+        let coverage = (radius - (dx * dx + dy * dy).sqrt() + 0.5).clamp(0.0, 1.0);
+        pixel.0[3] = (pixel.0[3] as f64 * coverage).round() as u8;
+    }
+}
+
+/// Return image shrunk over a blurred shadow of itself, a little below it, of the same size.
+fn drop_shadow(image: &RgbaImage) -> RgbaImage {
+    let (width, height) = image.dimensions();
+    let margin = ((width.min(height) as f64 * SHADOW_MARGIN).round() as u32).max(1);
+    if width <= 2 * margin || height <= 2 * margin {
+        return image.clone();
+    }
+    let icon = imageops::resize(
+        image,
+        width - 2 * margin,
+        height - 2 * margin,
+        imageops::FilterType::Triangle,
+    );
+    let mut shadow = RgbaImage::new(width, height);
+    for (x, y, pixel) in icon.enumerate_pixels() {
+        let alpha = (pixel.0[3] as f64 * SHADOW_OPACITY).round() as u8;
+        // The light comes from above
+        if let Some(below) = shadow.get_pixel_mut_checked(x + margin, y + margin + margin / 2) {
+            *below = Rgba([0, 0, 0, alpha]);
+        }
+    }
+    let mut result = imageops::blur(&shadow, margin as f32 / 2.0);
+    imageops::overlay(&mut result, &icon, margin as i64, margin as i64);
+    result
+}
+
+/// The appearance of the dock, read from the E4DOCKER section of e4docker.conf:
+///
+/// ```ini
+/// [e4docker]
+/// frame_style=flat
+/// show_frame=true
+/// window_border=false
+/// alignment=center
+/// min_width=400
+/// scale=1.25
+/// hover_label=true
+/// hover_label_delay=0.5
+/// hover_label_font=Helvetica Bold
+/// hover_label_font_size=16
+/// hover_preview=true
+/// icon_tint=monochrome
+/// icon_corner_radius=0.2
+/// icon_shadow=true
+/// animate_icons=true
+/// reveal_edge=bottom-left
+/// autohide=3
+/// reserve_space=true
+/// move_modifier=alt
+/// quick_launch_modifier=ctrl
+/// quick_launch_overlay=false
+/// new_instance_modifier=shift
+/// theme=auto
+/// dialog_position=beside
+/// ```
+///
+/// The options missing in e4docker.conf keep their default value.
+#[derive(Clone, Debug, PartialEq)]
+pub struct E4Style {
+    /// The box of the frame around the buttons: engraved, flat or none.
+    pub frame_style: E4FrameStyle,
+    /// False to hide the frame.
+    pub show_frame: bool,
+    /// True to show the border of the window, with its title bar.
+    pub window_border: bool,
+    /// Where the buttons are placed when the window is wider than them.
+    pub alignment: E4Alignment,
+    /// The minimum width of the window, 0 to fit the buttons.
+    pub min_width: i32,
+    /// The zoom of the whole dock: the sizes of the icons, the margins and the fonts are multiplied by it.
+    pub scale: f64,
+    /// True to show the name of the hovered button in a label above it.
+    pub hover_label: bool,
+    /// The seconds the mouse stays on a button before its label is shown.
+    pub hover_label_delay: f64,
+    /// The font of the label, empty for the font of the dock.
+    pub hover_label_font: String,
+    /// The size of the font of the label, 0 for the size of the fonts of the dock.
+    pub hover_label_font_size: i32,
+    /// True to show a thumbnail of the window of the program of the hovered button, when running.
+    pub hover_preview: bool,
+    /// How the icons are recolored: none, monochrome or a color.
+    pub icon_tint: E4IconTint,
+    /// The rounded corners and the shadow of the icons.
+    pub icon_effects: E4IconEffects,
+    /// True to cycle the frames of the animated GIF and APNG icons, unless their button says otherwise.
+    pub animate_icons: bool,
+    /// The edge or the corner of the screen revealing the dock, once hidden.
+    pub reveal_edge: E4Edge,
+    /// The seconds without the pointer over the dock before it slides off the screen beyond the reveal edge,
+    /// 0 to keep it.
+    pub autohide: f64,
+    /// True to keep the maximized windows off the dock, on X11: ignored with the auto-hide.
+    pub reserve_space: bool,
+    /// The key held to move the dock by dragging its background.
+    pub move_modifier: E4Modifier,
+    /// The key held with a number from 1 to 9 to launch the button in that place.
+    pub quick_launch_modifier: E4Modifier,
+    /// True to show the numbers over the buttons for a moment when the quick launch key is pressed.
+    pub quick_launch_overlay: bool,
+    /// The key held with a click on a single instance button to launch a new instance instead of showing
+    /// the running one, none to never launch it.
+    pub new_instance_modifier: E4Modifier,
+    /// The background of the hovered button, None for a shade of the selection color of the theme.
+    pub hover_color: Option<(u8, u8, u8)>,
+    /// The background of the pressed button, None for a shade of the selection color of the theme.
+    pub pressed_color: Option<(u8, u8, u8)>,
+    /// The colors of the dock: light, dark or auto, following the preference of the system.
+    pub theme: E4Theme,
+    /// Where the dialogs are opened: where fltk opens them, beside the dock or centered over it.
+    pub dialog_position: E4DialogPosition,
+}
+
+impl Default for E4Style {
+    fn default() -> Self {
+        Self {
+            frame_style: E4FrameStyle::default(),
+            show_frame: true,
+            window_border: false,
+            alignment: E4Alignment::default(),
+            min_width: 0,
+            scale: 1.0,
+            hover_label: false,
+            hover_label_delay: 0.5,
+            hover_label_font: String::new(),
+            hover_label_font_size: 0,
+            hover_preview: false,
+            icon_tint: E4IconTint::default(),
+            icon_effects: E4IconEffects::default(),
+            animate_icons: false,
+            reveal_edge: E4Edge::default(),
+            autohide: 0.0,
+            reserve_space: false,
+            move_modifier: E4Modifier::default(),
+            quick_launch_modifier: E4Modifier::Alt,
+            quick_launch_overlay: true,
+            new_instance_modifier: E4Modifier::Shift,
+            hover_color: None,
+            pressed_color: None,
+            theme: E4Theme::default(),
+            dialog_position: E4DialogPosition::default(),
+        }
+    }
+}
+
+impl E4Style {
+    /// Read the style from the loaded e4docker.conf.
+    pub fn from_ini(ini: &Ini) -> Result<Self, Box<dyn std::error::Error>> {
+        let mut style = Self::default();
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, FRAME_STYLE) {
+            style.frame_style = E4FrameStyle::from_name(&val).ok_or_else(|| StyleError {
+                details: format!("{}: {}", FRAME_STYLE, val),
+            })?;
+        }
+        if let Some(val) = ini.getbool(E4DOCKER_DOCKER_SECTION, SHOW_FRAME)? {
+            style.show_frame = val;
+        }
+        if let Some(val) = ini.getbool(E4DOCKER_DOCKER_SECTION, WINDOW_BORDER)? {
+            style.window_border = val;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, ALIGNMENT) {
+            style.alignment = E4Alignment::from_name(&val).ok_or_else(|| StyleError {
+                details: format!("{}: {}", ALIGNMENT, val),
+            })?;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, MIN_WIDTH) {
+            style.min_width = val.parse()?;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, SCALE) {
+            style.scale = val.parse()?;
+            if !style.scale.is_finite() || style.scale <= 0.0 {
+                return Err(Box::new(StyleError {
+                    details: format!("{}: {}", SCALE, val),
+                }));
+            }
+        }
+        if let Some(val) = ini.getbool(E4DOCKER_DOCKER_SECTION, HOVER_LABEL)? {
+            style.hover_label = val;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, HOVER_LABEL_DELAY) {
+            style.hover_label_delay = val.parse()?;
+            if !style.hover_label_delay.is_finite() || style.hover_label_delay < 0.0 {
+                return Err(Box::new(StyleError {
+                    details: format!("{}: {}", HOVER_LABEL_DELAY, val),
+                }));
+            }
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, HOVER_LABEL_FONT) {
+            style.hover_label_font = val.trim().to_string();
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, HOVER_LABEL_FONT_SIZE) {
+            style.hover_label_font_size = val.parse()?;
+        }
+        if let Some(val) = ini.getbool(E4DOCKER_DOCKER_SECTION, HOVER_PREVIEW)? {
+            style.hover_preview = val;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, ICON_TINT) {
+            style.icon_tint = E4IconTint::from_name(&val).ok_or_else(|| StyleError {
+                details: format!("{}: {}", ICON_TINT, val),
+            })?;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, ICON_CORNER_RADIUS) {
+            style.icon_effects.corner_radius = val.parse()?;
+            if !(0.0..=0.5).contains(&style.icon_effects.corner_radius) {
+                return Err(Box::new(StyleError {
+                    details: format!("{}: {}", ICON_CORNER_RADIUS, val),
+                }));
+            }
+        }
+        if let Some(val) = ini.getbool(E4DOCKER_DOCKER_SECTION, ICON_SHADOW)? {
+            style.icon_effects.shadow = val;
+        }
+        if let Some(val) = ini.getbool(E4DOCKER_DOCKER_SECTION, ANIMATE_ICONS)? {
+            style.animate_icons = val;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, REVEAL_EDGE) {
+            style.reveal_edge = E4Edge::from_name(&val).ok_or_else(|| StyleError {
+                details: format!("{}: {}", REVEAL_EDGE, val),
+            })?;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, AUTOHIDE) {
+            style.autohide = val.parse()?;
+            if !style.autohide.is_finite() || style.autohide < 0.0 {
+                return Err(Box::new(StyleError {
+                    details: format!("{}: {}", AUTOHIDE, val),
+                }));
+            }
+        }
+        if let Some(val) = ini.getbool(E4DOCKER_DOCKER_SECTION, RESERVE_SPACE)? {
+            style.reserve_space = val;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, MOVE_MODIFIER) {
+            style.move_modifier = E4Modifier::from_name(&val).ok_or_else(|| StyleError {
+                details: format!("{}: {}", MOVE_MODIFIER, val),
+            })?;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, QUICK_LAUNCH_MODIFIER) {
+            style.quick_launch_modifier =
+                E4Modifier::from_name(&val).ok_or_else(|| StyleError {
+                    details: format!("{}: {}", QUICK_LAUNCH_MODIFIER, val),
+                })?;
+        }
+        if let Some(val) = ini.getbool(E4DOCKER_DOCKER_SECTION, QUICK_LAUNCH_OVERLAY)? {
+            style.quick_launch_overlay = val;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, NEW_INSTANCE_MODIFIER) {
+            style.new_instance_modifier =
+                E4Modifier::from_name(&val).ok_or_else(|| StyleError {
+                    details: format!("{}: {}", NEW_INSTANCE_MODIFIER, val),
+                })?;
+        }
+        for (key, color) in [
+            (HOVER_COLOR, &mut style.hover_color),
+            (PRESSED_COLOR, &mut style.pressed_color),
+        ] {
+            if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, key) {
+                *color = Some(parse_color(&val).ok_or_else(|| StyleError {
+                    details: format!("{}: {}", key, val),
+                })?);
+            }
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, THEME) {
+            style.theme = E4Theme::from_name(&val).ok_or_else(|| StyleError {
+                details: format!("{}: {}", THEME, val),
+            })?;
+        }
+        if let Some(val) = ini.get(E4DOCKER_DOCKER_SECTION, DIALOG_POSITION) {
+            style.dialog_position =
+                E4DialogPosition::from_name(&val).ok_or_else(|| StyleError {
+                    details: format!("{}: {}", DIALOG_POSITION, val),
+                })?;
+        }
+        Ok(style)
+    }
+
+    /// Return size, in pixels, multiplied by the scale.
+    pub fn scaled(&self, size: i32) -> i32 {
+        (size as f64 * self.scale).round() as i32
+    }
+
+    /// Return size, in pixels, divided by the scale: the size to be written in e4docker.conf.
+    pub fn unscaled(&self, size: i32) -> i32 {
+        (size as f64 / self.scale).round() as i32
+    }
+
+    /// Return the colors of the background of a button with accent: the shades of the accent color if it has one,
+    /// otherwise the colors of the style or, if missing, the selection color of the theme over its background.
+    pub fn button_colors(&self, accent: Option<&E4Accent>) -> E4ButtonColors {
+        let theme = |color: Option<(u8, u8, u8)>, weight: f32| match color {
+            Some((r, g, b)) => Color::from_rgb(r, g, b),
+            None => Color::color_average(Color::Selection, Color::Background, weight),
+        };
+        match accent {
+            Some(accent) => E4ButtonColors {
+                normal: if accent.plate {
+                    accent.plate_color()
+                } else {
+                    Color::Background
+                },
+                hover: accent.shade(0.6),
+                pressed: accent.shade(0.8),
+            },
+            None => E4ButtonColors {
+                normal: Color::Background,
+                hover: theme(self.hover_color, HOVER_WEIGHT),
+                pressed: theme(self.pressed_color, PRESSED_WEIGHT),
+            },
+        }
+    }
+
+    /// Return the size of the fonts.
+    pub fn font_size(&self) -> i32 {
+        self.scaled(DEFAULT_FONT_SIZE)
+    }
+
+    /// Return the font of the hover label.
+    pub fn hover_label_font(&self) -> Font {
+        if self.hover_label_font.is_empty() {
+            Font::Helvetica
+        } else {
+            Font::by_name(&self.hover_label_font)
+        }
+    }
+
+    /// Return the largest side of the previews of the windows.
+    pub fn preview_size(&self) -> i32 {
+        self.scaled(PREVIEW_SIZE)
+    }
+
+    /// Return the size of the font of the hover label.
+    pub fn hover_label_font_size(&self) -> i32 {
+        match self.hover_label_font_size {
+            0 => self.font_size(),
+            size => self.scaled(size),
+        }
+    }
+}
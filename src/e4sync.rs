@@ -0,0 +1,324 @@
+use crate::{
+    e4cli,
+    e4config::{config_file, E4DOCKER_DOCKER_SECTION},
+    e4diff::{self, E4ConfigDiff, E4ConfigSnapshot},
+    e4initialize,
+    e4storage::{E4Storage, FsStorage},
+    e4toast, tr,
+    translations::Translations,
+};
+use configparser::ini::Ini;
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The key of e4docker.conf with the synced directory holding the configuration.
+pub const E4DOCKER_SYNC_DIR: &str = "SYNC_DIR";
+
+/// The file of the local configuration directory with the time of the last sync, in seconds.
+const SYNC_STAMP: &str = ".e4sync";
+
+/// The directory of the icons, synced with the configuration files.
+const ASSETS_DIR: &str = "assets";
+
+/// The files to be copied or deleted to make the local and the synced configuration the same,
+/// by their path relative to the configuration directory.
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct E4SyncPlan {
+    /// The files changed only in the synced directory.
+    pub to_local: Vec<PathBuf>,
+    /// The files changed only in the local directory.
+    pub to_synced: Vec<PathBuf>,
+    /// The files changed in both the directories since the last sync.
+    pub conflicts: Vec<PathBuf>,
+    /// The files deleted from the synced directory since the last sync, to delete from the local one.
+    pub delete_local: Vec<PathBuf>,
+    /// The files deleted from the local directory since the last sync, to delete from the synced one.
+    pub delete_synced: Vec<PathBuf>,
+}
+
+impl E4SyncPlan {
+    /// Compare the modification times, in seconds, of the local and the synced files with the last sync.
+    /// A file missing on one side is copied from the other if it has changed since the last sync: otherwise
+    /// it was there at the last sync, and has been deleted from the other side since. e4docker.conf is never deleted.
+    pub fn new(
+        local: &HashMap<PathBuf, u64>,
+        synced: &HashMap<PathBuf, u64>,
+        last_sync: u64,
+    ) -> Self {
+        let mut plan = Self::default();
+        let paths: BTreeSet<&PathBuf> = local.keys().chain(synced.keys()).collect();
+        let is_deleted =
+            |path: &PathBuf, time: u64| time <= last_sync && *path != config_file(Path::new(""));
+        for path in paths {
+            match (local.get(path), synced.get(path)) {
+                (Some(&local_time), None) if is_deleted(path, local_time) => {
+                    plan.delete_local.push(path.clone())
+                }
+                (None, Some(&synced_time)) if is_deleted(path, synced_time) => {
+                    plan.delete_synced.push(path.clone())
+                }
+                (Some(_), None) => plan.to_synced.push(path.clone()),
+                (None, Some(_)) => plan.to_local.push(path.clone()),
+                (Some(&local_time), Some(&synced_time)) => {
+                    match (local_time > last_sync, synced_time > last_sync) {
+                        (true, true) if local_time != synced_time => {
+                            plan.conflicts.push(path.clone())
+                        }
+                        (true, false) => plan.to_synced.push(path.clone()),
+                        (false, true) => plan.to_local.push(path.clone()),
+                        _ => {}
+                    }
+                }
+                (None, None) => {}
+            }
+        }
+        plan
+    }
+
+    /// Solve the conflicts keeping the newest file.
+    pub fn merge(&mut self, local: &HashMap<PathBuf, u64>, synced: &HashMap<PathBuf, u64>) {
+        for path in self.conflicts.drain(..) {
+            if local.get(&path) > synced.get(&path) {
+                self.to_synced.push(path);
+            } else {
+                self.to_local.push(path);
+            }
+        }
+    }
+}
+
+/// Return the directory holding the configuration: the SYNC_DIR of e4docker.conf in local_dir, if any, or local_dir.
+///
+/// local_dir keeps a copy of the synced configuration, used when the synced directory cannot be reached.
+/// The files changed on one side since the last sync are copied to the other one, the deleted ones are deleted
+/// from the other one too; the user chooses
+/// which files to keep when both the sides have changed, and confirms the changes to the local configuration then.
+pub fn config_dir(local_dir: &Path, translations: Arc<Mutex<Translations>>) -> PathBuf {
+    let synced_dir = match sync_dir(local_dir) {
+        Some(synced_dir) => synced_dir,
+        None => return local_dir.to_path_buf(),
+    };
+    if e4initialize::is_read_only() {
+        return local_dir.to_path_buf();
+    }
+    // An unmounted drive, or a cloud folder not created yet
+    if !synced_dir.exists() && !synced_dir.parent().is_some_and(Path::exists) {
+        let message = tr!(
+            translations,
+            format,
+            "cannot-reach-the-synced-directory",
+            &[&synced_dir.display().to_string()]
+        );
+        if e4cli::is_quiet() {
+            eprintln!("{}", message);
+        } else {
+            e4toast::show(&message);
+        }
+        return local_dir.to_path_buf();
+    }
+    match sync(local_dir, &synced_dir, translations.clone()) {
+        Ok(true) => synced_dir,
+        // The user keeps the local configuration
+        Ok(false) => local_dir.to_path_buf(),
+        Err(e) => {
+            let message = tr!(
+                translations,
+                format,
+                "cannot-sync-the-configuration",
+                &[&synced_dir.display().to_string(), &e.to_string()]
+            );
+            e4cli::alert(&message);
+            local_dir.to_path_buf()
+        }
+    }
+}
+
+/// Return the directory holding the configuration, like [config_dir] but without syncing it,
+/// for the subcommands: no file is copied and no dialog is shown.
+pub fn current_dir(local_dir: &Path) -> PathBuf {
+    match sync_dir(local_dir) {
+        Some(synced_dir) if config_file(&synced_dir).is_file() => synced_dir,
+        _ => local_dir.to_path_buf(),
+    }
+}
+
+/// Return the SYNC_DIR of e4docker.conf in local_dir.
+fn sync_dir(local_dir: &Path) -> Option<PathBuf> {
+    let mut ini = Ini::new();
+    ini.load(config_file(local_dir)).ok()?;
+    ini.get(E4DOCKER_DOCKER_SECTION, E4DOCKER_SYNC_DIR)
+        .map(|dir| dir.trim().to_string())
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Make the configuration in local_dir and in synced_dir the same.
+/// Return false if the user cancels the changes to the local configuration.
+fn sync(
+    local_dir: &Path,
+    synced_dir: &Path,
+    translations: Arc<Mutex<Translations>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    std::fs::create_dir_all(synced_dir.join(ASSETS_DIR))?;
+    let local = modification_times(local_dir)?;
+    let synced = modification_times(synced_dir)?;
+    let last_sync: u64 = std::fs::read_to_string(local_dir.join(SYNC_STAMP))
+        .ok()
+        .and_then(|stamp| stamp.trim().parse().ok())
+        .unwrap_or(0);
+    let mut plan = E4SyncPlan::new(&local, &synced, last_sync);
+
+    // Without dialogs the newest files are kept
+    if !plan.conflicts.is_empty() && e4cli::is_quiet() {
+        plan.merge(&local, &synced);
+    }
+    if !plan.conflicts.is_empty() {
+        let files: Vec<String> = plan
+            .conflicts
+            .iter()
+            .map(|path| path.display().to_string())
+            .collect();
+        let last_sync = tr!(translations, format_date_time, last_sync);
+        let message = tr!(
+            translations,
+            format,
+            "sync-conflict",
+            &[
+                &synced_dir.display().to_string(),
+                &files.join(", "),
+                &last_sync
+            ]
+        );
+        let choice = fltk::dialog::choice2_default(
+            &message,
+            &tr!(
+                translations,
+                get_or_default,
+                "keep-the-synced-files",
+                "Keep the synced files"
+            ),
+            &tr!(
+                translations,
+                get_or_default,
+                "keep-the-local-files",
+                "Keep the local files"
+            ),
+            &tr!(
+                translations,
+                get_or_default,
+                "keep-the-newest-files",
+                "Keep the newest files"
+            ),
+        );
+        match choice {
+            Some(0) => plan.to_local.append(&mut plan.conflicts),
+            Some(1) => plan.to_synced.append(&mut plan.conflicts),
+            _ => plan.merge(&local, &synced),
+        }
+        // Some local changes are lost: show what the synced files replace
+        if !plan.to_local.is_empty()
+            && !confirm_to_local(local_dir, synced_dir, &plan.to_local, translations.clone())?
+        {
+            return Ok(false);
+        }
+    }
+
+    for path in &plan.to_synced {
+        std::fs::copy(local_dir.join(path), synced_dir.join(path))?;
+    }
+    for path in &plan.to_local {
+        std::fs::copy(synced_dir.join(path), local_dir.join(path))?;
+    }
+    for path in &plan.delete_synced {
+        std::fs::remove_file(synced_dir.join(path))?;
+    }
+    for path in &plan.delete_local {
+        std::fs::remove_file(local_dir.join(path))?;
+    }
+    // The local e4docker.conf keeps pointing to the synced directory: its path can change between the machines
+    if plan.to_local.contains(&config_file(Path::new(""))) {
+        let mut ini = Ini::new();
+        ini.load(config_file(local_dir))?;
+        ini.set(
+            E4DOCKER_DOCKER_SECTION,
+            E4DOCKER_SYNC_DIR,
+            Some(synced_dir.display().to_string()),
+        );
+        FsStorage.write_ini(&config_file(local_dir), &ini)?;
+    }
+
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+    std::fs::write(local_dir.join(SYNC_STAMP), now.to_string())?;
+    Ok(true)
+}
+
+/// Show how copying the files of to_local from synced_dir changes the configuration in local_dir,
+/// and return true if the user applies the changes.
+fn confirm_to_local(
+    local_dir: &Path,
+    synced_dir: &Path,
+    to_local: &[PathBuf],
+    translations: Arc<Mutex<Translations>>,
+) -> Result<bool, Box<dyn std::error::Error>> {
+    let mut current = E4ConfigSnapshot::read(&FsStorage, local_dir)?;
+    let mut synced = E4ConfigSnapshot::read_with(|path| {
+        let dir = if to_local.iter().any(|file| file == path) {
+            synced_dir
+        } else {
+            local_dir
+        };
+        FsStorage.load_ini(&dir.join(path))
+    })?;
+    // SYNC_DIR is set again after the copy
+    for snapshot in [&mut current, &mut synced] {
+        snapshot.config.set(
+            E4DOCKER_DOCKER_SECTION,
+            E4DOCKER_SYNC_DIR,
+            Some(synced_dir.display().to_string()),
+        );
+    }
+    let message = tr!(
+        translations,
+        format,
+        "sync-will-change-the-configuration",
+        &[&synced_dir.display().to_string()]
+    );
+    Ok(e4diff::confirm(
+        &message,
+        &E4ConfigDiff::new(&current, &synced),
+        translations,
+    ))
+}
+
+/// Return the modification times, in seconds, of the configuration files and of the icons in dir,
+/// by their path relative to dir.
+fn modification_times(dir: &Path) -> std::io::Result<HashMap<PathBuf, u64>> {
+    let mut times = HashMap::new();
+    for (subdir, all_files) in [(PathBuf::new(), false), (PathBuf::from(ASSETS_DIR), true)] {
+        let entries = match std::fs::read_dir(dir.join(&subdir)) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            let is_conf = path
+                .extension()
+                .is_some_and(|extension| extension == "conf");
+            if !entry.file_type()?.is_file() || !(all_files || is_conf) {
+                continue;
+            }
+            let modified = entry
+                .metadata()?
+                .modified()?
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |duration| duration.as_secs());
+            times.insert(subdir.join(entry.file_name()), modified);
+        }
+    }
+    Ok(times)
+}
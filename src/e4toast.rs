@@ -0,0 +1,86 @@
+use fltk::{
+    app, button::Button, enums::Align, enums::FrameType, frame::Frame, prelude::*, window::Window,
+};
+
+/// The seconds a toast stays on screen.
+const TOAST_SECONDS: f64 = 3.0;
+
+/// The seconds a toast with actions stays on screen, to give the time to choose one.
+const ACTION_TOAST_SECONDS: f64 = 8.0;
+
+/// The size of the buttons of the actions, under the message.
+const ACTION_WIDTH: i32 = 90;
+const ACTION_HEIGHT: i32 = 25;
+
+/// The size of a toast.
+const TOAST_WIDTH: i32 = 320;
+const TOAST_HEIGHT: i32 = 60;
+
+/// The distance of a toast from the bottom right corner of the screen.
+const TOAST_MARGIN: i32 = 40;
+
+/// Show a message in the bottom right corner of the screen, closing by itself.
+/// Unlike an alert, it does not wait to be closed.
+pub fn show(message: &str) {
+    show_with_actions(message, vec![]);
+}
+
+/// Show a message like [show], with a button for each action under it.
+/// Pressing a button closes the toast and runs its action.
+pub fn show_with_actions(message: &str, actions: Vec<(String, Box<dyn FnOnce()>)>) {
+    let height = if actions.is_empty() {
+        TOAST_HEIGHT
+    } else {
+        TOAST_HEIGHT + ACTION_HEIGHT + 5
+    };
+    let (screen_w, screen_h) = app::screen_size();
+    let mut window = Window::new(
+        screen_w as i32 - TOAST_WIDTH - TOAST_MARGIN,
+        screen_h as i32 - height - TOAST_MARGIN,
+        TOAST_WIDTH,
+        height,
+        None,
+    );
+    window.set_border(false);
+    let mut frame = Frame::new(0, 0, TOAST_WIDTH, height, None);
+    frame.set_frame(FrameType::BorderBox);
+    let mut label = Frame::new(0, 0, TOAST_WIDTH, TOAST_HEIGHT, None);
+    label.set_align(Align::Inside | Align::Wrap);
+    // @ starts a symbol in the labels
+    label.set_label(&message.replace('@', "@@"));
+
+    let seconds = if actions.is_empty() {
+        TOAST_SECONDS
+    } else {
+        ACTION_TOAST_SECONDS
+    };
+    // The buttons are aligned to the right, in the order of the actions
+    let mut x = TOAST_WIDTH - (ACTION_WIDTH + 5) * actions.len() as i32;
+    for (name, action) in actions {
+        let mut button = Button::new(x, TOAST_HEIGHT, ACTION_WIDTH, ACTION_HEIGHT, None);
+        button.set_label(&name.replace('@', "@@"));
+        let mut action = Some(action);
+        let mut window = window.clone();
+        button.set_callback(move |_| {
+            window.hide();
+            // Run later: the toast is deleted by its timeout, maybe while the action shows a dialog
+            if let Some(action) = action.take() {
+                let mut action = Some(action);
+                app::add_timeout3(0.0, move |_| {
+                    if let Some(action) = action.take() {
+                        action();
+                    }
+                });
+            }
+        });
+        x += ACTION_WIDTH + 5;
+    }
+    window.end();
+    window.set_override();
+    window.show();
+
+    app::add_timeout3(seconds, move |_| {
+        window.hide();
+        Window::delete(window.clone());
+    });
+}
@@ -0,0 +1,144 @@
+use crate::{
+    e4command::shell_command,
+    e4config::{E4Config, BUTTON_BUTTON_SECTION},
+};
+
+/// Error raised when a toggle cannot be switched.
+#[derive(Debug)]
+struct ToggleError {
+    details: String,
+}
+
+impl std::fmt::Display for ToggleError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for ToggleError {}
+
+/// The commands of a toggle widget, read from the configuration file of its button.
+///
+/// ```ini
+/// [button]
+/// type=toggle
+/// toggle=wifi
+/// icon=wifi.png
+/// icon_off=wifi-off.png
+/// ```
+///
+/// The toggle key picks the default commands for wifi or bluetooth, which can be replaced by the
+/// state_command, state_on, on_command and off_command keys. The icons are read by
+/// [crate::e4widget::E4StateIcons].
+#[derive(Clone, Debug)]
+pub struct E4Toggle {
+    /// The command printing the state.
+    pub state_command: String,
+    /// The text printed by the state command when the toggle is on.
+    pub state_on: String,
+    /// The command switching on.
+    pub on_command: String,
+    /// The command switching off.
+    pub off_command: String,
+}
+
+/// Return the default commands (state, text when on, on, off) of a toggle.
+#[cfg(target_os = "windows")]
+fn preset(name: &str) -> Option<[&'static str; 4]> {
+    match name {
+        "wifi" => Some([
+            "netsh interface show interface name=\"Wi-Fi\"",
+            "enabled",
+            "netsh interface set interface name=\"Wi-Fi\" admin=enabled",
+            "netsh interface set interface name=\"Wi-Fi\" admin=disabled",
+        ]),
+        _ => None,
+    }
+}
+
+/// Return the default commands (state, text when on, on, off) of a toggle.
+#[cfg(target_os = "macos")]
+fn preset(name: &str) -> Option<[&'static str; 4]> {
+    match name {
+        "wifi" => Some([
+            "networksetup -getairportpower en0",
+            "): on",
+            "networksetup -setairportpower en0 on",
+            "networksetup -setairportpower en0 off",
+        ]),
+        _ => None,
+    }
+}
+
+/// Return the default commands (state, text when on, on, off) of a toggle.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+fn preset(name: &str) -> Option<[&'static str; 4]> {
+    match name {
+        "wifi" => Some([
+            "nmcli radio wifi",
+            "enabled",
+            "nmcli radio wifi on",
+            "nmcli radio wifi off",
+        ]),
+        "bluetooth" => Some([
+            "rfkill list bluetooth",
+            "soft blocked: no",
+            "rfkill unblock bluetooth",
+            "rfkill block bluetooth",
+        ]),
+        _ => None,
+    }
+}
+
+impl E4Toggle {
+    /// Read the toggle of the button button_name.
+    pub fn read(config: &E4Config, button_name: &str) -> Self {
+        let config_file = crate::e4config::button_file(&config.config_dir, button_name);
+        let ini = config.storage.load_ini(&config_file).unwrap_or_default();
+        let get = |key: &str| {
+            ini.get(BUTTON_BUTTON_SECTION, key)
+                .map(|v| v.trim().to_string())
+        };
+
+        let defaults = get("TOGGLE")
+            .and_then(|name| preset(&name.to_lowercase()))
+            .unwrap_or([""; 4]);
+        Self {
+            state_command: get("STATE_COMMAND").unwrap_or_else(|| defaults[0].to_string()),
+            state_on: get("STATE_ON").unwrap_or_else(|| defaults[1].to_string()),
+            on_command: get("ON_COMMAND").unwrap_or_else(|| defaults[2].to_string()),
+            off_command: get("OFF_COMMAND").unwrap_or_else(|| defaults[3].to_string()),
+        }
+    }
+
+    /// Run the state command and return true if the toggle is on, or None if the state is unknown.
+    pub fn is_on(&self) -> Option<bool> {
+        if self.state_command.is_empty() {
+            return None;
+        }
+        let output = shell_command(&self.state_command).output().ok()?;
+        let stdout = String::from_utf8_lossy(&output.stdout).to_lowercase();
+        Some(stdout.contains(&self.state_on.to_lowercase()))
+    }
+
+    /// Run the command switching the toggle on or off.
+    pub fn switch(&self, on: bool) -> Result<(), Box<dyn std::error::Error>> {
+        let command_line = if on {
+            &self.on_command
+        } else {
+            &self.off_command
+        };
+        if command_line.is_empty() {
+            return Err(Box::new(ToggleError {
+                details: String::from("No command to switch the toggle"),
+            }));
+        }
+        let output = shell_command(command_line).output()?;
+        if !output.status.success() {
+            return Err(Box::new(ToggleError {
+                details: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+            }));
+        }
+        Ok(())
+    }
+}
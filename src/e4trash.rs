@@ -0,0 +1,55 @@
+/// Move files to the trash.
+#[cfg(feature = "trash-widget")]
+pub fn delete(files: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    trash::delete_all(files)?;
+    Ok(())
+}
+
+/// Move files to the trash: never without the trash-widget feature, the files are kept.
+#[cfg(not(feature = "trash-widget"))]
+pub fn delete(_files: &[String]) -> Result<(), Box<dyn std::error::Error>> {
+    Err(Box::new(std::io::Error::new(
+        std::io::ErrorKind::Unsupported,
+        "this build cannot move the files to the trash",
+    )))
+}
+
+/// Return true if the trash is empty, or None if it cannot be read.
+#[cfg(all(feature = "trash-widget", not(target_os = "macos")))]
+pub fn is_empty() -> Option<bool> {
+    trash::os_limited::is_empty().ok()
+}
+
+/// Return true if the trash is empty, or None if it cannot be read.
+/// Reading ~/.Trash may need the full disk access permission.
+#[cfg(all(feature = "trash-widget", target_os = "macos"))]
+pub fn is_empty() -> Option<bool> {
+    let trash_dir = dirs::home_dir()?.join(".Trash");
+    let mut entries = std::fs::read_dir(trash_dir).ok()?;
+    // .DS_Store is always there
+    Some(!entries.any(|entry| match entry {
+        Ok(entry) => entry.file_name() != ".DS_Store",
+        Err(_) => false,
+    }))
+}
+
+/// Return true if the trash is empty, or None if it cannot be read: never without the trash-widget feature.
+#[cfg(not(feature = "trash-widget"))]
+pub fn is_empty() -> Option<bool> {
+    None
+}
+
+/// Return the location of the trash, to be opened in the file manager.
+pub fn location() -> String {
+    if cfg!(target_os = "windows") {
+        String::from("shell:RecycleBinFolder")
+    } else if cfg!(target_os = "macos") {
+        dirs::home_dir()
+            .unwrap_or_default()
+            .join(".Trash")
+            .display()
+            .to_string()
+    } else {
+        String::from("trash:///")
+    }
+}
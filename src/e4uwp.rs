@@ -0,0 +1,209 @@
+use crate::{tr, translations::Translations};
+use fltk::{app, browser::HoldBrowser, button::Button, prelude::*, window::Window};
+use regex::Regex;
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+    process::Command,
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+/// The prefix of the shell URI launching a UWP app from its AppUserModelID.
+pub const APPS_FOLDER: &str = "shell:AppsFolder\\";
+
+/// Error raised when the store apps cannot be enumerated.
+#[derive(Debug)]
+struct UwpError {
+    details: String,
+}
+
+impl std::fmt::Display for UwpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(f, "{}", self.details)
+    }
+}
+
+impl std::error::Error for UwpError {}
+
+/// An installed UWP / Microsoft Store app.
+pub struct UwpApp {
+    /// The display name of the app.
+    pub name: String,
+    /// The AppUserModelID of the app, e.g. Microsoft.WindowsCalculator_8wekyb3d8bbwe!App.
+    pub aumid: String,
+}
+
+/// Return true if cmd launches a UWP app.
+pub fn is_uwp_command(cmd: &str) -> bool {
+    cmd.starts_with(APPS_FOLDER)
+}
+
+/// Return the shell URI launching the app with the given AppUserModelID.
+pub fn shell_uri(aumid: &str) -> String {
+    if is_uwp_command(aumid) {
+        aumid.to_string()
+    } else {
+        format!("{}{}", APPS_FOLDER, aumid)
+    }
+}
+
+/// Return the [Command] launching a UWP app through explorer.exe.
+pub fn launch_command(uri: &str) -> Command {
+    let mut command = Command::new("explorer.exe");
+    command.arg(uri);
+    command
+}
+
+/// Run a PowerShell script and return its standard output.
+fn powershell(script: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output()?;
+    if !output.status.success() {
+        return Err(Box::new(UwpError {
+            details: String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        }));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+/// Return the installed store apps, sorted by name.
+/// Classic desktop programs listed in the start menu are skipped.
+pub fn installed_apps() -> Result<Vec<UwpApp>, Box<dyn std::error::Error>> {
+    let output = powershell("Get-StartApps | ForEach-Object { $_.Name + \"`t\" + $_.AppID }")?;
+    let mut apps: Vec<UwpApp> = output
+        .lines()
+        .filter_map(|line| {
+            let (name, aumid) = line.split_once('\t')?;
+            // Only packaged apps have an AppUserModelID in the form Family!App
+            if !aumid.contains('!') {
+                return None;
+            }
+            Some(UwpApp {
+                name: name.trim().to_string(),
+                aumid: aumid.trim().to_string(),
+            })
+        })
+        .collect();
+    apps.sort_by_key(|a| a.name.to_lowercase());
+    Ok(apps)
+}
+
+/// Return the logo of an installed store app, as declared in the AppxManifest.xml of its package.
+pub fn app_logo(aumid: &str) -> Option<PathBuf> {
+    let aumid = aumid.strip_prefix(APPS_FOLDER).unwrap_or(aumid);
+    let (family, _) = aumid.split_once('!')?;
+    let script = format!(
+        "(Get-AppxPackage | Where-Object {{ $_.PackageFamilyName -eq '{}' }} | Select-Object -First 1).InstallLocation",
+        family.replace('\'', "''")
+    );
+    let location = PathBuf::from(powershell(&script).ok()?.trim());
+    let manifest = std::fs::read_to_string(location.join("AppxManifest.xml")).ok()?;
+
+    // Prefer the tile logo, fall back to the store logo
+    let regex = Regex::new(r#"Square44x44Logo="([^"]+)"|<Logo>([^<]+)</Logo>"#).ok()?;
+    for captures in regex.captures_iter(&manifest) {
+        if let Some(logo) = captures.get(1).or(captures.get(2)) {
+            if let Some(found) = find_scaled_logo(&location.join(logo.as_str())) {
+                return Some(found);
+            }
+        }
+    }
+    None
+}
+
+/// Logos are stored with scale qualifiers (StoreLogo.scale-100.png): return the largest variant.
+fn find_scaled_logo(logo: &Path) -> Option<PathBuf> {
+    if logo.exists() {
+        return Some(logo.to_path_buf());
+    }
+    let dir = logo.parent()?;
+    let prefix = format!("{}.", logo.file_stem()?.to_str()?);
+    let suffix = format!(".{}", logo.extension()?.to_str()?);
+    let mut candidates: Vec<PathBuf> = std::fs::read_dir(dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(
+            |path| match path.file_name().and_then(std::ffi::OsStr::to_str) {
+                Some(name) => name.starts_with(&prefix) && name.ends_with(&suffix),
+                None => false,
+            },
+        )
+        .collect();
+    candidates.sort_by_key(|path| std::fs::metadata(path).map(|m| m.len()).unwrap_or(0));
+    candidates.pop()
+}
+
+/// Copy the logo of a store app in the assets directory and return its new path.
+/// Logos live in versioned package directories, which change when the app is updated.
+pub fn fetch_app_logo(aumid: &str, assets_dir: &Path) -> Option<PathBuf> {
+    let logo = app_logo(aumid)?;
+    let aumid = aumid.strip_prefix(APPS_FOLDER).unwrap_or(aumid);
+    let (family, _) = aumid.split_once('!')?;
+    let mut destination = assets_dir.join(family);
+    destination.set_extension(logo.extension()?);
+    std::fs::copy(&logo, &destination).ok()?;
+    Some(destination)
+}
+
+/// Show a dialog listing the installed store apps and return the chosen one.
+pub fn choose_app(translations: Arc<Mutex<Translations>>) -> Option<UwpApp> {
+    let mut apps = match installed_apps() {
+        Ok(apps) => apps,
+        Err(e) => {
+            let message = tr!(
+                translations,
+                format,
+                "cannot-enumerate-the-store-apps",
+                &[&e.to_string()]
+            );
+            fltk::dialog::alert_default(&message);
+            return None;
+        }
+    };
+
+    let mut window = Window::default().with_size(400, 450).with_label(&tr!(
+        translations,
+        get_or_default,
+        "choose-a-store-app",
+        "Choose a store app"
+    ));
+    let mut browser = HoldBrowser::new(10, 10, 380, 390, None);
+    for store_app in &apps {
+        browser.add(&store_app.name);
+    }
+
+    let selected = Rc::new(RefCell::new(None));
+    let mut ok_button = Button::new(
+        150,
+        410,
+        100,
+        30,
+        tr!(translations, get_or_default, "ok", "OK").as_str(),
+    );
+    ok_button.set_callback({
+        let mut window = window.clone();
+        let browser = browser.clone();
+        let selected = selected.clone();
+        move |_| {
+            if browser.value() > 0 {
+                *selected.borrow_mut() = Some(browser.value() as usize - 1);
+            }
+            window.hide();
+        }
+    });
+
+    window.make_modal(true);
+    window.end();
+    crate::e4screen::place_dialog(&mut window);
+    window.show();
+    // Run modal window
+    while window.shown() {
+        app::wait();
+    }
+
+    let index = *selected.borrow();
+    index.map(|i| apps.swap_remove(i))
+}
@@ -0,0 +1,731 @@
+use crate::{
+    e4button::{E4Button, E4ButtonType},
+    e4command::E4Command,
+    e4config::{E4Config, BUTTON_BUTTON_SECTION},
+    e4media::{self, MediaCommand},
+    e4state::E4State,
+    e4toggle::E4Toggle,
+    e4trash, tr,
+    translations::Translations,
+};
+#[cfg(feature = "script-widgets")]
+use crate::{e4plugin, e4script};
+use configparser::ini::Ini;
+use fltk::{
+    app,
+    browser::HoldBrowser,
+    button::Button,
+    enums::{Event, Key, Shortcut},
+    image::{PngImage, SharedImage},
+    menu,
+    prelude::*,
+    window::Window,
+};
+use std::{
+    cell::{Cell, RefCell},
+    ffi::OsStr,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::{Arc, Mutex},
+};
+
+/// The maximum number of characters of a clipboard text shown in the menu.
+const CLIPBOARD_PREVIEW_LENGTH: usize = 40;
+
+/// The size of the icons in the list of a folder widget.
+const FOLDER_ICON_SIZE: i32 = 16;
+
+/// The width of the list of a folder widget.
+const FOLDER_LIST_WIDTH: i32 = 300;
+
+/// The maximum number of lines shown at once in the list of a folder widget: the others are scrolled.
+const FOLDER_LIST_LINES: i32 = 15;
+
+/// The images larger than this size in bytes are not shown as thumbnails in the list of a folder widget.
+const FOLDER_THUMBNAIL_MAX_BYTES: u64 = 2 * 1024 * 1024;
+
+/// The extensions of the images shown as thumbnails in the list of a folder widget.
+const FOLDER_THUMBNAIL_EXTENSIONS: [&str; 6] = ["png", "jpg", "jpeg", "gif", "bmp", "svg"];
+
+thread_local! {
+    /// The clipboard widgets on the dock, by button name: the changes of the clipboard are pasted to them.
+    static CLIPBOARD_WIDGETS: RefCell<Vec<(String, Button)>> = const { RefCell::new(vec![]) };
+    /// True while the handler of the changes of the clipboard is added.
+    static CLIPBOARD_WATCHED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// An action chosen in the clipboard widget menu.
+#[derive(Clone)]
+enum ClipboardAction {
+    /// Copy the text in the clipboard again.
+    Copy(String),
+    /// Copy the text and open it with the command of a button.
+    PasteInto(String, String),
+    /// Forget the clipboard history.
+    Clear,
+}
+
+/// The icons of a widget showing a state, like a toggle or the trash.
+#[derive(Clone, Debug)]
+pub struct E4StateIcons {
+    /// The icon shown when the state is on: the generic one, embedded in the program, if missing.
+    pub on: Option<PathBuf>,
+    /// The icon shown when the state is off: the grayed on icon if missing.
+    pub off: Option<PathBuf>,
+    /// The last state shown by the button.
+    pub shown: Option<bool>,
+}
+
+impl E4StateIcons {
+    /// Read the icons of the button button_name, if its type shows a state.
+    /// A toggle is on with its icon and off with its icon_off.
+    /// The trash is full with its icon_full and empty with its icon.
+    pub fn read(config: &E4Config, button_name: &str, button_type: E4ButtonType) -> Option<Self> {
+        let ini = button_ini(config, button_name);
+        let get = |key: &str| {
+            ini.get(BUTTON_BUTTON_SECTION, key)
+                .map(|icon| icon_path(config, icon.trim()))
+        };
+        let icon = get("ICON");
+
+        let (on, off) = match button_type {
+            E4ButtonType::Toggle => (icon, get("ICON_OFF")),
+            E4ButtonType::Trash => match get("ICON_FULL") {
+                Some(icon_full) => (Some(icon_full), icon),
+                None => (icon, None),
+            },
+            _ => return None,
+        };
+        Some(Self {
+            on,
+            off,
+            shown: None,
+        })
+    }
+
+    /// Show a state on the button, if it changed.
+    pub fn show(&mut self, button: &mut Button, on: bool, (w, h): (i32, i32)) {
+        if self.shown == Some(on) {
+            return;
+        }
+        self.shown = Some(on);
+
+        let image = match (on, &self.off, &self.on) {
+            (false, Some(icon), _) | (_, _, Some(icon)) => {
+                E4Button::get_fltk_image(icon, Translations::get_instance())
+            }
+            _ => E4Button::generic_image(),
+        };
+        if let Ok(mut image) = image {
+            image.scale(w, h, true, true);
+            if !on && self.off.is_none() {
+                image.inactive();
+            }
+            button.set_image(Some(image));
+            button.redraw();
+        }
+    }
+}
+
+/// Load the configuration file of the button button_name, to read the keys of its widget.
+fn button_ini(config: &E4Config, button_name: &str) -> Ini {
+    let config_file = crate::e4config::button_file(&config.config_dir, button_name);
+    config.storage.load_ini(&config_file).unwrap_or_default()
+}
+
+/// Return the path of an icon, searching it in the assets directory if it does not exist.
+fn icon_path(config: &E4Config, icon: &str) -> PathBuf {
+    if Path::new(icon).exists() {
+        PathBuf::from(icon)
+    } else {
+        config.assets_dir.join(icon)
+    }
+}
+
+/// Open target with the default application, showing the error if it cannot be opened.
+fn open(target: &str, translations: Arc<Mutex<Translations>>) {
+    if let Err(e) = E4Command::open_with_default_app(target) {
+        let message = tr!(
+            translations,
+            format,
+            "failed-to-execute-command",
+            &[target, &e.to_string()]
+        );
+        fltk::dialog::alert_default(&message);
+    }
+}
+
+/// Escape the characters with a special meaning in a FLTK menu label.
+pub fn menu_label(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('/', "\\/")
+        .replace('&', "&&")
+}
+
+/// Set the callback and the event handler of a widget button.
+/// Command buttons are left untouched.
+pub fn setup(
+    button: &mut Button,
+    button_type: E4ButtonType,
+    name: &str,
+    config: &E4Config,
+    translations: Arc<Mutex<Translations>>,
+) {
+    match button_type {
+        E4ButtonType::Command => {}
+        E4ButtonType::Clipboard => setup_clipboard(button, name, config, translations),
+        E4ButtonType::Media => setup_media(button, translations),
+        E4ButtonType::Toggle => setup_toggle(button, E4Toggle::read(config, name), translations),
+        E4ButtonType::Trash => setup_trash(button, translations),
+        E4ButtonType::Folder => setup_folder(button, folder_of(config, name), translations),
+        #[cfg(feature = "script-widgets")]
+        E4ButtonType::Plugin => setup_plugin(button, name, config, translations),
+        #[cfg(feature = "script-widgets")]
+        E4ButtonType::Script => setup_script(button, script_of(config, name), translations),
+        #[cfg(not(feature = "script-widgets"))]
+        E4ButtonType::Plugin | E4ButtonType::Script => setup_without_scripts(button, translations),
+        E4ButtonType::Watch => setup_watch(button),
+    }
+}
+
+/// Set the callback of a widget button to click: the clicks with Ctrl held select the button instead,
+/// and the slow double clicks rename it.
+fn on_click<F: FnMut() + 'static>(button: &mut Button, mut click: F) {
+    button.set_callback(move |_| {
+        if !crate::e4selection::is_select_click() && !crate::e4rename::is_renaming() {
+            click();
+        }
+    });
+}
+
+/// The watch widget: its indicator shows if the process of its command is running, like a command button,
+/// but a click or a drop launches nothing.
+///
+/// ```ini
+/// [button]
+/// type=watch
+/// command=syncthing
+/// icon=syncthing.png
+/// ```
+fn setup_watch(button: &mut Button) {
+    button.handle(|_, _| false);
+    button.set_callback(|_| {});
+}
+
+/// The clipboard widget: remember the copied texts and show them in a menu when clicked.
+fn setup_clipboard(
+    button: &mut Button,
+    name: &str,
+    config: &E4Config,
+    translations: Arc<Mutex<Translations>>,
+) {
+    watch_clipboard(name, button);
+
+    let config_dir = config.config_dir.clone();
+    button.handle(move |_, ev| match ev {
+        Event::Paste => {
+            let text = app::event_text();
+            if !text.trim().is_empty() {
+                let mut state = E4State::load(&config_dir);
+                state.add_clipboard_entry(&text);
+                let _ = state.save();
+            }
+            true
+        }
+        _ => false,
+    });
+
+    let config = config.clone();
+    on_click(button, move || {
+        show_clipboard_menu(&config, translations.clone());
+    });
+}
+
+/// Paste the clipboard to button, the clipboard widget named name, whenever it changes.
+/// FLTK calls a single handler on the changes: it is added with the first clipboard widget, passes the
+/// clipboard to the widgets still on the dock, and is removed when none is left.
+fn watch_clipboard(name: &str, button: &Button) {
+    CLIPBOARD_WIDGETS.with(|widgets| {
+        let mut widgets = widgets.borrow_mut();
+        // A widget drawn again replaces the deleted one
+        widgets.retain(|(widget_name, widget)| widget_name != name && !widget.was_deleted());
+        widgets.push((name.to_string(), button.clone()));
+    });
+    if CLIPBOARD_WATCHED.replace(true) {
+        return;
+    }
+    // The clipboard content is requested when it changes, and delivered as a Paste event
+    app::add_clipboard_notify(|source| {
+        // 1 is the clipboard, 0 the selection buffer
+        if source != 1 {
+            return;
+        }
+        let receiver = CLIPBOARD_WIDGETS.with(|widgets| {
+            let mut widgets = widgets.borrow_mut();
+            widgets.retain(|(_, widget)| !widget.was_deleted());
+            // The widgets share the history: one of them records the text
+            widgets.first().map(|(_, widget)| widget.clone())
+        });
+        match receiver {
+            Some(receiver) => app::paste_text(&receiver),
+            None => {
+                app::remove_clipboard_notify();
+                CLIPBOARD_WATCHED.set(false);
+            }
+        }
+    });
+}
+
+/// Return the first line of a text, shortened to fit in a menu.
+fn preview(text: &str) -> String {
+    let line = text.trim().lines().next().unwrap_or("");
+    if line.chars().count() > CLIPBOARD_PREVIEW_LENGTH {
+        let short: String = line.chars().take(CLIPBOARD_PREVIEW_LENGTH).collect();
+        format!("{}...", short)
+    } else {
+        line.to_string()
+    }
+}
+
+/// Add an item to a widget menu, recording menu_action in action when chosen.
+fn add_menu_action<T: Clone + 'static>(
+    popup: &mut menu::MenuButton,
+    label: &str,
+    flag: menu::MenuFlag,
+    action: &Rc<RefCell<Option<T>>>,
+    menu_action: T,
+) {
+    let action = action.clone();
+    popup.add(label, Shortcut::None, flag, move |_| {
+        *action.borrow_mut() = Some(menu_action.clone());
+    });
+}
+
+/// Show the clipboard history and run the chosen action.
+fn show_clipboard_menu(config: &E4Config, translations: Arc<Mutex<Translations>>) {
+    let mut state = E4State::load(&config.config_dir);
+    let history = state.clipboard_history();
+    let copy_label = tr!(translations, get_or_default, "copy", "Copy");
+    let paste_into_label = tr!(translations, get_or_default, "paste-into", "Paste into");
+
+    // Only the command buttons can receive a text
+    let mut apps = vec![];
+    for name in &config.buttons {
+        if let Ok(button_config) = E4Button::read_config(config, name, translations.clone()) {
+            if button_config.button_type == E4ButtonType::Command {
+                apps.push(name.clone());
+            }
+        }
+    }
+
+    let action = Rc::new(RefCell::new(None));
+    let mut popup = menu::MenuButton::default();
+    for (n, text) in history.iter().enumerate() {
+        let entry = format!("{}. {}", n + 1, menu_label(&preview(text)));
+        add_menu_action(
+            &mut popup,
+            &format!("{}/{}", entry, menu_label(&copy_label)),
+            menu::MenuFlag::Normal,
+            &action,
+            ClipboardAction::Copy(text.clone()),
+        );
+        for app_name in &apps {
+            add_menu_action(
+                &mut popup,
+                &format!(
+                    "{}/{}/{}",
+                    entry,
+                    menu_label(&paste_into_label),
+                    menu_label(app_name)
+                ),
+                menu::MenuFlag::Normal,
+                &action,
+                ClipboardAction::PasteInto(text.clone(), app_name.clone()),
+            );
+        }
+    }
+    let clear_flag = if history.is_empty() {
+        menu::MenuFlag::Inactive
+    } else {
+        menu::MenuFlag::Normal
+    };
+    add_menu_action(
+        &mut popup,
+        &menu_label(&tr!(
+            translations,
+            get_or_default,
+            "clear-history",
+            "Clear history"
+        )),
+        clear_flag,
+        &action,
+        ClipboardAction::Clear,
+    );
+    popup.popup();
+    menu::MenuButton::delete(popup);
+
+    let chosen = action.borrow_mut().take();
+    match chosen {
+        Some(ClipboardAction::Copy(text)) => {
+            app::copy(&text);
+        }
+        Some(ClipboardAction::PasteInto(text, app_name)) => {
+            app::copy(&text);
+            match E4Button::read_config(config, &app_name, translations.clone()) {
+                Ok(button_config) => {
+                    let mut command = button_config.command;
+                    if let Err(e) = command.exec_with_files(&[text]) {
+                        let message = tr!(
+                            translations,
+                            format,
+                            "failed-to-execute-command",
+                            &[command.get_cmd(), &e.to_string()]
+                        );
+                        fltk::dialog::alert_default(&message);
+                    }
+                }
+                Err(e) => {
+                    let message = tr!(
+                        translations,
+                        format,
+                        "cannot-load-the-button-config-file",
+                        &[&e.to_string()]
+                    );
+                    fltk::dialog::alert_default(&message);
+                }
+            }
+        }
+        Some(ClipboardAction::Clear) => {
+            state.clear_clipboard_history();
+            if let Err(e) = state.save() {
+                let message = tr!(
+                    translations,
+                    format,
+                    "cannot-save",
+                    &[&config.config_dir.display().to_string(), &e.to_string()]
+                );
+                fltk::dialog::alert_default(&message);
+            }
+        }
+        None => {}
+    }
+}
+
+/// The media widget: control the media player from a menu, showing the current track as tooltip.
+fn setup_media(button: &mut Button, translations: Arc<Mutex<Translations>>) {
+    let no_track = tr!(translations, get_or_default, "no-track", "No track playing");
+    // The track changes by itself: ask for it when the mouse enters the button, read in background
+    e4media::cached_track_title();
+    button.handle(move |b, ev| {
+        if ev == Event::Enter {
+            let title = e4media::cached_track_title().unwrap_or_else(|| no_track.clone());
+            b.set_tooltip(&title);
+        }
+        false
+    });
+
+    on_click(button, move || {
+        show_media_menu(translations.clone());
+    });
+}
+
+/// Show the media controls and send the chosen command to the player.
+fn show_media_menu(translations: Arc<Mutex<Translations>>) {
+    let action = Rc::new(RefCell::new(None));
+    let mut popup = menu::MenuButton::default();
+    if let Some(title) = e4media::cached_track_title() {
+        popup.add(
+            &menu_label(&preview(&title)),
+            Shortcut::None,
+            menu::MenuFlag::Inactive | menu::MenuFlag::MenuDivider,
+            |_| {},
+        );
+    }
+    for (key, default, media_command) in [
+        ("play-pause", "Play / Pause", MediaCommand::PlayPause),
+        ("next-track", "Next", MediaCommand::Next),
+        ("previous-track", "Previous", MediaCommand::Previous),
+    ] {
+        add_menu_action(
+            &mut popup,
+            &menu_label(&tr!(translations, get_or_default, key, default)),
+            menu::MenuFlag::Normal,
+            &action,
+            media_command,
+        );
+    }
+    popup.popup();
+    menu::MenuButton::delete(popup);
+
+    let chosen = action.borrow_mut().take();
+    if let Some(media_command) = chosen {
+        if let Err(e) = e4media::send(media_command) {
+            let message = tr!(
+                translations,
+                format,
+                "cannot-control-the-media-player",
+                &[&e.to_string()]
+            );
+            fltk::dialog::alert_default(&message);
+        }
+    }
+}
+
+/// The toggle widget: switch on or off when clicked. The state is polled by the process checker.
+fn setup_toggle(button: &mut Button, toggle: E4Toggle, translations: Arc<Mutex<Translations>>) {
+    on_click(button, move || {
+        let on = toggle.is_on().unwrap_or(false);
+        if let Err(e) = toggle.switch(!on) {
+            let message = tr!(
+                translations,
+                format,
+                "cannot-switch-the-toggle",
+                &[&e.to_string()]
+            );
+            fltk::dialog::alert_default(&message);
+        }
+    });
+}
+
+/// The trash widget: move the dropped files to the trash, and open the trash when clicked.
+/// Its state, full or empty, is polled by the process checker.
+fn setup_trash(button: &mut Button, translations: Arc<Mutex<Translations>>) {
+    let translations_clone = translations.clone();
+    button.handle(move |_, ev| match ev {
+        Event::DndEnter | Event::DndDrag | Event::DndRelease => true,
+        Event::Paste => {
+            let files = crate::e4button::dropped_files(&app::event_text());
+            if !files.is_empty() {
+                if let Err(e) = e4trash::delete(&files) {
+                    let message = tr!(
+                        translations_clone,
+                        format,
+                        "cannot-move-to-the-trash",
+                        &[&e.to_string()]
+                    );
+                    fltk::dialog::alert_default(&message);
+                }
+            }
+            true
+        }
+        _ => false,
+    });
+
+    on_click(button, move || {
+        open(&e4trash::location(), translations.clone());
+    });
+}
+
+/// Return the directory of a folder widget, read from its FOLDER key: the home directory if missing.
+fn folder_of(config: &E4Config, button_name: &str) -> PathBuf {
+    match button_ini(config, button_name).get(BUTTON_BUTTON_SECTION, "FOLDER") {
+        Some(folder) if !folder.trim().is_empty() => PathBuf::from(folder.trim()),
+        _ => dirs::home_dir().unwrap_or_default(),
+    }
+}
+
+/// The folder widget: list the files of a directory when clicked, and open the chosen one.
+fn setup_folder(button: &mut Button, folder: PathBuf, translations: Arc<Mutex<Translations>>) {
+    on_click(button, move || {
+        show_folder_list(&folder, translations.clone());
+    });
+}
+
+/// Return the entries of a folder, the directories first, skipping the hidden files.
+fn folder_entries(folder: &Path) -> std::io::Result<Vec<PathBuf>> {
+    let mut entries: Vec<PathBuf> = std::fs::read_dir(folder)?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path())
+        .filter(|path| match path.file_name().and_then(OsStr::to_str) {
+            Some(name) => !name.starts_with('.'),
+            None => true,
+        })
+        .collect();
+    entries.sort_by_key(|path| {
+        (
+            !path.is_dir(),
+            path.file_name()
+                .map(|name| name.to_string_lossy().to_lowercase()),
+        )
+    });
+    Ok(entries)
+}
+
+/// Return the mini icon of a folder entry: a thumbnail for the small images, the generic icon otherwise.
+fn mini_icon(path: &Path, generic: &Option<SharedImage>) -> Option<SharedImage> {
+    let is_image = match path.extension().and_then(OsStr::to_str) {
+        Some(extension) => FOLDER_THUMBNAIL_EXTENSIONS.contains(&extension.to_lowercase().as_str()),
+        None => false,
+    };
+    let is_small = match std::fs::metadata(path) {
+        Ok(metadata) => metadata.len() <= FOLDER_THUMBNAIL_MAX_BYTES,
+        Err(_) => false,
+    };
+    let thumbnail = if is_image && is_small {
+        SharedImage::load(path).ok()
+    } else {
+        None
+    };
+    let mut icon = thumbnail.or_else(|| generic.as_ref().map(|generic| generic.copy()))?;
+    icon.scale(FOLDER_ICON_SIZE, FOLDER_ICON_SIZE, true, true);
+    Some(icon)
+}
+
+/// Show the files of a folder in a scrollable list under the mouse, and open the chosen one
+/// with its default application.
+fn show_folder_list(folder: &Path, translations: Arc<Mutex<Translations>>) {
+    let entries = match folder_entries(folder) {
+        Ok(entries) => entries,
+        Err(e) => {
+            let message = tr!(
+                translations,
+                format,
+                "cannot-read-the-folder",
+                &[&folder.display().to_string(), &e.to_string()]
+            );
+            fltk::dialog::alert_default(&message);
+            return;
+        }
+    };
+
+    // Open the list under the mouse, or above it near the bottom of the screen
+    let lines = (entries.len() as i32).clamp(1, FOLDER_LIST_LINES);
+    let (w, h) = (FOLDER_LIST_WIDTH, lines * (FOLDER_ICON_SIZE + 4) + 4);
+    let (screen_w, screen_h) = app::screen_size();
+    let x = app::event_x_root().min(screen_w as i32 - w).max(0);
+    let mut y = app::event_y_root();
+    if y + h > screen_h as i32 {
+        y = (y - h).max(0);
+    }
+    let mut window = Window::new(x, y, w, h, None);
+    window.set_border(false);
+    let mut browser = HoldBrowser::new(0, 0, w, h, None);
+
+    if entries.is_empty() {
+        browser.add(&format!(
+            "@i@.{}",
+            tr!(translations, get_or_default, "empty-folder", "Empty folder")
+        ));
+        browser.deactivate();
+    }
+    let generic = PngImage::from_data(crate::e4initialize::generic_png_data())
+        .ok()
+        .and_then(|generic| SharedImage::from_image(&generic).ok());
+    for (n, entry) in entries.iter().enumerate() {
+        let name = match entry.file_name() {
+            Some(name) => name.to_string_lossy().to_string(),
+            None => entry.display().to_string(),
+        };
+        // @. prints the rest of the line as is, without format characters
+        browser.add(&format!("@.{}", name));
+        browser.set_icon(n as i32 + 1, mini_icon(entry, &generic));
+    }
+
+    let chosen = Rc::new(RefCell::new(None));
+    browser.set_callback({
+        let chosen = chosen.clone();
+        let mut window = window.clone();
+        move |b| {
+            if b.value() > 0 {
+                *chosen.borrow_mut() = Some(b.value() as usize - 1);
+                window.hide();
+            }
+        }
+    });
+
+    // Close the list with Esc or clicking outside of it, as a menu
+    window.handle(|w, ev| match ev {
+        Event::Push => {
+            let (x, y) = (app::event_x_root(), app::event_y_root());
+            if x < w.x() || x >= w.x() + w.w() || y < w.y() || y >= w.y() + w.h() {
+                w.hide();
+                true
+            } else {
+                false
+            }
+        }
+        Event::KeyDown if app::event_key() == Key::Escape => {
+            w.hide();
+            true
+        }
+        _ => false,
+    });
+    window.end();
+    window.show();
+    app::set_grab(Some(window.clone()));
+    while window.shown() {
+        app::wait();
+    }
+    app::set_grab(None::<Window>);
+
+    let index = *chosen.borrow();
+    if let Some(index) = index {
+        open(&entries[index].display().to_string(), translations);
+    }
+}
+
+/// The plugin widget: call the on_click function of the plugin named by its PLUGIN key.
+#[cfg(feature = "script-widgets")]
+fn setup_plugin(
+    button: &mut Button,
+    name: &str,
+    config: &E4Config,
+    translations: Arc<Mutex<Translations>>,
+) {
+    let plugin = button_ini(config, name)
+        .get(BUTTON_BUTTON_SECTION, "PLUGIN")
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    let name = name.to_string();
+    on_click(button, move || {
+        if let Err(e) = e4plugin::click(&plugin, &name) {
+            let message = tr!(translations, format, "plugin-error", &[&e.to_string()]);
+            fltk::dialog::alert_default(&message);
+        }
+    });
+}
+
+/// Return the script of a script widget, read from its SCRIPT key.
+/// A relative path is searched in the configuration directory.
+#[cfg(feature = "script-widgets")]
+fn script_of(config: &E4Config, button_name: &str) -> PathBuf {
+    let script = button_ini(config, button_name)
+        .get(BUTTON_BUTTON_SECTION, "SCRIPT")
+        .unwrap_or_default();
+    let script = PathBuf::from(script.trim());
+    if script.is_absolute() {
+        script
+    } else {
+        config.config_dir.join(script)
+    }
+}
+
+/// The script widget: run a rhai script when clicked.
+#[cfg(feature = "script-widgets")]
+fn setup_script(button: &mut Button, script: PathBuf, translations: Arc<Mutex<Translations>>) {
+    on_click(button, move || {
+        if let Err(e) = e4script::run_script(&script) {
+            let message = tr!(
+                translations,
+                format,
+                "script-error",
+                &[&script.display().to_string(), &e.to_string()]
+            );
+            fltk::dialog::alert_default(&message);
+        }
+    });
+}
+
+/// The plugin and script widgets without the script-widgets feature: a click tells they cannot run.
+#[cfg(not(feature = "script-widgets"))]
+fn setup_without_scripts(button: &mut Button, translations: Arc<Mutex<Translations>>) {
+    on_click(button, move || {
+        fltk::dialog::alert_default(&tr!(
+            translations,
+            get_or_default,
+            "scripts-not-supported",
+            "This build of e4docker runs no scripts: build it with the script-widgets feature"
+        ));
+    });
+}
@@ -0,0 +1,259 @@
+use crate::e4command::split_arguments;
+use lazy_static::lazy_static;
+use std::{
+    collections::HashMap,
+    env, fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+/// The longest wait for the window of a launched program: then it is launched, with or without a window.
+pub const STARTUP_TIMEOUT: Duration = Duration::from_secs(30);
+
+lazy_static! {
+    /// The StartupWMClass of the .desktop entries, by the file name of their program.
+    static ref DESKTOP_CLASSES: HashMap<String, String> = read_desktop_entries(desktop_entry_class);
+}
+
+/// Return the file name of the program cmd, like firefox for /usr/bin/firefox.
+pub(crate) fn program_name(cmd: &str) -> Option<String> {
+    Path::new(cmd.trim())
+        .file_name()
+        .and_then(|name| name.to_str())
+        .filter(|name| !name.is_empty())
+        .map(str::to_string)
+}
+
+/// Return the file name of the program launched by a .desktop entry and the value of its key, if set.
+fn desktop_entry_value(entry: &str, key: &str) -> Option<(String, String)> {
+    let mut in_entry = false;
+    let mut exec = None;
+    let mut value = None;
+    for line in entry.lines().map(str::trim) {
+        if line.starts_with('[') {
+            // The actions have their own Exec
+            in_entry = line == "[Desktop Entry]";
+        } else if let (true, Some((name, text))) = (in_entry, line.split_once('=')) {
+            match name.trim() {
+                "Exec" => exec = Some(text.trim().to_string()),
+                name if name == key => value = Some(text.trim().to_string()),
+                _ => {}
+            }
+        }
+    }
+    let value = value.filter(|value| !value.is_empty())?;
+    // Skip env and its variables, as in Exec=env GDK_BACKEND=x11 program
+    let program = split_arguments(&exec?)
+        .into_iter()
+        .filter(|argument| !argument.contains('='))
+        .find(|argument| argument != "env")?;
+    Some((program_name(&program)?, value))
+}
+
+/// Return the file name of the program launched by a .desktop entry and its StartupWMClass, if set.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4window::desktop_entry_class;
+///
+/// let entry = "[Desktop Entry]\nName=Visual Studio Code\nExec=/usr/share/code/code %F\nStartupWMClass=Code\n";
+/// assert_eq!(
+///     desktop_entry_class(entry),
+///     Some(("code".to_string(), "Code".to_string()))
+/// );
+/// ```
+pub fn desktop_entry_class(entry: &str) -> Option<(String, String)> {
+    desktop_entry_value(entry, "StartupWMClass")
+}
+
+/// Return the file name of the program launched by a .desktop entry and its Icon, if set:
+/// the name of an icon of the theme or the path of an image.
+pub fn desktop_entry_icon(entry: &str) -> Option<(String, String)> {
+    desktop_entry_value(entry, "Icon")
+}
+
+/// Return the data directories, where the .desktop entries and the icon themes are: the one of the user first,
+/// as it overrides the others.
+pub(crate) fn data_dirs() -> Vec<PathBuf> {
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".local/share")));
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+    data_home
+        .into_iter()
+        .chain(env::split_paths(&data_dirs))
+        .collect()
+}
+
+/// Read a value of the installed .desktop entries, by the file name of their program: see [desktop_entry_class].
+fn read_desktop_entries(
+    entry_value: fn(&str) -> Option<(String, String)>,
+) -> HashMap<String, String> {
+    let mut values = HashMap::new();
+    for dir in data_dirs().into_iter().map(|dir| dir.join("applications")) {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(_) => continue,
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().and_then(|extension| extension.to_str()) != Some("desktop") {
+                continue;
+            }
+            let value = fs::read_to_string(&path)
+                .ok()
+                .and_then(|entry| entry_value(&entry));
+            if let Some((program, value)) = value {
+                values.entry(program).or_insert(value);
+            }
+        }
+    }
+    values
+}
+
+/// Return the Icon of the .desktop entry of cmd, if any. The entries are read again at each call.
+pub fn desktop_icon(cmd: &str) -> Option<String> {
+    read_desktop_entries(desktop_entry_icon).remove(&program_name(cmd)?)
+}
+
+/// Return the WM_CLASS of the windows of cmd, from the StartupWMClass of its .desktop entry, if any.
+pub fn wm_class(cmd: &str) -> Option<String> {
+    DESKTOP_CLASSES.get(&program_name(cmd)?).cloned()
+}
+
+/// Return the class searched to find the windows of cmd: its WM_CLASS if known, otherwise the name of its program.
+pub fn window_class(cmd: &str) -> Option<String> {
+    wm_class(cmd).or_else(|| program_name(cmd))
+}
+
+/// Return the ids of the visible windows of class.
+///
+/// Only X11 is supported, through xdotool: elsewhere, or without xdotool, no window is found.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn window_ids(class: &str) -> Vec<String> {
+    // xdotool takes a regular expression
+    let output = match std::process::Command::new("xdotool")
+        .args(["search", "--onlyvisible", "--class"])
+        .arg(regex::escape(class))
+        .output()
+    {
+        Ok(output) if output.status.success() => output,
+        _ => return vec![],
+    };
+    String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .map(|id| id.trim().to_string())
+        .filter(|id| !id.is_empty())
+        .collect()
+}
+
+/// Return the ids of the visible windows of class.
+///
+/// Only X11 is supported, through xdotool: elsewhere, or without xdotool, no window is found.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub fn window_ids(_class: &str) -> Vec<String> {
+    vec![]
+}
+
+/// Show the window of the running program cmd in front of the others, and return true: false if none is found.
+///
+/// Only X11 is supported, through xdotool, like [window_ids]: it asks the window manager with _NET_ACTIVE_WINDOW.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn activate(cmd: &str) -> bool {
+    let id = match window_class(cmd).and_then(|class| window_ids(&class).into_iter().next()) {
+        Some(id) => id,
+        None => return false,
+    };
+    std::process::Command::new("xdotool")
+        .args(["windowactivate", &id])
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Show the running app cmd in front of the others, and return true: false if it is not running.
+///
+/// Only the .app bundles are supported, through open, which would launch them if they were not running.
+#[cfg(target_os = "macos")]
+pub fn activate(cmd: &str) -> bool {
+    let bundle = Path::new(cmd.trim());
+    let name = match bundle.file_stem().and_then(|name| name.to_str()) {
+        Some(name) if crate::e4bundle::is_app_bundle(bundle) => name,
+        _ => return false,
+    };
+    let is_running = std::process::Command::new("pgrep")
+        .args(["-x", name])
+        .output()
+        .is_ok_and(|output| output.status.success());
+    is_running
+        && std::process::Command::new("open")
+            .arg("-a")
+            .arg(bundle)
+            .status()
+            .is_ok_and(|status| status.success())
+}
+
+/// Show the window of the running program cmd in front of the others, restored if minimized, and return true:
+/// false if none is found. Its window is the first visible top-level window of its processes.
+#[cfg(target_os = "windows")]
+pub fn activate(cmd: &str) -> bool {
+    use std::ffi::c_void;
+
+    /// The owner of a window, asked to GetWindow: the dialogs have one.
+    const GW_OWNER: u32 = 4;
+    /// Restore a minimized window, told to ShowWindow.
+    const SW_RESTORE: i32 = 9;
+
+    #[link(name = "user32")]
+    extern "system" {
+        fn EnumWindows(
+            callback: extern "system" fn(*mut c_void, isize) -> i32,
+            parameter: isize,
+        ) -> i32;
+        fn GetWindowThreadProcessId(hwnd: *mut c_void, pid: *mut u32) -> u32;
+        fn IsWindowVisible(hwnd: *mut c_void) -> i32;
+        fn GetWindow(hwnd: *mut c_void, command: u32) -> *mut c_void;
+        fn IsIconic(hwnd: *mut c_void) -> i32;
+        fn ShowWindow(hwnd: *mut c_void, command: i32) -> i32;
+        fn SetForegroundWindow(hwnd: *mut c_void) -> i32;
+    }
+
+    /// The processes of cmd, and their window once found.
+    struct Search {
+        pids: Vec<u32>,
+        window: *mut c_void,
+    }
+
+    extern "system" fn find_window(hwnd: *mut c_void, parameter: isize) -> i32 {
+        let search = unsafe { &mut *(parameter as *mut Search) };
+        let mut pid = 0;
+        unsafe { GetWindowThreadProcessId(hwnd, &mut pid) };
+        let is_main = unsafe { IsWindowVisible(hwnd) != 0 && GetWindow(hwnd, GW_OWNER).is_null() };
+        if is_main && search.pids.contains(&pid) {
+            search.window = hwnd;
+            // Stop the enumeration
+            return 0;
+        }
+        1
+    }
+
+    let mut search = Search {
+        pids: crate::e4processes::process_ids(cmd),
+        window: std::ptr::null_mut(),
+    };
+    if search.pids.is_empty() {
+        return false;
+    }
+    unsafe { EnumWindows(find_window, &mut search as *mut Search as isize) };
+    if search.window.is_null() {
+        return false;
+    }
+    unsafe {
+        if IsIconic(search.window) != 0 {
+            ShowWindow(search.window, SW_RESTORE);
+        }
+        SetForegroundWindow(search.window) != 0
+    }
+}
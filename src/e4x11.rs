@@ -0,0 +1,185 @@
+use crate::{e4screen::E4Area, e4style::E4Edge};
+use fltk::window::Window;
+use std::cell::Cell;
+
+thread_local! {
+    /// True if the dock reserves its space on the screen: see [set_reserve_space].
+    static RESERVE_SPACE: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Return the _NET_WM_STRUT_PARTIAL of the dock (x, y, w, h) on screen, in a root window (width, height) large:
+/// the space from the side of screen nearest to the dock to the far side of the dock, along the dock.
+/// The values are the left, right, top and bottom reserved, then the start and the end of each of them.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::e4x11::strut;
+///
+/// // On the bottom edge of a 1920x1080 screen
+/// assert_eq!(
+///     strut((100, 1000, 400, 80), (0, 0, 1920, 1080), (1920, 1080)),
+///     [0, 0, 0, 80, 0, 0, 0, 0, 0, 0, 100, 499]
+/// );
+/// // A little below the top edge of the second screen, right of a 1920 pixels wide one
+/// assert_eq!(
+///     strut((2400, 10, 400, 80), (1920, 0, 1920, 1080), (3840, 1080)),
+///     [0, 0, 90, 0, 0, 0, 0, 0, 2400, 2799, 0, 0]
+/// );
+/// ```
+pub fn strut(dock: E4Area, screen: E4Area, (root_w, root_h): (i32, i32)) -> [i64; 12] {
+    let (x, y, w, h) = dock;
+    let (screen_x, screen_y, screen_w, screen_h) = screen;
+    let distances = [
+        (E4Edge::Bottom, screen_y + screen_h - (y + h)),
+        (E4Edge::Top, y - screen_y),
+        (E4Edge::Left, x - screen_x),
+        (E4Edge::Right, screen_x + screen_w - (x + w)),
+    ];
+    let edge = distances
+        .iter()
+        .min_by_key(|(_, distance)| *distance)
+        .map_or(E4Edge::Bottom, |(edge, _)| *edge);
+    let (x, y, w, h) = (x as i64, y as i64, w as i64, h as i64);
+    let mut strut = [0; 12];
+    match edge {
+        E4Edge::Left => {
+            strut[0] = x + w;
+            strut[4] = y;
+            strut[5] = y + h - 1;
+        }
+        E4Edge::Right => {
+            strut[1] = root_w as i64 - x;
+            strut[6] = y;
+            strut[7] = y + h - 1;
+        }
+        E4Edge::Top => {
+            strut[2] = y + h;
+            strut[8] = x;
+            strut[9] = x + w - 1;
+        }
+        _ => {
+            strut[3] = root_h as i64 - y;
+            strut[10] = x;
+            strut[11] = x + w - 1;
+        }
+    }
+    strut.map(|value| value.max(0))
+}
+
+/// Reserve the space of the dock from now on, so the maximized windows do not cover it, or stop reserving it.
+pub fn set_reserve_space(reserve: bool) {
+    RESERVE_SPACE.with(|reserve_space| reserve_space.set(reserve));
+}
+
+/// Return true if the dock reserves its space, through the struts of X11: never on Windows and macOS.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn is_reserving_space() -> bool {
+    RESERVE_SPACE.with(Cell::get)
+}
+
+/// Return true if the dock reserves its space, through the struts of X11: never on Windows and macOS.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub fn is_reserving_space() -> bool {
+    false
+}
+
+/// Tell the window manager the space reserved by wind, the dock, once shown: its [strut] if it reserves
+/// its space, none otherwise. It must be called in the main thread, again whenever the dock moves.
+#[cfg(not(any(target_os = "windows", target_os = "macos")))]
+pub fn reserve_space(wind: &Window) {
+    use fltk::{app, prelude::*};
+    use std::ffi::{c_char, c_int, c_long, c_uchar, c_ulong, c_void};
+
+    /// The type of the struts, XA_CARDINAL.
+    const CARDINAL: c_ulong = 6;
+    /// Replace the property, told to XChangeProperty.
+    const PROP_MODE_REPLACE: c_int = 0;
+
+    #[link(name = "X11")]
+    extern "C" {
+        fn XInternAtom(display: *mut c_void, name: *const c_char, only_if_exists: c_int)
+            -> c_ulong;
+        fn XChangeProperty(
+            display: *mut c_void,
+            window: c_ulong,
+            property: c_ulong,
+            property_type: c_ulong,
+            format: c_int,
+            mode: c_int,
+            data: *const c_uchar,
+            elements: c_int,
+        ) -> c_int;
+        fn XDeleteProperty(display: *mut c_void, window: c_ulong, property: c_ulong) -> c_int;
+        fn XFlush(display: *mut c_void) -> c_int;
+    }
+
+    let display = app::display();
+    let window = wind.raw_handle() as c_ulong;
+    if display.is_null() || window == 0 || !wind.shown() {
+        return;
+    }
+    // _NET_WM_STRUT holds the first four values, for the window managers ignoring the partial struts
+    let (partial, full) = unsafe {
+        (
+            XInternAtom(display, c"_NET_WM_STRUT_PARTIAL".as_ptr(), 0),
+            XInternAtom(display, c"_NET_WM_STRUT".as_ptr(), 0),
+        )
+    };
+    if !is_reserving_space() {
+        unsafe {
+            XDeleteProperty(display, window, partial);
+            XDeleteProperty(display, window, full);
+            XFlush(display);
+        }
+        return;
+    }
+    // The struts are in the pixels of the root window, made of all the screens
+    let screen = app::screen_num(wind.x() + wind.w() / 2, wind.y() + wind.h() / 2);
+    let scale = app::screen_scale(screen) as f64;
+    let pixels = |(x, y, w, h): E4Area| {
+        let scaled = |value: i32| (value as f64 * scale).round() as i32;
+        (scaled(x), scaled(y), scaled(w), scaled(h))
+    };
+    let (root_w, root_h) = (0..app::screen_count())
+        .map(|screen| pixels(app::screen_xywh(screen)))
+        .fold((0, 0), |(root_w, root_h), (x, y, w, h)| {
+            (root_w.max(x + w), root_h.max(y + h))
+        });
+    // Xlib takes the 32 bits values of a property as longs
+    let values: Vec<c_long> = strut(
+        pixels((wind.x(), wind.y(), wind.w(), wind.h())),
+        pixels(app::screen_xywh(screen)),
+        (root_w, root_h),
+    )
+    .iter()
+    .map(|value| *value as c_long)
+    .collect();
+    unsafe {
+        XChangeProperty(
+            display,
+            window,
+            partial,
+            CARDINAL,
+            32,
+            PROP_MODE_REPLACE,
+            values.as_ptr() as *const c_uchar,
+            values.len() as c_int,
+        );
+        XChangeProperty(
+            display,
+            window,
+            full,
+            CARDINAL,
+            32,
+            PROP_MODE_REPLACE,
+            values.as_ptr() as *const c_uchar,
+            4,
+        );
+        XFlush(display);
+    }
+}
+
+/// Tell the window manager the space reserved by wind, the dock: only on X11, elsewhere nothing is reserved.
+#[cfg(any(target_os = "windows", target_os = "macos"))]
+pub fn reserve_space(_wind: &Window) {}
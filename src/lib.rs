@@ -1,19 +1,171 @@
 /// This module reads and returns the program global configuration from confi/e4docker.conf
 pub mod e4config;
 
+/// This module reads and writes the configuration files, on the file system or in memory for the tests.
+pub mod e4storage;
+
+/// This module keeps the named layouts: orders of the buttons to switch between, hiding the others.
+pub mod e4layout;
+
+/// This module compares two configurations, to show what changes before replacing the current one.
+pub mod e4diff;
+
+/// This module keeps the configuration in a synced directory, like a cloud folder, to share it between machines.
+pub mod e4sync;
+
+/// This module reads the appearance of the dock from e4docker.conf, like the style of its frame.
+pub mod e4style;
+
+/// This module applies the light or the dark theme, following the preference of the system with THEME=auto.
+pub mod e4appearance;
+
 /// This module manages the [e4command::E4Command] to be executed when a [e4button::E4Button] has been pressed.
 pub mod e4command;
 
-/// This program manages the icon of a [e4button::E4Button].
+/// This module edits the arguments of a command as a list, one argument per row.
+pub mod e4arguments;
+
+/// This module copies the buttons to the clipboard as JSON, to paste them in another dock.
+pub mod e4share;
+
+/// This module imports the buttons described in a JSON file, like one generated by another tool.
+pub mod e4import;
+
+/// This module saves the dock as a PNG image, to share a setup or document it.
+pub mod e4snapshot;
+
+/// This program manages the icon of a [e4button::E4Button] and finds the icon of a command.
 pub mod e4icon;
 
+/// This module reads the macOS .app bundles and their .icns icons.
+pub mod e4bundle;
+
+/// This module downloads the favicons of the sites opened by the URL buttons.
+pub mod e4favicon;
+
+/// This module launches and enumerates the UWP / Microsoft Store apps on Windows.
+pub mod e4uwp;
+
+/// This module lists the applications installed by the .desktop entries, on Linux and the BSDs.
+pub mod e4desktop;
+
+/// This module keeps the assets directory tidy: it finds the icons named by no button, to delete or archive them,
+/// and copies in the icons named by their paths outside it.
+pub mod e4assets;
+
+/// This module checks the configuration for likely mistakes, like buttons launching the same command.
+pub mod e4lint;
+
 /// This module manages a button.
 pub mod e4button;
 
+/// This module builds docks in code, for the programs embedding e4docker.
+pub mod e4dock;
+
+/// This module keeps the widgets of the dock in the window, to update one button without drawing all of them again.
+pub mod e4dockview;
+
+/// This module manages the widgets: buttons with a built-in behaviour instead of a command.
+pub mod e4widget;
+
+/// This module controls the media players: MPRIS over D-Bus on Linux, the media keys on Windows.
+pub mod e4media;
+
+/// This module runs the commands of the toggle widgets, like the wifi and bluetooth switches.
+pub mod e4toggle;
+
+/// This module runs the badge commands of the buttons and draws their text on the icons.
+pub mod e4badge;
+
+/// This module makes the buttons asking for attention blink until they are clicked.
+pub mod e4attention;
+
+/// This module moves files to the trash and tells if the trash is empty.
+pub mod e4trash;
+
+/// This module loads the rhai plugins adding widgets and launch hooks.
+#[cfg(feature = "script-widgets")]
+pub mod e4plugin;
+
+/// This module runs the rhai scripts of the script buttons and gives them their API.
+#[cfg(feature = "script-widgets")]
+pub mod e4script;
+
+/// This module runs the commands of the HOOKS section of e4docker.conf on the dock events.
+pub mod e4hooks;
+
+/// This module lets one dock run per profile: a dock started again shows the running one instead.
+pub mod e4instance;
+
+/// This module dumps the state of the running dock as JSON, for debugging and scripting.
+#[cfg(feature = "dump-state")]
+pub mod e4dump;
+
+/// This module runs the subcommands of the command line, like import-json, with their exit codes.
+pub mod e4cli;
+
+/// This module lists the buttons for the scripts and the status bars, for `e4docker list-buttons`.
+pub mod e4list;
+
+/// This module carries the events of the dock, like a changed configuration, from their source to the window.
+pub mod e4events;
+
+/// This module times the phases of the startup, for the --profile-startup flag.
+pub mod e4profile;
+
+/// This module shows the toasts: messages closing by themselves.
+pub mod e4toast;
+
+/// This module hides the dock until the pointer touches an edge or a corner of the screen.
+pub mod e4reveal;
+
+/// This module launches the buttons by their number, showing the numbers over them.
+pub mod e4quicklaunch;
+
+/// This module recognizes the swipes on the dock, from the scroll events of touchpads and touchscreens.
+pub mod e4gesture;
+
+/// This module follows the work area of the screen, keeping the dock inside it when it changes.
+pub mod e4screen;
+
+/// This module gives the dialogs the same keys: Enter saves, Esc cancels, asking first to save the changes.
+pub mod e4dialog;
+
+/// This module cycles the frames of the animated GIF and APNG icons.
+pub mod e4animation;
+
+/// This module shows the name of the hovered button in a floating label above it.
+pub mod e4label;
+
+/// This module finds the windows of the programs, by the StartupWMClass of their .desktop entries.
+pub mod e4window;
+
+/// This module captures thumbnails of the windows of the running programs, shown when their buttons are hovered.
+pub mod e4preview;
+
 /// To create a generic button
 pub mod e4initialize;
 
+/// This module selects several buttons with Ctrl+click, to delete, hide or export them at once.
+pub mod e4selection;
+
+/// This module renames a button on the dock, with a slow double click on it.
+pub mod e4rename;
+
+/// This module keeps the runtime state, like the recent files of each [e4button::E4Button].
+pub mod e4state;
+
+/// This module reports the usage of the dock: the launches of each button, the most active hours, the unused buttons.
+pub mod e4stats;
+
 /// Module for translations
 pub mod translations;
 
 pub mod e4processes;
+
+/// This module reserves the space of the dock on X11, so the maximized windows do not cover it.
+pub mod e4x11;
+
+/// This module draws the dock with egui instead of FLTK, for the displays where FLTK scales badly.
+#[cfg(feature = "egui-frontend")]
+pub mod e4egui;
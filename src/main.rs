@@ -7,7 +7,25 @@
 //! - assets: put here the icons for your favourite apps.
 
 use e4docker::{
-    e4button::E4Button, e4config, e4config::E4Config, e4initialize, e4processes, tr,
+    e4appearance, e4assets, e4attention,
+    e4button::{self, E4Button, E4ButtonType},
+    e4cli::{self, E4ExitCode},
+    e4command::E4Command,
+    e4config,
+    e4config::E4Config,
+    e4dockview::DockView,
+    e4events::{self, E4Event},
+    e4gesture::{E4SlowClickTracker, E4SwipeTracker},
+    e4hooks::{self, E4Hook},
+    e4import, e4initialize, e4instance, e4label, e4layout, e4lint, e4list, e4processes,
+    e4profile::{self, E4Phase},
+    e4quicklaunch, e4rename, e4reveal, e4screen, e4selection, e4share, e4snapshot,
+    e4state::E4State,
+    e4stats,
+    e4storage::E4Storage,
+    e4sync, e4toast,
+    e4widget::menu_label,
+    e4x11, tr,
     translations::Translations,
 };
 use fltk::{app, enums, enums::FrameType, frame::Frame, menu, prelude::*, window::Window};
@@ -18,20 +36,75 @@ use std::{
     path::Path,
     rc::Rc,
     sync::{Arc, Mutex},
+    time::Instant,
 };
 
 const APP_TITLE: &str = "E4 Docker";
 
+/// The width of the right edge of the window dragged to resize it.
+const RESIZE_HANDLE_WIDTH: i32 = 6;
+
+/// An action chosen in the popup menu of a button.
+#[derive(Clone)]
+enum PopupAction {
+    MoveLeft,
+    Edit,
+    Delete,
+    MoveRight,
+    /// Show the directory of the button command in the file manager.
+    OpenLocation,
+    /// Copy the command line of the button to the clipboard.
+    CopyCommand,
+    /// Copy the button to the clipboard, as JSON.
+    CopyButton,
+    /// Add the button in the clipboard after the button.
+    PasteButton,
+    /// Hide the button, keeping its configuration file.
+    Hide,
+    /// Open a recent file with the button command.
+    Recent(String),
+    /// Delete the selected buttons.
+    DeleteSelected,
+    /// Hide the selected buttons.
+    HideSelected,
+    /// Export the selected buttons to a JSON file.
+    ExportSelected,
+    /// Deselect the buttons.
+    Deselect,
+}
+
+/// Add an item to a popup menu, recording popup_action in action when chosen.
+fn add_popup_action(
+    popup: &mut menu::MenuButton,
+    label: &str,
+    active: bool,
+    action: &Rc<RefCell<Option<PopupAction>>>,
+    popup_action: PopupAction,
+) {
+    let flag = if active {
+        menu::MenuFlag::Normal
+    } else {
+        menu::MenuFlag::Inactive
+    };
+    let action = action.clone();
+    popup.add(label, enums::Shortcut::None, flag, move |_| {
+        *action.borrow_mut() = Some(popup_action.clone());
+    });
+}
+
 fn about(translations: Arc<Mutex<Translations>>) {
     let version = env!("CARGO_PKG_VERSION");
     let authors = env!("CARGO_PKG_AUTHORS");
+    let about = tr!(
+        translations,
+        format_display,
+        "about-dialog",
+        &[&version, &authors]
+    );
+    // The translations missing so far, to report them
+    let diagnostics = translations.lock().unwrap().diagnostics();
     e4config::create_about_dialog(
-        &tr!(
-            translations,
-            format_display,
-            "about-dialog",
-            &[&version, &authors]
-        ),
+        &format!("{}\n\n{}", about, diagnostics),
         translations.clone(),
     );
 }
@@ -51,24 +124,215 @@ fn settings(config: &mut E4Config, translations: Arc<Mutex<Translations>>) {
     }
 }
 
-/// Redraw the [app] window.
+/// Save the position of the window in e4docker.conf, showing the error if it cannot be saved.
+/// A read-only configuration keeps the position until the dock is closed.
+fn save_position(config: &mut E4Config, x: i32, y: i32, translations: Arc<Mutex<Translations>>) {
+    if e4initialize::is_read_only() {
+        return;
+    }
+    let result = config
+        .set_value(
+            e4config::E4DOCKER_DOCKER_SECTION.to_string(),
+            "x".to_string(),
+            Some(x.to_string()),
+            translations.clone(),
+        )
+        .and_then(|_| {
+            config.set_value(
+                e4config::E4DOCKER_DOCKER_SECTION.to_string(),
+                "y".to_string(),
+                Some(y.to_string()),
+                translations,
+            )
+        });
+    if let Err(e) = result {
+        fltk::dialog::alert_default(&e.to_string());
+    }
+}
+
+/// Ask where to save the PNG image of wind, the dock, and save it.
+fn save_dock_preview(wind: &Window, translations: Arc<Mutex<Translations>>) {
+    let mut chooser = fltk::dialog::FileChooser::new(
+        ".",
+        "*.png",
+        fltk::dialog::FileChooserType::Create,
+        &tr!(
+            translations,
+            get_or_default,
+            "save-dock-preview",
+            "Save dock preview"
+        ),
+    );
+    chooser.show();
+    while chooser.shown() {
+        app::wait();
+    }
+    if let Some(path) = chooser.value(1) {
+        match e4snapshot::save(wind, Path::new(&path), translations.clone()) {
+            Ok(path) => e4toast::show(&tr!(
+                translations,
+                format,
+                "dock-preview-saved",
+                &[&path.display().to_string()]
+            )),
+            Err(e) => fltk::dialog::alert_default(&e.to_string()),
+        }
+    }
+}
+
+/// Ask for a JSON file describing buttons and add them to the dock.
+fn import_buttons(config: &mut E4Config, translations: Arc<Mutex<Translations>>) {
+    let mut chooser = fltk::dialog::FileChooser::new(
+        ".",
+        "*.json",
+        fltk::dialog::FileChooserType::Single,
+        &tr!(
+            translations,
+            get_or_default,
+            "import-buttons",
+            "Import buttons"
+        ),
+    );
+    chooser.show();
+    while chooser.shown() {
+        app::wait();
+    }
+    if let Some(path) = chooser.value(1) {
+        match e4import::import_file(config, Path::new(&path), translations.clone()) {
+            Ok(names) => {
+                e4toast::show(&tr!(
+                    translations,
+                    format,
+                    "buttons-imported",
+                    &[&names.len().to_string()]
+                ));
+                for name in names {
+                    e4events::publish(E4Event::ButtonAdded(name));
+                }
+                // The same buttons imported twice
+                e4lint::resolve_duplicates(config, true, translations.clone());
+            }
+            Err(e) => {
+                let message = tr!(
+                    translations,
+                    format,
+                    "cannot-import-the-buttons",
+                    &[&e.to_string()]
+                );
+                fltk::dialog::alert_default(&message);
+            }
+        }
+    }
+}
+
+/// Ask for a JSON file and export the buttons names of config to it, to import them in another dock.
+fn export_buttons(config: &E4Config, names: &[String], translations: Arc<Mutex<Translations>>) {
+    let mut chooser = fltk::dialog::FileChooser::new(
+        ".",
+        "*.json",
+        fltk::dialog::FileChooserType::Create,
+        &tr!(
+            translations,
+            get_or_default,
+            "export-buttons",
+            "Export buttons"
+        ),
+    );
+    chooser.show();
+    while chooser.shown() {
+        app::wait();
+    }
+    if let Some(path) = chooser.value(1) {
+        match e4import::export_file(config, names, Path::new(&path)) {
+            Ok(_) => e4toast::show(&tr!(
+                translations,
+                format,
+                "buttons-exported",
+                &[&names.len().to_string(), &path]
+            )),
+            Err(e) => {
+                let message = tr!(
+                    translations,
+                    format,
+                    "cannot-export-the-buttons",
+                    &[&e.to_string()]
+                );
+                fltk::dialog::alert_default(&message);
+            }
+        }
+    }
+}
+
+/// Add the button copied as JSON after the entry at index of e4docker.conf, renaming it if its name is taken.
+fn paste_button(
+    config: &mut E4Config,
+    index: usize,
+    json: &str,
+    translations: Arc<Mutex<Translations>>,
+) {
+    let result = e4share::button_from_json(json).and_then(|(name, button)| {
+        let name = if config.is_button_name_taken(&name, None) {
+            config.unique_button_name(&name)
+        } else {
+            name
+        };
+        config.insert_button(index, &name, &button, translations.clone())?;
+        Ok(name)
+    });
+    match result {
+        Ok(name) => e4events::publish(E4Event::ButtonAdded(name)),
+        Err(e) => {
+            let message = tr!(
+                translations,
+                format,
+                "cannot-paste-the-button",
+                &[&e.to_string()]
+            );
+            fltk::dialog::alert_default(&message);
+        }
+    }
+}
+
+/// Redraw the [app] window, replacing the shared buttons, and return its [DockView].
 fn redraw_window(
     project_config_dir: &Path,
     wind: &mut Window,
+    buttons: &Arc<Mutex<Vec<E4Button>>>,
     translations: Arc<Mutex<Translations>>,
-) -> Result<Vec<E4Button>, Box<dyn std::error::Error>> {
+) -> Result<DockView, Box<dyn std::error::Error>> {
     // Read the global configuration
-    let config = Rc::new(RefCell::new(E4Config::read(
-        project_config_dir,
-        translations.clone(),
+    let config = Rc::new(RefCell::new(e4profile::time(
+        E4Phase::ConfigParsing,
+        || E4Config::read(project_config_dir, translations.clone()),
     )?));
+    e4hooks::set(config.borrow().hooks.clone());
+    // The widgets created from now on take the colors of the theme
+    e4appearance::apply(config.borrow().style.theme);
+    // The dialogs opened from now on, beside the dock if asked
+    e4screen::set_dialog_position(config.borrow().style.dialog_position);
+    // Slid off the screen when the pointer leaves it, if asked
+    e4reveal::set_autohide(
+        config.borrow().style.autohide,
+        config.borrow().style.reveal_edge,
+    );
+    // Kept off by the maximized windows, if asked: a dock hidden by the auto-hide reserves nothing
+    e4x11::set_reserve_space(
+        config.borrow().style.reserve_space && config.borrow().style.autohide <= 0.0,
+    );
     let config_clone = config.clone();
     let config_second_clone = config.clone();
     let config_third_clone = config.clone();
     let config_fourth_clone = config.clone();
+    let config_fifth_clone = config.clone();
 
     let menu_height = round(config.borrow().window_height as f64 / 3.0, 0) as i32;
+    // The widgets created from now on take the scaled font
+    app::set_font_size(config.borrow().style.font_size());
+    // The numbers of the quick launch are children of the window
+    e4quicklaunch::hide_numbers();
     wind.clear();
+    // The window has been ended by the previous drawing
+    wind.begin();
     wind.set_size(
         config.borrow().window_width,
         config.borrow().window_height + 2 * menu_height,
@@ -82,28 +346,24 @@ fn redraw_window(
         //.center_of(&wind)
         .center_of(wind)
         .with_label("");
-    frame.set_frame(FrameType::EngravedBox);
+    frame.set_frame(config.borrow().style.frame_style.frame_type());
+    if !config.borrow().style.show_frame {
+        frame.hide();
+    }
     // Move the frame down to let space for the MenuBar
     frame.set_pos(frame.x(), frame.y() + menu_height);
-    // Remove the border
-    wind.set_border(false);
+    // Remove the border, unless asked
+    wind.set_border(config.borrow().style.window_border);
 
     // Put the buttons in the window
-    let buttons =
-        e4docker::e4button::create_buttons(&config.borrow(), wind, &frame, translations.clone());
-
-    let buttons_second_clone = buttons?.clone();
+    *buttons.lock().unwrap() =
+        e4docker::e4button::create_buttons(&config.borrow(), wind, &frame, translations.clone())?;
+    // The buttons drawn again are not selected
+    e4selection::clear(&mut buttons.lock().unwrap());
 
-    let mut buttons_names: Vec<String> = vec![];
-
-    let buttons_for_names =
-        e4docker::e4button::create_buttons(&config.borrow(), wind, &frame, translations.clone());
-    for button in buttons_for_names? {
-        buttons_names.push(button.name);
-    }
     // For the menu bar
     let mut menubar = menu::MenuBar::default().with_size(config.borrow().window_width, menu_height);
-    menubar.set_color(fltk::enums::Color::from_u32(0xe8dcca));
+    menubar.set_color(e4appearance::menu_color());
     menubar.set_frame(FrameType::FlatBox);
     let new_menu = match tr!(translations, get, "new-button-menu") {
         Some(m) => m.to_string(),
@@ -126,10 +386,17 @@ fn redraw_window(
     let translations_third_clone = translations.clone();
     let translations_fourth_clone = translations.clone();
 
+    // A read-only configuration cannot be edited: the buttons can only be launched
+    let read_only = e4initialize::is_read_only();
+    let edit_flag = if read_only {
+        menu::MenuFlag::Inactive
+    } else {
+        menu::MenuFlag::Normal
+    };
     menubar.add(
         &new_menu,
         enums::Shortcut::Ctrl | 'n',
-        menu::MenuFlag::Normal,
+        edit_flag,
         move |_| {
             E4Button::new_button(&mut config_clone.borrow_mut(), translations_clone.clone());
         },
@@ -138,7 +405,7 @@ fn redraw_window(
     menubar.add(
         &settings_menu,
         enums::Shortcut::Ctrl | 's',
-        menu::MenuFlag::Normal,
+        edit_flag,
         move |_| {
             settings(
                 &mut config_second_clone.borrow_mut(),
@@ -146,6 +413,301 @@ fn redraw_window(
             );
         },
     );
+    // Hidden, the dock is revealed by the pointer on the edge of the screen
+    menubar.add(
+        &tr!(
+            translations,
+            get_or_default,
+            "file-hide-menu",
+            "&File/Hide the dock\t"
+        ),
+        enums::Shortcut::Ctrl | 'h',
+        menu::MenuFlag::Normal,
+        {
+            let wind = wind.clone();
+            let edge = config.borrow().style.reveal_edge;
+            move |_| e4reveal::hide(&wind, edge)
+        },
+    );
+    // The configuration files edited by hand, read again without restarting
+    menubar.add(
+        &tr!(
+            translations,
+            get_or_default,
+            "file-reload-menu",
+            "&File/Reload\t"
+        ),
+        enums::Shortcut::None | enums::Key::F5,
+        menu::MenuFlag::Normal,
+        |_| e4events::publish(E4Event::ConfigChanged),
+    );
+    // The configuration files, without searching where they are on each system
+    menubar.add(
+        &tr!(
+            translations,
+            get_or_default,
+            "file-open-configuration-folder-menu",
+            "&File/Open configuration folder\t"
+        ),
+        enums::Shortcut::None,
+        menu::MenuFlag::Normal,
+        {
+            let config_dir = config.borrow().config_dir.clone();
+            let translations = translations.clone();
+            move |_| {
+                let target = config_dir.display().to_string();
+                if let Err(e) = E4Command::open_with_default_app(&target) {
+                    let message = tr!(
+                        translations,
+                        format,
+                        "failed-to-execute-command",
+                        &[&target, &e.to_string()]
+                    );
+                    fltk::dialog::alert_default(&message);
+                }
+            }
+        },
+    );
+    menubar.add(
+        &tr!(
+            translations,
+            get_or_default,
+            "file-edit-configuration-menu",
+            "&File/Edit e4docker.conf...\t"
+        ),
+        enums::Shortcut::None,
+        edit_flag,
+        {
+            let config_file = e4config::config_file(&config.borrow().config_dir);
+            let translations = translations.clone();
+            move |_| {
+                // Once saved, File/Reload applies the changes
+                let target = config_file.display().to_string();
+                if let Err(e) = E4Command::edit_with_default_editor(&target) {
+                    let message = tr!(
+                        translations,
+                        format,
+                        "failed-to-execute-command",
+                        &[&target, &e.to_string()]
+                    );
+                    fltk::dialog::alert_default(&message);
+                }
+            }
+        },
+    );
+    // The buttons generated by another tool
+    menubar.add(
+        &tr!(
+            translations,
+            get_or_default,
+            "file-import-buttons-menu",
+            "&File/Import buttons...\t"
+        ),
+        enums::Shortcut::None,
+        edit_flag,
+        {
+            let config = config.clone();
+            let translations = translations.clone();
+            move |_| import_buttons(&mut config.borrow_mut(), translations.clone())
+        },
+    );
+    // The dock as a PNG image, to share the setup
+    menubar.add(
+        &tr!(
+            translations,
+            get_or_default,
+            "file-save-preview-menu",
+            "&File/Save dock preview...\t"
+        ),
+        enums::Shortcut::None,
+        menu::MenuFlag::Normal,
+        {
+            let wind = wind.clone();
+            let translations = translations.clone();
+            move |_| save_dock_preview(&wind, translations.clone())
+        },
+    );
+    // The launches of the buttons, to find the unused ones
+    menubar.add(
+        &tr!(
+            translations,
+            get_or_default,
+            "file-statistics-menu",
+            "&File/Statistics...\t"
+        ),
+        enums::Shortcut::None,
+        menu::MenuFlag::Normal,
+        {
+            let config = config.clone();
+            let buttons = buttons.clone();
+            let translations = translations.clone();
+            move |_| {
+                // Only the commands count their launches
+                let names: Vec<String> = buttons
+                    .lock()
+                    .unwrap()
+                    .iter()
+                    .filter(|button| {
+                        button.button_type == E4ButtonType::Command
+                            && !e4config::same_button_name(&button.name, e4button::GENERIC)
+                    })
+                    .map(|button| button.name.clone())
+                    .collect();
+                e4stats::show(
+                    &mut config.borrow_mut(),
+                    &names,
+                    read_only,
+                    translations.clone(),
+                );
+            }
+        },
+    );
+    // The icons left behind by the edited and removed buttons
+    menubar.add(
+        &tr!(
+            translations,
+            get_or_default,
+            "file-clean-icons-menu",
+            "&File/Clean unused icons...\t"
+        ),
+        enums::Shortcut::None,
+        edit_flag,
+        {
+            let config = config.clone();
+            let translations = translations.clone();
+            move |_| e4assets::clean(&config.borrow(), false, translations.clone())
+        },
+    );
+    // The icons named by their paths, lost when they are moved
+    menubar.add(
+        &tr!(
+            translations,
+            get_or_default,
+            "file-migrate-icons-menu",
+            "&File/Copy icons into the assets directory...\t"
+        ),
+        enums::Shortcut::None,
+        edit_flag,
+        {
+            let config = config.clone();
+            let translations = translations.clone();
+            move |_| e4assets::migrate_icons(&config.borrow(), translations.clone())
+        },
+    );
+    // The buttons launching the same command, often imported twice
+    menubar.add(
+        &tr!(
+            translations,
+            get_or_default,
+            "file-find-duplicates-menu",
+            "&File/Find duplicate buttons...\t"
+        ),
+        enums::Shortcut::None,
+        edit_flag,
+        {
+            let config = config.clone();
+            let translations = translations.clone();
+            move |_| {
+                e4lint::resolve_duplicates(&mut config.borrow_mut(), false, translations.clone())
+            }
+        },
+    );
+    // The layouts: the orders of the buttons to switch between
+    let layouts_menu = tr!(
+        translations,
+        get_or_default,
+        "file-layouts-menu",
+        "&File/Layouts"
+    );
+    let layouts = e4layout::layouts(&config.borrow());
+    let current_layout = e4layout::current_layout(&config.borrow());
+    menubar.add(
+        &format!(
+            "{}/{}",
+            layouts_menu,
+            tr!(
+                translations,
+                get_or_default,
+                "save-layout-menu",
+                "Save the layout..."
+            )
+        ),
+        enums::Shortcut::None,
+        if layouts.is_empty() {
+            edit_flag
+        } else {
+            edit_flag | menu::MenuFlag::MenuDivider
+        },
+        {
+            let config = config.clone();
+            let translations = translations.clone();
+            let current_layout = current_layout.clone().unwrap_or_default();
+            move |_| {
+                let prompt = tr!(
+                    translations,
+                    get_or_default,
+                    "layout-name",
+                    "Name of the layout:"
+                );
+                if let Some(name) = fltk::dialog::input_default(&prompt, &current_layout) {
+                    match e4layout::save_layout(
+                        &mut config.borrow_mut(),
+                        &name,
+                        translations.clone(),
+                    ) {
+                        // Show the layout in the menu
+                        Ok(_) => e4events::publish(E4Event::ConfigChanged),
+                        Err(e) => fltk::dialog::alert_default(&e.to_string()),
+                    }
+                }
+            }
+        },
+    );
+    // The buttons hidden, to show again
+    let show_menu = tr!(translations, get_or_default, "show-button-menu", "Show");
+    for name in e4layout::hidden_buttons(&config.borrow()) {
+        menubar.add(
+            &format!("{}/{}/{}", layouts_menu, show_menu, menu_label(&name)),
+            enums::Shortcut::None,
+            edit_flag,
+            {
+                let config = config.clone();
+                let translations = translations.clone();
+                move |_| match e4layout::show_button(
+                    &mut config.borrow_mut(),
+                    &name,
+                    translations.clone(),
+                ) {
+                    Ok(_) => e4events::publish(E4Event::ConfigChanged),
+                    Err(e) => fltk::dialog::alert_default(&e.to_string()),
+                }
+            },
+        );
+    }
+    for (name, _) in layouts {
+        let flag = if current_layout.as_ref() == Some(&name) {
+            edit_flag | menu::MenuFlag::Radio | menu::MenuFlag::Value
+        } else {
+            edit_flag | menu::MenuFlag::Radio
+        };
+        menubar.add(
+            &format!("{}/{}", layouts_menu, menu_label(&name)),
+            enums::Shortcut::None,
+            flag,
+            {
+                let config = config.clone();
+                let translations = translations.clone();
+                move |_| match e4layout::apply_layout(
+                    &mut config.borrow_mut(),
+                    &name,
+                    translations.clone(),
+                ) {
+                    Ok(_) => e4events::publish(E4Event::ConfigChanged),
+                    Err(e) => fltk::dialog::alert_default(&e.to_string()),
+                }
+            },
+        );
+    }
     menubar.add(
         &about_menu,
         enums::Shortcut::Ctrl | 'a',
@@ -162,9 +724,46 @@ fn redraw_window(
             app::quit();
         },
     );
+    if read_only {
+        // The banner explaining why the editing menus are disabled
+        let banner = tr!(
+            translations,
+            get_or_default,
+            "read-only-banner",
+            "Read-only: the changes cannot be saved"
+        );
+        menubar.add(
+            &menu_label(&banner),
+            enums::Shortcut::None,
+            menu::MenuFlag::Inactive,
+            |_| {},
+        );
+    }
 
     wind.end();
     wind.show();
+    e4appearance::style_title_bar(wind);
+    // The values of e4docker.conf replaced not to break the window
+    let clamped: Vec<String> = config
+        .borrow()
+        .clamped
+        .iter()
+        .map(|clamped| {
+            tr!(
+                translations,
+                format,
+                "value-out-of-range",
+                &[
+                    &clamped.key,
+                    &clamped.value.to_string(),
+                    &clamped.used.to_string()
+                ]
+            )
+        })
+        .collect();
+    if !clamped.is_empty() {
+        e4toast::show(&clamped.join("\n"));
+    }
 
     // Always on top
     wind.set_on_top();
@@ -175,110 +774,409 @@ fn redraw_window(
         //let _ = &wind.set_pos(cx, cy);
         wind.set_pos(cx, cy);
     }
+    // The saved position may be out of the screen, after a change of resolution
+    e4screen::clamp(&mut wind);
+    e4x11::reserve_space(wind);
 
     // For the popup menu
-    let move_left_menu: &'static str = Box::leak(
-        format!(
-            "{} {}",
-            "\u{2190}",
-            tr!(translations, get_or_default, "move", "Move")
-        )
-        .into_boxed_str(),
+    let move_left_menu = format!(
+        "{} {}",
+        "\u{2190}",
+        tr!(translations, get_or_default, "move", "Move")
     );
-    let edit_menu: &'static str =
-        Box::leak(tr!(translations, get_or_default, "edit-menu", "Edit").into_boxed_str());
-    let delete_menu: &'static str =
-        Box::leak(tr!(translations, get_or_default, "delete", "Delete").into_boxed_str());
-    let move_right_menu: &'static str = Box::leak(
-        format!(
-            "{} {}",
-            tr!(translations, get_or_default, "move", "Move"),
-            "\u{2192}"
-        )
-        .into_boxed_str(),
+    let edit_menu = tr!(translations, get_or_default, "edit-menu", "Edit");
+    let delete_menu = tr!(translations, get_or_default, "delete", "Delete");
+    let move_right_menu = format!(
+        "{} {}",
+        tr!(translations, get_or_default, "move", "Move"),
+        "\u{2192}"
     );
-
-    let empty_label_message = tr!(
+    let recent_menu = tr!(translations, get_or_default, "recent", "Recent");
+    let open_location_menu = tr!(
         translations,
         get_or_default,
-        "error-empty-menu-label",
-        "Error: empty menu label"
+        "open-file-location",
+        "Open file location"
+    );
+    let hide_menu = tr!(translations, get_or_default, "hide-button", "Hide");
+    let deselect_menu = tr!(translations, get_or_default, "deselect-buttons", "Deselect");
+    let cancel_menu = tr!(translations, get_or_default, "cancel", "Cancel");
+    let copy_button_menu = tr!(translations, get_or_default, "copy-button", "Copy button");
+    let paste_button_menu = tr!(translations, get_or_default, "paste-button", "Paste button");
+    let copy_command_menu = tr!(
+        translations,
+        get_or_default,
+        "copy-command-to-the-clipboard",
+        "Copy command to the clipboard"
     );
 
-    let items = [move_left_menu, edit_menu, delete_menu, move_right_menu];
-    let menu_button = menu::MenuItem::new(&items);
-    let buttons_clone = buttons_second_clone.clone();
+    let buttons_clone = buttons.clone();
+    // The style is read now: the configuration can be borrowed by a dialog when the mouse moves
+    let style = config.borrow().style.clone();
+    let config_dir = config.borrow().config_dir.clone();
 
-    // Handle tre popup menu and the drag event
+    // Handle tre popup menu, the drag event and the hover label
     wind.handle({
         let mut x = 0;
         let mut y = 0;
+        // The entry of e4docker.conf after which the button in the clipboard is pasted
+        let mut paste_after = None;
+        // The right edge of the window is being dragged
+        let mut resizing = false;
+        // The dock is being dragged: its reserved space follows it once released
+        let mut moving = false;
+        let mut swipes = E4SwipeTracker::default();
+        let mut slow_clicks = E4SlowClickTracker::default();
         move |w, ev| match ev {
+            enums::Event::Move => {
+                let (ex, ey) = app::event_coords();
+                w.set_cursor(if !read_only && ex >= w.w() - RESIZE_HANDLE_WIDTH {
+                    enums::Cursor::WE
+                } else {
+                    enums::Cursor::Default
+                });
+                let mut buttons = buttons_clone.lock().unwrap();
+                let is_hovered = |button: &E4Button| {
+                    let b = &button.button;
+                    b.active()
+                        && (b.x()..b.x() + b.w()).contains(&ex)
+                        && (b.y()..b.y() + b.h()).contains(&ey)
+                };
+                for button in buttons.iter_mut() {
+                    button.set_hovered(is_hovered(button));
+                }
+                let hovered = buttons
+                    .iter()
+                    .find(|button| is_hovered(button))
+                    .map(|button| {
+                        let b = &button.button;
+                        // The window of a running program can be previewed
+                        let running_command = (button.button_type == E4ButtonType::Command
+                            && button.border.is_active())
+                        .then(|| button.command.lock().unwrap().get_cmd().clone());
+                        (
+                            button.name.clone(),
+                            w.x() + b.x() + b.w() / 2,
+                            w.y() + b.y(),
+                            running_command,
+                        )
+                    });
+                drop(buttons);
+                match hovered {
+                    Some((name, bx, by, running_command)) => {
+                        e4label::hover(&style, &config_dir, &name, bx, by, running_command)
+                    }
+                    None => e4label::hide(),
+                }
+                false
+            }
+            enums::Event::Leave => {
+                for button in buttons_clone.lock().unwrap().iter_mut() {
+                    button.set_hovered(false);
+                }
+                e4label::hide();
+                false
+            }
+            // The quick launch: a number launches the button in its place
+            enums::Event::KeyDown | enums::Event::Shortcut => {
+                let key = app::event_key();
+                let modifier = style.quick_launch_modifier;
+                if modifier.is_key(key) {
+                    if style.quick_launch_overlay {
+                        e4quicklaunch::show_numbers(w, &buttons_clone.lock().unwrap());
+                    }
+                    return false;
+                }
+                match e4quicklaunch::button_index(key) {
+                    Some(index) if modifier.is_held() => {
+                        e4quicklaunch::hide_numbers();
+                        // The buttons are not locked while launching: a hook can update them
+                        let buttons = buttons_clone.lock().unwrap().clone();
+                        e4quicklaunch::launch(&buttons, index)
+                    }
+                    _ => false,
+                }
+            }
+            // A swipe toward the edge revealing the dock hides it
+            enums::Event::MouseWheel => {
+                let edge = style.reveal_edge;
+                match swipes.add(Instant::now(), app::event_dx_value(), app::event_dy_value()) {
+                    Some(swipe) if swipe.is_toward(edge) => {
+                        // The dock cannot be hidden in its own handler
+                        let w = w.clone();
+                        app::add_timeout3(0.0, move |_| e4reveal::hide(&w, edge));
+                        true
+                    }
+                    _ => false,
+                }
+            }
             enums::Event::Push => {
+                e4label::hide();
                 // Handle the popup menu
                 if app::event_mouse_button() == app::MouseButton::Right {
                     let (ex, ey) = app::event_coords();
-                    for (i, mut button) in &mut <Vec<E4Button> as Clone>::clone(&buttons_clone)
-                        .into_iter()
-                        .enumerate()
-                    {
+                    // The buttons as they are now: the view can have updated some of them
+                    let current_buttons = buttons_clone.lock().unwrap().clone();
+                    let buttons_names: Vec<String> = current_buttons
+                        .iter()
+                        .map(|button| button.name.clone())
+                        .collect();
+                    for (i, mut button) in current_buttons.into_iter().enumerate() {
                         if (ex >= button.position.x()
                             && ex <= button.position.x() + button.size.width())
                             && (ey >= button.position.y()
                                 && ey <= button.position.y() + button.size.height())
                             && button.button.active()
                         {
-                            let move_left_index = items
-                                .iter()
-                                .position(|&item| item == move_left_menu)
-                                .unwrap() as i32;
-                            let move_right_index = items
-                                .iter()
-                                .position(|&item| item == move_right_menu)
-                                .unwrap() as i32;
-                            if i == 0 {
-                                menu_button.at(move_left_index).unwrap().deactivate();
-                                menu_button.at(move_right_index).unwrap().activate();
-                            } else if i == (buttons_clone.len() - 1) {
-                                menu_button.at(move_left_index).unwrap().activate();
-                                menu_button.at(move_right_index).unwrap().deactivate();
+                            let config_dir = config.borrow().config_dir.clone();
+                            let recent_files =
+                                E4State::load(&config_dir).recent_files(&button.name);
+
+                            // The menu items record the chosen action, run once the menu is closed
+                            let action = Rc::new(RefCell::new(None));
+                            let mut popup = menu::MenuButton::default();
+                            // A selected button acts on the whole selection
+                            let selected = e4selection::selected(&buttons_clone.lock().unwrap());
+                            let is_selection = selected.contains(&button.name);
+                            let entries = if is_selection {
+                                let count = selected.len().to_string();
+                                let label = |key: &str| {
+                                    tr!(translations_fourth_clone, format, key, &[&count])
+                                };
+                                vec![
+                                    (
+                                        label("delete-selected-buttons"),
+                                        PopupAction::DeleteSelected,
+                                        !read_only,
+                                    ),
+                                    (
+                                        label("hide-selected-buttons"),
+                                        PopupAction::HideSelected,
+                                        !read_only,
+                                    ),
+                                    (
+                                        label("export-selected-buttons"),
+                                        PopupAction::ExportSelected,
+                                        true,
+                                    ),
+                                    (deselect_menu.clone(), PopupAction::Deselect, true),
+                                ]
                             } else {
-                                menu_button.at(move_left_index).unwrap().activate();
-                                menu_button.at(move_right_index).unwrap().activate();
+                                vec![
+                                    (&move_left_menu, PopupAction::MoveLeft, !read_only && i > 0),
+                                    (&edit_menu, PopupAction::Edit, !read_only),
+                                    (&delete_menu, PopupAction::Delete, !read_only),
+                                    (&hide_menu, PopupAction::Hide, !read_only),
+                                    (
+                                        &move_right_menu,
+                                        PopupAction::MoveRight,
+                                        !read_only && i < buttons_names.len() - 1,
+                                    ),
+                                    (
+                                        &open_location_menu,
+                                        PopupAction::OpenLocation,
+                                        button.button_type == E4ButtonType::Command,
+                                    ),
+                                    (
+                                        &copy_command_menu,
+                                        PopupAction::CopyCommand,
+                                        button.button_type == E4ButtonType::Command,
+                                    ),
+                                    (&copy_button_menu, PopupAction::CopyButton, true),
+                                    (&paste_button_menu, PopupAction::PasteButton, !read_only),
+                                ]
+                                .into_iter()
+                                .map(|(label, popup_action, active)| {
+                                    (label.clone(), popup_action, active)
+                                })
+                                .collect()
+                            };
+                            for (label, popup_action, active) in entries {
+                                add_popup_action(&mut popup, &label, active, &action, popup_action);
+                            }
+                            // The recent files are of the button alone
+                            let recent_files: &[String] =
+                                if is_selection { &[] } else { &recent_files };
+                            for (n, file) in recent_files.iter().enumerate() {
+                                let file_name = match Path::new(file).file_name() {
+                                    Some(file_name) => file_name.to_string_lossy().to_string(),
+                                    None => file.to_string(),
+                                };
+                                let label = format!(
+                                    "{}/{}. {}",
+                                    menu_label(&recent_menu),
+                                    n + 1,
+                                    menu_label(&file_name)
+                                );
+                                add_popup_action(
+                                    &mut popup,
+                                    &label,
+                                    true,
+                                    &action,
+                                    PopupAction::Recent(file.to_string()),
+                                );
                             }
-                            if let Some(val) = menu_button.popup(ex, ey) {
-                                match val.label() {
-                                    Some(label) => {
-                                        if label == move_left_menu {
-                                            let _ = &mut config.borrow_mut().swap_buttons(
-                                                &mut buttons_names,
-                                                i,
-                                                i - 1,
-                                                translations_fourth_clone.clone(),
-                                            );
-                                        } else if label == edit_menu {
-                                            button.edit(
-                                                &mut config.borrow_mut(),
-                                                translations_fourth_clone.clone(),
-                                            );
-                                        } else if label == delete_menu {
-                                            button.delete(
-                                                &mut config.borrow_mut(),
-                                                translations_fourth_clone.clone(),
-                                            );
-                                        } else if label == move_right_menu {
-                                            let _ = &mut config.borrow_mut().swap_buttons(
-                                                &mut buttons_names,
-                                                i,
-                                                i + 1,
-                                                translations_fourth_clone.clone(),
-                                            );
+                            popup.popup();
+                            menu::MenuButton::delete(popup);
+
+                            let chosen = action.borrow_mut().take();
+                            match chosen {
+                                Some(PopupAction::MoveLeft) => {
+                                    if let Err(e) = config.borrow_mut().swap_buttons(
+                                        &button.name,
+                                        &buttons_names[i - 1],
+                                        translations_fourth_clone.clone(),
+                                    ) {
+                                        fltk::dialog::alert_default(&e.to_string());
+                                    }
+                                    e4events::publish(E4Event::ConfigChanged);
+                                }
+                                Some(PopupAction::Edit) => {
+                                    button.edit(
+                                        &mut config.borrow_mut(),
+                                        translations_fourth_clone.clone(),
+                                    );
+                                }
+                                Some(PopupAction::Delete) => {
+                                    button.delete(
+                                        &mut config.borrow_mut(),
+                                        translations_fourth_clone.clone(),
+                                    );
+                                }
+                                Some(PopupAction::Hide) => {
+                                    if let Err(e) = e4layout::hide_button(
+                                        &mut config.borrow_mut(),
+                                        &button.name,
+                                        translations_fourth_clone.clone(),
+                                    ) {
+                                        fltk::dialog::alert_default(&e.to_string());
+                                    }
+                                    e4events::publish(E4Event::ConfigChanged);
+                                }
+                                Some(PopupAction::MoveRight) => {
+                                    if let Err(e) = config.borrow_mut().swap_buttons(
+                                        &button.name,
+                                        &buttons_names[i + 1],
+                                        translations_fourth_clone.clone(),
+                                    ) {
+                                        fltk::dialog::alert_default(&e.to_string());
+                                    }
+                                    e4events::publish(E4Event::ConfigChanged);
+                                }
+                                Some(PopupAction::OpenLocation) => {
+                                    let command = button.command.lock().unwrap();
+                                    let (cmd, location) =
+                                        (command.get_cmd().clone(), command.location());
+                                    drop(command);
+                                    let result = match location {
+                                        Some(dir) => E4Command::open_with_default_app(
+                                            &dir.display().to_string(),
+                                        )
+                                        .map_err(|e| e.to_string()),
+                                        None => Err(tr!(
+                                            translations_fourth_clone,
+                                            format,
+                                            "cannot-find-the-command",
+                                            &[&cmd]
+                                        )),
+                                    };
+                                    if let Err(message) = result {
+                                        fltk::dialog::alert_default(&message);
+                                    }
+                                }
+                                Some(PopupAction::CopyCommand) => {
+                                    let command_line =
+                                        button.command.lock().unwrap().command_line();
+                                    app::copy(&command_line);
+                                    e4toast::show(&tr!(
+                                        translations_fourth_clone,
+                                        format,
+                                        "copied-to-the-clipboard",
+                                        &[&command_line]
+                                    ));
+                                }
+                                Some(PopupAction::CopyButton) => {
+                                    let config = config.borrow();
+                                    let button_file =
+                                        e4config::button_file(&config.config_dir, &button.name);
+                                    match config.storage.load_ini(&button_file) {
+                                        Ok(ini) => {
+                                            app::copy(&e4share::button_to_json(&button.name, &ini));
+                                            e4toast::show(&tr!(
+                                                translations_fourth_clone,
+                                                format,
+                                                "copied-to-the-clipboard",
+                                                &[&button.name]
+                                            ));
                                         }
+                                        Err(e) => fltk::dialog::alert_default(&e),
                                     }
-                                    None => {
-                                        fltk::dialog::alert_default(&empty_label_message);
+                                }
+                                Some(PopupAction::PasteButton) => {
+                                    let config = config.borrow();
+                                    let entries = &config.buttons;
+                                    paste_after = Some(
+                                        entries
+                                            .iter()
+                                            .position(|entry| *entry == button.name)
+                                            .map_or(entries.len(), |index| index + 1),
+                                    );
+                                    // The clipboard is read by the Paste event, out of this handler
+                                    let w = w.clone();
+                                    app::add_timeout3(0.0, move |_| app::paste_text(&w));
+                                }
+                                Some(PopupAction::Recent(file)) => {
+                                    E4Button::open_files(
+                                        &button.name,
+                                        &button.command,
+                                        &config_dir,
+                                        &[file],
+                                        translations_fourth_clone.clone(),
+                                    );
+                                }
+                                Some(PopupAction::DeleteSelected) => {
+                                    let message = tr!(
+                                        translations_fourth_clone,
+                                        format,
+                                        "confirm-delete-selected-buttons",
+                                        &[&selected.len().to_string()]
+                                    );
+                                    let choice = fltk::dialog::choice2_default(
+                                        &message,
+                                        &cancel_menu,
+                                        &delete_menu,
+                                        "",
+                                    );
+                                    if choice == Some(1) {
+                                        e4selection::clear(&mut buttons_clone.lock().unwrap());
+                                        E4Button::delete_all(
+                                            &selected,
+                                            &mut config.borrow_mut(),
+                                            translations_fourth_clone.clone(),
+                                        );
+                                    }
+                                }
+                                Some(PopupAction::HideSelected) => {
+                                    if let Err(e) = e4layout::hide_buttons(
+                                        &mut config.borrow_mut(),
+                                        &selected,
+                                        translations_fourth_clone.clone(),
+                                    ) {
+                                        fltk::dialog::alert_default(&e.to_string());
                                     }
+                                    e4events::publish(E4Event::ConfigChanged);
                                 }
+                                Some(PopupAction::ExportSelected) => {
+                                    export_buttons(
+                                        &config.borrow(),
+                                        &selected,
+                                        translations_fourth_clone.clone(),
+                                    );
+                                }
+                                Some(PopupAction::Deselect) => {
+                                    e4selection::clear(&mut buttons_clone.lock().unwrap());
+                                }
+                                None => {}
                             }
                         }
                     }
@@ -286,34 +1184,109 @@ fn redraw_window(
                     let coords = app::event_coords();
                     x = coords.0;
                     y = coords.1;
+                    // A click ends the attention asked by the button, a Ctrl+click selects it
+                    // and a slow double click renames it
+                    let is_select_click = e4selection::is_select_click();
+                    let mut buttons = buttons_clone.lock().unwrap();
+                    let clicked = buttons
+                        .iter()
+                        .find(|button| {
+                            let b = &button.button;
+                            (b.x()..b.x() + b.w()).contains(&x)
+                                && (b.y()..b.y() + b.h()).contains(&y)
+                        })
+                        .map(|button| button.name.clone());
+                    if let Some(name) = clicked {
+                        if is_select_click {
+                            e4selection::toggle(&mut buttons, &name);
+                        } else if let Some(button) =
+                            buttons.iter_mut().find(|button| button.name == name)
+                        {
+                            e4attention::end(button);
+                            if slow_clicks.add(Instant::now(), &name) && !read_only {
+                                e4rename::start(
+                                    w,
+                                    button,
+                                    config.clone(),
+                                    translations_fourth_clone.clone(),
+                                );
+                            }
+                        }
+                    }
+                    drop(buttons);
+                    resizing = !read_only && x >= w.w() - RESIZE_HANDLE_WIDTH;
                 }
                 true
             }
-            // Handle the drag event
-            enums::Event::Drag => {
-                config_third_clone.borrow_mut().set_value(
-                    e4config::E4DOCKER_DOCKER_SECTION.to_string(),
-                    "x".to_string(),
-                    Some((app::event_x_root() - x).to_string()),
+            // The clipboard read for the Paste button action
+            enums::Event::Paste => match paste_after.take() {
+                Some(index) => {
+                    paste_button(
+                        &mut config.borrow_mut(),
+                        index,
+                        &app::event_text(),
+                        translations_fourth_clone.clone(),
+                    );
+                    true
+                }
+                None => false,
+            },
+            // Resize the window, the buttons are fitted once it is released
+            enums::Event::Drag if resizing => {
+                let min_width = config_third_clone.borrow().frame_margin * 2;
+                w.set_size(app::event_x().max(min_width), w.h());
+                w.redraw();
+                true
+            }
+            enums::Event::Released if resizing => {
+                resizing = false;
+                let (icon_width, icon_height) =
+                    config_third_clone.borrow().icon_size_for_width(w.w());
+                if let Err(e) = config_third_clone.borrow_mut().save_icon_size(
+                    icon_width,
+                    icon_height,
                     translations_fourth_clone.clone(),
-                );
-                config_third_clone.borrow_mut().set_value(
-                    e4config::E4DOCKER_DOCKER_SECTION.to_string(),
-                    "y".to_string(),
-                    Some((app::event_y_root() - y).to_string()),
+                ) {
+                    fltk::dialog::alert_default(&e.to_string());
+                }
+                e4events::publish(E4Event::ConfigChanged);
+                true
+            }
+            // Handle the drag event, with the modifier moving the dock held
+            enums::Event::Drag if style.move_modifier.is_held() => {
+                save_position(
+                    &mut config_third_clone.borrow_mut(),
+                    app::event_x_root() - x,
+                    app::event_y_root() - y,
                     translations_fourth_clone.clone(),
                 );
                 w.set_pos(app::event_x_root() - x, app::event_y_root() - y);
+                moving = true;
                 true
             }
+            enums::Event::Released if moving => {
+                moving = false;
+                e4x11::reserve_space(w);
+                false
+            }
             _ => false,
         }
     });
 
+    let view = DockView::new(
+        wind,
+        &frame,
+        &menubar,
+        config_fifth_clone,
+        buttons.clone(),
+        translations.clone(),
+    );
+
     let mut wind_clone = wind.clone();
     menubar.handle({
         let mut x = 0;
         let mut y = 0;
+        let mut moving = false;
         move |_, ev| match ev {
             enums::Event::Push => {
                 // Handle the popup menu
@@ -326,32 +1299,124 @@ fn redraw_window(
             }
             // Handle the drag event
             enums::Event::Drag => {
-                config_fourth_clone.borrow_mut().set_value(
-                    e4config::E4DOCKER_DOCKER_SECTION.to_string(),
-                    "x".to_string(),
-                    Some((app::event_x_root() - x).to_string()),
-                    translations.clone(),
-                );
-                config_fourth_clone.borrow_mut().set_value(
-                    e4config::E4DOCKER_DOCKER_SECTION.to_string(),
-                    "y".to_string(),
-                    Some((app::event_y_root() - y).to_string()),
+                save_position(
+                    &mut config_fourth_clone.borrow_mut(),
+                    app::event_x_root() - x,
+                    app::event_y_root() - y,
                     translations.clone(),
                 );
                 wind_clone.set_pos(app::event_x_root() - x, app::event_y_root() - y);
+                moving = true;
                 true
             }
+            enums::Event::Released if moving => {
+                moving = false;
+                e4x11::reserve_space(&wind_clone);
+                false
+            }
             _ => false,
         }
     });
 
-    Ok(buttons_second_clone)
+    Ok(view)
 }
 
 fn main() {
+    // Report the time spent in the phases of the startup
+    if env::args().any(|arg| arg == "--profile-startup") {
+        e4profile::enable();
+    }
+    let args: Vec<String> = env::args().collect();
+    // Without dialogs, on the servers without a display
+    e4cli::set_quiet(&args);
     let translations = Translations::get_instance();
+    // A profile has its own configuration directory, and its own dock
+    if let Some(profile) = e4instance::profile_from_args(&args) {
+        if !e4instance::is_valid_profile(&profile) {
+            let message = tr!(translations, format, "invalid-profile", &[&profile]);
+            e4cli::alert(&message);
+            E4ExitCode::Usage.exit();
+        }
+        e4initialize::set_profile(&profile);
+    }
+    // The configuration is checked as it is: a missing one is not created
+    if let Some(result) = e4cli::run_check(&args, translations.clone()) {
+        match result {
+            Ok(_) => E4ExitCode::Success.exit(),
+            Err(e) => {
+                eprintln!("{}", e);
+                e.code.exit()
+            }
+        }
+    }
     // Get (or create) the path of the configuration directory for this app
-    let project_config_dir = e4initialize::get_package_config_dir(translations.clone());
+    let project_config_dir = match e4initialize::get_package_config_dir(translations.clone()) {
+        Ok(project_config_dir) => project_config_dir,
+        Err(e) => {
+            let guidance = tr!(
+                translations,
+                get_or_default,
+                "check-the-configuration-directory",
+                "Check that the configuration directory of the user exists and can be written, then start e4docker again."
+            );
+            e4cli::alert(&format!("{}\n\n{}", e, guidance));
+            E4ExitCode::ConfigNotFound.exit();
+        }
+    };
+    // Print the state of the running dock, instead of starting another one
+    #[cfg(feature = "dump-state")]
+    if env::args().any(|arg| arg == "--dump-state") {
+        match e4instance::request(&project_config_dir, e4instance::DUMP_STATE_REQUEST) {
+            Ok(state) if !state.is_empty() => println!("{}", state),
+            _ => eprintln!(
+                "{}",
+                tr!(
+                    translations,
+                    get_or_default,
+                    "no-running-dock",
+                    "No dock is running"
+                )
+            ),
+        }
+        return;
+    }
+    let local_config_dir = project_config_dir.clone();
+
+    // The subcommands read the synced configuration as it is, without syncing it
+    if let Some(result) = e4cli::run(
+        &args,
+        &e4sync::current_dir(&local_config_dir),
+        &local_config_dir,
+        translations.clone(),
+    ) {
+        match result {
+            Ok(_) => E4ExitCode::Success.exit(),
+            Err(e) => {
+                eprintln!("{}", e);
+                e.code.exit()
+            }
+        }
+    }
+    // The configuration can be kept in a synced directory
+    let project_config_dir = e4sync::config_dir(&local_config_dir, translations.clone());
+    // Without a subcommand, --quiet creates the configuration, if missing, without starting the dock
+    if e4cli::is_quiet() {
+        return;
+    }
+    // One dock per profile: starting it again shows the running one.
+    // The lock stays in the local directory, not in the synced one shared with other machines.
+    let _instance = match e4instance::acquire(&local_config_dir) {
+        Ok(Some(instance)) => Some(instance),
+        Ok(None) => return,
+        // A read-only directory cannot be locked: the dock runs anyway
+        Err(_) => None,
+    };
+    // Load the plugins before creating the buttons using them
+    #[cfg(feature = "script-widgets")]
+    for error in e4docker::e4plugin::load(&project_config_dir) {
+        let message = tr!(translations, format, "cannot-load-the-plugin", &[&error]);
+        fltk::dialog::alert_default(&message);
+    }
 
     // Create a FLTK app
     let app = app::App::default();
@@ -359,43 +1424,130 @@ fn main() {
     // Create a window
     let mut wind = Window::default().with_label(APP_TITLE); //.center_screen();
 
-    // Populate and draw the window
-    match redraw_window(&project_config_dir, &mut wind, translations.clone()) {
-        Ok(buttons) => {
-            e4processes::setup_process_checker(buttons, &app);
-            // redraw the buttons backgound_color when needed
-            /*let mut buttons_clone = buttons.clone();
-            let check = Box::leak(Box::new(None));
-            *check = Some(Box::new(move |_| {
-                s.refresh_processes(sysinfo::ProcessesToUpdate::All, true);
-                for button in &mut buttons_clone {
-                    let command = button.command.lock().unwrap();
-                    let command_path = &command.get().clone();
-                    drop(command);
-                    let command_path = Path::new(command_path);
-                    let process_name = command_path.file_name().unwrap();
-                    let process_running = s.processes_by_name(process_name).next().is_some();
-                    match (process_running, button.button.color()) {
-                        (true, fltk::enums::Color::TransparentBg) => {
-                            button.button.set_color(fltk::enums::Color::White);
-                            button.button.redraw();
-                        },
-                        (false, fltk::enums::Color::White) => {
-                            button.button.set_color(fltk::enums::Color::TransparentBg);
-                            button.button.redraw();
-                        },
-                        _ => {}
-                    }
+    // Populate and draw the window, decoding the icons once it is shown
+    e4docker::e4button::set_lazy_icons(true);
+    let buttons = Arc::new(Mutex::new(vec![]));
+    match redraw_window(
+        &project_config_dir,
+        &mut wind,
+        &buttons,
+        translations.clone(),
+    ) {
+        Ok(mut view) => {
+            e4profile::report("window shown");
+            e4screen::watch(&wind);
+            e4reveal::watch_autohide(&wind);
+            if e4initialize::is_read_only() {
+                e4toast::show(&tr!(
+                    translations,
+                    format,
+                    "read-only-configuration",
+                    &[&project_config_dir.display().to_string()]
+                ));
+            } else if let Ok(config) = E4Config::read(&project_config_dir, translations.clone()) {
+                // The icons left behind since the last start, if asked
+                if e4assets::clean_on_start(&config) {
+                    e4assets::clean(&config, true, translations.clone());
                 }
-                if let Some(f) = check.as_ref() {
-                    app::add_timeout3(interval, f.clone());
+                // The likely mistakes, fixed from the menu
+                let lints: Vec<String> = e4lint::lint(&config)
+                    .iter()
+                    .map(|lint| lint.message(translations.clone()))
+                    .collect();
+                if !lints.is_empty() {
+                    e4toast::show(&lints.join("\n"));
                 }
-            }));
-
-            // Avvia il primo timeout
-            if let Some(f) = check.as_ref() {
-                app::add_timeout3(interval, f.clone());
-            }*/
+            }
+            // Apply the events of the dock to the window, without restarting the program
+            e4events::subscribe({
+                let buttons = buttons.clone();
+                let mut wind = wind.clone();
+                let translations = translations.clone();
+                let project_config_dir = project_config_dir.clone();
+                move |event| {
+                    // A dialog is using the configuration: apply the change once it is closed
+                    if view.is_busy()
+                        && !matches!(
+                            event,
+                            E4Event::ProcessStateChanged(..)
+                                | E4Event::BadgeChanged(..)
+                                | E4Event::AttentionRequested(_)
+                                | E4Event::IconLoaded(..)
+                                | E4Event::PreviewCaptured(..)
+                                | E4Event::WindowShown(_)
+                                | E4Event::FocusRequested
+                                | E4Event::Error(_)
+                        )
+                    {
+                        e4events::publish_later(event.clone());
+                        return;
+                    }
+                    let result = match event {
+                        E4Event::ProcessStateChanged(name, is_running) => {
+                            view.set_state(name, *is_running);
+                            Ok(())
+                        }
+                        E4Event::BadgeChanged(name, text) => {
+                            view.set_badge(name, text.clone());
+                            Ok(())
+                        }
+                        E4Event::AttentionRequested(name) => {
+                            view.request_attention(name);
+                            Ok(())
+                        }
+                        E4Event::IconLoaded(name, png_data) => view.set_icon(name, png_data),
+                        E4Event::PreviewCaptured(name, png_data) => {
+                            e4label::show_preview(name, png_data);
+                            Ok(())
+                        }
+                        E4Event::ButtonAdded(name) => view.add_button(name),
+                        E4Event::ButtonChanged { old_name, name } => {
+                            view.update_button(old_name, name)
+                        }
+                        E4Event::ButtonRemoved(name) => view.remove_button(name),
+                        // The sizes or the order of the buttons changed: draw them all again
+                        E4Event::ConfigChanged => redraw_window(
+                            &project_config_dir,
+                            &mut wind,
+                            &buttons,
+                            translations.clone(),
+                        )
+                        .map(|new_view| view = new_view),
+                        E4Event::WindowShown(name) => {
+                            view.end_launch(name);
+                            Ok(())
+                        }
+                        E4Event::LaunchFailed { name, code, output } => {
+                            view.show_launch_failure(name, *code, output);
+                            Ok(())
+                        }
+                        E4Event::FocusRequested => {
+                            e4reveal::reveal(&mut wind);
+                            wind.show();
+                            Ok(())
+                        }
+                        E4Event::Error(message) => {
+                            fltk::dialog::alert_default(message);
+                            Ok(())
+                        }
+                    };
+                    if let Err(e) = result {
+                        let message = tr!(
+                            translations,
+                            format_display,
+                            "cannot-draw-the-window",
+                            &[&e]
+                        );
+                        fltk::dialog::alert_default(&message);
+                    }
+                }
+            });
+            e4hooks::run(E4Hook::Startup, None);
+            #[cfg(feature = "dump-state")]
+            e4docker::e4dump::watch(buttons.clone());
+            e4list::watch(buttons.clone());
+            e4processes::setup_process_checker(buttons);
+            e4events::start_dispatching();
 
             // Run the FLTK app
             match app.run() {
@@ -405,6 +1557,7 @@ fn main() {
                     fltk::dialog::alert_default(&message);
                 }
             }
+            e4hooks::run_and_wait(E4Hook::Quit);
         }
         Err(e) => {
             let message = tr!(
@@ -9,7 +9,14 @@ use sys_locale::get_locale;
 /// Path to the English translations file.
 const TRANSLATIONS_EN: &str = include_str!("../locales/en.txt");
 /// Path to the Italian translations file.
+#[cfg(feature = "translations")]
 const TRANSLATIONS_IT: &str = include_str!("../locales/it.txt");
+/// Language code of the fallback translations.
+const FALLBACK_LANGUAGE: &str = "en";
+/// Units of the sizes formatted by [Translations::format_size], by powers of 1024.
+const SIZE_UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+/// Seconds in a day, without leap seconds as in Unix time.
+const SECONDS_PER_DAY: u64 = 86_400;
 
 lazy_static! {
     /// Static reference to the translations object, initialized lazily.
@@ -20,6 +27,23 @@ lazy_static! {
     }));
     /// Regular expression to match locale strings.
     static ref LOCALE_REGEX: Regex = Regex::new(r"^([a-z]{2})[-_]?.*$").unwrap();
+    /// Regular expression to match the placeholders of the arguments, like {0}.
+    static ref PLACEHOLDER_REGEX: Regex = Regex::new(r"\{(\d+)\}").unwrap();
+}
+
+/// The metadata of a key, read from the comments before it in a locale file:
+///
+/// ```text
+/// # The synced directory and the files changed in both places.
+/// # args: 2
+/// sync-conflict = "The configuration has changed both here and in {0}: {1}."
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct KeyMetadata {
+    /// The comments describing the key, one per line.
+    pub comment: Option<String>,
+    /// The number of arguments of the key, declared by "# args: N".
+    pub args: Option<usize>,
 }
 
 /// Struct representing a set of translations.
@@ -27,6 +51,8 @@ lazy_static! {
 struct TranslationSet {
     /// Map of translations.
     translations: Arc<HashMap<String, String>>,
+    /// Map of the metadata of the keys.
+    metadata: Arc<HashMap<String, KeyMetadata>>,
     /// Set of missing translation keys.
     missing_keys: HashSet<String>,
 }
@@ -38,6 +64,10 @@ pub struct Translations {
     current: TranslationSet,
     /// Fallback set of translations.
     fallback: TranslationSet,
+    /// Language code of the current set, like "en".
+    language: String,
+    /// The keys whose placeholders do not match their declared arguments, found while loading.
+    placeholder_mismatches: Vec<String>,
 }
 
 impl Default for Translations {
@@ -51,6 +81,7 @@ impl TranslationSet {
     fn new() -> Self {
         TranslationSet {
             translations: Arc::new(HashMap::new()),
+            metadata: Arc::new(HashMap::new()),
             missing_keys: HashSet::new(),
         }
     }
@@ -72,9 +103,18 @@ impl Translations {
         Translations {
             current: TranslationSet::new(),
             fallback: TranslationSet::new(),
+            language: FALLBACK_LANGUAGE.to_string(),
+            placeholder_mismatches: vec![],
         }
     }
 
+    /// Creates a `Translations` object for locale, like "it_IT", instead of the locale of the system.
+    pub fn for_locale(locale: &str) -> io::Result<Self> {
+        let mut translations = Self::new();
+        translations.init_locale(Some(locale.to_string()))?;
+        Ok(translations)
+    }
+
     /// Gets the singleton instance of `Translations`.
     pub fn get_instance() -> Arc<Mutex<Translations>> {
         TRANSLATIONS.clone()
@@ -82,25 +122,38 @@ impl Translations {
 
     /// Formats a translation string with the given arguments.
     pub fn format(&mut self, key: &str, args: &[&str]) -> String {
+        let declared_args = self.metadata(key).and_then(|metadata| metadata.args);
         let template = self.get_or_default(key, key);
 
-        let placeholder_count = (0..args.len())
-            .map(|i| format!("{{{}}}", i))
-            .filter(|p| template.contains(p))
-            .count();
-
-        if placeholder_count != args.len() {
-            warn!(
-                "Mismatch in placeholder count for key '{}'. Expected {}, found {}",
-                key,
-                args.len(),
-                placeholder_count
-            );
+        match declared_args {
+            // The template has been checked against the declaration while loading
+            Some(declared_args) if declared_args != args.len() => {
+                warn!(
+                    "Mismatch in argument count for key '{}'. Declared {}, given {}",
+                    key,
+                    declared_args,
+                    args.len()
+                );
+            }
+            Some(_) => {}
+            None => {
+                let placeholder_count = (0..args.len())
+                    .map(|i| format!("{{{}}}", i))
+                    .filter(|p| template.contains(p))
+                    .count();
+
+                if placeholder_count != args.len() {
+                    warn!(
+                        "Mismatch in placeholder count for key '{}'. Expected {}, found {}",
+                        key,
+                        args.len(),
+                        placeholder_count
+                    );
+                }
+            }
         }
 
-        args.iter().enumerate().fold(template, |acc, (i, arg)| {
-            acc.replace(&format!("{{{}}}", i), arg)
-        })
+        format_template(&template, args)
     }
 
     /// Formats a translation string with the given arguments, converting them to strings.
@@ -112,44 +165,200 @@ impl Translations {
 
     /// Initializes the translations from the locale.
     pub fn init(&mut self) -> io::Result<()> {
+        self.init_locale(get_locale())
+    }
+
+    /// Initializes the translations from locale, the fallback ones without it.
+    fn init_locale(&mut self, locale: Option<String>) -> io::Result<()> {
         let mut fallback_map = HashMap::new();
+        let mut fallback_metadata = HashMap::new();
         Self::load_into_map(
             &mut fallback_map,
+            &mut fallback_metadata,
             BufReader::new(TRANSLATIONS_EN.as_bytes()),
         )?;
         self.fallback = TranslationSet {
             translations: Arc::new(fallback_map),
+            metadata: Arc::new(fallback_metadata),
             missing_keys: HashSet::new(),
         };
 
         let mut current_map = HashMap::new();
-        if let Some(locale) = get_locale() {
+        let mut current_metadata = HashMap::new();
+        self.language = FALLBACK_LANGUAGE.to_string();
+        if let Some(locale) = locale {
             if let Some(captures) = LOCALE_REGEX.captures(&locale.to_lowercase()) {
                 if let Some(lang_code) = captures.get(1) {
                     match lang_code.as_str() {
+                        // Without the translations feature, every language falls back to English
+                        #[cfg(feature = "translations")]
                         "it" => {
                             Self::load_into_map(
                                 &mut current_map,
+                                &mut current_metadata,
                                 BufReader::new(TRANSLATIONS_IT.as_bytes()),
                             )?;
                             self.validate_translations(&current_map);
+                            self.language = "it".to_string();
+                        }
+                        _ => {
+                            current_map = (*self.fallback.translations).clone();
+                            current_metadata = (*self.fallback.metadata).clone();
                         }
-                        _ => current_map = (*self.fallback.translations).clone(),
                     }
                 }
             }
         } else {
             current_map = (*self.fallback.translations).clone();
+            current_metadata = (*self.fallback.metadata).clone();
         }
 
         self.current = TranslationSet {
             translations: Arc::new(current_map),
+            metadata: Arc::new(current_metadata),
             missing_keys: HashSet::new(),
         };
+        self.placeholder_mismatches = self.find_placeholder_mismatches();
+        for mismatch in &self.placeholder_mismatches {
+            warn!("{}", mismatch);
+        }
 
         Ok(())
     }
 
+    /// Gets the metadata of the given key, from the current language or, if not declared there, the fallback one.
+    pub fn metadata(&self, key: &str) -> Option<&KeyMetadata> {
+        let current = self.current.metadata.get(key);
+        let fallback = self.fallback.metadata.get(key);
+        match current {
+            Some(metadata) if metadata.args.is_some() || fallback.is_none() => Some(metadata),
+            _ => fallback,
+        }
+    }
+
+    /// Gets the keys whose placeholders do not match the arguments declared, found while loading.
+    pub fn placeholder_mismatches(&self) -> &[String] {
+        &self.placeholder_mismatches
+    }
+
+    /// Checks the placeholders of the translations of both sets against the arguments declared for their keys.
+    fn find_placeholder_mismatches(&self) -> Vec<String> {
+        let mut mismatches = vec![];
+        for (language, set) in [
+            (self.language.as_str(), &self.current),
+            (FALLBACK_LANGUAGE, &self.fallback),
+        ] {
+            for (key, template) in set.translations.iter() {
+                let declared_args = match self.metadata(key).and_then(|metadata| metadata.args) {
+                    Some(declared_args) => declared_args,
+                    None => continue,
+                };
+                let placeholders: HashSet<usize> = PLACEHOLDER_REGEX
+                    .captures_iter(template)
+                    .filter_map(|captures| captures[1].parse().ok())
+                    .collect();
+                if placeholders != (0..declared_args).collect() {
+                    let mut found: Vec<usize> = placeholders.into_iter().collect();
+                    found.sort();
+                    mismatches.push(format!(
+                        "Key '{}' in '{}' declares {} arguments, but has the placeholders {:?}",
+                        key, language, declared_args, found
+                    ));
+                }
+            }
+            // The fallback set is the current one
+            if language == FALLBACK_LANGUAGE {
+                break;
+            }
+        }
+        mismatches.sort();
+        mismatches
+    }
+
+    /// Gets the language code of the current translations, like "en".
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Formats a number with decimals digits after the separator, and the thousands grouped,
+    /// as written in the current language.
+    pub fn format_number(&mut self, value: f64, decimals: usize) -> String {
+        let decimal_separator = self.get_or_default("decimal-separator", ".");
+        let thousands_separator = self.get_or_default("thousands-separator", ",");
+        let digits = format!("{:.*}", decimals, value.abs());
+        let (integer, fraction) = match digits.split_once('.') {
+            Some((integer, fraction)) => (integer, Some(fraction)),
+            None => (digits.as_str(), None),
+        };
+
+        let mut result = String::new();
+        // Nothing negative is left once rounded, like -0.001 with 2 decimals
+        if value < 0.0
+            && digits
+                .chars()
+                .any(|digit| digit.is_ascii_digit() && digit != '0')
+        {
+            result.push('-');
+        }
+        for (i, digit) in integer.chars().enumerate() {
+            if i > 0 && (integer.len() - i) % 3 == 0 {
+                result.push_str(&thousands_separator);
+            }
+            result.push(digit);
+        }
+        if let Some(fraction) = fraction {
+            result.push_str(&decimal_separator);
+            result.push_str(fraction);
+        }
+        result
+    }
+
+    /// Formats a size in bytes with the largest unit keeping it at least 1, like "1.5 MB".
+    pub fn format_size(&mut self, bytes: u64) -> String {
+        let mut size = bytes as f64;
+        let mut unit = 0;
+        while size >= 1024.0 && unit < SIZE_UNITS.len() - 1 {
+            size /= 1024.0;
+            unit += 1;
+        }
+        // The bytes have no fraction
+        let decimals = if unit == 0 { 0 } else { 1 };
+        format!(
+            "{} {}",
+            self.format_number(size, decimals),
+            SIZE_UNITS[unit]
+        )
+    }
+
+    /// Formats the day of a time, in seconds since the Unix epoch, as written in the current language.
+    /// The time is in UTC.
+    pub fn format_date(&mut self, seconds: u64) -> String {
+        let (year, month, day) = civil_date((seconds / SECONDS_PER_DAY) as i64);
+        self.format(
+            "date-format",
+            &[
+                &year.to_string(),
+                &format!("{:02}", month),
+                &format!("{:02}", day),
+            ],
+        )
+    }
+
+    /// Formats the day and the time of a time, in seconds since the Unix epoch, as written in the current language.
+    /// The time is in UTC.
+    pub fn format_date_time(&mut self, seconds: u64) -> String {
+        let minutes = seconds % SECONDS_PER_DAY / 60;
+        let date = self.format_date(seconds);
+        self.format(
+            "date-time-format",
+            &[
+                &date,
+                &format!("{:02}", minutes / 60),
+                &format!("{:02}", minutes % 60),
+            ],
+        )
+    }
+
     /// Gets the missing translations for both current and fallback sets.
     pub fn get_missing_translations(&self) -> (Vec<String>, Vec<String>) {
         (
@@ -158,6 +367,35 @@ impl Translations {
         )
     }
 
+    /// Describes the language in use and the keys looked up without a translation in it, sorted:
+    /// the ones taken from the fallback language and the ones missing in every language.
+    pub fn diagnostics(&mut self) -> String {
+        let (mut fallback_hits, mut missing) = self.get_missing_translations();
+        // Missing everywhere, the keys are not taken from the fallback language
+        fallback_hits.retain(|key| !missing.contains(key));
+        fallback_hits.sort();
+        missing.sort();
+        let none = self.get_or_default("none", "none");
+        let list = |keys: &[String]| {
+            if keys.is_empty() {
+                none.clone()
+            } else {
+                keys.join(", ")
+            }
+        };
+        let language = self.language.clone();
+        self.format_display(
+            "translation-diagnostics",
+            &[
+                language,
+                fallback_hits.len().to_string(),
+                list(&fallback_hits),
+                missing.len().to_string(),
+                list(&missing),
+            ],
+        )
+    }
+
     /// Validates the current translations against the fallback translations.
     fn validate_translations(&self, current_map: &HashMap<String, String>) {
         for key in self.fallback.translations.keys() {
@@ -176,18 +414,45 @@ impl Translations {
         }
     }
 
-    /// Loads translations from a reader into a map.
-    fn load_into_map<R: BufRead>(map: &mut HashMap<String, String>, reader: R) -> io::Result<()> {
+    /// Loads translations from a reader into a map, and the metadata of their keys into metadata.
+    /// The comments before a key are its metadata, unless a blank line separates them.
+    fn load_into_map<R: BufRead>(
+        map: &mut HashMap<String, String>,
+        metadata: &mut HashMap<String, KeyMetadata>,
+        reader: R,
+    ) -> io::Result<()> {
+        let mut comments: Vec<String> = vec![];
+        let mut args = None;
         for line in reader.lines() {
             let line = line?;
-            if line.trim().is_empty() || line.starts_with('#') {
+            if line.trim().is_empty() {
+                comments.clear();
+                args = None;
+                continue;
+            }
+            if let Some(comment) = line.strip_prefix('#') {
+                let comment = comment.trim();
+                match comment.strip_prefix("args:") {
+                    Some(count) => match count.trim().parse() {
+                        Ok(count) => args = Some(count),
+                        Err(_) => warn!("Invalid argument count in '{}'", line),
+                    },
+                    None if !comment.is_empty() => comments.push(comment.to_string()),
+                    None => {}
+                }
                 continue;
             }
             if let Some((key, value)) = line.split_once('=') {
                 let key = key.trim().to_string();
                 let value = value.trim().trim_matches('"').to_string();
+                if !comments.is_empty() || args.is_some() {
+                    let comment = (!comments.is_empty()).then(|| comments.join("\n"));
+                    metadata.insert(key.clone(), KeyMetadata { comment, args });
+                }
                 map.insert(key, unescape(&value));
             }
+            comments.clear();
+            args = None;
         }
         Ok(())
     }
@@ -243,8 +508,62 @@ macro_rules! tr {
     }};
 }
 
-/// Unescapes a string by replacing escape sequences with their corresponding characters.
-fn unescape(s: &str) -> String {
+/// Converts the days since the Unix epoch to the year, the month and the day of the Gregorian calendar.
+fn civil_date(days: i64) -> (i64, u32, u32) {
+    // The eras of 400 years start on March 1st, leaving the leap day at their end
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u32;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Replaces the placeholders of template, like {0}, by the arguments at their index, in one pass: the
+/// placeholders inside the arguments are not replaced, and the ones without an argument are kept as they are.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::translations::format_template;
+///
+/// assert_eq!(format_template("Move {0} to {1}", &["a", "b"]), "Move a to b");
+/// assert_eq!(format_template("{1} and {0}", &["{1}", "b"]), "b and {1}");
+/// assert_eq!(format_template("Move {0} to {1}", &["a"]), "Move a to {1}");
+/// ```
+pub fn format_template(template: &str, args: &[&str]) -> String {
+    PLACEHOLDER_REGEX
+        .replace_all(template, |captures: &regex::Captures| {
+            match captures[1].parse::<usize>().ok().and_then(|i| args.get(i)) {
+                Some(arg) => arg.to_string(),
+                None => captures[0].to_string(),
+            }
+        })
+        .into_owned()
+}
+
+/// Unescapes a string by replacing escape sequences with their corresponding characters:
+/// `\n`, `\t`, `\r`, `\\`, `\"` and `\'`. The other backslashes are kept, with the character after them.
+///
+/// # Example
+///
+/// ```rust
+/// use e4docker::translations::unescape;
+///
+/// assert_eq!(unescape(r#"Say \"hi\"\n"#), "Say \"hi\"\n");
+/// assert_eq!(unescape(r"C:\Users\\me"), r"C:\Users\me");
+/// assert_eq!(unescape(r"trailing\"), r"trailing\");
+/// ```
+pub fn unescape(s: &str) -> String {
     let mut result = String::new();
     let mut chars = s.chars().peekable();
     while let Some(&c) = chars.peek() {
@@ -258,7 +577,11 @@ fn unescape(s: &str) -> String {
                     '\\' => result.push('\\'),
                     '"' => result.push('"'),
                     '\'' => result.push('\''),
-                    _ => result.push('\\'),
+                    // An unknown escape is not one: the character after the backslash is kept
+                    _ => {
+                        result.push('\\');
+                        result.push(next);
+                    }
                 }
                 chars.next();
             } else {
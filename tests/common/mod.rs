@@ -0,0 +1,62 @@
+//! The configuration shared by the tests, held in memory through [MemoryStorage].
+// Each test file uses only some of them
+#![allow(dead_code)]
+
+use e4docker::{
+    e4config::{self, E4Config},
+    e4storage::MemoryStorage,
+};
+use std::{path::PathBuf, sync::Arc};
+
+pub const E4DOCKER_CONF: &str = "[e4docker]
+number_of_buttons=3
+frame_margin=10
+margin_between_buttons=20
+icon_width=32
+icon_height=32
+x=100
+y=50
+[buttons]
+button1=firefox
+button2=terminal
+button3=trash
+[hooks]
+on_startup=echo started
+";
+
+pub const FIREFOX_CONF: &str = "[button]
+command=/usr/bin/firefox
+arguments=https://www.rust-lang.org
+icon=firefox.png
+";
+
+pub const TRASH_CONF: &str = "[button]
+type=trash
+icon=trash.png
+";
+
+pub fn config_dir() -> PathBuf {
+    PathBuf::from("memory").join("e4docker")
+}
+
+/// Return a storage holding e4docker.conf and the buttons configuration files.
+pub fn storage() -> Arc<MemoryStorage> {
+    let dir = config_dir();
+    Arc::new(
+        MemoryStorage::new()
+            .with_file(e4config::config_file(&dir), E4DOCKER_CONF)
+            .with_file(e4config::button_file(&dir, "firefox"), FIREFOX_CONF)
+            .with_file(e4config::button_file(&dir, "terminal"), FIREFOX_CONF)
+            .with_file(e4config::button_file(&dir, "trash"), TRASH_CONF),
+    )
+}
+
+/// Return a storage holding e4docker.conf with the style options.
+pub fn styled_storage(options: &str) -> Arc<MemoryStorage> {
+    let conf = E4DOCKER_CONF.replace("[e4docker]\n", &format!("[e4docker]\n{}", options));
+    Arc::new(MemoryStorage::new().with_file(e4config::config_file(&config_dir()), &conf))
+}
+
+pub fn read(storage: &Arc<MemoryStorage>) -> E4Config {
+    E4Config::read_from(storage.clone(), &config_dir(), &config_dir().join("assets")).unwrap()
+}
@@ -0,0 +1,96 @@
+//! Tests of the icons of the assets directory: the unused ones and the ones outside of it.
+
+mod common;
+
+use common::{config_dir, read, storage, styled_storage, FIREFOX_CONF, TRASH_CONF};
+use e4docker::{
+    e4assets,
+    e4config::{self, E4Config},
+    e4storage::E4Storage,
+};
+
+#[test]
+fn find_the_unused_icons() {
+    let assets_dir = std::env::temp_dir().join(format!("e4docker-assets-{}", std::process::id()));
+    std::fs::create_dir_all(assets_dir.join("favicons")).unwrap();
+    for file in [
+        "firefox.png",
+        "trash.png",
+        "trash-full.png",
+        "generic.png",
+        "old.png",
+        ".directory",
+        "favicons/www.rust-lang.org.png",
+    ] {
+        std::fs::write(assets_dir.join(file), "").unwrap();
+    }
+    let storage = storage();
+    storage
+        .write(
+            &e4config::button_file(&config_dir(), "trash"),
+            &format!("{}icon_full=trash-full.png\n", TRASH_CONF),
+        )
+        .unwrap();
+    let config = E4Config::read_from(storage, &config_dir(), &assets_dir).unwrap();
+    assert!(!e4assets::clean_on_start(&config));
+    assert_eq!(
+        e4assets::orphaned_assets(&config),
+        vec![assets_dir.join("old.png")]
+    );
+
+    e4assets::archive(&assets_dir, &[assets_dir.join("old.png")]).unwrap();
+    assert!(assets_dir
+        .join(e4assets::ARCHIVE_DIR)
+        .join("old.png")
+        .is_file());
+    assert!(e4assets::orphaned_assets(&config).is_empty());
+    std::fs::remove_dir_all(&assets_dir).unwrap();
+
+    let config = read(&styled_storage("clean_assets_on_start=true\n"));
+    assert!(e4assets::clean_on_start(&config));
+}
+
+#[test]
+fn copy_the_icons_outside_the_assets_directory() {
+    let dir = std::env::temp_dir().join(format!("e4docker-external-{}", std::process::id()));
+    let assets_dir = dir.join("assets");
+    std::fs::create_dir_all(&assets_dir).unwrap();
+    std::fs::write(dir.join("terminal.png"), "moved soon").unwrap();
+    // Taken by another icon
+    std::fs::write(assets_dir.join("terminal.png"), "another").unwrap();
+    let storage = storage();
+    let terminal = FIREFOX_CONF.replace(
+        "icon=firefox.png",
+        &format!("icon={}", dir.join("terminal.png").display()),
+    );
+    storage
+        .write(&e4config::button_file(&config_dir(), "terminal"), &terminal)
+        .unwrap();
+    let firefox = FIREFOX_CONF.replace(
+        "icon=firefox.png",
+        &format!("icon={}", dir.join("moved.png").display()),
+    );
+    storage
+        .write(&e4config::button_file(&config_dir(), "firefox"), &firefox)
+        .unwrap();
+    let config = E4Config::read_from(storage.clone(), &config_dir(), &assets_dir).unwrap();
+
+    let icons = e4assets::external_icons(&config);
+    let buttons: Vec<&str> = icons.iter().map(|icon| icon.button.as_str()).collect();
+    assert_eq!(buttons, vec!["firefox", "terminal"]);
+    let missing = e4assets::migrate(&config, &icons).unwrap();
+    assert_eq!(missing, vec![icons[0].clone()]);
+    let ini = storage
+        .load_ini(&e4config::button_file(&config_dir(), "terminal"))
+        .unwrap();
+    assert_eq!(
+        ini.get(e4config::BUTTON_BUTTON_SECTION, "icon"),
+        Some("terminal-2.png".to_string())
+    );
+    assert_eq!(
+        std::fs::read_to_string(assets_dir.join("terminal-2.png")).unwrap(),
+        "moved soon"
+    );
+    assert_eq!(e4assets::external_icons(&config), vec![icons[0].clone()]);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
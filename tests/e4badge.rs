@@ -0,0 +1,47 @@
+//! Tests of the badges of the buttons, printed by their badge commands.
+
+mod common;
+
+use common::{config_dir, read, E4DOCKER_CONF, FIREFOX_CONF};
+use e4docker::{
+    e4badge::{self, E4Badge},
+    e4config,
+    e4storage::MemoryStorage,
+};
+use std::{sync::Arc, time::Duration};
+
+#[test]
+fn read_the_badge_of_a_button() {
+    let dir = config_dir();
+    let storage = Arc::new(
+        MemoryStorage::new()
+            .with_file(e4config::config_file(&dir), E4DOCKER_CONF)
+            .with_file(
+                e4config::button_file(&dir, "firefox"),
+                &format!("{}badge_command=echo 3\nbadge_interval=30\n", FIREFOX_CONF),
+            )
+            .with_file(e4config::button_file(&dir, "terminal"), FIREFOX_CONF),
+    );
+    let config = read(&storage);
+
+    let badge = E4Badge::read(&config, "firefox").unwrap();
+    assert_eq!(badge.command, "echo 3");
+    assert_eq!(badge.interval, Duration::from_secs(30));
+    assert_eq!(badge.text(), None);
+    assert!(E4Badge::read(&config, "terminal").is_none());
+
+    assert_eq!(e4badge::badge_text("12\nunread\n"), Some("12".to_string()));
+    assert_eq!(e4badge::badge_text("12345"), Some("123+".to_string()));
+    assert_eq!(e4badge::badge_text(" 0 \n"), None);
+    assert_eq!(e4badge::badge_text(""), None);
+}
+
+#[test]
+fn ask_for_attention_from_a_badge_command() {
+    assert!(e4badge::badge_attention("!3\n"));
+    assert_eq!(e4badge::badge_text("!3\n"), Some("3".to_string()));
+    // Attention without a badge
+    assert!(e4badge::badge_attention(" !\n"));
+    assert_eq!(e4badge::badge_text(" !\n"), None);
+    assert!(!e4badge::badge_attention("3\n!\n"));
+}
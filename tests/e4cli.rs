@@ -0,0 +1,61 @@
+//! Tests of the subcommands for the scripts.
+
+mod common;
+
+use common::{config_dir, read, storage};
+use e4docker::{
+    e4cli::{self, E4ExitCode},
+    e4config, e4layout,
+    e4list::{self, E4ListFormat},
+    e4storage::E4Storage,
+    translations::Translations,
+};
+
+#[test]
+fn list_the_buttons_for_the_scripts() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+    e4layout::hide_buttons(&mut config, &["trash".to_string()], translations).unwrap();
+
+    let mut buttons = e4list::list_buttons(&config);
+    let names: Vec<(&str, bool)> = buttons
+        .iter()
+        .map(|button| (button.name.as_str(), button.enabled))
+        .collect();
+    assert_eq!(
+        names,
+        vec![("firefox", true), ("terminal", true), ("trash", false)]
+    );
+    assert_eq!(buttons[0].command, "/usr/bin/firefox");
+    assert_eq!(buttons[0].arguments, vec!["https://www.rust-lang.org"]);
+    assert_eq!(buttons[0].icon, "firefox.png");
+    assert_eq!(buttons[0].running, None);
+
+    // The running dock answers with the names of its running buttons
+    e4list::set_running(&mut buttons, "terminal");
+    assert_eq!(buttons[0].running, Some(false));
+    assert_eq!(buttons[1].running, Some(true));
+    assert_eq!(E4ListFormat::from_name("JSON"), Some(E4ListFormat::Json));
+    assert_eq!(E4ListFormat::from_name("xml"), None);
+    let tsv = E4ListFormat::Tsv.write(&buttons);
+    assert_eq!(tsv.lines().count(), 4);
+    assert!(tsv.contains(
+        "terminal\t/usr/bin/firefox\thttps://www.rust-lang.org\tfirefox.png\ttrue\ttrue\n"
+    ));
+}
+
+#[test]
+fn find_the_buttons_without_configuration_file() {
+    let storage = storage();
+    let config = read(&storage);
+    assert!(e4cli::missing_buttons(&config).is_empty());
+
+    storage
+        .remove(&e4config::button_file(&config_dir(), "terminal"))
+        .unwrap();
+    assert_eq!(e4cli::missing_buttons(&config), vec!["terminal"]);
+    let error = e4cli::E4CliError::new(E4ExitCode::ButtonMissing, "terminal");
+    assert_eq!(error.code as i32, 5);
+    assert_eq!(error.to_string(), "terminal");
+}
@@ -0,0 +1,112 @@
+//! Tests of the commands of the buttons: their launches, their processes and their arguments. A line split by
+//! split_arguments and joined back by join_arguments gives the same arguments, whatever their quotes,
+//! backslashes and spaces.
+
+use e4docker::{
+    e4command::{self, join_arguments, split_arguments, E4Command, RecordingExecutor},
+    e4processes,
+};
+use proptest::prelude::*;
+use std::{sync::Arc, time::Duration};
+
+#[test]
+fn split_the_quoted_arguments() {
+    assert_eq!(
+        split_arguments(r#""it's" 'say "hi"' "a \"b\"" --opt="x y""#),
+        vec!["it's", r#"say "hi""#, r#"a "b""#, "--opt=x y"]
+    );
+    let arguments = vec![
+        "it's".to_string(),
+        r#"say "hi""#.to_string(),
+        "'".to_string(),
+    ];
+    assert_eq!(split_arguments(&join_arguments(&arguments)), arguments);
+}
+
+#[test]
+fn keep_the_backslashes() {
+    assert_eq!(
+        split_arguments(r#"C:\Users\me a\ b \'x \"y"#),
+        vec![r"C:\Users\me", "a b", "'x", "\"y"]
+    );
+    let arguments = vec![
+        r"C:\Program Files\".to_string(),
+        r"\\server\share".to_string(),
+        r"a\'b".to_string(),
+    ];
+    assert_eq!(split_arguments(&join_arguments(&arguments)), arguments);
+}
+
+#[test]
+fn keep_the_empty_arguments() {
+    assert_eq!(split_arguments(r#"'' -a """#), vec!["", "-a", ""]);
+    assert!(split_arguments("  ").is_empty());
+    let arguments = vec![String::new(), "-a".to_string(), String::new()];
+    assert_eq!(join_arguments(&arguments), "'' -a ''");
+    assert_eq!(split_arguments(&join_arguments(&arguments)), arguments);
+}
+
+proptest! {
+    #[test]
+    fn joined_arguments_are_split_back(arguments in prop::collection::vec(".*", 0..6)) {
+        prop_assert_eq!(split_arguments(&join_arguments(&arguments)), arguments);
+    }
+
+    #[test]
+    fn arguments_of_quotes_backslashes_and_spaces_are_split_back(
+        arguments in prop::collection::vec("[a \\\\'\"\t]*", 0..6),
+    ) {
+        prop_assert_eq!(split_arguments(&join_arguments(&arguments)), arguments);
+    }
+}
+
+#[test]
+fn record_the_launches_of_a_command() {
+    let executor = Arc::new(RecordingExecutor::new());
+    let mut command = E4Command::new(
+        "/usr/bin/nano".to_string(),
+        e4command::split_arguments("-w 'my notes.txt'"),
+    );
+    command.set_executor(executor.clone());
+    command.set_timeout(Some(Duration::from_secs(10)));
+
+    command.exec().unwrap();
+    // A double click launches once
+    assert!(command.exec().is_err());
+    command.set_cooldown(Duration::ZERO);
+    command
+        .exec_with_files(&["/tmp/todo.txt".to_string()])
+        .unwrap();
+
+    let launches = executor.launches();
+    assert_eq!(launches.len(), 2);
+    assert_eq!(launches[0].program, "/usr/bin/nano");
+    assert_eq!(launches[0].args, vec!["-w", "my notes.txt"]);
+    assert_eq!(launches[0].timeout, Some(Duration::from_secs(10)));
+    assert!(launches[0].env.is_empty());
+    assert_eq!(
+        launches[1].args,
+        vec!["-w", "my notes.txt", "/tmp/todo.txt"]
+    );
+}
+
+#[test]
+#[cfg(feature = "process-checking")]
+fn find_the_processes_of_a_command() {
+    // The tests run in a process of their own executable
+    let exe = std::env::current_exe().unwrap();
+    assert!(e4processes::process_ids(&exe.display().to_string()).contains(&std::process::id()));
+    assert!(e4processes::process_ids("").is_empty());
+}
+
+#[test]
+fn count_the_launches_of_a_button() {
+    let mut command = E4Command::new("/usr/bin/counted-notes".to_string(), vec![]);
+    command.set_executor(Arc::new(RecordingExecutor::new()));
+    command.set_cooldown(Duration::ZERO);
+    command.set_button("counted-notes");
+    assert_eq!(e4command::launch_count("counted-notes"), 0);
+    command.exec().unwrap();
+    command.exec().unwrap();
+    assert_eq!(e4command::launch_count("counted-notes"), 2);
+}
@@ -0,0 +1,1006 @@
+//! Tests of the configuration, read and written in memory through [MemoryStorage].
+
+mod common;
+
+use common::{config_dir, read, storage, styled_storage, E4DOCKER_CONF, FIREFOX_CONF, TRASH_CONF};
+use e4docker::{
+    e4appearance,
+    e4button::{E4Button, E4ButtonType},
+    e4command,
+    e4config::{self, E4Config, E4DOCKER_BUTTON_SECTION, E4DOCKER_DOCKER_SECTION},
+    e4dock::{Dock, E4ButtonBuilder},
+    e4hooks::E4Hook,
+    e4layout, e4share,
+    e4storage::{E4Storage, MemoryStorage},
+    e4style::{
+        E4Accent, E4Alignment, E4DialogPosition, E4Edge, E4FrameStyle, E4IconEffects, E4IconTint,
+        E4Modifier, E4Style, E4Theme,
+    },
+    translations::Translations,
+};
+use fltk::enums::Color;
+use std::{sync::Arc, time::Duration};
+
+/// Return the value of key in section of e4docker.conf in storage.
+fn saved_value(storage: &MemoryStorage, section: &str, key: &str) -> Option<String> {
+    storage
+        .load_ini(&e4config::config_file(&config_dir()))
+        .unwrap()
+        .get(section, key)
+}
+
+#[test]
+fn read_the_configuration() {
+    let config = read(&storage());
+    assert_eq!(config.buttons, vec!["firefox", "terminal", "trash"]);
+    assert_eq!((config.x, config.y), (100, 50));
+    assert_eq!((config.icon_width, config.icon_height), (32, 32));
+    assert_eq!(config.margin_between_buttons, 20);
+    assert_eq!(config.frame_margin, 10);
+    assert_eq!(config.assets_dir, config_dir().join("assets"));
+    assert_eq!(
+        config.hooks.command(E4Hook::Startup),
+        Some(&"echo started".to_string())
+    );
+    assert_eq!(config.hooks.command(E4Hook::Quit), None);
+}
+
+#[test]
+fn compute_the_window_size() {
+    let config = read(&storage());
+    // 3 icons, 3 margins between the buttons and the frame margins on both sides
+    assert_eq!(config.window_width, 3 * 32 + 3 * 20 + 2 * 10);
+    // The icon height and the frame margins on the 4 sides
+    assert_eq!(config.window_height, 32 + 4 * 10);
+    assert_eq!(e4config::window_size(0, 32, 32, 20, 10), (20, 72));
+}
+
+#[test]
+fn fail_without_e4docker_conf() {
+    let storage = Arc::new(MemoryStorage::new());
+    assert!(E4Config::read_from(storage, &config_dir(), &config_dir()).is_err());
+}
+
+#[test]
+fn fail_with_an_invalid_number() {
+    let storage = Arc::new(MemoryStorage::new().with_file(
+        e4config::config_file(&config_dir()),
+        "[e4docker]\nicon_width=big\n",
+    ));
+    assert!(E4Config::read_from(storage, &config_dir(), &config_dir()).is_err());
+}
+
+#[test]
+fn save_a_dock_built_in_code() {
+    let storage = Arc::new(MemoryStorage::new());
+    let mut dock = Dock::new(&config_dir()).icon_size(48, 48).position(100, 50);
+    dock.add_button(
+        E4ButtonBuilder::new("firefox")
+            .command("/usr/bin/firefox")
+            .icon("firefox.png"),
+    )
+    .add_button(E4ButtonBuilder::new("trash").button_type(E4ButtonType::Trash));
+    dock.save_to(storage.as_ref()).unwrap();
+
+    let config = read(&storage);
+    assert_eq!(config.buttons, vec!["firefox", "trash"]);
+    assert_eq!((config.x, config.y), (100, 50));
+    assert_eq!((config.icon_width, config.icon_height), (48, 48));
+    let firefox = storage
+        .load_ini(&e4config::button_file(&config_dir(), "firefox"))
+        .unwrap();
+    assert_eq!(
+        firefox.get("button", "command"),
+        Some("/usr/bin/firefox".to_string())
+    );
+    assert_eq!(
+        firefox.get("button", "icon"),
+        Some("firefox.png".to_string())
+    );
+    let trash = storage
+        .load_ini(&e4config::button_file(&config_dir(), "trash"))
+        .unwrap();
+    assert_eq!(trash.get("button", "type"), Some("trash".to_string()));
+}
+
+#[test]
+fn clamp_the_geometry_out_of_range() {
+    let conf = E4DOCKER_CONF
+        .replace("frame_margin=10", "frame_margin=100000")
+        .replace("icon_width=32", "icon_width=0")
+        .replace("icon_height=32", "icon_height=-5");
+    let clamped_storage =
+        Arc::new(MemoryStorage::new().with_file(e4config::config_file(&config_dir()), &conf));
+    let config = read(&clamped_storage);
+    assert_eq!((config.icon_width, config.icon_height), (16, 16));
+    assert_eq!(config.frame_margin, 200);
+    assert_eq!(
+        config.clamped[0],
+        e4config::E4ClampedValue {
+            key: "frame_margin".to_string(),
+            value: 100000,
+            used: 200
+        }
+    );
+    assert_eq!(config.clamped.len(), 3);
+    assert!(read(&storage()).clamped.is_empty());
+}
+
+#[test]
+fn read_the_style() {
+    assert_eq!(read(&storage()).style, E4Style::default());
+    let config = read(&styled_storage(
+        "frame_style=Flat\nshow_frame=false\nwindow_border=true\nreveal_edge=Top-Left\nmove_modifier=Alt\n\
+         quick_launch_modifier=ctrl\nquick_launch_overlay=false\nnew_instance_modifier=alt\n\
+         dialog_position=Beside\nautohide=1.5\nanimate_icons=true\nreserve_space=true\n",
+    ));
+    assert_eq!(
+        config.style,
+        E4Style {
+            frame_style: E4FrameStyle::Flat,
+            show_frame: false,
+            window_border: true,
+            reveal_edge: E4Edge::TopLeft,
+            move_modifier: E4Modifier::Alt,
+            quick_launch_modifier: E4Modifier::Ctrl,
+            quick_launch_overlay: false,
+            new_instance_modifier: E4Modifier::Alt,
+            dialog_position: E4DialogPosition::Beside,
+            autohide: 1.5,
+            animate_icons: true,
+            reserve_space: true,
+            ..E4Style::default()
+        }
+    );
+}
+
+#[test]
+fn read_the_accent_of_a_button() {
+    let storage = storage();
+    let firefox = format!("{}accent_color=#E66000\naccent_plate=true\n", FIREFOX_CONF);
+    storage
+        .write(&e4config::button_file(&config_dir(), "firefox"), &firefox)
+        .unwrap();
+    let terminal = format!("{}accent_color=orange\n", FIREFOX_CONF);
+    storage
+        .write(&e4config::button_file(&config_dir(), "terminal"), &terminal)
+        .unwrap();
+    let config = read(&storage);
+
+    let accent = E4Accent::read(&config, "firefox").unwrap();
+    assert_eq!(
+        accent,
+        E4Accent {
+            color: (230, 96, 0),
+            plate: true
+        }
+    );
+    assert_eq!(accent.plate_color().to_rgb(), (245, 191, 153));
+    // An invalid color is no accent
+    assert_eq!(E4Accent::read(&config, "terminal"), None);
+    assert_eq!(E4Accent::read(&config, "trash"), None);
+}
+
+#[test]
+fn color_the_hovered_and_the_pressed_buttons() {
+    let style = read(&styled_storage(
+        "hover_color=#DDEEFF\npressed_color=#3daee9\n",
+    ))
+    .style;
+    assert_eq!(style.hover_color, Some((221, 238, 255)));
+    let colors = style.button_colors(None);
+    assert_eq!(colors.normal, Color::Background);
+    assert_eq!(colors.hover.to_rgb(), (221, 238, 255));
+    assert_eq!(colors.pressed.to_rgb(), (61, 174, 233));
+
+    // The accent wins over the style
+    let accent = E4Accent {
+        color: (230, 96, 0),
+        plate: true,
+    };
+    let colors = style.button_colors(Some(&accent));
+    assert_eq!(colors.normal, accent.plate_color());
+    assert_eq!(colors.hover.to_rgb(), (240, 160, 102));
+    assert_eq!(colors.pressed.to_rgb(), (235, 128, 51));
+}
+
+#[test]
+fn tint_the_icons() {
+    let tint = |value: &str| {
+        read(&styled_storage(&format!("icon_tint={}\n", value)))
+            .style
+            .icon_tint
+    };
+    assert_eq!(read(&storage()).style.icon_tint, E4IconTint::None);
+    assert_eq!(tint("Monochrome"), E4IconTint::Monochrome);
+    assert_eq!(tint("#C86400"), E4IconTint::Color(200, 100, 0));
+    assert_eq!(E4IconTint::Color(200, 100, 0).name(), "#c86400");
+
+    // A white and a black pixel, the second half transparent
+    let image = image::RgbaImage::from_raw(2, 1, vec![255, 255, 255, 255, 0, 0, 0, 128]).unwrap();
+    let mut png_data = std::io::Cursor::new(vec![]);
+    image
+        .write_to(&mut png_data, image::ImageFormat::Png)
+        .unwrap();
+    let pixels = |tint: E4IconTint| {
+        let png_data = tint.apply(png_data.get_ref()).unwrap();
+        image::load_from_memory(&png_data)
+            .unwrap()
+            .to_rgba8()
+            .into_raw()
+    };
+    assert_eq!(
+        pixels(E4IconTint::Color(200, 100, 0)),
+        vec![200, 100, 0, 255, 100, 50, 0, 128]
+    );
+    assert_eq!(
+        pixels(E4IconTint::Monochrome),
+        vec![255, 255, 255, 255, 0, 0, 0, 128]
+    );
+}
+
+#[test]
+fn round_and_shadow_the_icons() {
+    let effects = read(&styled_storage(
+        "icon_corner_radius=0.25\nicon_shadow=true\n",
+    ))
+    .style
+    .icon_effects;
+    assert_eq!(
+        effects,
+        E4IconEffects {
+            corner_radius: 0.25,
+            shadow: true
+        }
+    );
+
+    let red = image::Rgba([255, 0, 0, 255]);
+    let mut png_data = std::io::Cursor::new(vec![]);
+    image::RgbaImage::from_pixel(32, 32, red)
+        .write_to(&mut png_data, image::ImageFormat::Png)
+        .unwrap();
+    let apply = |effects: E4IconEffects| {
+        let png_data = effects.apply(png_data.get_ref()).unwrap();
+        image::load_from_memory(&png_data).unwrap().to_rgba8()
+    };
+    assert_eq!(
+        apply(E4IconEffects::default()).into_raw(),
+        image::RgbaImage::from_pixel(32, 32, red).into_raw()
+    );
+    let rounded = apply(E4IconEffects {
+        corner_radius: 0.25,
+        shadow: false,
+    });
+    assert_eq!(rounded.get_pixel(0, 0).0[3], 0);
+    assert_eq!(*rounded.get_pixel(16, 0), red);
+    // The shadow is below the icon, shrunk to leave it room
+    let shadowed = apply(E4IconEffects {
+        corner_radius: 0.0,
+        shadow: true,
+    });
+    assert_eq!(shadowed.dimensions(), (32, 32));
+    assert_eq!(*shadowed.get_pixel(16, 16), red);
+    assert_eq!(shadowed.get_pixel(16, 0).0[3], 0);
+    let shadow = shadowed.get_pixel(16, 31);
+    assert!(shadow.0[..3] == [0, 0, 0] && shadow.0[3] > 0);
+}
+
+#[test]
+fn place_the_buttons_by_alignment() {
+    // 3 buttons need a window 176 pixels wide: 100 pixels of room are left
+    let positions = |alignment: &str| {
+        read(&styled_storage(&format!(
+            "alignment={}\nmin_width=276\n",
+            alignment
+        )))
+        .button_positions()
+    };
+    assert_eq!(read(&storage()).button_positions(), vec![20, 72, 124]);
+    assert_eq!(positions("start"), vec![20, 72, 124]);
+    assert_eq!(positions("center"), vec![70, 122, 174]);
+    assert_eq!(positions("end"), vec![120, 172, 224]);
+    assert_eq!(positions("space-between"), vec![20, 122, 224]);
+    assert_eq!(
+        read(&styled_storage("alignment=end\n")).style.alignment,
+        E4Alignment::End
+    );
+}
+
+#[test]
+fn zoom_the_sizes_by_the_scale() {
+    let config = read(&styled_storage("scale=1.5\nmin_width=100\n"));
+    assert_eq!((config.icon_width, config.icon_height), (48, 48));
+    assert_eq!(config.margin_between_buttons, 30);
+    assert_eq!(config.frame_margin, 15);
+    assert_eq!((config.window_width, config.window_height), (264, 108));
+    assert_eq!(config.style.unscaled(config.icon_width), 32);
+    assert_eq!(config.style.font_size(), 21);
+    for scale in ["0", "-1", "big"] {
+        let storage = styled_storage(&format!("scale={}\n", scale));
+        assert!(E4Config::read_from(storage, &config_dir(), &config_dir()).is_err());
+    }
+}
+
+#[test]
+fn share_the_room_between_the_spacers() {
+    let conf = E4DOCKER_CONF
+        .replace("number_of_buttons=3", "number_of_buttons=5")
+        .replace("button3=trash", "button3=|\nbutton4=trash\nbutton5=|")
+        .replace(
+            "[e4docker]\n",
+            "[e4docker]\nalignment=center\nmin_width=365\n",
+        );
+    let storage =
+        Arc::new(MemoryStorage::new().with_file(e4config::config_file(&config_dir()), &conf));
+    let config = read(&storage);
+    // The spacers take a slot each and share 85 pixels of room, ignoring the alignment
+    assert_eq!(config.button_names().count(), 3);
+    assert_eq!(config.button_positions(), vec![20, 72, 219]);
+    assert_eq!(config.window_width, 365);
+}
+
+#[test]
+fn read_the_hover_label_options() {
+    assert!(!read(&storage()).style.hover_label);
+    assert!(!read(&storage()).style.hover_preview);
+    let style = read(&styled_storage(
+        "hover_label=true\nhover_label_delay=1.5\nhover_label_font=Courier\nhover_label_font_size=12\nhover_preview=true\n",
+    ))
+    .style;
+    assert!(style.hover_label);
+    assert!(style.hover_preview);
+    assert_eq!(style.hover_label_delay, 1.5);
+    assert_eq!(style.hover_label_font, "Courier");
+    assert_eq!(style.hover_label_font_size(), 12);
+    assert_eq!(E4Style::default().hover_label_font_size(), 14);
+    assert_eq!(E4Style::default().preview_size(), 200);
+}
+
+#[test]
+fn follow_the_theme_of_the_system() {
+    let config = read(&styled_storage("theme=Auto\n"));
+    assert_eq!(config.style.theme, E4Theme::Auto);
+    let portal = "(<<uint32 1>>,)";
+    assert_eq!(e4appearance::dark_from_portal(portal), Some(true));
+    let registry = "    AppsUseLightTheme    REG_DWORD    0x1\n";
+    assert_eq!(e4appearance::dark_from_registry(registry), Some(false));
+}
+
+#[test]
+fn fail_with_an_invalid_style() {
+    for options in [
+        "frame_style=embossed\n",
+        "show_frame=maybe\n",
+        "hover_label_delay=-1\n",
+        "icon_tint=#c864\n",
+        "icon_corner_radius=0.75\n",
+        "reveal_edge=middle\n",
+        "move_modifier=super\n",
+        "quick_launch_modifier=fn\n",
+        "hover_color=blue\n",
+        "theme=sepia\n",
+    ] {
+        let storage = styled_storage(options);
+        assert!(E4Config::read_from(storage, &config_dir(), &config_dir()).is_err());
+    }
+}
+
+#[test]
+fn set_get_and_remove_a_value() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+
+    config
+        .set_value(
+            E4DOCKER_DOCKER_SECTION.to_string(),
+            "x".to_string(),
+            Some("300".to_string()),
+            translations.clone(),
+        )
+        .unwrap();
+    assert_eq!(
+        config
+            .get_value(
+                E4DOCKER_DOCKER_SECTION.to_string(),
+                "x".to_string(),
+                translations.clone()
+            )
+            .unwrap(),
+        Some("300".to_string())
+    );
+    assert_eq!(read(&storage).x, 300);
+
+    config
+        .remove_key(
+            E4DOCKER_DOCKER_SECTION.to_string(),
+            "x".to_string(),
+            translations.clone(),
+        )
+        .unwrap();
+    assert_eq!(saved_value(&storage, E4DOCKER_DOCKER_SECTION, "x"), None);
+    // The other keys are kept
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_DOCKER_SECTION, "y"),
+        Some("50".to_string())
+    );
+}
+
+#[test]
+fn get_and_set_the_number_of_buttons() {
+    let translations = Translations::get_instance();
+    let mut config = read(&storage());
+    assert_eq!(
+        config.get_number_of_buttons(translations.clone()).unwrap(),
+        3
+    );
+    config
+        .set_number_of_buttons(4, translations.clone())
+        .unwrap();
+    assert_eq!(config.get_number_of_buttons(translations).unwrap(), 4);
+}
+
+#[test]
+fn repair_the_numbers_of_the_buttons() {
+    let conf = E4DOCKER_CONF
+        .replace("number_of_buttons=3", "number_of_buttons=5")
+        .replace(
+            "button2=terminal\nbutton3=trash",
+            "button3=terminal\nbutton7=trash",
+        );
+    let storage =
+        Arc::new(MemoryStorage::new().with_file(e4config::config_file(&config_dir()), &conf));
+    assert_eq!(read(&storage).buttons, vec!["firefox", "terminal", "trash"]);
+    // The entries are renumbered on load
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_DOCKER_SECTION, "number_of_buttons"),
+        Some("3".to_string())
+    );
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_BUTTON_SECTION, "button3"),
+        Some("trash".to_string())
+    );
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_BUTTON_SECTION, "button7"),
+        None
+    );
+
+    // Saving fewer buttons removes the entries after the last one
+    let translations = Translations::get_instance();
+    let mut config = read(&storage);
+    config
+        .update_buttons(
+            |buttons| buttons.retain(|button| button == "trash"),
+            translations,
+        )
+        .unwrap();
+    assert_eq!(read(&storage).buttons, vec!["trash"]);
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_BUTTON_SECTION, "button2"),
+        None
+    );
+}
+
+#[test]
+fn save_the_buttons() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+
+    config
+        .update_buttons(
+            |buttons| {
+                buttons.retain(|button| button != "terminal");
+                buttons.reverse();
+            },
+            translations,
+        )
+        .unwrap();
+    assert_eq!(config.buttons, vec!["trash", "firefox"]);
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_BUTTON_SECTION, "button1"),
+        Some("trash".to_string())
+    );
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_BUTTON_SECTION, "button2"),
+        Some("firefox".to_string())
+    );
+}
+
+#[test]
+fn stage_the_changes_in_a_transaction() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+
+    let mut transaction = config.transaction(translations.clone()).unwrap();
+    transaction.set_buttons(&["trash".to_string(), "firefox".to_string()]);
+    transaction.set_value(
+        E4DOCKER_DOCKER_SECTION,
+        "frame_margin",
+        Some("4".to_string()),
+    );
+    transaction.set_value(E4DOCKER_DOCKER_SECTION, "x", None);
+    // Nothing is written before the commit
+    assert_eq!(read(&storage).buttons, vec!["firefox", "terminal", "trash"]);
+    assert_eq!(config.buttons, vec!["firefox", "terminal", "trash"]);
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_DOCKER_SECTION, "frame_margin"),
+        Some("10".to_string())
+    );
+
+    config.commit(transaction, translations).unwrap();
+    assert_eq!(config.buttons, vec!["trash", "firefox"]);
+    assert_eq!(read(&storage).buttons, vec!["trash", "firefox"]);
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_DOCKER_SECTION, "frame_margin"),
+        Some("4".to_string())
+    );
+    assert_eq!(saved_value(&storage, E4DOCKER_DOCKER_SECTION, "x"), None);
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_BUTTON_SECTION, "button3"),
+        None
+    );
+}
+
+#[test]
+fn swap_two_buttons() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+
+    config
+        .swap_buttons("firefox", "trash", translations.clone())
+        .unwrap();
+    assert_eq!(config.buttons, vec!["trash", "terminal", "firefox"]);
+    assert_eq!(read(&storage).buttons, vec!["trash", "terminal", "firefox"]);
+    assert!(config
+        .swap_buttons("firefox", "chrome", translations)
+        .is_err());
+}
+
+#[test]
+fn move_a_button_across_a_spacer() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let conf = E4DOCKER_CONF
+        .replace("number_of_buttons=3", "number_of_buttons=4")
+        .replace("button3=trash", "button3=|\nbutton4=trash");
+    storage
+        .write(&e4config::config_file(&config_dir()), &conf)
+        .unwrap();
+    let mut config = read(&storage);
+
+    // Moving trash left swaps it with terminal, the button before it on the dock
+    config
+        .swap_buttons("trash", "terminal", translations)
+        .unwrap();
+    assert_eq!(config.buttons, vec!["firefox", "trash", "|", "terminal"]);
+    assert_eq!(
+        read(&storage).buttons,
+        vec!["firefox", "trash", "|", "terminal"]
+    );
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_BUTTON_SECTION, "button3"),
+        Some("|".to_string())
+    );
+}
+
+#[test]
+fn remove_a_button() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+
+    config.remove_button("terminal", translations).unwrap();
+    assert_eq!(config.buttons, vec!["firefox", "trash"]);
+
+    let saved = read(&storage);
+    assert_eq!(saved.buttons, vec!["firefox", "trash"]);
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_BUTTON_SECTION, "button3"),
+        None
+    );
+    assert!(!storage.exists(&e4config::button_file(&config_dir(), "terminal")));
+    assert!(storage.exists(&e4config::button_file(&config_dir(), "firefox")));
+}
+
+#[test]
+fn rename_a_button() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+
+    config
+        .rename_button("terminal", " console ", translations.clone())
+        .unwrap();
+    assert_eq!(config.buttons, vec!["firefox", "console", "trash"]);
+    assert_eq!(read(&storage).buttons, vec!["firefox", "console", "trash"]);
+    assert!(!storage.exists(&e4config::button_file(&config_dir(), "terminal")));
+    assert_eq!(
+        storage
+            .read_to_string(&e4config::button_file(&config_dir(), "console"))
+            .unwrap(),
+        FIREFOX_CONF
+    );
+
+    // The names of the other buttons and of the files are refused
+    assert!(config
+        .rename_button("console", "firefox", translations.clone())
+        .is_err());
+    assert!(config
+        .rename_button("console", "../console", translations.clone())
+        .is_err());
+    assert!(config.rename_button("console", "", translations).is_err());
+    assert_eq!(read(&storage).buttons, vec!["firefox", "console", "trash"]);
+}
+
+#[test]
+fn switch_between_layouts() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+    assert!(e4layout::layouts(&config).is_empty());
+
+    e4layout::save_layout(&mut config, "All", translations.clone()).unwrap();
+    e4layout::hide_button(&mut config, "trash", translations.clone()).unwrap();
+    config
+        .swap_buttons("firefox", "terminal", translations.clone())
+        .unwrap();
+    e4layout::save_layout(&mut config, "Coding", translations.clone()).unwrap();
+    assert_eq!(
+        e4layout::layouts(&config),
+        vec![
+            (
+                "all".to_string(),
+                vec![
+                    "firefox".to_string(),
+                    "terminal".to_string(),
+                    "trash".to_string()
+                ]
+            ),
+            (
+                "coding".to_string(),
+                vec!["terminal".to_string(), "firefox".to_string()]
+            ),
+        ]
+    );
+    assert_eq!(
+        e4layout::current_layout(&config),
+        Some("coding".to_string())
+    );
+    assert_eq!(e4layout::hidden_buttons(&config), vec!["trash"]);
+
+    e4layout::apply_layout(&mut config, "all", translations.clone()).unwrap();
+    assert_eq!(read(&storage).buttons, vec!["firefox", "terminal", "trash"]);
+    assert!(e4layout::hidden_buttons(&config).is_empty());
+    e4layout::apply_layout(&mut config, "coding", translations.clone()).unwrap();
+    assert_eq!(read(&storage).buttons, vec!["terminal", "firefox"]);
+    assert_eq!(
+        saved_value(&storage, E4DOCKER_BUTTON_SECTION, "button3"),
+        None
+    );
+
+    e4layout::show_button(&mut config, "trash", translations.clone()).unwrap();
+    assert_eq!(read(&storage).buttons, vec!["terminal", "firefox", "trash"]);
+    assert!(e4layout::apply_layout(&mut config, "streaming", translations.clone()).is_err());
+    assert!(e4layout::save_layout(&mut config, "a=b", translations).is_err());
+}
+
+#[test]
+fn remove_a_button_without_configuration_file() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    storage
+        .remove(&e4config::button_file(&config_dir(), "trash"))
+        .unwrap();
+    let mut config = read(&storage);
+
+    // The button is removed from e4docker.conf anyway
+    assert!(config.remove_button("trash", translations).is_err());
+    assert_eq!(read(&storage).buttons, vec!["firefox", "terminal"]);
+}
+
+#[test]
+fn delete_and_hide_several_buttons() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+
+    e4layout::hide_buttons(
+        &mut config,
+        &["firefox".to_string(), "trash".to_string()],
+        translations.clone(),
+    )
+    .unwrap();
+    assert_eq!(read(&storage).buttons, vec!["terminal"]);
+    assert_eq!(e4layout::hidden_buttons(&config), vec!["firefox", "trash"]);
+
+    e4layout::show_button(&mut config, "firefox", translations.clone()).unwrap();
+    e4layout::show_button(&mut config, "trash", translations.clone()).unwrap();
+    config
+        .remove_buttons(&["terminal".to_string(), "trash".to_string()], translations)
+        .unwrap();
+    assert_eq!(read(&storage).buttons, vec!["firefox"]);
+    assert!(!storage.exists(&e4config::button_file(&config_dir(), "terminal")));
+    assert!(!storage.exists(&e4config::button_file(&config_dir(), "trash")));
+}
+
+#[test]
+fn copy_and_paste_a_button() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+    let firefox = storage
+        .load_ini(&e4config::button_file(&config_dir(), "firefox"))
+        .unwrap();
+
+    let json = e4share::button_to_json("firefox", &firefox);
+    let (name, button) = e4share::button_from_json(&json).unwrap();
+    assert_eq!(name, "firefox");
+    assert_eq!(button.get_map_ref(), firefox.get_map_ref());
+    assert!(e4share::button_from_json("{\"name\": \"firefox\"}").is_err());
+
+    let name = config.unique_button_name(&name);
+    config
+        .insert_button(1, &name, &button, translations)
+        .unwrap();
+    assert_eq!(
+        config.buttons,
+        vec!["firefox", "firefox-2", "terminal", "trash"]
+    );
+    let saved = read(&storage);
+    assert_eq!(saved.buttons, config.buttons);
+    assert_eq!(
+        storage
+            .load_ini(&e4config::button_file(&config_dir(), "firefox-2"))
+            .unwrap()
+            .get(e4config::BUTTON_BUTTON_SECTION, "command"),
+        Some("/usr/bin/firefox".to_string())
+    );
+}
+
+#[test]
+fn find_the_taken_button_names() {
+    let config = read(&storage());
+    assert!(config.is_button_name_taken("terminal", None));
+    assert!(!config.is_button_name_taken("editor", None));
+    // A button keeping its name while edited does not collide with itself
+    assert!(!config.is_button_name_taken("terminal", Some("terminal")));
+    assert!(config.is_button_name_taken("terminal", Some("firefox")));
+    // The .conf files ignore the case on Windows only
+    assert_eq!(config.is_button_name_taken("Terminal", None), cfg!(windows));
+}
+
+#[test]
+fn suggest_a_unique_button_name() {
+    let translations = Translations::get_instance();
+    let mut config = read(&storage());
+    assert_eq!(config.unique_button_name("terminal"), "terminal-2");
+
+    config
+        .update_buttons(
+            |buttons| buttons.push("terminal-2".to_string()),
+            translations,
+        )
+        .unwrap();
+    assert_eq!(config.unique_button_name("terminal"), "terminal-3");
+}
+
+#[test]
+fn number_the_name_of_a_new_button() {
+    let translations = Translations::get_instance();
+    let mut config = read(&storage());
+    let new_button = |n: usize| format!("Nuovo pulsante {}", n);
+    assert_eq!(config.numbered_button_name(new_button), "Nuovo pulsante 1");
+
+    config
+        .update_buttons(
+            |buttons| buttons.push("Nuovo pulsante 1".to_string()),
+            translations,
+        )
+        .unwrap();
+    assert_eq!(config.numbered_button_name(new_button), "Nuovo pulsante 2");
+}
+
+#[test]
+fn read_the_button_configurations() {
+    let translations = Translations::get_instance();
+    let config = read(&storage());
+
+    let firefox =
+        E4Button::read_config(&config, &"firefox".to_string(), translations.clone()).unwrap();
+    assert_eq!(firefox.button_type, E4ButtonType::Command);
+    assert_eq!(firefox.command.get_cmd(), "/usr/bin/firefox");
+    assert_eq!(
+        firefox.command.get_arguments(),
+        ["https://www.rust-lang.org"]
+    );
+    assert_eq!(firefox.icon_path, "firefox.png");
+
+    let trash = E4Button::read_config(&config, &"trash".to_string(), translations).unwrap();
+    assert_eq!(trash.button_type, E4ButtonType::Trash);
+    assert_eq!(trash.command.get_cmd(), "");
+}
+
+#[test]
+fn read_a_watch_button() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    storage
+        .write(
+            &e4config::button_file(&config_dir(), "terminal"),
+            "[button]\ntype=Watch\ncommand=syncthing\nicon=syncthing.png\n",
+        )
+        .unwrap();
+    let config = read(&storage);
+
+    let watch = E4Button::read_config(&config, &"terminal".to_string(), translations).unwrap();
+    assert_eq!(watch.button_type, E4ButtonType::Watch);
+    assert_eq!(watch.command.get_cmd(), "syncthing");
+    assert_eq!(E4ButtonType::Watch.name(), "watch");
+}
+
+#[test]
+fn read_the_cooldown_of_a_button() {
+    let translations = Translations::get_instance();
+    let dir = config_dir();
+    let storage = Arc::new(
+        MemoryStorage::new()
+            .with_file(e4config::config_file(&dir), E4DOCKER_CONF)
+            .with_file(
+                e4config::button_file(&dir, "firefox"),
+                &format!("{}cooldown=2.5\n", FIREFOX_CONF),
+            )
+            .with_file(
+                e4config::button_file(&dir, "terminal"),
+                &format!("{}cooldown=soon\n", FIREFOX_CONF),
+            )
+            .with_file(e4config::button_file(&dir, "trash"), TRASH_CONF),
+    );
+    let config = read(&storage);
+
+    let firefox =
+        E4Button::read_config(&config, &"firefox".to_string(), translations.clone()).unwrap();
+    assert_eq!(firefox.command.get_cooldown(), Duration::from_millis(2500));
+    assert!(!firefox.command.is_launching());
+    let trash = E4Button::read_config(&config, &"trash".to_string(), translations.clone()).unwrap();
+    assert_eq!(trash.command.get_cooldown(), e4command::DEFAULT_COOLDOWN);
+    assert!(E4Button::read_config(&config, &"terminal".to_string(), translations).is_err());
+}
+
+#[test]
+fn read_a_single_instance_button() {
+    let translations = Translations::get_instance();
+    let dir = config_dir();
+    let storage = Arc::new(
+        MemoryStorage::new()
+            .with_file(e4config::config_file(&dir), E4DOCKER_CONF)
+            .with_file(
+                e4config::button_file(&dir, "firefox"),
+                &format!("{}single_instance=true\n", FIREFOX_CONF),
+            )
+            .with_file(
+                e4config::button_file(&dir, "terminal"),
+                &format!("{}single_instance=sometimes\n", FIREFOX_CONF),
+            )
+            .with_file(e4config::button_file(&dir, "trash"), TRASH_CONF),
+    );
+    let config = read(&storage);
+
+    let firefox =
+        E4Button::read_config(&config, &"firefox".to_string(), translations.clone()).unwrap();
+    assert!(firefox.command.is_single_instance());
+    let trash = E4Button::read_config(&config, &"trash".to_string(), translations.clone()).unwrap();
+    assert!(!trash.command.is_single_instance());
+    assert!(E4Button::read_config(&config, &"terminal".to_string(), translations).is_err());
+    // Shift launches a new instance, unless changed
+    assert_eq!(config.style.new_instance_modifier, E4Modifier::Shift);
+}
+
+#[test]
+fn read_the_timeout_of_a_button() {
+    let translations = Translations::get_instance();
+    let dir = config_dir();
+    let storage = Arc::new(
+        MemoryStorage::new()
+            .with_file(e4config::config_file(&dir), E4DOCKER_CONF)
+            .with_file(
+                e4config::button_file(&dir, "firefox"),
+                &format!("{}timeout=90\n", FIREFOX_CONF),
+            )
+            .with_file(
+                e4config::button_file(&dir, "terminal"),
+                &format!("{}timeout=0\n", FIREFOX_CONF),
+            )
+            .with_file(e4config::button_file(&dir, "trash"), TRASH_CONF),
+    );
+    let config = read(&storage);
+
+    let firefox =
+        E4Button::read_config(&config, &"firefox".to_string(), translations.clone()).unwrap();
+    assert_eq!(firefox.command.get_timeout(), Some(Duration::from_secs(90)));
+    let trash = E4Button::read_config(&config, &"trash".to_string(), translations.clone()).unwrap();
+    assert_eq!(trash.command.get_timeout(), None);
+    assert!(E4Button::read_config(&config, &"terminal".to_string(), translations).is_err());
+    assert!(e4command::supervised_pids("firefox").is_empty());
+}
+
+#[test]
+fn fit_the_icons_to_the_width_of_the_window() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+    assert_eq!(config.window_width, 176);
+    assert_eq!(config.icon_size_for_width(176), (32, 32));
+    assert_eq!(config.icon_size_for_width(326), (82, 82));
+    assert_eq!(config.icon_size_for_width(10), (16, 16));
+    assert_eq!(config.icon_size_for_width(100_000), (512, 512));
+
+    config.save_icon_size(82, 82, translations).unwrap();
+    let config = read(&storage);
+    assert_eq!((config.icon_width, config.icon_height), (82, 82));
+    assert_eq!(config.window_width, 326);
+}
+
+#[test]
+fn fail_to_write_without_e4docker_conf() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+    storage
+        .remove(&e4config::config_file(&config_dir()))
+        .unwrap();
+
+    // The error is returned instead of being shown, and nothing is written
+    assert!(config
+        .set_value(
+            E4DOCKER_DOCKER_SECTION.to_string(),
+            "x".to_string(),
+            Some("0".to_string()),
+            translations.clone(),
+        )
+        .is_err());
+    assert!(config
+        .get_value(
+            E4DOCKER_DOCKER_SECTION.to_string(),
+            "x".to_string(),
+            translations.clone()
+        )
+        .is_err());
+    assert!(!storage.exists(&e4config::config_file(&config_dir())));
+}
+
+#[test]
+fn fail_to_read_a_missing_button_configuration() {
+    let translations = Translations::get_instance();
+    let config = read(&storage());
+    assert!(E4Button::read_config(&config, &"missing".to_string(), translations).is_err());
+}
+
+#[test]
+fn keep_the_comments_and_the_order_when_saving() {
+    let translations = Translations::get_instance();
+    let commented = format!(
+        "; My dock\n{}",
+        E4DOCKER_CONF.replace("x=100", "# The position\nX = 100")
+    );
+    let storage =
+        Arc::new(MemoryStorage::new().with_file(e4config::config_file(&config_dir()), &commented));
+    let mut config = read(&storage);
+
+    config
+        .set_value(
+            E4DOCKER_DOCKER_SECTION.to_string(),
+            "x".to_string(),
+            Some("300".to_string()),
+            translations,
+        )
+        .unwrap();
+    assert_eq!(
+        storage
+            .read_to_string(&e4config::config_file(&config_dir()))
+            .unwrap(),
+        commented.replace("X = 100", "X = 300")
+    );
+}
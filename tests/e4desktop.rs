@@ -0,0 +1,46 @@
+//! Tests of the reading of the .desktop entries of the installed applications.
+
+use e4docker::{e4desktop, e4window};
+
+#[test]
+fn read_the_window_class_of_a_desktop_entry() {
+    let entry = "[Desktop Entry]\nExec=env GDK_BACKEND=x11 /opt/gimp/bin/gimp-2.10 %U\nStartupWMClass=Gimp\n\n\
+                 [Desktop Action new]\nExec=other\n";
+    assert_eq!(
+        e4window::desktop_entry_class(entry),
+        Some(("gimp-2.10".to_string(), "Gimp".to_string()))
+    );
+    // Without StartupWMClass the windows are found by the name of the program
+    assert_eq!(
+        e4window::desktop_entry_class("[Desktop Entry]\nExec=nano\n"),
+        None
+    );
+}
+
+#[test]
+fn read_the_applications_of_the_desktop_entries() {
+    let entry = "[Desktop Entry]\nType=Application\nName=AC/DC player\nName[it]=Lettore AC/DC\n\
+                 Exec=env GDK_BACKEND=x11 \"/opt/my player/run\" --volume 100%% %F\nIcon=\n\n\
+                 [Desktop Action new-window]\nName=New Window\nExec=player --new-window\n";
+    assert_eq!(
+        e4desktop::desktop_app(entry),
+        Some(e4desktop::DesktopApp {
+            name: "AC-DC player".to_string(),
+            command: "env".to_string(),
+            arguments: vec![
+                "GDK_BACKEND=x11".to_string(),
+                "/opt/my player/run".to_string(),
+                "--volume".to_string(),
+                "100%".to_string(),
+            ],
+            icon: None,
+        })
+    );
+    // Links and hidden entries are not applications to launch
+    assert!(
+        e4desktop::desktop_app("[Desktop Entry]\nType=Link\nName=Docs\nURL=https://docs.rs\n")
+            .is_none()
+    );
+    assert!(e4desktop::desktop_app("[Desktop Entry]\nName=Old\nExec=old\nHidden=true\n").is_none());
+    assert!(e4desktop::desktop_app("[Desktop Entry]\nName=Nothing\n").is_none());
+}
@@ -0,0 +1,91 @@
+//! Tests of the changes between two configurations, shown before applying them.
+
+mod common;
+
+use common::{config_dir, storage, E4DOCKER_CONF, FIREFOX_CONF};
+use e4docker::{
+    e4config,
+    e4diff::{self, E4Change, E4ConfigDiff, E4ConfigSnapshot, E4SettingChange},
+    e4import,
+    e4storage::{E4Storage, MemoryStorage},
+    translations::Translations,
+};
+
+#[test]
+fn compare_two_configurations() {
+    let current = E4ConfigSnapshot::read(storage().as_ref(), &config_dir()).unwrap();
+    let dir = config_dir();
+    let new_storage = MemoryStorage::new()
+        .with_file(
+            e4config::config_file(&dir),
+            &E4DOCKER_CONF
+                .replace(
+                    "button1=firefox\nbutton2=terminal",
+                    "button1=terminal\nbutton2=firefox",
+                )
+                .replace("button3=trash", "button3=editor")
+                .replace("x=100", "x=200"),
+        )
+        .with_file(e4config::button_file(&dir, "firefox"), FIREFOX_CONF)
+        .with_file(
+            e4config::button_file(&dir, "terminal"),
+            &FIREFOX_CONF.replace("firefox", "alacritty"),
+        )
+        .with_file(e4config::button_file(&dir, "editor"), FIREFOX_CONF);
+    let new = E4ConfigSnapshot::read(&new_storage, &dir).unwrap();
+
+    let diff = E4ConfigDiff::new(&current, &new);
+    assert_eq!(
+        diff.buttons,
+        vec![
+            ("terminal".to_string(), E4Change::Changed),
+            ("trash".to_string(), E4Change::Removed),
+            ("editor".to_string(), E4Change::Added),
+        ]
+    );
+    assert!(diff.reordered);
+    assert_eq!(
+        diff.settings,
+        vec![E4SettingChange {
+            section: "e4docker".to_string(),
+            key: "x".to_string(),
+            old: Some("100".to_string()),
+            new: Some("200".to_string()),
+        }]
+    );
+    assert!(E4ConfigDiff::new(&current, &current).is_empty());
+}
+
+#[test]
+fn import_the_buttons_in_a_dry_run() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let paths = storage.paths();
+    let conf = storage
+        .read_to_string(&e4config::config_file(&config_dir()))
+        .unwrap();
+    let json = r#"{"format": "e4docker-buttons", "buttons": [{"name": "downloads", "type": "folder", "icon": "folder.png"}]}"#;
+    let buttons = e4import::buttons_from_json(json).unwrap();
+
+    let (names, diff) = e4diff::dry_run(
+        storage.clone(),
+        &config_dir(),
+        &config_dir().join("assets"),
+        |config| e4import::import(config, &buttons, translations.clone()),
+    )
+    .unwrap();
+    assert_eq!(names, vec!["downloads"]);
+    assert_eq!(
+        diff.buttons,
+        vec![("downloads".to_string(), E4Change::Added)]
+    );
+    assert!(!diff.reordered);
+    // Nothing is written
+    assert_eq!(storage.paths(), paths);
+    assert_eq!(
+        storage
+            .read_to_string(&e4config::config_file(&config_dir()))
+            .unwrap(),
+        conf
+    );
+}
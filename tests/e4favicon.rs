@@ -0,0 +1,64 @@
+//! Tests of the favicons of the buttons opening a web address.
+
+mod common;
+
+use common::{config_dir, read, E4DOCKER_CONF, TRASH_CONF};
+use e4docker::{
+    e4button::E4Button, e4config, e4favicon, e4storage::MemoryStorage, translations::Translations,
+};
+use std::sync::Arc;
+
+#[test]
+fn show_the_favicon_of_a_url_button() {
+    let translations = Translations::get_instance();
+    let dir = config_dir();
+    let storage = Arc::new(
+        MemoryStorage::new()
+            .with_file(e4config::config_file(&dir), E4DOCKER_CONF)
+            .with_file(
+                e4config::button_file(&dir, "firefox"),
+                "[button]\ncommand=https://www.rust-lang.org/learn\n",
+            )
+            .with_file(
+                e4config::button_file(&dir, "terminal"),
+                "[button]\ncommand=https://www.rust-lang.org\nicon=rust.png\n",
+            )
+            .with_file(e4config::button_file(&dir, "trash"), TRASH_CONF),
+    );
+    let config = read(&storage);
+
+    let favicon =
+        E4Button::read_config(&config, &"firefox".to_string(), translations.clone()).unwrap();
+    let favicon_path = config
+        .assets_dir
+        .join("favicons")
+        .join("www.rust-lang.org.png");
+    assert_eq!(favicon.icon_path, favicon_path.display().to_string());
+    assert!(e4favicon::is_favicon(
+        &favicon_path,
+        &config.assets_dir,
+        "https://www.rust-lang.org/learn"
+    ));
+    // The icon chosen by the user wins
+    let chosen = E4Button::read_config(&config, &"terminal".to_string(), translations).unwrap();
+    assert_eq!(chosen.icon_path, "rust.png");
+}
+
+#[test]
+fn find_the_icons_of_a_page() {
+    let html = r#"<head>
+        <link rel="icon" href="/static/favicon.png">
+        <link rel='apple-touch-icon' sizes=180x180 href='touch.png'>
+        <link href="//cdn.example.org/icon.ico" rel="shortcut icon">
+        <link rel=stylesheet href=style.css>
+    </head>"#;
+    assert_eq!(
+        e4favicon::icon_links("https://www.rust-lang.org/learn/get-started?lang=it", html),
+        vec![
+            "https://www.rust-lang.org/learn/touch.png",
+            "https://www.rust-lang.org/static/favicon.png",
+            "https://cdn.example.org/icon.ico",
+            "https://www.rust-lang.org/favicon.ico",
+        ]
+    );
+}
@@ -0,0 +1,42 @@
+//! Tests of the gestures over the dock: the swipes and the slow double clicks.
+
+use e4docker::{
+    e4gesture::{E4SlowClickTracker, E4Swipe, E4SwipeTracker},
+    e4style::E4Edge,
+};
+use std::time::{Duration, Instant};
+
+#[test]
+fn recognize_the_swipes() {
+    let start = Instant::now();
+    let at = |millis: u64| start + Duration::from_millis(millis);
+
+    // A slow scroll is not a swipe
+    let mut tracker = E4SwipeTracker::default();
+    assert!((0..10).all(|i| tracker.add(at(i * 100), 0, -1).is_none()));
+
+    // A fast one is, once
+    let mut tracker = E4SwipeTracker::default();
+    let swipes: Vec<Option<E4Swipe>> = (0..5).map(|i| tracker.add(at(i * 20), -1, -3)).collect();
+    assert_eq!(swipes, vec![None, None, Some(E4Swipe::Up), None, None]);
+
+    // A diagonal one has no direction
+    let mut tracker = E4SwipeTracker::default();
+    assert_eq!(tracker.add(start, 10, 10), None);
+
+    assert!(E4Swipe::Down.is_toward(E4Edge::BottomLeft));
+    assert!(E4Swipe::Right.is_away_from(E4Edge::Left));
+    assert!(!E4Swipe::Up.is_toward(E4Edge::Left));
+}
+
+#[test]
+fn recognize_the_slow_double_clicks() {
+    // The second click of a slow double click comes after a double click
+    let mut clicks = E4SlowClickTracker::default();
+    let start = Instant::now();
+    assert!(!clicks.add(start, "console"));
+    assert!(!clicks.add(start + Duration::from_millis(200), "console"));
+    assert!(!clicks.add(start + Duration::from_millis(3000), "firefox"));
+    assert!(clicks.add(start + Duration::from_millis(4000), "firefox"));
+    assert!(!clicks.add(start + Duration::from_millis(5000), "firefox"));
+}
@@ -0,0 +1,101 @@
+//! Tests of the icons: found for a command, converted to PNG and split in frames when animated.
+
+use e4docker::{
+    e4animation,
+    e4icon::{self, E4IconResolver, E4MonogramResolver},
+    e4window,
+};
+use std::time::Duration;
+
+#[test]
+fn resolve_the_icon_of_a_command() {
+    struct KnownIcon;
+    impl E4IconResolver for KnownIcon {
+        fn resolve(&self, _name: &str, cmd: &str) -> Option<Vec<u8>> {
+            (cmd == "/opt/known/bin/known").then_some(vec![1, 2, 3])
+        }
+    }
+    let resolvers: Vec<Box<dyn E4IconResolver>> =
+        vec![Box::new(KnownIcon), Box::new(E4MonogramResolver)];
+    assert_eq!(
+        e4icon::resolve_icon(&resolvers, "known", "/opt/known/bin/known"),
+        Some(vec![1, 2, 3])
+    );
+    // The monogram is the last resort
+    let png_data = e4icon::resolve_icon(&resolvers, "notes", "/opt/notes/bin/notes").unwrap();
+    let monogram = image::load_from_memory(&png_data).unwrap();
+    assert_eq!((monogram.width(), monogram.height()), (128, 128));
+    assert_eq!(
+        e4window::desktop_entry_icon("[Desktop Entry]\nExec=/usr/bin/gimp %U\nIcon=gimp\n"),
+        Some(("gimp".to_string(), "gimp".to_string()))
+    );
+}
+
+#[test]
+fn convert_the_icons_to_png() {
+    let dir = std::env::temp_dir().join(format!("e4docker-icons-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let red = image::Rgba([255, 0, 0, 255]);
+    let image = image::RgbaImage::from_pixel(3, 2, red);
+    for extension in ["bmp", "webp", "gif"] {
+        let path = dir.join(format!("icon.{}", extension));
+        image.save(&path).unwrap();
+        let png = image::load_from_memory(&e4icon::to_png(&path).unwrap()).unwrap();
+        assert_eq!((png.width(), png.height()), (3, 2), "{}", extension);
+    }
+    // An animated GIF, saved with the wrong extension, gives its first frame
+    let path = dir.join("animated.png");
+    let mut encoder = image::codecs::gif::GifEncoder::new(std::fs::File::create(&path).unwrap());
+    encoder
+        .encode_frames([
+            image::Frame::new(image::RgbaImage::from_pixel(2, 2, red)),
+            image::Frame::new(image::RgbaImage::from_pixel(
+                2,
+                2,
+                image::Rgba([0, 0, 255, 255]),
+            )),
+        ])
+        .unwrap();
+    drop(encoder);
+    let png = image::load_from_memory(&e4icon::to_png(&path).unwrap()).unwrap();
+    assert_eq!(*png.to_rgba8().get_pixel(0, 0), red);
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn read_the_frames_of_the_animated_icons() {
+    let dir = std::env::temp_dir().join(format!("e4docker-animation-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("animated.gif");
+    let mut encoder = image::codecs::gif::GifEncoder::new(std::fs::File::create(&path).unwrap());
+    let frame = |color| image::RgbaImage::from_pixel(2, 2, image::Rgba(color));
+    encoder
+        .encode_frames([
+            image::Frame::from_parts(
+                frame([255, 0, 0, 255]),
+                0,
+                0,
+                image::Delay::from_numer_denom_ms(250, 1),
+            ),
+            // Too short: shown as long as the browsers do
+            image::Frame::new(frame([0, 0, 255, 255])),
+        ])
+        .unwrap();
+    drop(encoder);
+    let frames = e4animation::frames(&path).unwrap();
+    let delays: Vec<_> = frames.iter().map(|(_, delay)| *delay).collect();
+    assert_eq!(
+        delays,
+        vec![Duration::from_millis(250), Duration::from_millis(100)]
+    );
+    let png = image::load_from_memory(&frames[1].0).unwrap();
+    assert_eq!(
+        *png.to_rgba8().get_pixel(0, 0),
+        image::Rgba([0, 0, 255, 255])
+    );
+    // A still image is not animated
+    let path = dir.join("still.png");
+    frame([255, 0, 0, 255]).save(&path).unwrap();
+    assert!(e4animation::frames(&path).unwrap().is_empty());
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,87 @@
+//! Tests of the import and the export of the buttons as JSON files.
+
+mod common;
+
+use common::{config_dir, read, storage};
+use e4docker::{
+    e4button::E4ButtonType, e4config, e4import, e4storage::E4Storage, translations::Translations,
+};
+
+#[test]
+fn import_the_buttons_of_a_json_file() {
+    let translations = Translations::get_instance();
+    let storage = storage();
+    let mut config = read(&storage);
+    let json = r##"{
+        "format": "e4docker-buttons",
+        "buttons": [
+            {
+                "name": "firefox",
+                "command": "/usr/bin/firefox",
+                "args": ["--new-window", "https://www.rust-lang.org"],
+                "flags": { "accent_color": "#e66000", "cooldown": 2, "accent_plate": true }
+            },
+            { "name": "downloads", "type": "folder", "icon": "folder.png" }
+        ]
+    }"##;
+
+    let buttons = e4import::buttons_from_json(json).unwrap();
+    assert_eq!(buttons.len(), 2);
+    assert_eq!(
+        buttons[0].arguments,
+        vec!["--new-window", "https://www.rust-lang.org"]
+    );
+    assert_eq!(buttons[1].button_type, Some(E4ButtonType::Folder));
+    let names = e4import::import(&mut config, &buttons, translations).unwrap();
+    assert_eq!(names, vec!["firefox-2", "downloads"]);
+    assert_eq!(
+        config.buttons,
+        vec!["firefox", "terminal", "trash", "firefox-2", "downloads"]
+    );
+    let firefox = storage
+        .load_ini(&e4config::button_file(&config_dir(), "firefox-2"))
+        .unwrap();
+    assert_eq!(
+        firefox.get("button", "arguments").unwrap(),
+        "--new-window https://www.rust-lang.org"
+    );
+    assert_eq!(firefox.get("button", "icon").unwrap(), "generic.png");
+    assert_eq!(firefox.get("button", "cooldown").unwrap(), "2");
+    assert_eq!(firefox.get("button", "accent_plate").unwrap(), "true");
+
+    // The errors tell where the JSON does not match the schema
+    let error = |json: &str| e4import::buttons_from_json(json).unwrap_err().to_string();
+    assert_eq!(
+        error(r#"{"format": "e4docker-buttons", "buttons": [{"name": "firefox"}]}"#),
+        "buttons[0].command: missing"
+    );
+    assert_eq!(
+        error(r#"{"format": "e4docker-buttons", "buttons": [{"name": "x", "type": "rocket"}]}"#),
+        "buttons[0].type: unknown type"
+    );
+    assert_eq!(
+        error(r#"{"format": "e4docker-buttons", "buttons": [{"name": "x", "command": 1}]}"#),
+        "buttons[0].command: expected a string"
+    );
+    assert!(error(r#"{"buttons": []}"#).starts_with("format:"));
+}
+
+#[test]
+fn export_the_selected_buttons() {
+    let storage = storage();
+    let config = read(&storage);
+    let path = config_dir().join("buttons.json");
+    let names = vec!["firefox".to_string(), "trash".to_string()];
+    e4import::export_file(&config, &names, &path).unwrap();
+
+    // The exported buttons can be imported again
+    let buttons = e4import::buttons_from_json(&storage.read_to_string(&path).unwrap()).unwrap();
+    assert_eq!(buttons.len(), 2);
+    assert_eq!(buttons[0].name, "firefox");
+    assert_eq!(buttons[0].command, "/usr/bin/firefox");
+    assert_eq!(buttons[0].arguments, vec!["https://www.rust-lang.org"]);
+    assert_eq!(buttons[0].icon, Some("firefox.png".to_string()));
+    assert_eq!(buttons[1].button_type, Some(E4ButtonType::Trash));
+    assert!(buttons[1].command.is_empty());
+    assert!(e4import::export_file(&config, &["missing".to_string()], &path).is_err());
+}
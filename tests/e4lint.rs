@@ -0,0 +1,104 @@
+//! Tests of the check of the configuration, finding the problems of the buttons.
+
+mod common;
+
+use common::{config_dir, read, storage, E4DOCKER_CONF, FIREFOX_CONF, TRASH_CONF};
+use e4docker::{
+    e4config,
+    e4lint::{self, E4Lint},
+    e4storage::{E4Storage, MemoryStorage},
+};
+use std::sync::Arc;
+
+#[test]
+fn check_the_configuration() {
+    let dir = config_dir();
+    let assets_dir =
+        std::env::temp_dir().join(format!("e4docker-check-config-{}", std::process::id()));
+    std::fs::create_dir_all(&assets_dir).unwrap();
+    std::fs::write(assets_dir.join("firefox.png"), b"").unwrap();
+    // The entries skip button3, and notes has no configuration file
+    let conf = E4DOCKER_CONF.replace("button3=trash", "button4=trash\nbutton5=notes");
+    let missing = "[button]\ncommand=/nonexistent/e4docker-firefox\nicon=firefox.png\n";
+    let storage = Arc::new(
+        MemoryStorage::new()
+            .with_file(e4config::config_file(&dir), &conf)
+            .with_file(e4config::button_file(&dir, "firefox"), missing)
+            .with_file(
+                e4config::button_file(&dir, "terminal"),
+                &missing.replace("firefox.png", "terminal.png"),
+            )
+            .with_file(e4config::button_file(&dir, "trash"), TRASH_CONF),
+    );
+
+    let lints = e4lint::check_config(storage.clone(), &dir, &assets_dir).unwrap();
+    std::fs::remove_dir_all(&assets_dir).unwrap();
+    assert!(lints.contains(&E4Lint::MisnumberedButtons));
+    assert!(lints.contains(&E4Lint::MissingConfiguration {
+        button: "notes".to_string()
+    }));
+    assert!(lints.contains(&E4Lint::CommandNotFound {
+        button: "firefox".to_string(),
+        command: "/nonexistent/e4docker-firefox".to_string()
+    }));
+    assert!(lints.contains(&E4Lint::MissingIcon {
+        button: "terminal".to_string(),
+        icon: "terminal.png".to_string()
+    }));
+    assert!(!lints
+        .iter()
+        .any(|lint| matches!(lint, E4Lint::MissingIcon { button, .. } if button == "firefox")));
+    // Two buttons launching the same program are only a warning
+    let duplicate = lints
+        .iter()
+        .find(|lint| matches!(lint, E4Lint::DuplicateCommand { .. }))
+        .unwrap();
+    assert!(!duplicate.is_error());
+    assert!(!E4Lint::MisnumberedButtons.is_error());
+    // The buttons are not numbered again
+    assert_eq!(
+        storage
+            .read_to_string(&e4config::config_file(&dir))
+            .unwrap(),
+        conf
+    );
+}
+
+#[test]
+fn find_the_buttons_launching_the_same_command() {
+    let storage = storage();
+    let config = read(&storage);
+    // The terminal button of the tests launches firefox too
+    assert_eq!(
+        e4lint::lint(&config),
+        vec![E4Lint::DuplicateCommand {
+            command: e4lint::command_line("/usr/bin/firefox", " https://www.rust-lang.org"),
+            buttons: vec!["firefox".to_string(), "terminal".to_string()],
+        }]
+    );
+    assert_ne!(
+        e4lint::command_line("/usr/bin/firefox", "https://www.rust-lang.org"),
+        e4lint::command_line("/usr/bin/firefox", "https://docs.rs")
+    );
+
+    let terminal = format!(
+        "{}accent_color=#e66000\n",
+        FIREFOX_CONF.replace("firefox.png", "terminal.png")
+    );
+    storage
+        .write(&e4config::button_file(&config_dir(), "terminal"), &terminal)
+        .unwrap();
+    e4lint::merge_buttons(&config, "firefox", &["terminal".to_string()]).unwrap();
+    let firefox = storage
+        .load_ini(&e4config::button_file(&config_dir(), "firefox"))
+        .unwrap();
+    // The keys of firefox are kept
+    assert_eq!(
+        firefox.get(e4config::BUTTON_BUTTON_SECTION, "icon"),
+        Some("firefox.png".to_string())
+    );
+    assert_eq!(
+        firefox.get(e4config::BUTTON_BUTTON_SECTION, "accent_color"),
+        Some("#e66000".to_string())
+    );
+}
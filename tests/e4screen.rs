@@ -0,0 +1,28 @@
+//! Tests of the place of the dock when the work area of the screen changes.
+
+use e4docker::e4screen;
+
+#[test]
+fn follow_the_work_area() {
+    let screen = (0, 0, 1920, 1080);
+    // A panel appears on the left: the dock on the left edge moves with it
+    assert_eq!(
+        e4screen::follow((5, 300, 40, 400), screen, (48, 0, 1872, 1080)),
+        (48, 300)
+    );
+    // A panel appears on the top: the dock in the middle keeps its place
+    assert_eq!(
+        e4screen::follow((700, 500, 400, 40), screen, (0, 32, 1920, 1048)),
+        (700, 500)
+    );
+    // The screen on the right of another one is removed: the dock is moved inside the work area
+    assert_eq!(
+        e4screen::follow((2500, 1040, 400, 40), (0, 0, 3840, 1080), screen),
+        (1520, 1040)
+    );
+    // A dock larger than the work area starts with it
+    assert_eq!(
+        e4screen::follow((0, 0, 2000, 40), screen, (0, 0, 1280, 720)),
+        (0, 0)
+    );
+}
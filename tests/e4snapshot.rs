@@ -0,0 +1,22 @@
+//! Tests of the preview of the dock saved as a PNG image.
+
+use e4docker::e4snapshot;
+use std::path::{Path, PathBuf};
+
+#[test]
+fn save_the_dock_preview_as_png() {
+    let png_data = e4snapshot::png_from_pixels(&[255, 0, 0, 0, 0, 255], 2, 1, 3).unwrap();
+    let image = image::load_from_memory(&png_data).unwrap().to_rgb8();
+    assert_eq!(image.dimensions(), (2, 1));
+    assert_eq!(image.get_pixel(1, 0).0, [0, 0, 255]);
+    assert!(e4snapshot::png_from_pixels(&[0; 6], 2, 1, 5).is_err());
+
+    assert_eq!(
+        e4snapshot::png_path(Path::new("/tmp/dock")),
+        PathBuf::from("/tmp/dock.png")
+    );
+    assert_eq!(
+        e4snapshot::png_path(Path::new("dock.PNG")),
+        PathBuf::from("dock.PNG")
+    );
+}
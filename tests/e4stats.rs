@@ -0,0 +1,66 @@
+//! Tests of the launches counted in the state and of the statistics of the dock read from them.
+
+use e4docker::{
+    e4state::{self, E4State, ACTIVITY_DAYS},
+    e4stats::E4Stats,
+};
+
+#[test]
+fn count_the_launches_of_the_last_days() {
+    let dir = std::env::temp_dir().join(format!("e4docker-launches-{}", std::process::id()));
+    let mut state = E4State::load(&dir);
+    let today = 20_000;
+    state.add_launch("firefox", today - ACTIVITY_DAYS);
+    state.add_launch("firefox", today - 1);
+    state.add_launch("firefox", today);
+    state.add_launch("firefox", today);
+
+    let launches = state.launches_per_day("firefox", today);
+    assert_eq!(launches.len(), ACTIVITY_DAYS as usize);
+    // The launch older than the last days is forgotten
+    assert_eq!(launches.iter().sum::<u32>(), 3);
+    assert_eq!(launches[launches.len() - 2..], [1, 2]);
+    assert!(state
+        .launches_per_day("trash", today)
+        .iter()
+        .all(|&count| count == 0));
+}
+
+#[test]
+fn report_the_usage_of_the_dock() {
+    let dir = std::env::temp_dir().join(format!("e4docker-stats-{}", std::process::id()));
+    let mut state = E4State::load(&dir);
+    let today = 20_000;
+    state.add_launch("terminal", today - 2);
+    state.add_launch("terminal", today);
+    state.add_launch("firefox", today);
+    for hour in [9, 14, 14, 9, 14] {
+        state.add_launch_hour(hour);
+    }
+
+    let buttons = vec![
+        "firefox".to_string(),
+        "terminal".to_string(),
+        "trash".to_string(),
+    ];
+    let stats = E4Stats::new(&state, &buttons, today, 0);
+    assert_eq!(
+        stats.launches,
+        vec![
+            ("terminal".to_string(), 2),
+            ("firefox".to_string(), 1),
+            ("trash".to_string(), 0)
+        ]
+    );
+    assert_eq!(stats.most_active_hours(3), vec![14, 9]);
+    assert_eq!(stats.unused(), vec!["trash".to_string()]);
+
+    // The hours are shown in the local time
+    let stats = E4Stats::new(&state, &buttons, today, -3 * 3_600);
+    assert_eq!(stats.most_active_hours(3), vec![11, 6]);
+    let stats = E4Stats::new(&state, &buttons, today, 10 * 3_600 + 1_800);
+    assert_eq!(stats.most_active_hours(3), vec![1, 20]);
+    assert_eq!(e4state::parse_utc_offset("+0100"), Some(3_600));
+    assert_eq!(e4state::parse_utc_offset("-0930"), Some(-34_200));
+    assert_eq!(e4state::parse_utc_offset("0100"), None);
+}
@@ -0,0 +1,70 @@
+//! Tests of the storages of the configuration files, in memory and on the file system.
+
+mod common;
+
+use common::E4DOCKER_CONF;
+use configparser::ini::Ini;
+use e4docker::{
+    e4config::E4Config,
+    e4storage::{self, E4Storage, FsStorage, MemoryStorage},
+};
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+#[test]
+fn merge_the_new_values_in_the_ini_file() {
+    let original = "[buttons]\nbutton1=firefox\n; The last one\nbutton2=trash\n[old]\nkey=value\n";
+    let mut ini = Ini::new();
+    ini.read(original.to_string()).unwrap();
+    ini.remove_key("buttons", "button2");
+    ini.set("buttons", "button10", Some("editor".to_string()));
+    ini.set("buttons", "button2", Some("terminal".to_string()));
+    ini.remove_section("old");
+    ini.set("hooks", "on_quit", Some("echo bye".to_string()));
+
+    assert_eq!(
+        e4storage::merge_ini(original, &ini),
+        "[buttons]\nbutton1=firefox\n; The last one\nbutton2=terminal\nbutton10=editor\n[hooks]\non_quit=echo bye\n"
+    );
+}
+
+#[test]
+fn keep_the_files_in_memory() {
+    let storage = MemoryStorage::new().with_file("a.conf", "[a]\nkey=value\n");
+    let path = Path::new("a.conf");
+    assert!(storage.exists(path));
+    assert_eq!(
+        storage.load_ini(path).unwrap().get("a", "key"),
+        Some("value".to_string())
+    );
+
+    storage.write(Path::new("b.conf"), "").unwrap();
+    assert_eq!(
+        storage.paths(),
+        vec![PathBuf::from("a.conf"), PathBuf::from("b.conf")]
+    );
+
+    storage.remove(path).unwrap();
+    assert!(!storage.exists(path));
+    assert!(storage.read_to_string(path).is_err());
+    assert!(storage.remove(path).is_err());
+}
+
+#[test]
+fn keep_the_files_on_the_file_system() {
+    let dir = std::env::temp_dir().join(format!("e4docker-test-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("e4docker.conf");
+
+    let storage = FsStorage;
+    storage.write(&path, E4DOCKER_CONF).unwrap();
+    assert!(storage.exists(&path));
+    let config = E4Config::read_from(Arc::new(storage), &dir, &dir.join("assets")).unwrap();
+    assert_eq!(config.buttons.len(), 3);
+
+    storage.remove(&path).unwrap();
+    assert!(!storage.exists(&path));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
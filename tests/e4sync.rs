@@ -0,0 +1,78 @@
+//! Tests of the plan syncing the local and the synced configuration.
+
+use e4docker::e4sync::E4SyncPlan;
+use std::{collections::HashMap, path::PathBuf};
+
+#[test]
+fn plan_the_sync_of_the_configuration() {
+    let times = |files: &[(&str, u64)]| -> HashMap<PathBuf, u64> {
+        files
+            .iter()
+            .map(|(path, time)| (PathBuf::from(path), *time))
+            .collect()
+    };
+    let local = times(&[
+        ("e4docker.conf", 50),
+        ("firefox.conf", 150),
+        ("terminal.conf", 120),
+        ("local.conf", 10),
+    ]);
+    let synced = times(&[
+        ("e4docker.conf", 200),
+        ("firefox.conf", 50),
+        ("terminal.conf", 130),
+        ("synced.conf", 10),
+    ]);
+
+    let mut plan = E4SyncPlan::new(&local, &synced, 100);
+    assert_eq!(
+        plan,
+        E4SyncPlan {
+            to_local: vec![PathBuf::from("e4docker.conf")],
+            to_synced: vec![PathBuf::from("firefox.conf")],
+            conflicts: vec![PathBuf::from("terminal.conf")],
+            delete_local: vec![PathBuf::from("local.conf")],
+            delete_synced: vec![PathBuf::from("synced.conf")],
+        }
+    );
+    plan.merge(&local, &synced);
+    assert!(plan.conflicts.is_empty());
+    assert_eq!(plan.to_local.last(), Some(&PathBuf::from("terminal.conf")));
+}
+
+#[test]
+fn delete_the_files_deleted_on_the_other_side() {
+    let times = |files: &[(&str, u64)]| -> HashMap<PathBuf, u64> {
+        files
+            .iter()
+            .map(|(path, time)| (PathBuf::from(path), *time))
+            .collect()
+    };
+    // firefox.conf has been deleted from the synced directory, and its icon from the local one,
+    // since the last sync; new.conf has been created locally after it
+    let local = times(&[
+        ("e4docker.conf", 50),
+        ("firefox.conf", 50),
+        ("new.conf", 150),
+    ]);
+    let synced = times(&[("e4docker.conf", 50), ("assets/firefox.png", 50)]);
+
+    let plan = E4SyncPlan::new(&local, &synced, 100);
+    assert_eq!(plan.delete_local, vec![PathBuf::from("firefox.conf")]);
+    assert_eq!(
+        plan.delete_synced,
+        vec![PathBuf::from("assets/firefox.png")]
+    );
+    assert_eq!(plan.to_synced, vec![PathBuf::from("new.conf")]);
+    assert!(plan.to_local.is_empty());
+
+    // Before the first sync every file is new: nothing is deleted
+    let plan = E4SyncPlan::new(&local, &synced, 0);
+    assert!(plan.delete_local.is_empty() && plan.delete_synced.is_empty());
+    assert_eq!(plan.to_local, vec![PathBuf::from("assets/firefox.png")]);
+
+    // e4docker.conf is copied, never deleted
+    let plan = E4SyncPlan::new(&times(&[("e4docker.conf", 50)]), &HashMap::new(), 100);
+    assert_eq!(plan.to_synced, vec![PathBuf::from("e4docker.conf")]);
+    assert!(plan.delete_local.is_empty());
+}
@@ -0,0 +1,133 @@
+//! Tests of the translations: the values formatted in the language, the metadata and the diagnostics of the
+//! locale files, and property tests of the placeholders, their arguments and the escapes.
+
+use e4docker::translations::{format_template, unescape, Translations};
+use proptest::prelude::*;
+
+/// Return s written in a locale file: its backslashes, quotes and control characters escaped.
+fn escape(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '\n' => r"\n".to_string(),
+            '\t' => r"\t".to_string(),
+            '\r' => r"\r".to_string(),
+            '\\' => r"\\".to_string(),
+            '"' => "\\\"".to_string(),
+            '\'' => r"\'".to_string(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+proptest! {
+    #[test]
+    fn a_template_without_placeholders_is_kept(template in "[^{}]*", args in prop::collection::vec(".*", 0..4)) {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        prop_assert_eq!(format_template(&template, &args), template);
+    }
+
+    #[test]
+    fn every_placeholder_is_replaced_by_its_argument(
+        texts in prop::collection::vec("[^{}]*", 1..6),
+        args in prop::collection::vec(".*", 1..6),
+        indexes in prop::collection::vec(any::<prop::sample::Index>(), 5),
+    ) {
+        // The texts joined by the placeholders of random arguments
+        let mut template = texts[0].clone();
+        let mut expected = texts[0].clone();
+        for (text, index) in texts[1..].iter().zip(&indexes) {
+            let i = index.index(args.len());
+            template.push_str(&format!("{{{}}}{}", i, text));
+            expected.push_str(&format!("{}{}", args[i], text));
+        }
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        // The placeholders inside the arguments are not replaced again
+        prop_assert_eq!(format_template(&template, &args), expected);
+    }
+
+    #[test]
+    fn a_placeholder_without_argument_is_kept(
+        before in "[^{}]*",
+        after in "[^{}]*",
+        args in prop::collection::vec(".*", 0..4),
+        extra in 0usize..100,
+    ) {
+        let missing = format!("{{{}}}", args.len() + extra);
+        let template = format!("{}{}{}", before, missing, after);
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        prop_assert_eq!(format_template(&template, &args), template);
+    }
+
+    #[test]
+    fn a_text_without_backslashes_is_not_unescaped(text in "[^\\\\]*") {
+        prop_assert_eq!(unescape(&text), text);
+    }
+
+    #[test]
+    fn an_escaped_text_is_unescaped(text in any::<String>()) {
+        prop_assert_eq!(unescape(&escape(&text)), text);
+    }
+
+    #[test]
+    fn an_unknown_escape_is_kept(before in "[^\\\\]*", c in "[^ntr\\\\\"']", after in "[^\\\\]*") {
+        let text = format!("{}\\{}{}", before, c, after);
+        prop_assert_eq!(unescape(&text), text);
+    }
+}
+
+#[test]
+#[cfg(feature = "translations")]
+fn format_the_values_in_the_language() {
+    let mut english = Translations::for_locale("en_US").unwrap();
+    assert_eq!(english.language(), "en");
+    assert_eq!(english.format_number(-1234567.891, 2), "-1,234,567.89");
+    assert_eq!(english.format_size(512), "512 B");
+    assert_eq!(english.format_size(1536 * 1024), "1.5 MB");
+    // 16 October 2026, 14:05 UTC
+    assert_eq!(english.format_date(1_792_159_500), "10/16/2026");
+    assert_eq!(
+        english.format_date_time(1_792_159_500),
+        "10/16/2026 14:05 UTC"
+    );
+
+    let mut italian = Translations::for_locale("it_IT").unwrap();
+    assert_eq!(italian.language(), "it");
+    assert_eq!(italian.format_number(-1234567.891, 2), "-1.234.567,89");
+    assert_eq!(italian.format_size(1536 * 1024), "1,5 MB");
+    assert_eq!(italian.format_date(1_792_159_500), "16/10/2026");
+    // The leap day
+    assert_eq!(italian.format_date(951_782_400), "29/02/2000");
+}
+
+#[test]
+fn declare_the_arguments_of_the_translations() {
+    for locale in ["en_US", "it_IT"] {
+        let translations = Translations::for_locale(locale).unwrap();
+        assert_eq!(translations.placeholder_mismatches(), &[] as &[String]);
+        let metadata = translations.metadata("sync-conflict").unwrap();
+        assert_eq!(metadata.args, Some(3));
+        assert!(metadata
+            .comment
+            .as_deref()
+            .unwrap()
+            .contains("synced directory"));
+        // The comments at the start of the file describe it, not the first key
+        assert_eq!(translations.metadata("about"), None);
+    }
+}
+
+#[test]
+#[cfg(feature = "translations")]
+fn describe_the_missing_translations() {
+    let mut translations = Translations::for_locale("it_IT").unwrap();
+    assert!(translations
+        .diagnostics()
+        .contains("Prese dall'inglese (0): nessuna"));
+
+    assert_eq!(translations.get("not-a-key"), None);
+    let diagnostics = translations.diagnostics();
+    assert!(diagnostics.contains("Lingua: it"));
+    assert!(diagnostics.contains("Mancanti in tutte le lingue (1): not-a-key"));
+    // Missing everywhere, it is not taken from English
+    assert!(diagnostics.contains("Prese dall'inglese (0): nessuna"));
+}